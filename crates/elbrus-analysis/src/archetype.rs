@@ -0,0 +1,259 @@
+//! Deck similarity and archetype clustering for metagame breakdowns.
+//!
+//! Decks are compared by the quantities of their resolved mainboard cards and
+//! grouped by average-linkage agglomerative clustering: the two most similar
+//! clusters merge until no pair is at least as similar as the threshold. Each
+//! archetype is named after the cards that set it apart from the rest of the
+//! field.
+
+use elbrus_core::Color;
+use elbrus_deck::Deck;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Cards listed in an archetype's signature and used to name it.
+pub const SIGNATURE_CARDS: usize = 3;
+
+/// Copies of each resolved mainboard card, by oracle id.
+pub type DeckVector = HashMap<Uuid, u32>;
+
+#[must_use]
+pub fn deck_vector(deck: &Deck) -> DeckVector {
+    let mut vector = DeckVector::new();
+    for entry in &deck.mainboard {
+        if let Some(id) = entry.resolved {
+            *vector.entry(id).or_default() += entry.quantity;
+        }
+    }
+    vector
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityMetric {
+    /// Sum of the smaller quantities over sum of the larger ones.
+    #[default]
+    WeightedJaccard,
+    /// Cosine of the angle between quantity vectors.
+    Cosine,
+}
+
+impl SimilarityMetric {
+    /// Similarity from 0 (nothing shared) to 1 (identical).
+    #[must_use]
+    pub fn similarity(self, a: &DeckVector, b: &DeckVector) -> f64 {
+        match self {
+            Self::WeightedJaccard => weighted_jaccard(a, b),
+            Self::Cosine => cosine(a, b),
+        }
+    }
+}
+
+#[must_use]
+pub fn weighted_jaccard(a: &DeckVector, b: &DeckVector) -> f64 {
+    let (mut min, mut max) = (0u32, 0u32);
+    for (id, &qa) in a {
+        let qb = b.get(id).copied().unwrap_or(0);
+        min += qa.min(qb);
+        max += qa.max(qb);
+    }
+    max += b
+        .iter()
+        .filter(|(id, _)| !a.contains_key(id))
+        .map(|(_, q)| q)
+        .sum::<u32>();
+    if max == 0 {
+        0.0
+    } else {
+        f64::from(min) / f64::from(max)
+    }
+}
+
+#[must_use]
+pub fn cosine(a: &DeckVector, b: &DeckVector) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(id, &qa)| b.get(id).map(|&qb| f64::from(qa) * f64::from(qb)))
+        .sum();
+    let norm = |v: &DeckVector| {
+        v.values()
+            .map(|&q| f64::from(q).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    };
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// Pairwise similarity of `decks`, with 1 on the diagonal.
+#[must_use]
+pub fn similarity_matrix(decks: &[Deck], metric: SimilarityMetric) -> Vec<Vec<f64>> {
+    let vectors: Vec<DeckVector> = decks.iter().map(deck_vector).collect();
+    let mut matrix = vec![vec![1.0; decks.len()]; decks.len()];
+    for i in 0..vectors.len() {
+        for j in i + 1..vectors.len() {
+            let s = metric.similarity(&vectors[i], &vectors[j]);
+            matrix[i][j] = s;
+            matrix[j][i] = s;
+        }
+    }
+    matrix
+}
+
+/// A card that sets an archetype apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureCard {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+    /// Share of the archetype's decks playing the card.
+    pub frequency: f64,
+    /// `frequency` minus the share of all other decks playing it.
+    pub distinctiveness: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archetype {
+    /// Signature card names joined with " / ".
+    pub name: String,
+    /// Indices into the clustered decks, ascending.
+    pub decks: Vec<usize>,
+    /// Share of all decks in this archetype.
+    pub share: f64,
+    pub signature: Vec<SignatureCard>,
+}
+
+/// Group `decks` into archetypes, largest first. Clusters merge while their
+/// average pairwise similarity is at least `threshold`.
+#[must_use]
+pub fn cluster_archetypes(
+    decks: &[Deck],
+    metric: SimilarityMetric,
+    threshold: f64,
+) -> Vec<Archetype> {
+    let matrix = similarity_matrix(decks, metric);
+    let mut clusters: Vec<Vec<usize>> = (0..decks.len()).map(|i| vec![i]).collect();
+
+    let linkage = |a: &[usize], b: &[usize]| {
+        let total: f64 = a
+            .iter()
+            .flat_map(|&i| b.iter().map(move |&j| (i, j)))
+            .map(|(i, j)| matrix[i][j])
+            .sum();
+        total / (a.len() * b.len()) as f64
+    };
+    loop {
+        let mut best: Option<(usize, usize, f64)> = None;
+        for i in 0..clusters.len() {
+            for j in i + 1..clusters.len() {
+                let s = linkage(&clusters[i], &clusters[j]);
+                if best.is_none_or(|(_, _, b)| s > b) {
+                    best = Some((i, j, s));
+                }
+            }
+        }
+        match best {
+            Some((i, j, s)) if s >= threshold => {
+                let merged = clusters.remove(j);
+                clusters[i].extend(merged);
+                clusters[i].sort_unstable();
+            }
+            _ => break,
+        }
+    }
+
+    let vectors: Vec<DeckVector> = decks.iter().map(deck_vector).collect();
+    let names = card_names(decks);
+    let mut archetypes: Vec<Archetype> = clusters
+        .into_iter()
+        .map(|members| {
+            let signature = signature(&members, &vectors, &names);
+            Archetype {
+                name: signature
+                    .iter()
+                    .map(|c| &*c.name)
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+                share: members.len() as f64 / decks.len() as f64,
+                decks: members,
+                signature,
+            }
+        })
+        .collect();
+    archetypes.sort_by(|a, b| {
+        b.decks
+            .len()
+            .cmp(&a.decks.len())
+            .then(a.decks.cmp(&b.decks))
+    });
+    archetypes
+}
+
+/// The first name each resolved card appears under, skipping basic lands,
+/// which say nothing about an archetype.
+fn card_names(decks: &[Deck]) -> BTreeMap<Uuid, Arc<str>> {
+    let mut names = BTreeMap::new();
+    for entry in decks.iter().flat_map(|d| &d.mainboard) {
+        if let Some(id) = entry.resolved
+            && !is_basic_land(&entry.card_name)
+        {
+            names.entry(id).or_insert_with(|| entry.card_name.clone());
+        }
+    }
+    names
+}
+
+pub(crate) fn is_basic_land(name: &str) -> bool {
+    let name = name.strip_prefix("Snow-Covered ").unwrap_or(name);
+    name == "Wastes" || Color::ALL.iter().any(|c| c.land() == name)
+}
+
+fn signature(
+    members: &[usize],
+    vectors: &[DeckVector],
+    names: &BTreeMap<Uuid, Arc<str>>,
+) -> Vec<SignatureCard> {
+    let others = vectors.len() - members.len();
+    let share = |decks: usize, of: usize| {
+        if of == 0 {
+            0.0
+        } else {
+            decks as f64 / of as f64
+        }
+    };
+
+    let mut cards: Vec<SignatureCard> = names
+        .iter()
+        .filter_map(|(id, name)| {
+            let inside = members
+                .iter()
+                .filter(|&&m| vectors[m].contains_key(id))
+                .count();
+            if inside == 0 {
+                return None;
+            }
+            let total = vectors.iter().filter(|v| v.contains_key(id)).count();
+            let frequency = share(inside, members.len());
+            Some(SignatureCard {
+                oracle_id: *id,
+                name: name.clone(),
+                frequency,
+                distinctiveness: frequency - share(total - inside, others),
+            })
+        })
+        .filter(|c| c.distinctiveness > 0.0)
+        .collect();
+    cards.sort_by(|a, b| {
+        b.distinctiveness
+            .total_cmp(&a.distinctiveness)
+            .then(b.frequency.total_cmp(&a.frequency))
+            .then(a.name.cmp(&b.name))
+    });
+    cards.truncate(SIGNATURE_CARDS);
+    cards
+}
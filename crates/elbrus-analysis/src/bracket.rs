@@ -0,0 +1,265 @@
+//! Commander bracket estimation.
+//!
+//! A deck is checked against card lists for the signals the official bracket
+//! guidelines care about. Each signal that shows up becomes a piece of
+//! [`BracketEvidence`] naming the cards and the lowest bracket they fit in;
+//! the estimate is the highest of those. The lists live in
+//! `data/brackets.json` so they can follow changes to the official ones
+//! without touching code, and a different file can be loaded at runtime.
+
+use crate::mana::{is_land, mana_value};
+use elbrus_core::OracleCard;
+use elbrus_deck::Deck;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The lists bundled with this crate.
+pub const DEFAULT_BRACKET_LISTS: &str = include_str!("../data/brackets.json");
+
+/// Average nonland mana value at or below which a deck counts as lean.
+pub const LOW_CURVE: f64 = 2.5;
+/// Average nonland mana value at or above which a deck with nothing else
+/// flagged is treated as a casual, bracket 1 deck.
+pub const HIGH_CURVE: f64 = 3.5;
+
+/// Card names for each signal, matched case-insensitively against full card
+/// names and individual face names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BracketLists {
+    pub game_changers: Vec<Arc<str>>,
+    pub fast_mana: Vec<Arc<str>>,
+    pub tutors: Vec<Arc<str>>,
+    pub extra_turns: Vec<Arc<str>>,
+    pub mass_land_denial: Vec<Arc<str>>,
+    pub free_interaction: Vec<Arc<str>>,
+    pub two_card_combos: Vec<[Arc<str>; 2]>,
+}
+
+impl BracketLists {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The lists in [`DEFAULT_BRACKET_LISTS`].
+    #[must_use]
+    pub fn bundled() -> Self {
+        Self::from_json(DEFAULT_BRACKET_LISTS).expect("bundled bracket lists are valid JSON")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BracketSignal {
+    GameChangers,
+    FastMana,
+    Tutors,
+    ExtraTurns,
+    MassLandDenial,
+    FreeInteraction,
+    TwoCardCombos,
+    AverageManaValue,
+}
+
+/// One signal found in a deck and what it says about the bracket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketEvidence {
+    pub signal: BracketSignal,
+    /// Matching cards; for combos, each pair joined with " + ".
+    pub cards: Vec<Arc<str>>,
+    /// The lowest bracket this evidence is compatible with.
+    pub min_bracket: u8,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketEstimate {
+    /// 1 (exhibition) to 5 (cEDH).
+    pub bracket: u8,
+    pub average_mana_value: f64,
+    pub evidence: Vec<BracketEvidence>,
+}
+
+impl BracketEstimate {
+    #[must_use]
+    pub fn count(&self, signal: BracketSignal) -> usize {
+        self.evidence
+            .iter()
+            .find(|e| e.signal == signal)
+            .map_or(0, |e| e.cards.len())
+    }
+}
+
+/// Estimate the bracket of a resolved Commander deck, commander included.
+///
+/// Brackets implied by each signal:
+/// - game changers: 1–3 → 3, more → 4
+/// - mass land denial: any → 4
+/// - extra turns: 1–2 → 2, more (likely to chain) → 4
+/// - tutors: 1–2 → 2, 3–5 → 3, more → 4
+/// - fast mana: 1–2 → 3, more → 4
+/// - free interaction: 1–2 → 3, more → 4
+/// - two-card combos: any → 3, or 4 with two or more tutors to find them
+/// - average mana value at most [`LOW_CURVE`] → 3
+///
+/// A bracket 4 deck with a combo, six or more tutors, three or more pieces of
+/// fast mana and a low curve is estimated as 5. A deck with none of the above
+/// and an average mana value of at least [`HIGH_CURVE`] is estimated as 1;
+/// anything else is at least 2.
+#[must_use]
+pub fn estimate_bracket(
+    deck: &Deck,
+    cards: &HashMap<Uuid, OracleCard>,
+    lists: &BracketLists,
+) -> BracketEstimate {
+    let resolved: Vec<(&OracleCard, u32)> = deck
+        .commander
+        .iter()
+        .chain(&deck.mainboard)
+        .filter_map(|e| {
+            e.resolved
+                .and_then(|id| cards.get(&id))
+                .map(|c| (c, e.quantity))
+        })
+        .collect();
+
+    // Lowercased names the deck answers to, mapped to the card's display name.
+    let mut names: HashMap<String, Arc<str>> = HashMap::new();
+    for (card, _) in &resolved {
+        let full: Arc<str> = card.full_name().into();
+        names.insert(full.to_lowercase(), full.clone());
+        for face in &card.faces {
+            names.insert(face.name.to_lowercase(), full.clone());
+        }
+    }
+    let matching = |list: &[Arc<str>]| -> Vec<Arc<str>> {
+        list.iter()
+            .filter_map(|name| names.get(&name.to_lowercase()).cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    };
+
+    let (mut nonland, mut total) = (0u32, 0.0);
+    for (card, quantity) in &resolved {
+        if !is_land(card) {
+            nonland += quantity;
+            total += f64::from(mana_value(card)) * f64::from(*quantity);
+        }
+    }
+    let average_mana_value = if nonland == 0 {
+        0.0
+    } else {
+        total / f64::from(nonland)
+    };
+
+    let game_changers = matching(&lists.game_changers);
+    let fast_mana = matching(&lists.fast_mana);
+    let tutors = matching(&lists.tutors);
+    let extra_turns = matching(&lists.extra_turns);
+    let mass_land_denial = matching(&lists.mass_land_denial);
+    let free_interaction = matching(&lists.free_interaction);
+    let combos: Vec<Arc<str>> = lists
+        .two_card_combos
+        .iter()
+        .filter_map(|[a, b]| {
+            let a = names.get(&a.to_lowercase())?;
+            let b = names.get(&b.to_lowercase())?;
+            Some(format!("{a} + {b}").into())
+        })
+        .collect();
+
+    let tutor_count = tutors.len();
+    let fast_mana_count = fast_mana.len();
+    let combo_count = combos.len();
+
+    let mut evidence = Vec::new();
+    let mut add = |signal, cards: Vec<Arc<str>>, min_bracket, reason: String| {
+        if !cards.is_empty() {
+            evidence.push(BracketEvidence {
+                signal,
+                cards,
+                min_bracket,
+                reason,
+            });
+        }
+    };
+    let n = game_changers.len();
+    add(
+        BracketSignal::GameChangers,
+        game_changers,
+        if n <= 3 { 3 } else { 4 },
+        format!("{n} game changer(s); brackets 1–2 allow none and bracket 3 up to three"),
+    );
+    add(
+        BracketSignal::MassLandDenial,
+        mass_land_denial,
+        4,
+        "mass land denial is only expected from bracket 4".to_owned(),
+    );
+    let n = extra_turns.len();
+    add(
+        BracketSignal::ExtraTurns,
+        extra_turns,
+        if n <= 2 { 2 } else { 4 },
+        format!("{n} extra turn card(s); chaining extra turns is a bracket 4 pattern"),
+    );
+    add(
+        BracketSignal::Tutors,
+        tutors,
+        match tutor_count {
+            0..=2 => 2,
+            3..=5 => 3,
+            _ => 4,
+        },
+        format!("{tutor_count} tutor(s)"),
+    );
+    add(
+        BracketSignal::FastMana,
+        fast_mana,
+        if fast_mana_count <= 2 { 3 } else { 4 },
+        format!("{fast_mana_count} piece(s) of fast mana"),
+    );
+    let n = free_interaction.len();
+    add(
+        BracketSignal::FreeInteraction,
+        free_interaction,
+        if n <= 2 { 3 } else { 4 },
+        format!("{n} free interaction spell(s)"),
+    );
+    add(
+        BracketSignal::TwoCardCombos,
+        combos,
+        if tutor_count >= 2 { 4 } else { 3 },
+        format!("{combo_count} two-card combo(s) with {tutor_count} tutor(s) to assemble them"),
+    );
+    let flagged = !evidence.is_empty();
+    if average_mana_value > 0.0 && average_mana_value <= LOW_CURVE {
+        evidence.push(BracketEvidence {
+            signal: BracketSignal::AverageManaValue,
+            cards: Vec::new(),
+            min_bracket: 3,
+            reason: format!("average mana value {average_mana_value:.2} is a lean, fast curve"),
+        });
+    }
+
+    let mut bracket = evidence.iter().map(|e| e.min_bracket).max().unwrap_or(2);
+    if !flagged && average_mana_value >= HIGH_CURVE {
+        bracket = 1;
+    }
+    let is_cedh = bracket == 4
+        && combo_count > 0
+        && tutor_count >= 6
+        && fast_mana_count >= 3
+        && average_mana_value <= LOW_CURVE;
+    if is_cedh {
+        bracket = 5;
+    }
+
+    BracketEstimate {
+        bracket,
+        average_mana_value,
+        evidence,
+    }
+}
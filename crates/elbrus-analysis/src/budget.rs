@@ -0,0 +1,250 @@
+//! Budget substitutions: cheaper stand-ins for a deck's expensive cards.
+//!
+//! Replacements come from the oracle-text [`OracleIndex`], restricted to
+//! cards of a shared card type, within a mana value of the original, inside
+//! the deck's color identity (the commander's, or else the original card's)
+//! and, optionally, legal in a format.
+
+use crate::archetype::is_basic_land;
+use crate::mana::mana_value;
+use crate::similarity::{OracleIndex, SimilarFilter};
+use elbrus_core::{ColorSet, Format, OracleCard};
+use elbrus_deck::Deck;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetOptions {
+    /// Replace every card costing more than this per copy.
+    pub max_card_price: Option<Decimal>,
+    /// Replace the most expensive cards until the deck costs at most this.
+    pub max_total: Option<Decimal>,
+    /// Only suggest cards legal in this format.
+    pub format: Option<Format>,
+    /// Suggestions listed per replaced card.
+    pub suggestions: usize,
+    /// Similar cards examined per replaced card before filtering.
+    pub candidates: usize,
+}
+
+impl Default for BudgetOptions {
+    fn default() -> Self {
+        Self {
+            max_card_price: None,
+            max_total: None,
+            format: None,
+            suggestions: 3,
+            candidates: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Substitution {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+    /// Per copy.
+    pub price: Decimal,
+    /// Similarity to the replaced card, from [`OracleIndex::similar_cards`].
+    pub score: f32,
+}
+
+/// An expensive card and its cheaper stand-ins, best first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetSwap {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+    pub quantity: u32,
+    /// Per copy.
+    pub price: Decimal,
+    pub suggestions: Vec<Substitution>,
+}
+
+impl BudgetSwap {
+    /// Saved by playing the first suggestion instead, across all copies.
+    #[must_use]
+    pub fn savings(&self) -> Decimal {
+        self.suggestions.first().map_or(Decimal::ZERO, |s| {
+            (self.price - s.price) * Decimal::from(self.quantity)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetReport {
+    pub total: Decimal,
+    /// Deck total after taking the first suggestion of every swap.
+    pub total_after: Decimal,
+    /// Most expensive first.
+    pub swaps: Vec<BudgetSwap>,
+    /// Cards over budget with no cheaper stand-in.
+    pub irreplaceable: Vec<Arc<str>>,
+    /// Resolved cards with no price, counted as free.
+    pub unpriced: Vec<Arc<str>>,
+}
+
+impl BudgetReport {
+    /// Whether `total_after` meets the total ceiling, if there is one.
+    #[must_use]
+    pub fn within(&self, options: &BudgetOptions) -> bool {
+        options.max_total.is_none_or(|max| self.total_after <= max)
+    }
+}
+
+/// Suggest cheaper replacements for the cards of `deck` that break the
+/// ceilings in `options`. `pool` holds every card `index` was built from and
+/// `prices` the cheapest price of each, by oracle id. Commanders and
+/// companions count towards the total but are never replaced.
+#[must_use]
+pub fn budget_substitutions(
+    deck: &Deck,
+    pool: &HashMap<Uuid, OracleCard>,
+    index: &OracleIndex,
+    prices: &HashMap<Uuid, Decimal>,
+    options: &BudgetOptions,
+) -> BudgetReport {
+    let commander_colors = deck
+        .commander
+        .iter()
+        .filter_map(|e| e.resolved.and_then(|id| pool.get(&id)))
+        .fold(None, |acc: Option<ColorSet>, c| {
+            Some(acc.unwrap_or_else(ColorSet::empty) | c.color_identity)
+        });
+    let in_deck: HashSet<Uuid> = deck.entries().filter_map(|e| e.resolved).collect();
+
+    // Copies and per-copy price of each card, merging repeated entries.
+    let mut total = Decimal::ZERO;
+    let mut unpriced = Vec::new();
+    let mut replaceable: Vec<(Uuid, u32, Decimal)> = Vec::new();
+    let fixed: HashSet<Uuid> = deck
+        .commander
+        .iter()
+        .chain(&deck.companion)
+        .filter_map(|e| e.resolved)
+        .collect();
+    for entry in deck.entries() {
+        let Some(id) = entry.resolved else { continue };
+        let Some(&price) = prices.get(&id) else {
+            if !is_basic_land(&entry.card_name) && !unpriced.contains(&entry.card_name) {
+                unpriced.push(entry.card_name.clone());
+            }
+            continue;
+        };
+        total += price * Decimal::from(entry.quantity);
+        if fixed.contains(&id) || is_basic_land(&entry.card_name) {
+            continue;
+        }
+        match replaceable.iter_mut().find(|(other, _, _)| *other == id) {
+            Some((_, quantity, _)) => *quantity += entry.quantity,
+            None => replaceable.push((id, entry.quantity, price)),
+        }
+    }
+    replaceable.sort_by(|a, b| {
+        (b.2 * Decimal::from(b.1))
+            .cmp(&(a.2 * Decimal::from(a.1)))
+            .then(b.2.cmp(&a.2))
+    });
+
+    let catalog = Catalog {
+        pool,
+        index,
+        prices,
+    };
+    let mut chosen: HashSet<Uuid> = HashSet::new();
+    let mut swaps = Vec::new();
+    let mut irreplaceable = Vec::new();
+    let mut total_after = total;
+    for (id, quantity, price) in replaceable {
+        let over_card = options.max_card_price.is_some_and(|max| price > max);
+        let over_total = options.max_total.is_some_and(|max| total_after > max);
+        if !over_card && !over_total {
+            continue;
+        }
+        let Some(card) = pool.get(&id) else { continue };
+
+        let suggestions = substitutes(
+            card,
+            price,
+            commander_colors.unwrap_or(card.color_identity),
+            &catalog,
+            &in_deck,
+            &chosen,
+            options,
+        );
+        let name: Arc<str> = card.name().into();
+        let Some(best) = suggestions.first() else {
+            irreplaceable.push(name);
+            continue;
+        };
+        chosen.insert(best.oracle_id);
+        let swap = BudgetSwap {
+            oracle_id: id,
+            name,
+            quantity,
+            price,
+            suggestions,
+        };
+        total_after -= swap.savings();
+        swaps.push(swap);
+    }
+
+    BudgetReport {
+        total,
+        total_after,
+        swaps,
+        irreplaceable,
+        unpriced,
+    }
+}
+
+struct Catalog<'a> {
+    pool: &'a HashMap<Uuid, OracleCard>,
+    index: &'a OracleIndex,
+    prices: &'a HashMap<Uuid, Decimal>,
+}
+
+fn substitutes(
+    card: &OracleCard,
+    price: Decimal,
+    colors: ColorSet,
+    catalog: &Catalog<'_>,
+    in_deck: &HashSet<Uuid>,
+    chosen: &HashSet<Uuid>,
+    options: &BudgetOptions,
+) -> Vec<Substitution> {
+    let mv = mana_value(card);
+    let filter = SimilarFilter {
+        within_colors: Some(colors),
+        max_mana_value: Some((mv + 1.0).floor() as u32),
+        same_card_type: true,
+    };
+    let within_ceiling = |candidate: Decimal| {
+        candidate < price && options.max_card_price.is_none_or(|max| candidate <= max)
+    };
+
+    catalog
+        .index
+        .similar_cards(card.oracle_id, options.candidates, &filter)
+        .into_iter()
+        .filter(|s| !in_deck.contains(&s.oracle_id) && !chosen.contains(&s.oracle_id))
+        .filter_map(|s| {
+            let candidate = catalog.pool.get(&s.oracle_id)?;
+            let legal = options
+                .format
+                .as_ref()
+                .is_none_or(|f| candidate.legalities.is_legal_in(f));
+            let price = *catalog.prices.get(&s.oracle_id)?;
+            let close_cost = (mana_value(candidate) - mv).abs() <= 1.0;
+            (legal && close_cost && within_ceiling(price)).then_some(Substitution {
+                oracle_id: s.oracle_id,
+                name: s.name,
+                price,
+                score: s.score,
+            })
+        })
+        .take(options.suggestions)
+        .collect()
+}
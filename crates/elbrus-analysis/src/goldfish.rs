@@ -0,0 +1,369 @@
+//! Goldfish simulator: play a deck against an empty board.
+//!
+//! Each game keeps its opening seven, draws a card a turn (skipping the first
+//! draw on the play), plays one land a turn and then greedily casts the most
+//! expensive spells its untapped lands can pay for. There is no stack, no
+//! combat and no card text beyond what lands produce: spells simply leave the
+//! hand when cast.
+
+use crate::hypergeometric::HAND_SIZE;
+use crate::mana::{basic_colors, castable_faces, is_land, land_colors, resolved_cards};
+use crate::mulligan::{DrawnCard, land_count, library};
+use elbrus_core::{ColorSet, ManaCost, ManaSymbol, OracleCard};
+use elbrus_deck::Deck;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// Lands in play compared against a count on a given turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LandCheck {
+    pub turn: u32,
+    pub lands: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldfishConfig {
+    /// Turns played per game.
+    pub turns: u32,
+    pub on_play: bool,
+    /// Screwed: fewer than `lands` lands in play on `turn`.
+    pub screw: LandCheck,
+    /// Flooded: at least `lands` lands drawn, in play or in hand, by `turn`.
+    pub flood: LandCheck,
+    /// Oracle ids to report the first castable turn for.
+    pub track: Vec<Uuid>,
+}
+
+impl Default for GoldfishConfig {
+    fn default() -> Self {
+        Self {
+            turns: 7,
+            on_play: true,
+            screw: LandCheck { turn: 4, lands: 3 },
+            flood: LandCheck { turn: 6, lands: 7 },
+            track: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnStats {
+    pub turn: u32,
+    /// Games by mana spent on this turn.
+    pub mana_spent: BTreeMap<u32, u32>,
+    pub avg_mana_spent: f64,
+    /// Average lands in play at the end of the turn.
+    pub avg_lands: f64,
+}
+
+/// When a tracked card was first in hand with the lands to cast it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirstCastable {
+    pub oracle_id: Uuid,
+    /// Games by turn the card first became castable.
+    pub by_turn: BTreeMap<u32, u32>,
+    /// Games where it never did.
+    pub never: u32,
+}
+
+impl FirstCastable {
+    /// Share of games where the card was castable by `turn`.
+    #[must_use]
+    pub fn castable_by(&self, turn: u32) -> f64 {
+        let games = self.by_turn.values().sum::<u32>() + self.never;
+        let hits: u32 = self.by_turn.range(..=turn).map(|(_, n)| n).sum();
+        if games == 0 {
+            0.0
+        } else {
+            f64::from(hits) / f64::from(games)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldfishReport {
+    pub games: u32,
+    pub on_play: bool,
+    pub turns: Vec<TurnStats>,
+    /// In the order of [`GoldfishConfig::track`].
+    pub first_castable: Vec<FirstCastable>,
+    pub screw_rate: f64,
+    pub flood_rate: f64,
+}
+
+pub struct GoldfishSimulator<'a> {
+    library: Vec<DrawnCard<'a>>,
+    /// Colors produced by each land in the deck, by oracle id.
+    produces: HashMap<Uuid, ColorSet>,
+}
+
+impl<'a> GoldfishSimulator<'a> {
+    /// Simulate the mainboard of `deck`. `cards` is keyed by oracle id.
+    #[must_use]
+    pub fn new(deck: &Deck, cards: &'a HashMap<Uuid, OracleCard>) -> Self {
+        let resolved = resolved_cards(deck, cards);
+        let basics = basic_colors(&resolved);
+        let produces = resolved
+            .iter()
+            .filter(|(c, _)| is_land(c))
+            .map(|(c, _)| (c.oracle_id, land_colors(c, basics)))
+            .collect();
+        Self {
+            library: library(deck, cards),
+            produces,
+        }
+    }
+
+    /// Play `games` seeded games.
+    #[must_use]
+    pub fn run(&self, config: &GoldfishConfig, games: u32, seed: u64) -> GoldfishReport {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut library = self.library.clone();
+        let turns = config.turns as usize;
+        let mut spent: Vec<BTreeMap<u32, u32>> = vec![BTreeMap::new(); turns];
+        let mut lands_in_play = vec![0u64; turns];
+        let mut first_castable: Vec<FirstCastable> = config
+            .track
+            .iter()
+            .map(|&oracle_id| FirstCastable {
+                oracle_id,
+                by_turn: BTreeMap::new(),
+                never: 0,
+            })
+            .collect();
+        let mut screwed = 0u32;
+        let mut flooded = 0u32;
+
+        for _ in 0..games {
+            library.shuffle(&mut rng);
+            let game = self.play(&library, config);
+            for (turn, &mana) in game.spent.iter().enumerate() {
+                *spent[turn].entry(mana).or_default() += 1;
+                lands_in_play[turn] += u64::from(game.lands[turn]);
+            }
+            for (tracked, first) in first_castable.iter_mut().zip(&game.first_castable) {
+                match first {
+                    Some(turn) => *tracked.by_turn.entry(*turn).or_default() += 1,
+                    None => tracked.never += 1,
+                }
+            }
+            screwed += u32::from(game.screwed);
+            flooded += u32::from(game.flooded);
+        }
+
+        let per_game = |n: f64| {
+            if games == 0 {
+                0.0
+            } else {
+                n / f64::from(games)
+            }
+        };
+        let turns = spent
+            .into_iter()
+            .zip(lands_in_play)
+            .enumerate()
+            .map(|(i, (mana_spent, lands))| TurnStats {
+                turn: i as u32 + 1,
+                avg_mana_spent: per_game(
+                    mana_spent
+                        .iter()
+                        .map(|(mana, n)| f64::from(*mana) * f64::from(*n))
+                        .sum(),
+                ),
+                mana_spent,
+                avg_lands: per_game(lands as f64),
+            })
+            .collect();
+        GoldfishReport {
+            games,
+            on_play: config.on_play,
+            turns,
+            first_castable,
+            screw_rate: per_game(screwed.into()),
+            flood_rate: per_game(flooded.into()),
+        }
+    }
+
+    /// One game from an already shuffled library.
+    fn play(&self, library: &[DrawnCard<'a>], config: &GoldfishConfig) -> Game {
+        let opening = (HAND_SIZE as usize).min(library.len());
+        let mut hand: Vec<DrawnCard<'a>> = library[..opening].to_vec();
+        let mut deck = library[opening..].iter();
+        let mut battlefield: Vec<ColorSet> = Vec::new();
+        let mut lands_drawn = land_count(&hand);
+        let mut game = Game {
+            spent: Vec::new(),
+            lands: Vec::new(),
+            first_castable: vec![None; config.track.len()],
+            screwed: false,
+            flooded: false,
+        };
+
+        for turn in 1..=config.turns {
+            if !(config.on_play && turn == 1)
+                && let Some(card) = deck.next()
+            {
+                lands_drawn += land_count(std::slice::from_ref(card));
+                hand.push(*card);
+            }
+
+            if let Some(i) = self.choose_land(&hand, &battlefield) {
+                let land = hand.remove(i).expect("chosen lands are resolved");
+                battlefield.push(self.produces[&land.oracle_id]);
+            }
+
+            for (first, oracle_id) in game.first_castable.iter_mut().zip(&config.track) {
+                if first.is_none()
+                    && hand
+                        .iter()
+                        .flatten()
+                        .any(|c| c.oracle_id == *oracle_id && castable(c, &battlefield))
+                {
+                    *first = Some(turn);
+                }
+            }
+
+            let mut untapped = battlefield.clone();
+            let mut mana = 0;
+            while let Some((i, tapped)) = best_spell(&hand, &untapped) {
+                hand.remove(i);
+                mana += tapped.len() as u32;
+                for land in tapped.into_iter().rev() {
+                    untapped.remove(land);
+                }
+            }
+
+            game.spent.push(mana);
+            game.lands.push(battlefield.len() as u32);
+            if turn == config.screw.turn {
+                game.screwed = (battlefield.len() as u32) < config.screw.lands;
+            }
+            if turn == config.flood.turn {
+                game.flooded = lands_drawn >= config.flood.lands;
+            }
+        }
+        game
+    }
+
+    /// The land in hand adding the most colors not yet in play, if any.
+    fn choose_land(&self, hand: &[DrawnCard<'_>], battlefield: &[ColorSet]) -> Option<usize> {
+        let in_play = battlefield
+            .iter()
+            .fold(ColorSet::empty(), |acc, c| acc | *c);
+        hand.iter()
+            .enumerate()
+            .filter_map(|(i, c)| {
+                let produced = self.produces.get(&(*c)?.oracle_id)?;
+                Some((i, (*produced - in_play).bits().count_ones()))
+            })
+            .max_by(|(a, new_a), (b, new_b)| new_a.cmp(new_b).then(b.cmp(a)))
+            .map(|(i, _)| i)
+    }
+}
+
+struct Game {
+    spent: Vec<u32>,
+    lands: Vec<u32>,
+    first_castable: Vec<Option<u32>>,
+    screwed: bool,
+    flooded: bool,
+}
+
+/// Whether some face of `card` can be paid for with `lands`.
+fn castable(card: &OracleCard, lands: &[ColorSet]) -> bool {
+    !is_land(card)
+        && castable_faces(card)
+            .iter()
+            .filter_map(|f| f.mana_cost.as_ref())
+            .any(|cost| mana_payment(cost, lands).is_some())
+}
+
+/// The most expensive castable spell in `hand`, with the lands paying for it.
+fn best_spell(hand: &[DrawnCard<'_>], untapped: &[ColorSet]) -> Option<(usize, Vec<usize>)> {
+    hand.iter()
+        .enumerate()
+        .filter_map(|(i, c)| {
+            let card = (*c)?;
+            if is_land(card) {
+                return None;
+            }
+            castable_faces(card)
+                .iter()
+                .filter_map(|f| f.mana_cost.as_ref())
+                .filter_map(|cost| mana_payment(cost, untapped))
+                .max_by_key(Vec::len)
+                .map(|tapped| (i, tapped))
+        })
+        .max_by(|(a, tapped_a), (b, tapped_b)| tapped_a.len().cmp(&tapped_b.len()).then(b.cmp(a)))
+}
+
+/// Indices of `lands` to tap for `cost`, in ascending order, or `None` if it
+/// can't be paid. Each land taps for one mana of any color it produces, and
+/// colorless lands (an empty set) pay `{C}`. X is 0, `{2/W}` falls back to two
+/// generic, and Phyrexian symbols use mana left after everything else,
+/// otherwise life.
+#[must_use]
+pub fn mana_payment(cost: &ManaCost, lands: &[ColorSet]) -> Option<Vec<usize>> {
+    let mut pips: Vec<ColorSet> = Vec::new();
+    let mut two_brid: Vec<ColorSet> = Vec::new();
+    let mut phyrexian: Vec<ColorSet> = Vec::new();
+    let mut generic = 0u32;
+    for symbol in &cost.0 {
+        match symbol {
+            ManaSymbol::Colored(c) => pips.push((*c).into()),
+            ManaSymbol::Hybrid(a, b) => pips.push(ColorSet::from(*a) | ColorSet::from(*b)),
+            ManaSymbol::Colorless => pips.push(ColorSet::empty()),
+            ManaSymbol::TwoBrid(c) => two_brid.push((*c).into()),
+            ManaSymbol::Phyrexian(c) => phyrexian.push((*c).into()),
+            ManaSymbol::HybridPhyrexian(a, b) => {
+                phyrexian.push(ColorSet::from(*a) | ColorSet::from(*b));
+            }
+            ManaSymbol::Generic(n) => generic += n.value(),
+            ManaSymbol::Snow => generic += 1,
+            _ => {}
+        }
+    }
+    pips.sort_by_key(|p| p.bits().count_ones());
+
+    let mut free: Vec<usize> = (0..lands.len()).collect();
+    // Least flexible lands first, so dual lands stay open for later pips.
+    free.sort_by_key(|&i| lands[i].bits().count_ones());
+    let pays = |land: ColorSet, pip: ColorSet| {
+        if pip.is_empty() {
+            land.is_empty()
+        } else {
+            land.intersects(pip)
+        }
+    };
+    let mut tapped = Vec::new();
+    let mut take = |free: &mut Vec<usize>, pip: ColorSet| {
+        let pos = free.iter().position(|&i| pays(lands[i], pip))?;
+        let land = free.remove(pos);
+        tapped.push(land);
+        Some(land)
+    };
+
+    for pip in pips {
+        take(&mut free, pip)?;
+    }
+    for pip in two_brid {
+        if take(&mut free, pip).is_none() {
+            generic += 2;
+        }
+    }
+    let generic = generic as usize;
+    if free.len() < generic {
+        return None;
+    }
+    let rest: Vec<usize> = free.drain(..generic).collect();
+    for pip in phyrexian {
+        let _ = take(&mut free, pip);
+    }
+    tapped.extend(rest);
+    tapped.sort_unstable();
+    Some(tapped)
+}
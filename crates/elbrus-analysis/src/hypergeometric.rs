@@ -0,0 +1,197 @@
+//! Exact hypergeometric odds for drawing cards from a deck.
+//!
+//! Binomial coefficients are computed in log space, so populations well past
+//! 250 cards stay finite where a direct product of factorials would overflow.
+
+use elbrus_core::OracleCard;
+use elbrus_deck::Deck;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Opening hand size.
+pub const HAND_SIZE: u32 = 7;
+
+/// `ln(n choose k)`, or negative infinity when `k > n`.
+#[must_use]
+pub fn ln_choose(n: u32, k: u32) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (1..=k)
+        .map(|i| f64::from(n - k + i).ln() - f64::from(i).ln())
+        .sum()
+}
+
+/// P(exactly `exactly` successes) when drawing `draws` cards without
+/// replacement from `population` cards, `successes` of which are hits.
+///
+/// Drawing more than the whole population draws all of it.
+#[must_use]
+pub fn hypergeometric_pmf(population: u32, successes: u32, draws: u32, exactly: u32) -> f64 {
+    let successes = successes.min(population);
+    let draws = draws.min(population);
+    if exactly > successes || exactly > draws || draws - exactly > population - successes {
+        return 0.0;
+    }
+    let ln_p = ln_choose(successes, exactly) + ln_choose(population - successes, draws - exactly)
+        - ln_choose(population, draws);
+    ln_p.exp().clamp(0.0, 1.0)
+}
+
+/// P(at least `wanted` successes) when drawing `draws` from `population`.
+#[must_use]
+pub fn hypergeometric_cdf(population: u32, successes: u32, draws: u32, wanted: u32) -> f64 {
+    let max = successes.min(draws).min(population);
+    (wanted..=max)
+        .map(|k| hypergeometric_pmf(population, successes, draws, k))
+        .sum::<f64>()
+        .clamp(0.0, 1.0)
+}
+
+/// P(at most `max` successes) when drawing `draws` from `population`.
+#[must_use]
+pub fn hypergeometric_at_most(population: u32, successes: u32, draws: u32, max: u32) -> f64 {
+    (0..=max.min(successes).min(draws))
+        .map(|k| hypergeometric_pmf(population, successes, draws, k))
+        .sum::<f64>()
+        .clamp(0.0, 1.0)
+}
+
+/// One category of cards in a multivariate draw: `successes` copies in the
+/// population, of which at least `at_least` must be drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Requirement {
+    pub successes: u32,
+    pub at_least: u32,
+}
+
+/// P(every requirement is met at once) when drawing `draws` from `population`.
+///
+/// Categories must be disjoint; cards outside all of them make up the rest of
+/// the population. Returns 0 if the categories add up to more than the
+/// population.
+#[must_use]
+pub fn multivariate_hypergeometric(
+    population: u32,
+    requirements: &[Requirement],
+    draws: u32,
+) -> f64 {
+    let Some(others) = requirements
+        .iter()
+        .try_fold(population, |left, r| left.checked_sub(r.successes))
+    else {
+        return 0.0;
+    };
+    let draws = draws.min(population);
+    let ln_total = ln_choose(population, draws);
+
+    sum_outcomes(requirements, others, draws, -ln_total).clamp(0.0, 1.0)
+}
+
+/// Sum the probabilities of every joint outcome that meets all requirements.
+/// `ln_acc` carries the log-probability contributed by categories fixed so far.
+fn sum_outcomes(requirements: &[Requirement], others: u32, draws_left: u32, ln_acc: f64) -> f64 {
+    let Some((first, rest)) = requirements.split_first() else {
+        return (ln_acc + ln_choose(others, draws_left)).exp();
+    };
+    (first.at_least..=first.successes.min(draws_left))
+        .map(|k| {
+            sum_outcomes(
+                rest,
+                others,
+                draws_left - k,
+                ln_acc + ln_choose(first.successes, k),
+            )
+        })
+        .sum()
+}
+
+/// Cards seen by `turn`: the opening hand plus one draw per turn, skipping the
+/// first draw on the play.
+#[must_use]
+pub fn cards_seen(turn: u32, on_play: bool) -> u32 {
+    let draws = if on_play {
+        turn.saturating_sub(1)
+    } else {
+        turn
+    };
+    HAND_SIZE + draws
+}
+
+/// Number of mainboard cards.
+#[must_use]
+pub fn deck_size(deck: &Deck) -> u32 {
+    deck.mainboard.iter().map(|e| e.quantity).sum()
+}
+
+/// Mainboard copies of cards matching `predicate`. `cards` is keyed by
+/// oracle id; unresolved entries never match but still count toward the deck.
+pub fn count_matching<F>(deck: &Deck, cards: &HashMap<Uuid, OracleCard>, predicate: F) -> u32
+where
+    F: Fn(&OracleCard) -> bool,
+{
+    deck.mainboard
+        .iter()
+        .filter(|e| {
+            e.resolved
+                .and_then(|id| cards.get(&id))
+                .is_some_and(&predicate)
+        })
+        .map(|e| e.quantity)
+        .sum()
+}
+
+/// P(at least `at_least` cards matching `predicate` seen by `turn`).
+pub fn draw_odds<F>(
+    deck: &Deck,
+    cards: &HashMap<Uuid, OracleCard>,
+    predicate: F,
+    at_least: u32,
+    turn: u32,
+    on_play: bool,
+) -> f64
+where
+    F: Fn(&OracleCard) -> bool,
+{
+    hypergeometric_cdf(
+        deck_size(deck),
+        count_matching(deck, cards, predicate),
+        cards_seen(turn, on_play),
+        at_least,
+    )
+}
+
+/// A card condition and how many matching cards are needed.
+pub type CardRequirement<'a> = (&'a dyn Fn(&OracleCard) -> bool, u32);
+
+/// P(every requirement is met by `turn`), e.g. "at least 1 one-drop AND
+/// 2 lands". A card matching several predicates counts toward the first.
+pub fn draw_odds_all(
+    deck: &Deck,
+    cards: &HashMap<Uuid, OracleCard>,
+    requirements: &[CardRequirement<'_>],
+    turn: u32,
+    on_play: bool,
+) -> f64 {
+    let mut counts = vec![0u32; requirements.len()];
+    for entry in &deck.mainboard {
+        let Some(card) = entry.resolved.and_then(|id| cards.get(&id)) else {
+            continue;
+        };
+        if let Some(i) = requirements.iter().position(|(matches, _)| matches(card)) {
+            counts[i] += entry.quantity;
+        }
+    }
+
+    let requirements: Vec<Requirement> = requirements
+        .iter()
+        .zip(counts)
+        .map(|((_, at_least), successes)| Requirement {
+            successes,
+            at_least: *at_least,
+        })
+        .collect();
+    multivariate_hypergeometric(deck_size(deck), &requirements, cards_seen(turn, on_play))
+}
@@ -0,0 +1,199 @@
+//! Frank Karsten's mana base guidelines: how many sources of a color a card
+//! needs to be cast on curve, and how many lands a deck should play.
+//!
+//! Source counts come straight from the hypergeometric distribution over
+//! cards seen by the turn a card's mana value says it should be cast, without
+//! conditioning on having hit every land drop.
+
+use crate::hypergeometric::{cards_seen, deck_size, hypergeometric_cdf};
+use crate::mana::{castable_faces, is_land, land_productions, resolved_cards};
+use elbrus_core::{ColorSet, ManaCost, ManaSymbol, OracleCard};
+use elbrus_deck::Deck;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Chance of having the colored sources on curve that Karsten's tables aim for.
+pub const KARSTEN_PROBABILITY: f64 = 0.9;
+
+/// Smallest number of sources in a `deck_size` deck giving at least
+/// `probability` of seeing `pips` of them by `turn`. `None` if no number of
+/// sources is enough, e.g. more pips than cards seen.
+#[must_use]
+pub fn sources_needed(
+    deck_size: u32,
+    pips: u32,
+    turn: u32,
+    on_play: bool,
+    probability: f64,
+) -> Option<u32> {
+    let seen = cards_seen(turn, on_play);
+    (0..=deck_size)
+        .find(|&sources| hypergeometric_cdf(deck_size, sources, seen, pips) >= probability)
+}
+
+/// Pips a mana cost needs from sources of `colors`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRequirement {
+    /// Colors that can pay these pips: one color, or both halves of a hybrid.
+    pub colors: ColorSet,
+    pub pips: u32,
+    /// Sources required for [`KARSTEN_PROBABILITY`], if reachable at all.
+    pub needed: Option<u32>,
+    /// Lands in the deck producing any of `colors`.
+    pub available: u32,
+}
+
+impl SourceRequirement {
+    #[must_use]
+    pub fn is_met(&self) -> bool {
+        self.needed.is_some_and(|needed| self.available >= needed)
+    }
+}
+
+/// Whether one castable face of a card can be cast on curve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CastCheck {
+    pub oracle_id: Uuid,
+    pub face: Arc<str>,
+    pub quantity: u32,
+    /// The turn the face should be cast: its mana value, at least 1.
+    pub turn: u32,
+    pub requirements: Vec<SourceRequirement>,
+}
+
+impl CastCheck {
+    #[must_use]
+    pub fn is_supported(&self) -> bool {
+        self.requirements.iter().all(SourceRequirement::is_met)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceReport {
+    pub deck_size: u32,
+    pub on_play: bool,
+    /// One check per castable face of each nonland card, in deck order.
+    pub checks: Vec<CastCheck>,
+}
+
+impl SourceReport {
+    /// Faces the mana base cannot reliably cast on curve.
+    pub fn under_supported(&self) -> impl Iterator<Item = &CastCheck> {
+        self.checks.iter().filter(|c| !c.is_supported())
+    }
+}
+
+/// Colored pips of `cost`, grouped by the colors able to pay them.
+///
+/// Phyrexian symbols can be paid with life and are left out. A group counts
+/// every pip its colors can pay, so `{G}{G/W}` needs one green source and two
+/// green-or-white sources. `{2/W}` needs the color to be cast on curve.
+#[must_use]
+pub fn pip_groups(cost: &ManaCost) -> Vec<(ColorSet, u32)> {
+    let pips: Vec<ColorSet> = cost
+        .0
+        .iter()
+        .filter_map(|symbol| match symbol {
+            ManaSymbol::Colored(c) | ManaSymbol::TwoBrid(c) => Some(ColorSet::from(*c)),
+            ManaSymbol::Hybrid(a, b) => Some(ColorSet::from(*a) | ColorSet::from(*b)),
+            _ => None,
+        })
+        .collect();
+
+    let mut groups: Vec<ColorSet> = pips.clone();
+    groups.sort_by_key(|g| (g.bits().count_ones(), g.bits()));
+    groups.dedup();
+    groups
+        .into_iter()
+        .map(|group| {
+            let count = pips.iter().filter(|p| group.contains(**p)).count();
+            (group, u32::try_from(count).unwrap_or(u32::MAX))
+        })
+        .collect()
+}
+
+/// Check every nonland card in the mainboard of `deck` against the deck's
+/// colored sources. `cards` is keyed by oracle id.
+#[must_use]
+pub fn color_source_report(
+    deck: &Deck,
+    cards: &HashMap<Uuid, OracleCard>,
+    on_play: bool,
+) -> SourceReport {
+    let resolved = resolved_cards(deck, cards);
+    let lands = land_productions(&resolved);
+    let deck_size = deck_size(deck);
+
+    let mut checks = Vec::new();
+    for &(card, quantity) in &resolved {
+        if is_land(card) {
+            continue;
+        }
+        for face in castable_faces(card) {
+            let Some(cost) = &face.mana_cost else {
+                continue;
+            };
+            let turn = (cost.cmc().ceil() as u32).max(1);
+            let requirements = pip_groups(cost)
+                .into_iter()
+                .map(|(colors, pips)| SourceRequirement {
+                    colors,
+                    pips,
+                    needed: sources_needed(deck_size, pips, turn, on_play, KARSTEN_PROBABILITY),
+                    available: lands
+                        .iter()
+                        .filter(|(produced, _)| produced.intersects(colors))
+                        .map(|(_, n)| n)
+                        .sum(),
+                })
+                .collect();
+            checks.push(CastCheck {
+                oracle_id: card.oracle_id,
+                face: face.name.clone(),
+                quantity,
+                turn,
+                requirements,
+            });
+        }
+    }
+
+    SourceReport {
+        deck_size,
+        on_play,
+        checks,
+    }
+}
+
+/// Karsten's land count regression: the lands a deck of `deck_size` cards
+/// with nonland average mana value `avg_cmc` should play. `cantrips` is the
+/// number of cheap card draw, card selection and ramp spells, each of which
+/// replaces about a quarter of a land.
+///
+/// Decks of 99 cards or more use the Commander fit; smaller decks scale the
+/// 60-card fit by size, which lands on 17 for a typical 40-card Limited deck.
+#[must_use]
+pub fn recommended_land_count(deck_size: u32, avg_cmc: f64, cantrips: u32) -> u32 {
+    let size = f64::from(deck_size);
+    let base = if deck_size >= 99 {
+        31.42 + 3.13 * avg_cmc
+    } else {
+        (19.59 + 1.90 * avg_cmc) * size / 60.0
+    };
+    let lands = (base - 0.28 * f64::from(cantrips)).round().clamp(0.0, size);
+    lands as u32
+}
+
+/// Fewest lands giving at least `probability` of having `lands` of them by
+/// `turn`. `None` if even an all-land deck falls short.
+#[must_use]
+pub fn lands_for_target(
+    deck_size: u32,
+    lands: u32,
+    turn: u32,
+    on_play: bool,
+    probability: f64,
+) -> Option<u32> {
+    sources_needed(deck_size, lands, turn, on_play, probability)
+}
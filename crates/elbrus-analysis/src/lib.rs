@@ -1 +1,22 @@
 // Game analysis and math
+pub mod archetype;
+pub mod bracket;
+pub mod budget;
+pub mod goldfish;
+pub mod hypergeometric;
+pub mod karsten;
+pub mod mana;
+pub mod metagame;
+pub mod mulligan;
+pub mod similarity;
+
+pub use archetype::*;
+pub use bracket::*;
+pub use budget::*;
+pub use goldfish::*;
+pub use hypergeometric::*;
+pub use karsten::*;
+pub use mana::*;
+pub use metagame::*;
+pub use mulligan::*;
+pub use similarity::*;
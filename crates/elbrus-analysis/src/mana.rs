@@ -0,0 +1,303 @@
+//! Mana curve and mana base analysis for a resolved deck.
+//!
+//! Cards are looked up by the oracle id each `DeckEntry` resolved to, the same
+//! as the draw odds in [`crate::hypergeometric`]. Unresolved entries still
+//! count toward the deck size but contribute nothing else.
+
+use crate::hypergeometric::{cards_seen, deck_size, hypergeometric_cdf};
+use crate::karsten::recommended_land_count;
+use elbrus_core::{
+    CardFace, CardLayout, OracleCard,
+    color::{Color, ColorSet},
+    mana::ManaSymbol,
+    oracle::{OracleText, OracleTextSegment},
+    types::Supertype,
+};
+use elbrus_deck::Deck;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// Last turn `on_curve_probability` is computed for.
+pub const ON_CURVE_TURNS: u32 = 7;
+
+/// Number of nonland cards at each mana value.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManaCurve(pub BTreeMap<u32, u32>);
+
+impl ManaCurve {
+    /// Cards at exactly `mana_value`.
+    #[must_use]
+    pub fn count(&self, mana_value: u32) -> u32 {
+        self.0.get(&mana_value).copied().unwrap_or(0)
+    }
+
+    /// Cards on the curve.
+    #[must_use]
+    pub fn total(&self) -> u32 {
+        self.0.values().sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManaBaseAnalysis {
+    /// Mainboard size, including unresolved entries.
+    pub deck_size: u32,
+    pub curve: ManaCurve,
+    /// Average mana value of nonland cards.
+    pub avg_cmc: f64,
+    /// Lands, including modal double-faced cards with a land face.
+    pub land_count: u32,
+    /// How many of `land_count` are modal double-faced cards.
+    pub mdfc_lands: u32,
+    /// [`recommended_land_count`] for this deck, counting no cantrips.
+    pub recommended_lands: u32,
+    /// Pips per color across nonland cards. Hybrid symbols count half toward
+    /// each of their colors.
+    pub color_requirements: HashMap<Color, f64>,
+    /// Lands able to produce each color.
+    pub color_sources: HashMap<Color, u32>,
+    /// P(at least N lands by turn N) on the play, for turns 1 through
+    /// [`ON_CURVE_TURNS`].
+    pub on_curve_probability: Vec<f64>,
+}
+
+impl ManaBaseAnalysis {
+    /// Analyse the mainboard of `deck`. `cards` is keyed by oracle id.
+    #[must_use]
+    pub fn analyze(deck: &Deck, cards: &HashMap<Uuid, OracleCard>) -> Self {
+        let resolved = resolved_cards(deck, cards);
+        let basics = basic_colors(&resolved);
+
+        let mut curve = ManaCurve::default();
+        let mut total_mana_value = 0.0;
+        let mut land_count = 0;
+        let mut mdfc_lands = 0;
+        let mut color_requirements: HashMap<Color, f64> = HashMap::new();
+        let mut color_sources: HashMap<Color, u32> = HashMap::new();
+
+        for &(card, quantity) in &resolved {
+            if is_land(card) {
+                land_count += quantity;
+                if card.layout == CardLayout::ModalDfc {
+                    mdfc_lands += quantity;
+                }
+                let produced = land_colors(card, basics);
+                for color in Color::ALL {
+                    if produced.contains(color.into()) {
+                        *color_sources.entry(color).or_default() += quantity;
+                    }
+                }
+                continue;
+            }
+
+            let mana_value = mana_value(card);
+            *curve.0.entry(mana_value as u32).or_default() += quantity;
+            total_mana_value += f64::from(mana_value) * f64::from(quantity);
+            for (color, weight) in pips(card) {
+                *color_requirements.entry(color).or_default() += weight * f64::from(quantity);
+            }
+        }
+
+        let deck_size = deck_size(deck);
+        let nonland = curve.total();
+        let avg_cmc = if nonland == 0 {
+            0.0
+        } else {
+            total_mana_value / f64::from(nonland)
+        };
+        let on_curve_probability = (1..=ON_CURVE_TURNS)
+            .map(|turn| hypergeometric_cdf(deck_size, land_count, cards_seen(turn, true), turn))
+            .collect();
+
+        Self {
+            deck_size,
+            curve,
+            avg_cmc,
+            land_count,
+            mdfc_lands,
+            recommended_lands: recommended_land_count(deck_size, avg_cmc, 0),
+            color_requirements,
+            color_sources,
+            on_curve_probability,
+        }
+    }
+}
+
+/// Mainboard cards that resolved, with their quantities.
+pub(crate) fn resolved_cards<'a>(
+    deck: &Deck,
+    cards: &'a HashMap<Uuid, OracleCard>,
+) -> Vec<(&'a OracleCard, u32)> {
+    deck.mainboard
+        .iter()
+        .filter_map(|e| {
+            e.resolved
+                .and_then(|id| cards.get(&id))
+                .map(|c| (c, e.quantity))
+        })
+        .collect()
+}
+
+/// Colors of the basic lands among `resolved`.
+pub(crate) fn basic_colors(resolved: &[(&OracleCard, u32)]) -> ColorSet {
+    resolved
+        .iter()
+        .flat_map(|(c, _)| c.faces.iter())
+        .filter(|f| f.type_line.supertypes.contains(&Supertype::Basic))
+        .fold(ColorSet::empty(), |acc, f| acc | basic_type_colors(f))
+}
+
+/// Lands among `resolved` with the colors each produces.
+pub(crate) fn land_productions(resolved: &[(&OracleCard, u32)]) -> Vec<(ColorSet, u32)> {
+    let basics = basic_colors(resolved);
+    resolved
+        .iter()
+        .filter(|(c, _)| is_land(c))
+        .map(|&(c, quantity)| (land_colors(c, basics), quantity))
+        .collect()
+}
+
+/// A land for deck-building purposes: its front face is a land, or it is a
+/// modal double-faced card that can be played as one.
+pub(crate) fn is_land(card: &OracleCard) -> bool {
+    card.primary_face().type_line.is_land()
+        || (card.layout == CardLayout::ModalDfc && card.faces.iter().any(|f| f.type_line.is_land()))
+}
+
+/// Faces that can be cast from hand. Split and adventure cards offer either
+/// half; other layouts are cast by their front face.
+pub(crate) fn castable_faces(card: &OracleCard) -> &[CardFace] {
+    match card.layout {
+        CardLayout::Split | CardLayout::Adventure => &card.faces,
+        _ => std::slice::from_ref(card.primary_face()),
+    }
+}
+
+/// Mana value for curve purposes: the cheapest castable face, so a split card
+/// sits where it can first be cast.
+pub(crate) fn mana_value(card: &OracleCard) -> f32 {
+    castable_faces(card)
+        .iter()
+        .map(|f| f.mana_cost.as_ref().map_or(0.0, |m| m.cmc()))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Pips per color, taking the heaviest castable face for each color.
+fn pips(card: &OracleCard) -> HashMap<Color, f64> {
+    let mut card_pips: HashMap<Color, f64> = HashMap::new();
+    for face in castable_faces(card) {
+        let mut face_pips: HashMap<Color, f64> = HashMap::new();
+        for symbol in face.mana_cost.iter().flat_map(|m| m.0.iter()) {
+            match symbol {
+                ManaSymbol::Colored(c) | ManaSymbol::TwoBrid(c) | ManaSymbol::Phyrexian(c) => {
+                    *face_pips.entry(*c).or_default() += 1.0;
+                }
+                ManaSymbol::Hybrid(a, b) | ManaSymbol::HybridPhyrexian(a, b) => {
+                    *face_pips.entry(*a).or_default() += 0.5;
+                    *face_pips.entry(*b).or_default() += 0.5;
+                }
+                _ => {}
+            }
+        }
+        for (color, weight) in face_pips {
+            let best = card_pips.entry(color).or_default();
+            *best = best.max(weight);
+        }
+    }
+    card_pips
+}
+
+/// Colors named by a face's basic land types (Plains, Island, ...).
+fn basic_type_colors(face: &CardFace) -> ColorSet {
+    Color::ALL
+        .into_iter()
+        .filter(|c| face.type_line.subtypes.iter().any(|s| *s.0 == c.land()))
+        .fold(ColorSet::empty(), |acc, c| acc | c.into())
+}
+
+/// Colors a land can produce, from its land faces' types and rules text.
+/// `basics` are the colors of the deck's basic lands, which a fetch for
+/// "a basic land card" can find.
+pub(crate) fn land_colors(card: &OracleCard, basics: ColorSet) -> ColorSet {
+    card.faces
+        .iter()
+        .filter(|f| f.type_line.is_land())
+        .fold(ColorSet::empty(), |acc, f| {
+            acc | basic_type_colors(f) | text_colors(&f.oracle_text, basics)
+        })
+}
+
+/// Colors added by "Add ..." clauses or fetched by "Search your library for
+/// ..." clauses. A clause runs to the next period or line break, and may
+/// continue through structured mana symbols between text segments.
+fn text_colors(text: &OracleText, basics: ColorSet) -> ColorSet {
+    let mut colors = ColorSet::empty();
+    let mut adding = false;
+    for segment in &text.0 {
+        match segment {
+            OracleTextSegment::Text(s) => {
+                for clause in s.split_inclusive(['.', '\n']) {
+                    if let Some(added) = after_add(clause) {
+                        adding = true;
+                        colors |= added_colors(added);
+                    } else if adding {
+                        colors |= added_colors(clause);
+                    }
+                    if let Some((_, fetched)) = clause.split_once("Search your library for") {
+                        colors |= fetched_colors(fetched, basics);
+                    }
+                    if clause.ends_with(['.', '\n']) {
+                        adding = false;
+                    }
+                }
+            }
+            OracleTextSegment::ManaCost(cost) if adding => colors |= cost.color_identity(),
+            _ => {}
+        }
+    }
+    colors
+}
+
+/// The rest of `clause` after the word "Add", if present.
+fn after_add(clause: &str) -> Option<&str> {
+    clause.match_indices("Add").find_map(|(i, _)| {
+        let rest = &clause[i + 3..];
+        (!rest.starts_with(char::is_alphabetic)).then_some(rest)
+    })
+}
+
+fn added_colors(clause: &str) -> ColorSet {
+    if clause.contains("any color") || clause.contains("any type") {
+        return ColorSet::all();
+    }
+    let mut colors = ColorSet::empty();
+    for symbol in clause.split('{').skip(1) {
+        let Some((inner, _)) = symbol.split_once('}') else {
+            continue;
+        };
+        for part in inner.split('/') {
+            colors |= match part {
+                "W" => ColorSet::WHITE,
+                "U" => ColorSet::BLUE,
+                "B" => ColorSet::BLACK,
+                "R" => ColorSet::RED,
+                "G" => ColorSet::GREEN,
+                _ => ColorSet::empty(),
+            };
+        }
+    }
+    colors
+}
+
+fn fetched_colors(clause: &str, basics: ColorSet) -> ColorSet {
+    let named = Color::ALL
+        .into_iter()
+        .filter(|c| clause.contains(c.land().as_str()))
+        .fold(ColorSet::empty(), |acc, c| acc | c.into());
+    if named.is_empty() && clause.contains("basic land card") {
+        basics
+    } else {
+        named
+    }
+}
@@ -0,0 +1,178 @@
+//! Metagame snapshots: which cards a format's decks play, which archetypes
+//! they fall into, and how card play moves between two periods.
+
+use crate::archetype::{SimilarityMetric, cluster_archetypes, is_basic_land};
+use chrono::NaiveDate;
+use elbrus_core::{ArchetypeShare, Format, MetagameSnapshot, StapleStat};
+use elbrus_deck::Deck;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A decklist and the day it was played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatedDeck {
+    pub date: NaiveDate,
+    pub deck: Deck,
+}
+
+#[derive(Default)]
+struct Tally {
+    main_decks: u32,
+    side_decks: u32,
+    decks: u32,
+    main_copies: u32,
+    side_copies: u32,
+}
+
+/// Inclusion rate and average copies of every resolved card in `decks`,
+/// most played first. Basic lands are left out.
+#[must_use]
+pub fn staples_index(decks: &[Deck]) -> Vec<StapleStat> {
+    let mut names: HashMap<Uuid, Arc<str>> = HashMap::new();
+    let mut tallies: BTreeMap<Uuid, Tally> = BTreeMap::new();
+    for deck in decks {
+        let mut main: HashMap<Uuid, u32> = HashMap::new();
+        let mut side: HashMap<Uuid, u32> = HashMap::new();
+        let boards = [(&mut main, &deck.mainboard), (&mut side, &deck.sideboard)];
+        for (copies, entries) in boards {
+            for entry in entries {
+                if let Some(id) = entry.resolved
+                    && !is_basic_land(&entry.card_name)
+                {
+                    *copies.entry(id).or_default() += entry.quantity;
+                    names.entry(id).or_insert_with(|| entry.card_name.clone());
+                }
+            }
+        }
+
+        for (&id, &n) in &main {
+            let tally = tallies.entry(id).or_default();
+            tally.main_decks += 1;
+            tally.main_copies += n;
+        }
+        for (&id, &n) in &side {
+            let tally = tallies.entry(id).or_default();
+            tally.side_decks += 1;
+            tally.side_copies += n;
+        }
+        for id in main
+            .keys()
+            .chain(side.keys().filter(|id| !main.contains_key(id)))
+        {
+            tallies.entry(*id).or_default().decks += 1;
+        }
+    }
+
+    let average = |copies: u32, decks: u32| {
+        if decks == 0 {
+            0.0
+        } else {
+            f64::from(copies) / f64::from(decks)
+        }
+    };
+    let mut staples: Vec<StapleStat> = tallies
+        .into_iter()
+        .map(|(oracle_id, t)| StapleStat {
+            oracle_id,
+            name: names[&oracle_id].clone(),
+            decks: t.decks,
+            main_decks: t.main_decks,
+            side_decks: t.side_decks,
+            inclusion_rate: f64::from(t.decks) / decks.len() as f64,
+            avg_main_copies: average(t.main_copies, t.main_decks),
+            avg_side_copies: average(t.side_copies, t.side_decks),
+        })
+        .collect();
+    staples.sort_by(|a, b| {
+        b.decks
+            .cmp(&a.decks)
+            .then(b.main_decks.cmp(&a.main_decks))
+            .then(a.name.cmp(&b.name))
+    });
+    staples
+}
+
+/// Summarise the decks played from `start` to `end` inclusive: a staples
+/// index plus archetype shares from [`cluster_archetypes`].
+#[must_use]
+pub fn metagame_snapshot(
+    format: Format,
+    start: NaiveDate,
+    end: NaiveDate,
+    decks: &[DatedDeck],
+    metric: SimilarityMetric,
+    threshold: f64,
+) -> MetagameSnapshot {
+    let decks: Vec<Deck> = decks
+        .iter()
+        .filter(|d| (start..=end).contains(&d.date))
+        .map(|d| d.deck.clone())
+        .collect();
+    let archetypes = cluster_archetypes(&decks, metric, threshold)
+        .into_iter()
+        .map(|a| ArchetypeShare {
+            name: a.name.into(),
+            decks: a.decks.len() as u32,
+            share: a.share,
+        })
+        .collect();
+
+    MetagameSnapshot {
+        id: Uuid::new_v4(),
+        format,
+        start,
+        end,
+        deck_count: decks.len() as u32,
+        staples: staples_index(&decks),
+        archetypes,
+    }
+}
+
+/// Change in a card's inclusion rate from one snapshot to the next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StapleTrend {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+    /// Inclusion rate in the earlier snapshot, 0 if unplayed.
+    pub before: f64,
+    pub after: f64,
+}
+
+impl StapleTrend {
+    #[must_use]
+    pub fn change(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// Every card played in either snapshot, biggest movers first.
+#[must_use]
+pub fn staple_trends(before: &MetagameSnapshot, after: &MetagameSnapshot) -> Vec<StapleTrend> {
+    let mut trends: BTreeMap<Uuid, StapleTrend> = BTreeMap::new();
+    for (stats, later) in [(&before.staples, false), (&after.staples, true)] {
+        for stat in stats {
+            let trend = trends.entry(stat.oracle_id).or_insert_with(|| StapleTrend {
+                oracle_id: stat.oracle_id,
+                name: stat.name.clone(),
+                before: 0.0,
+                after: 0.0,
+            });
+            if later {
+                trend.after = stat.inclusion_rate;
+            } else {
+                trend.before = stat.inclusion_rate;
+            }
+        }
+    }
+
+    let mut trends: Vec<StapleTrend> = trends.into_values().collect();
+    trends.sort_by(|a, b| {
+        b.change()
+            .abs()
+            .total_cmp(&a.change().abs())
+            .then(a.name.cmp(&b.name))
+    });
+    trends
+}
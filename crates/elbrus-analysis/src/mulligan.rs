@@ -0,0 +1,259 @@
+//! London mulligan Monte Carlo.
+//!
+//! Every attempt shuffles the whole deck and draws seven. Once a hand is kept
+//! after `n` mulligans, the strategy picks `n` cards to put on the bottom.
+//! Runs are seeded, so the same deck, strategy and seed always give the same
+//! report.
+
+use crate::hypergeometric::HAND_SIZE;
+use crate::mana::{is_land, mana_value};
+use elbrus_core::OracleCard;
+use elbrus_deck::Deck;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A drawn card; `None` for deck entries that never resolved.
+pub type DrawnCard<'a> = Option<&'a OracleCard>;
+
+/// One entry per mainboard copy, in deck order.
+pub(crate) fn library<'a>(deck: &Deck, cards: &'a HashMap<Uuid, OracleCard>) -> Vec<DrawnCard<'a>> {
+    deck.mainboard
+        .iter()
+        .flat_map(|e| {
+            let card = e.resolved.and_then(|id| cards.get(&id));
+            std::iter::repeat_n(card, e.quantity as usize)
+        })
+        .collect()
+}
+
+/// Lands in `hand`. Unresolved cards are not lands.
+#[must_use]
+pub fn land_count(hand: &[DrawnCard<'_>]) -> u32 {
+    hand.iter().filter(|c| c.is_some_and(is_land)).count() as u32
+}
+
+/// Decides which opening hands to keep and what to bottom afterwards.
+pub trait KeepStrategy {
+    /// Whether to keep `hand`, the seven cards drawn after `mulligan_count`
+    /// mulligans.
+    fn should_keep(&self, hand: &[DrawnCard<'_>], on_play: bool, mulligan_count: u32) -> bool;
+
+    /// Indices of `count` cards in `hand` to put on the bottom.
+    ///
+    /// The default keeps the mix of lands and spells even: it bottoms a land
+    /// while lands outnumber spells, otherwise the most expensive spell.
+    /// Unresolved cards go first.
+    fn bottom(&self, hand: &[DrawnCard<'_>], count: usize) -> Vec<usize> {
+        let mut remaining: Vec<usize> = (0..hand.len()).collect();
+        let mut bottomed = Vec::with_capacity(count);
+        while bottomed.len() < count && !remaining.is_empty() {
+            let lands = remaining
+                .iter()
+                .filter(|&&i| hand[i].is_some_and(is_land))
+                .count();
+            let spells = remaining.len() - lands;
+            let pick = remaining
+                .iter()
+                .position(|&i| hand[i].is_none())
+                .or_else(|| {
+                    if lands > spells {
+                        remaining.iter().position(|&i| hand[i].is_some_and(is_land))
+                    } else {
+                        remaining
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, i)| hand[**i].is_some_and(|c| !is_land(c)))
+                            .max_by(|(_, a), (_, b)| {
+                                let mv = |i: usize| hand[i].map_or(0.0, mana_value);
+                                mv(**a).total_cmp(&mv(**b))
+                            })
+                            .map(|(pos, _)| pos)
+                    }
+                })
+                .unwrap_or(0);
+            bottomed.push(remaining.remove(pick));
+        }
+        bottomed
+    }
+}
+
+/// Keep hands with between `min` and `max` lands, inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LandWindow {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Default for LandWindow {
+    fn default() -> Self {
+        Self { min: 2, max: 5 }
+    }
+}
+
+impl KeepStrategy for LandWindow {
+    fn should_keep(&self, hand: &[DrawnCard<'_>], _on_play: bool, _mulligan_count: u32) -> bool {
+        (self.min..=self.max).contains(&land_count(hand))
+    }
+}
+
+/// Keep hands inside `lands` that also hold a spell with mana value 2.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HasTwoDrop {
+    pub lands: LandWindow,
+}
+
+impl KeepStrategy for HasTwoDrop {
+    fn should_keep(&self, hand: &[DrawnCard<'_>], on_play: bool, mulligan_count: u32) -> bool {
+        self.lands.should_keep(hand, on_play, mulligan_count)
+            && hand
+                .iter()
+                .flatten()
+                .any(|c| !is_land(c) && mana_value(c) == 2.0)
+    }
+}
+
+/// Keep hands for which the predicate returns true. Bottoms like the default.
+pub struct KeepIf<F>(pub F);
+
+impl<F> KeepStrategy for KeepIf<F>
+where
+    F: Fn(&[DrawnCard<'_>], bool, u32) -> bool,
+{
+    fn should_keep(&self, hand: &[DrawnCard<'_>], on_play: bool, mulligan_count: u32) -> bool {
+        (self.0)(hand, on_play, mulligan_count)
+    }
+}
+
+/// Outcomes at one mulligan depth.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthStats {
+    pub mulligans: u32,
+    /// Games that saw a hand at this depth.
+    pub reached: u32,
+    pub kept: u32,
+    /// `kept / reached`, or 0 if never reached.
+    pub keep_rate: f64,
+    /// Cards in hand after bottoming.
+    pub hand_size: u32,
+    /// Average lands in kept hands after bottoming.
+    pub avg_lands: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MulliganReport {
+    pub iterations: u32,
+    pub on_play: bool,
+    /// Indexed by mulligan count, from 0 up to the simulator's limit.
+    pub depths: Vec<DepthStats>,
+    /// Average size of the hand kept, over all games.
+    pub avg_hand_size: f64,
+}
+
+impl MulliganReport {
+    /// Share of games kept without a mulligan.
+    #[must_use]
+    pub fn keep_seven_rate(&self) -> f64 {
+        self.depths.first().map_or(0.0, |d| d.keep_rate)
+    }
+}
+
+pub struct MulliganSimulator<'a> {
+    library: Vec<DrawnCard<'a>>,
+    /// Mulligans after which any hand is kept, at most [`HAND_SIZE`].
+    max_mulligans: u32,
+}
+
+impl<'a> MulliganSimulator<'a> {
+    /// Simulate the mainboard of `deck`. `cards` is keyed by oracle id.
+    #[must_use]
+    pub fn new(deck: &Deck, cards: &'a HashMap<Uuid, OracleCard>) -> Self {
+        Self {
+            library: library(deck, cards),
+            max_mulligans: 3,
+        }
+    }
+
+    /// Keep any hand after `max_mulligans`, capped at [`HAND_SIZE`].
+    #[must_use]
+    pub fn with_max_mulligans(mut self, max_mulligans: u32) -> Self {
+        self.max_mulligans = max_mulligans.min(HAND_SIZE);
+        self
+    }
+
+    /// Mulligans after which any hand is kept.
+    #[must_use]
+    pub fn max_mulligans(&self) -> u32 {
+        self.max_mulligans
+    }
+
+    /// Play `iterations` games' worth of opening hands.
+    pub fn run<S>(&self, strategy: &S, iterations: u32, on_play: bool, seed: u64) -> MulliganReport
+    where
+        S: KeepStrategy + ?Sized,
+    {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut library = self.library.clone();
+        let draw = (HAND_SIZE as usize).min(library.len());
+        let depths = self.max_mulligans as usize + 1;
+        let mut reached = vec![0u32; depths];
+        let mut kept = vec![0u32; depths];
+        let mut kept_lands = vec![0u32; depths];
+        let mut total_hand_size = 0u64;
+
+        for _ in 0..iterations {
+            for mulligans in 0..=self.max_mulligans {
+                library.shuffle(&mut rng);
+                let hand = &library[..draw];
+                let depth = mulligans as usize;
+                reached[depth] += 1;
+                if mulligans < self.max_mulligans && !strategy.should_keep(hand, on_play, mulligans)
+                {
+                    continue;
+                }
+
+                let mut bottom = strategy.bottom(hand, depth);
+                bottom.sort_unstable();
+                bottom.dedup();
+                let final_hand: Vec<DrawnCard<'_>> = hand
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| bottom.binary_search(i).is_err())
+                    .map(|(_, c)| *c)
+                    .collect();
+                kept[depth] += 1;
+                kept_lands[depth] += land_count(&final_hand);
+                total_hand_size += final_hand.len() as u64;
+                break;
+            }
+        }
+
+        let depths = (0..depths)
+            .map(|d| DepthStats {
+                mulligans: d as u32,
+                reached: reached[d],
+                kept: kept[d],
+                keep_rate: ratio(kept[d].into(), reached[d].into()),
+                hand_size: HAND_SIZE - d as u32,
+                avg_lands: ratio(kept_lands[d].into(), kept[d].into()),
+            })
+            .collect();
+        MulliganReport {
+            iterations,
+            on_play,
+            depths,
+            avg_hand_size: ratio(total_hand_size as f64, iterations.into()),
+        }
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
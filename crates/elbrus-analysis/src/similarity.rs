@@ -0,0 +1,360 @@
+//! "Cards like this": similarity search over oracle text.
+//!
+//! Each card's rules text is normalised (reminder text dropped, its own name
+//! and "this creature"-style references replaced by [`SELF_TOKEN`]) and turned
+//! into a TF-IDF vector of words and word pairs. Cards are compared by the
+//! cosine of those vectors, blended with how much of their type line they
+//! share and how close their mana values are. The index is built offline from
+//! every oracle card in the db and serialises with serde, so the WASM build
+//! can load a prebuilt copy instead of the db.
+
+use crate::mana::mana_value;
+use elbrus_core::{CardType, ColorSet, OracleCard, OracleTextSegment};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Stands in for a card's own name in its rules text.
+pub const SELF_TOKEN: &str = "~";
+
+/// Share of the score from oracle text.
+pub const TEXT_WEIGHT: f32 = 0.7;
+/// Share of the score from overlapping card types and subtypes.
+pub const TYPE_WEIGHT: f32 = 0.2;
+/// Share of the score from mana value distance.
+pub const MANA_VALUE_WEIGHT: f32 = 0.1;
+
+/// Card types that oracle text uses to refer to the card itself, as in
+/// "this creature" in place of its name.
+const SELF_REFERENCES: [&str; 8] = [
+    "this spell",
+    "this creature",
+    "this artifact",
+    "this enchantment",
+    "this land",
+    "this planeswalker",
+    "this battle",
+    "this permanent",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedCard {
+    oracle_id: Uuid,
+    name: Arc<str>,
+    mana_value: f32,
+    color_identity: ColorSet,
+    card_types: Vec<CardType>,
+    /// Ids of type-line words, sorted.
+    types: Vec<u32>,
+    /// Unit-length TF-IDF weights by term id, sorted by id.
+    text: Vec<(u32, f32)>,
+}
+
+/// Restricts [`OracleIndex::similar_cards`] results.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimilarFilter {
+    /// Only cards whose color identity fits within these colors.
+    pub within_colors: Option<ColorSet>,
+    pub max_mana_value: Option<u32>,
+    /// Only cards sharing a card type with the query card.
+    pub same_card_type: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimilarCard {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+    /// 0 (nothing in common) to 1.
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OracleIndex {
+    cards: Vec<IndexedCard>,
+}
+
+impl OracleIndex {
+    /// Index `cards`, typically every oracle card in the db.
+    #[must_use]
+    pub fn build(cards: &[OracleCard]) -> Self {
+        let mut vocabulary: HashMap<String, u32> = HashMap::new();
+        let mut type_vocabulary: HashMap<String, u32> = HashMap::new();
+        let mut document_frequency: Vec<u32> = Vec::new();
+
+        let mut counted: Vec<(BTreeMap<u32, u32>, Vec<u32>)> = Vec::with_capacity(cards.len());
+        for card in cards {
+            let mut counts: BTreeMap<u32, u32> = BTreeMap::new();
+            for term in terms(&normalized_text(card)) {
+                let next = vocabulary.len() as u32;
+                let id = *vocabulary.entry(term).or_insert(next);
+                *counts.entry(id).or_default() += 1;
+            }
+            document_frequency.resize(vocabulary.len(), 0);
+            for &id in counts.keys() {
+                document_frequency[id as usize] += 1;
+            }
+
+            let mut types: Vec<u32> = type_words(card)
+                .into_iter()
+                .map(|word| {
+                    let next = type_vocabulary.len() as u32;
+                    *type_vocabulary.entry(word).or_insert(next)
+                })
+                .collect();
+            types.sort_unstable();
+            types.dedup();
+            counted.push((counts, types));
+        }
+
+        let n = cards.len() as f32;
+        let idf: Vec<f32> = document_frequency
+            .iter()
+            .map(|&df| ((1.0 + n) / (1.0 + df as f32)).ln() + 1.0)
+            .collect();
+
+        let cards = cards
+            .iter()
+            .zip(counted)
+            .map(|(card, (counts, types))| {
+                let mut text: Vec<(u32, f32)> = counts
+                    .into_iter()
+                    .map(|(id, count)| (id, (1.0 + (count as f32).ln()) * idf[id as usize]))
+                    .collect();
+                let norm = text.iter().map(|(_, w)| w * w).sum::<f32>().sqrt();
+                if norm > 0.0 {
+                    for (_, w) in &mut text {
+                        *w /= norm;
+                    }
+                }
+
+                let mut card_types: Vec<CardType> = Vec::new();
+                for t in card.faces.iter().flat_map(|f| &f.type_line.card_types) {
+                    if !card_types.contains(t) {
+                        card_types.push(t.clone());
+                    }
+                }
+                let mana_value = mana_value(card);
+                IndexedCard {
+                    oracle_id: card.oracle_id,
+                    name: card.full_name().into(),
+                    mana_value: if mana_value.is_finite() {
+                        mana_value
+                    } else {
+                        0.0
+                    },
+                    color_identity: card.color_identity,
+                    card_types,
+                    types,
+                    text,
+                }
+            })
+            .collect();
+        Self { cards }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// How alike two indexed cards are, or `None` if either isn't indexed.
+    #[must_use]
+    pub fn similarity(&self, a: Uuid, b: Uuid) -> Option<f32> {
+        Some(score(self.card(a)?, self.card(b)?))
+    }
+
+    /// The `k` cards most like `oracle_id` that pass `filter`, best first.
+    /// Empty if the card isn't indexed.
+    #[must_use]
+    pub fn similar_cards(
+        &self,
+        oracle_id: Uuid,
+        k: usize,
+        filter: &SimilarFilter,
+    ) -> Vec<SimilarCard> {
+        let Some(query) = self.card(oracle_id) else {
+            return Vec::new();
+        };
+
+        let mut scored: Vec<SimilarCard> = self
+            .cards
+            .iter()
+            .filter(|c| c.oracle_id != oracle_id)
+            .filter(|c| {
+                filter
+                    .within_colors
+                    .is_none_or(|colors| colors.contains(c.color_identity))
+            })
+            .filter(|c| {
+                filter
+                    .max_mana_value
+                    .is_none_or(|mv| c.mana_value <= mv as f32)
+            })
+            .filter(|c| {
+                !filter.same_card_type || c.card_types.iter().any(|t| query.card_types.contains(t))
+            })
+            .map(|c| SimilarCard {
+                oracle_id: c.oracle_id,
+                name: c.name.clone(),
+                score: score(query, c),
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.name.cmp(&b.name)));
+        scored.truncate(k);
+        scored
+    }
+
+    fn card(&self, oracle_id: Uuid) -> Option<&IndexedCard> {
+        self.cards.iter().find(|c| c.oracle_id == oracle_id)
+    }
+}
+
+fn score(a: &IndexedCard, b: &IndexedCard) -> f32 {
+    TEXT_WEIGHT * sparse_dot(&a.text, &b.text)
+        + TYPE_WEIGHT * jaccard(&a.types, &b.types)
+        + MANA_VALUE_WEIGHT / (1.0 + (a.mana_value - b.mana_value).abs())
+}
+
+fn sparse_dot(a: &[(u32, f32)], b: &[(u32, f32)]) -> f32 {
+    let (mut i, mut j, mut dot) = (0, 0, 0.0);
+    while i < a.len() && j < b.len() {
+        match a[i].0.cmp(&b[j].0) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                dot += a[i].1 * b[j].1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    dot
+}
+
+fn jaccard(a: &[u32], b: &[u32]) -> f32 {
+    let shared = a.iter().filter(|id| b.binary_search(id).is_ok()).count();
+    let union = a.len() + b.len() - shared;
+    if union == 0 {
+        0.0
+    } else {
+        shared as f32 / union as f32
+    }
+}
+
+/// Card types and subtypes of every face, lowercased.
+fn type_words(card: &OracleCard) -> Vec<String> {
+    let mut words = Vec::new();
+    for face in &card.faces {
+        let line = &face.type_line;
+        words.extend(line.card_types.iter().map(|t| match t {
+            CardType::Unknown(word) => word.to_lowercase(),
+            t => format!("{t:?}").to_lowercase(),
+        }));
+        words.extend(line.subtypes.iter().map(|s| s.0.to_lowercase()));
+    }
+    words
+}
+
+/// Rules text of every face, lowercased, with reminder text removed and
+/// self-references replaced by [`SELF_TOKEN`].
+fn normalized_text(card: &OracleCard) -> String {
+    let mut names: Vec<&str> = card.faces.iter().map(|f| &*f.name).collect();
+    // Legends are often referred to by the part of their name before the comma.
+    names.extend(
+        card.faces
+            .iter()
+            .filter_map(|f| f.name.split_once(", ").map(|(short, _)| short))
+            .filter(|short| short.len() > 2),
+    );
+    // Longest first, so "Jace, Vryn's Prodigy" goes before "Jace".
+    names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+
+    let mut text = String::new();
+    for face in &card.faces {
+        for segment in &face.oracle_text.0 {
+            match segment {
+                OracleTextSegment::Text(s) => {
+                    let mut s = strip_parentheses(s);
+                    for name in &names {
+                        s = s.replace(name, SELF_TOKEN);
+                    }
+                    text.push_str(&s);
+                }
+                OracleTextSegment::Keyword { keyword, parameter } => {
+                    text.push_str(&format!("{keyword:?}"));
+                    if let Some(parameter) = parameter {
+                        text.push(' ');
+                        text.push_str(parameter);
+                    }
+                }
+                OracleTextSegment::Symbol(s) => text.push_str(s),
+                OracleTextSegment::Paragraph => text.push('\n'),
+                _ => {}
+            }
+        }
+        text.push('\n');
+    }
+
+    let mut text = text.to_lowercase();
+    for reference in SELF_REFERENCES {
+        text = text.replace(reference, SELF_TOKEN);
+    }
+    text
+}
+
+fn strip_parentheses(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut depth = 0u32;
+    for c in s.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Words, mana symbols and [`SELF_TOKEN`], plus each adjacent pair within a
+/// sentence.
+fn terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for sentence in text.split(['.', '\n', ':', ';']) {
+        let words = words(sentence);
+        terms.extend(
+            words
+                .windows(2)
+                .map(|pair| format!("{} {}", pair[0], pair[1])),
+        );
+        terms.extend(words.into_iter().map(str::to_owned));
+    }
+    terms
+}
+
+fn words(sentence: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut rest = sentence;
+    while let Some(start) =
+        rest.find(|c: char| c.is_alphanumeric() || matches!(c, '{' | '~' | '+' | '-'))
+    {
+        rest = &rest[start..];
+        let len = if rest.starts_with('{') {
+            rest.find('}').map_or(rest.len(), |i| i + 1)
+        } else if rest.starts_with('~') {
+            SELF_TOKEN.len()
+        } else {
+            rest.find(|c: char| !(c.is_alphanumeric() || matches!(c, '\'' | '+' | '-' | '/')))
+                .unwrap_or(rest.len())
+        };
+        words.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+    words
+}
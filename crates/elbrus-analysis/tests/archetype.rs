@@ -0,0 +1,111 @@
+use common::{Pool, close};
+use elbrus_analysis::{
+    DeckVector, SimilarityMetric, cluster_archetypes, cosine, deck_vector, similarity_matrix,
+    weighted_jaccard,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use uuid::Uuid;
+
+mod common;
+
+#[test]
+fn test_metrics() {
+    let (x, y, z) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    let a: DeckVector = [(x, 4), (y, 2)].into();
+    let b: DeckVector = [(x, 2), (z, 2)].into();
+
+    close(weighted_jaccard(&a, &b), 0.25);
+    close(cosine(&a, &b), 8.0 / 160f64.sqrt());
+    close(weighted_jaccard(&a, &a), 1.0);
+    close(cosine(&a, &DeckVector::new()), 0.0);
+    close(SimilarityMetric::Cosine.similarity(&b, &b), 1.0);
+
+    let mut deck = Deck::default();
+    deck.mainboard.push(DeckEntry::new(2, "Unresolved"));
+    let mut bolt = DeckEntry::new(3, "Lightning Bolt");
+    bolt.resolved = Some(x);
+    deck.mainboard.push(bolt.clone());
+    bolt.quantity = 1;
+    deck.mainboard.push(bolt);
+    assert_eq!(deck_vector(&deck), [(x, 4)].into());
+}
+
+#[test]
+fn test_cluster_archetypes() {
+    let mut pool = Pool::default();
+    let decks = vec![
+        pool.deck(
+            &[
+                ("Lightning Bolt", 4),
+                ("Goblin Guide", 4),
+                ("Eidolon of the Great Revel", 4),
+                ("Mountain", 20),
+            ],
+            &[],
+        ),
+        pool.deck(
+            &[("Llanowar Elves", 4), ("Primeval Titan", 4), ("Forest", 20)],
+            &[],
+        ),
+        pool.deck(
+            &[
+                ("Lightning Bolt", 4),
+                ("Goblin Guide", 4),
+                ("Eidolon of the Great Revel", 3),
+                ("Skewer the Critics", 2),
+                ("Mountain", 20),
+            ],
+            &[],
+        ),
+        pool.deck(
+            &[
+                ("Llanowar Elves", 4),
+                ("Primeval Titan", 3),
+                ("Lightning Bolt", 1),
+                ("Forest", 20),
+            ],
+            &[],
+        ),
+        pool.deck(
+            &[
+                ("Lightning Bolt", 4),
+                ("Goblin Guide", 4),
+                ("Skewer the Critics", 4),
+                ("Mountain", 18),
+            ],
+            &[],
+        ),
+    ];
+
+    let matrix = similarity_matrix(&decks, SimilarityMetric::WeightedJaccard);
+    close(matrix[0][2], 31.0 / 34.0);
+    close(matrix[2][0], matrix[0][2]);
+    close(matrix[1][1], 1.0);
+
+    let archetypes = cluster_archetypes(&decks, SimilarityMetric::WeightedJaccard, 0.5);
+    assert_eq!(archetypes.len(), 2);
+
+    let burn = &archetypes[0];
+    assert_eq!(burn.decks, vec![0, 2, 4]);
+    close(burn.share, 0.6);
+    // Basic lands never name an archetype; Bolt is diluted by the ramp deck.
+    assert_eq!(
+        burn.name,
+        "Goblin Guide / Eidolon of the Great Revel / Skewer the Critics"
+    );
+    close(burn.signature[0].distinctiveness, 1.0);
+    close(burn.signature[1].frequency, 2.0 / 3.0);
+
+    let ramp = &archetypes[1];
+    assert_eq!(ramp.decks, vec![1, 3]);
+    assert_eq!(ramp.name, "Llanowar Elves / Primeval Titan");
+
+    assert_eq!(
+        cluster_archetypes(&decks, SimilarityMetric::Cosine, 1.01).len(),
+        5
+    );
+    let everything = cluster_archetypes(&decks, SimilarityMetric::Cosine, 0.0);
+    assert_eq!(everything.len(), 1);
+    close(everything[0].share, 1.0);
+    assert!(cluster_archetypes(&[], SimilarityMetric::Cosine, 0.5).is_empty());
+}
@@ -0,0 +1,198 @@
+use elbrus_analysis::{BracketLists, BracketSignal, estimate_bracket};
+use elbrus_core::{
+    CardFace, CardLayout, ColorSet, OracleCard, legality::Legalities, oracle::OracleText,
+    types::TypeLine,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn face(name: &str, cost: &str, type_line: &str) -> CardFace {
+    CardFace {
+        name: name.into(),
+        mana_cost: (!cost.is_empty()).then(|| elbrus_parser::mana_cost::parse(cost).unwrap()),
+        type_line: TypeLine::parse(type_line).unwrap(),
+        oracle_text: OracleText::default(),
+        colors: ColorSet::empty(),
+        power: None,
+        toughness: None,
+        loyalty: None,
+        defense: None,
+        flavor_text: None,
+    }
+}
+
+#[derive(Default)]
+struct Builder {
+    cards: HashMap<Uuid, OracleCard>,
+    deck: Deck,
+}
+
+impl Builder {
+    fn add(&mut self, quantity: u32, layout: CardLayout, faces: Vec<CardFace>) -> &mut Self {
+        let card = OracleCard {
+            oracle_id: Uuid::new_v4(),
+            layout,
+            faces: faces.into_iter().collect(),
+            color_identity: ColorSet::empty(),
+            keywords: vec![],
+            legalities: Legalities::default(),
+            edh_rank: None,
+            reserved: false,
+        };
+        let mut entry = DeckEntry::new(quantity, card.name());
+        entry.resolved = Some(card.oracle_id);
+        self.deck.mainboard.push(entry);
+        self.cards.insert(card.oracle_id, card);
+        self
+    }
+
+    fn spell(&mut self, name: &str, cost: &str) -> &mut Self {
+        self.add(1, CardLayout::Normal, vec![face(name, cost, "Instant")])
+    }
+
+    fn lands(&mut self, n: u32) -> &mut Self {
+        self.add(
+            n,
+            CardLayout::Normal,
+            vec![face("Wastes", "", "Basic Land")],
+        )
+    }
+
+    fn commander(&mut self, name: &str, cost: &str) -> &mut Self {
+        self.add(
+            1,
+            CardLayout::Normal,
+            vec![face(name, cost, "Legendary Creature — Human")],
+        );
+        let entry = self.deck.mainboard.pop().unwrap();
+        self.deck.commander.push(entry);
+        self
+    }
+}
+
+#[test]
+fn test_casual_and_core() {
+    let lists = BracketLists::bundled();
+    assert!(lists.game_changers.len() > 40);
+    assert!(!lists.two_card_combos.is_empty());
+
+    let mut casual = Builder::default();
+    casual
+        .commander("Kenrith, the Returned King", "{4}{W}")
+        .spell("Colossal Dreadmaw", "{4}{G}{G}")
+        .spell("Divination", "{2}{U}")
+        .lands(36);
+    let estimate = estimate_bracket(&casual.deck, &casual.cards, &lists);
+    assert_eq!(estimate.bracket, 1);
+    assert!(estimate.evidence.is_empty());
+    assert!((estimate.average_mana_value - 14.0 / 3.0).abs() < 1e-9);
+
+    casual.spell("Shock", "{R}").spell("Opt", "{U}");
+    let estimate = estimate_bracket(&casual.deck, &casual.cards, &lists);
+    assert_eq!(estimate.bracket, 2);
+
+    casual.spell("Time Warp", "{3}{U}{U}");
+    let estimate = estimate_bracket(&casual.deck, &casual.cards, &lists);
+    assert_eq!(estimate.bracket, 2);
+    assert_eq!(estimate.evidence[0].signal, BracketSignal::ExtraTurns);
+    assert_eq!(estimate.evidence[0].cards, vec!["Time Warp".into()]);
+}
+
+#[test]
+fn test_game_changers_and_combos() {
+    let lists = BracketLists::bundled();
+    let mut deck = Builder::default();
+    deck.commander("Kenrith, the Returned King", "{4}{W}")
+        .spell("rhystic study", "{2}{U}")
+        .spell("Smothering Tithe", "{3}{W}")
+        .spell("Divination", "{2}{U}")
+        .lands(36);
+    let estimate = estimate_bracket(&deck.deck, &deck.cards, &lists);
+    assert_eq!(estimate.bracket, 3);
+    assert_eq!(estimate.count(BracketSignal::GameChangers), 2);
+    assert!(estimate.evidence[0].reason.starts_with("2 game changer"));
+
+    // A split card is matched by its full name or either face.
+    deck.add(
+        1,
+        CardLayout::Split,
+        vec![
+            face("Boom", "{1}{R}", "Sorcery"),
+            face("Bust", "{5}{R}", "Sorcery"),
+        ],
+    );
+    let estimate = estimate_bracket(&deck.deck, &deck.cards, &lists);
+    assert_eq!(estimate.bracket, 4);
+    let mld = estimate
+        .evidence
+        .iter()
+        .find(|e| e.signal == BracketSignal::MassLandDenial)
+        .unwrap();
+    assert_eq!(mld.cards, vec!["Boom // Bust".into()]);
+
+    let mut combo = Builder::default();
+    combo
+        .commander("Thassa's Oracle", "{U}{U}")
+        .spell("Demonic Consultation", "{B}")
+        .spell("Mystic Remora", "{U}")
+        .lands(30);
+    let estimate = estimate_bracket(&combo.deck, &combo.cards, &lists);
+    // One tutor: the combo alone is a bracket 3 signal, but Thassa's Oracle
+    // is also a game changer and the curve is low.
+    assert_eq!(estimate.count(BracketSignal::TwoCardCombos), 1);
+    assert_eq!(estimate.bracket, 3);
+
+    combo.spell("Vampiric Tutor", "{B}");
+    let estimate = estimate_bracket(&combo.deck, &combo.cards, &lists);
+    assert_eq!(estimate.bracket, 4);
+    let evidence = estimate
+        .evidence
+        .iter()
+        .find(|e| e.signal == BracketSignal::TwoCardCombos)
+        .unwrap();
+    assert_eq!(
+        evidence.cards,
+        vec!["Thassa's Oracle + Demonic Consultation".into()]
+    );
+    assert_eq!(evidence.min_bracket, 4);
+
+    for tutor in [
+        "Demonic Tutor",
+        "Imperial Seal",
+        "Mystical Tutor",
+        "Tainted Pact",
+    ] {
+        combo.spell(tutor, "{1}{B}");
+    }
+    for rock in ["Mana Crypt", "Chrome Mox", "Mox Diamond"] {
+        combo.spell(rock, "{0}");
+    }
+    let estimate = estimate_bracket(&combo.deck, &combo.cards, &lists);
+    assert_eq!(estimate.count(BracketSignal::Tutors), 6);
+    assert_eq!(estimate.bracket, 5);
+}
+
+#[test]
+fn test_custom_lists() {
+    let lists = BracketLists::from_json(
+        r#"{
+            "game_changers": ["Divination"],
+            "fast_mana": [],
+            "tutors": [],
+            "extra_turns": [],
+            "mass_land_denial": [],
+            "free_interaction": [],
+            "two_card_combos": []
+        }"#,
+    )
+    .unwrap();
+    let mut deck = Builder::default();
+    deck.spell("Divination", "{2}{U}")
+        .spell("Rhystic Study", "{2}{U}");
+    let estimate = estimate_bracket(&deck.deck, &deck.cards, &lists);
+    assert_eq!(estimate.evidence.len(), 1);
+    assert_eq!(estimate.evidence[0].cards, vec!["Divination".into()]);
+
+    assert!(BracketLists::from_json("{}").is_err());
+}
@@ -0,0 +1,245 @@
+use elbrus_analysis::{BudgetOptions, OracleIndex, budget_substitutions};
+use elbrus_core::{ColorSet, Format, LegalityStatus, OracleCard, legality::Legalities};
+use elbrus_deck::{Deck, DeckEntry};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use uuid::Uuid;
+
+mod common;
+
+fn card(name: &str, cost: &str, type_line: &str, text: &str, colors: ColorSet) -> OracleCard {
+    let status = if name == "Lightning Strike" {
+        LegalityStatus::Banned
+    } else {
+        LegalityStatus::Legal
+    };
+    OracleCard {
+        legalities: Legalities([(Format("modern".into()), status)].into_iter().collect()),
+        ..common::card(name, cost, type_line, text, colors)
+    }
+}
+
+struct Fixture {
+    pool: HashMap<Uuid, OracleCard>,
+    index: OracleIndex,
+    prices: HashMap<Uuid, Decimal>,
+    deck: Deck,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let (r, g, u) = (ColorSet::RED, ColorSet::GREEN, ColorSet::BLUE);
+        let cards = [
+            (
+                "Lightning Bolt",
+                "{R}",
+                "Instant",
+                "Lightning Bolt deals 3 damage to any target.",
+                r,
+                Some("2.00"),
+            ),
+            (
+                "Shock",
+                "{R}",
+                "Instant",
+                "Shock deals 2 damage to any target.",
+                r,
+                Some("0.25"),
+            ),
+            (
+                "Lightning Strike",
+                "{1}{R}",
+                "Instant",
+                "Lightning Strike deals 3 damage to any target.",
+                r,
+                Some("0.10"),
+            ),
+            (
+                "Searing Spear",
+                "{1}{R}",
+                "Instant",
+                "Searing Spear deals 3 damage to any target.",
+                r,
+                Some("5.00"),
+            ),
+            (
+                "Lava Axe",
+                "{4}{R}",
+                "Sorcery",
+                "Lava Axe deals 5 damage to target player or planeswalker.",
+                r,
+                Some("0.05"),
+            ),
+            (
+                "Counterspell",
+                "{U}{U}",
+                "Instant",
+                "Counter target spell.",
+                u,
+                Some("0.10"),
+            ),
+            (
+                "Llanowar Elves",
+                "{G}",
+                "Creature — Elf Druid",
+                "{T}: Add {G}.",
+                g,
+                Some("3.00"),
+            ),
+            (
+                "Elvish Mystic",
+                "{G}",
+                "Creature — Elf Druid",
+                "{T}: Add {G}.",
+                g,
+                Some("0.50"),
+            ),
+            (
+                "Fyndhorn Elves",
+                "{G}",
+                "Creature — Elf Druid",
+                "{T}: Add {G}.",
+                g,
+                Some("4.00"),
+            ),
+            (
+                "Mountain",
+                "",
+                "Basic Land — Mountain",
+                "",
+                ColorSet::empty(),
+                None,
+            ),
+        ];
+        let mut pool = HashMap::new();
+        let mut prices = HashMap::new();
+        for (name, cost, type_line, text, colors, price) in cards {
+            let card = card(name, cost, type_line, text, colors);
+            if let Some(price) = price {
+                prices.insert(card.oracle_id, Decimal::from_str(price).unwrap());
+            }
+            pool.insert(card.oracle_id, card);
+        }
+        let index = OracleIndex::build(&pool.values().cloned().collect::<Vec<_>>());
+        let mut fixture = Self {
+            pool,
+            index,
+            prices,
+            deck: Deck::default(),
+        };
+        fixture
+            .add(4, "Lightning Bolt")
+            .add(4, "Llanowar Elves")
+            .add(10, "Mountain");
+        fixture
+    }
+
+    fn id(&self, name: &str) -> Uuid {
+        self.pool
+            .values()
+            .find(|c| c.name() == name)
+            .unwrap()
+            .oracle_id
+    }
+
+    fn add(&mut self, quantity: u32, name: &str) -> &mut Self {
+        let mut entry = DeckEntry::new(quantity, name);
+        entry.resolved = Some(self.id(name));
+        self.deck.mainboard.push(entry);
+        self
+    }
+}
+
+fn price(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+#[test]
+fn test_card_ceiling() {
+    let f = Fixture::new();
+    let options = BudgetOptions {
+        max_card_price: Some(price("1.00")),
+        ..BudgetOptions::default()
+    };
+    let report = budget_substitutions(&f.deck, &f.pool, &f.index, &f.prices, &options);
+    assert_eq!(report.total, price("20.00"));
+    assert!(report.unpriced.is_empty());
+    assert!(report.irreplaceable.is_empty());
+
+    // Most expensive line first.
+    assert_eq!(report.swaps.len(), 2);
+    let elves = &report.swaps[0];
+    assert_eq!(&*elves.name, "Llanowar Elves");
+    assert_eq!(elves.quantity, 4);
+    // Fyndhorn Elves is a perfect match but costs more.
+    assert_eq!(elves.suggestions.len(), 1);
+    assert_eq!(&*elves.suggestions[0].name, "Elvish Mystic");
+    assert_eq!(elves.savings(), price("10.00"));
+
+    let bolt = &report.swaps[1];
+    assert_eq!(&*bolt.name, "Lightning Bolt");
+    let mut names: Vec<&str> = bolt.suggestions.iter().map(|s| &*s.name).collect();
+    names.sort_unstable();
+    // Not Searing Spear (pricier), Lava Axe (sorcery at 5) or Counterspell (blue).
+    assert_eq!(names, ["Lightning Strike", "Shock"]);
+    assert!(bolt.suggestions.iter().all(|s| s.price <= price("1.00")));
+    assert_eq!(
+        report.total_after,
+        report.total - elves.savings() - bolt.savings()
+    );
+    assert!(report.within(&options));
+
+    // Format legality drops the banned Lightning Strike.
+    let options = BudgetOptions {
+        format: Some(Format("modern".into())),
+        ..options
+    };
+    let report = budget_substitutions(&f.deck, &f.pool, &f.index, &f.prices, &options);
+    assert_eq!(report.swaps[1].suggestions.len(), 1);
+    assert_eq!(&*report.swaps[1].suggestions[0].name, "Shock");
+    assert_eq!(report.swaps[1].suggestions[0].price, price("0.25"));
+}
+
+#[test]
+fn test_total_ceiling() {
+    let mut f = Fixture::new();
+    let options = BudgetOptions {
+        max_total: Some(price("15.00")),
+        ..BudgetOptions::default()
+    };
+    // Swapping the elves alone brings the deck from 20.00 to 10.00.
+    let report = budget_substitutions(&f.deck, &f.pool, &f.index, &f.prices, &options);
+    assert_eq!(report.swaps.len(), 1);
+    assert_eq!(&*report.swaps[0].name, "Llanowar Elves");
+    assert_eq!(report.total_after, price("10.00"));
+    assert!(report.within(&options));
+
+    let options = BudgetOptions {
+        max_total: Some(price("1.00")),
+        ..BudgetOptions::default()
+    };
+    f.add(1, "Searing Spear");
+    let report = budget_substitutions(&f.deck, &f.pool, &f.index, &f.prices, &options);
+    assert_eq!(report.swaps.len(), 3);
+    // Cards already in the deck are never suggested.
+    assert!(report.swaps.iter().all(|s| {
+        s.suggestions
+            .iter()
+            .all(|c| !["Lightning Bolt", "Searing Spear"].contains(&&*c.name))
+    }));
+    // Two swaps never share a first choice.
+    assert_ne!(
+        report.swaps[1].suggestions[0].oracle_id,
+        report.swaps[2].suggestions[0].oracle_id
+    );
+    assert!(!report.within(&options));
+
+    // A red commander keeps green stand-ins out.
+    let mut commander = DeckEntry::new(1, "Shock");
+    commander.resolved = Some(f.id("Shock"));
+    f.deck.commander.push(commander);
+    let report = budget_substitutions(&f.deck, &f.pool, &f.index, &f.prices, &options);
+    assert!(report.irreplaceable.contains(&"Llanowar Elves".into()));
+    assert!(report.swaps.iter().all(|s| &*s.name != "Llanowar Elves"));
+}
@@ -0,0 +1,107 @@
+// Shared fixtures; each test binary uses its own subset.
+#![allow(dead_code)]
+
+use elbrus_core::{
+    CardFace, CardLayout, ColorSet, GenericCost, ManaSymbol, OracleCard, legality::Legalities,
+    oracle::OracleText, oracle::OracleTextSegment, types::TypeLine,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub fn close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {expected}, got {actual}"
+    );
+}
+
+pub fn generic(n: u32) -> ManaSymbol {
+    ManaSymbol::Generic(GenericCost::new(n))
+}
+
+/// A single-faced card; an empty `cost` means no mana cost.
+pub fn card(name: &str, cost: &str, type_line: &str, text: &str, colors: ColorSet) -> OracleCard {
+    OracleCard {
+        oracle_id: Uuid::new_v4(),
+        layout: CardLayout::Normal,
+        faces: [CardFace {
+            name: name.into(),
+            mana_cost: (!cost.is_empty()).then(|| elbrus_parser::mana_cost::parse(cost).unwrap()),
+            type_line: TypeLine::parse(type_line).unwrap(),
+            oracle_text: OracleText(vec![OracleTextSegment::Text(text.into())]),
+            colors,
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }]
+        .into_iter()
+        .collect(),
+        color_identity: colors,
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    }
+}
+
+/// A colorless card with `faces` laid out as `layout`.
+pub fn oracle(layout: CardLayout, faces: Vec<CardFace>) -> OracleCard {
+    OracleCard {
+        oracle_id: Uuid::new_v4(),
+        layout,
+        faces: faces.into_iter().collect(),
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    }
+}
+
+/// A mainboard of `counts` copies of each card, with the cards by oracle id.
+pub fn deck(counts: &[(&OracleCard, u32)]) -> (Deck, HashMap<Uuid, OracleCard>) {
+    let deck = Deck {
+        mainboard: counts
+            .iter()
+            .map(|(c, quantity)| {
+                let mut entry = DeckEntry::new(*quantity, "Test");
+                entry.resolved = Some(c.oracle_id);
+                entry
+            })
+            .collect(),
+        ..Deck::default()
+    };
+    let cards = counts
+        .iter()
+        .map(|(c, _)| (c.oracle_id, (*c).clone()))
+        .collect();
+    (deck, cards)
+}
+
+/// Oracle ids by card name, made up on first use.
+#[derive(Default)]
+pub struct Pool(HashMap<&'static str, Uuid>);
+
+impl Pool {
+    pub fn entries(&mut self, cards: &[(&'static str, u32)]) -> Vec<DeckEntry> {
+        cards
+            .iter()
+            .map(|(name, quantity)| {
+                let mut entry = DeckEntry::new(*quantity, name);
+                entry.resolved = Some(*self.0.entry(name).or_insert_with(Uuid::new_v4));
+                entry
+            })
+            .collect()
+    }
+
+    pub fn deck(&mut self, main: &[(&'static str, u32)], side: &[(&'static str, u32)]) -> Deck {
+        Deck {
+            mainboard: self.entries(main),
+            sideboard: self.entries(side),
+            ..Deck::default()
+        }
+    }
+}
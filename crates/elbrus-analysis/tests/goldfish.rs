@@ -0,0 +1,170 @@
+use common::deck;
+use elbrus_analysis::{
+    GoldfishConfig, GoldfishSimulator, Requirement, hypergeometric_at_most, hypergeometric_cdf,
+    mana_payment, multivariate_hypergeometric,
+};
+use elbrus_core::{
+    CardFace, CardLayout, Color, ColorSet, GenericCost, ManaCost, ManaSymbol, OracleCard, VarSym,
+    legality::Legalities, oracle::OracleText, types::TypeLine,
+};
+use uuid::Uuid;
+
+use ManaSymbol::{Colored, Generic, Hybrid, Phyrexian, TwoBrid};
+
+mod common;
+
+fn cost(symbols: &[ManaSymbol]) -> ManaCost {
+    ManaCost(symbols.iter().cloned().collect())
+}
+
+fn card(type_line: &str, mana_cost: &[ManaSymbol]) -> OracleCard {
+    OracleCard {
+        oracle_id: Uuid::new_v4(),
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: "Test".into(),
+            mana_cost: (!mana_cost.is_empty()).then(|| cost(mana_cost)),
+            type_line: TypeLine::parse(type_line).unwrap(),
+            oracle_text: OracleText::default(),
+            colors: ColorSet::empty(),
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    }
+}
+
+#[test]
+fn test_mana_payment() {
+    let g = ColorSet::GREEN;
+    let u = ColorSet::BLUE;
+    let dual = g | u;
+    let one = Generic(GenericCost::new(1));
+
+    // The dual is kept for the generic pip.
+    assert_eq!(
+        mana_payment(
+            &cost(&[one.clone(), Colored(Color::G), Colored(Color::U)]),
+            &[dual, g, u]
+        ),
+        Some(vec![0, 1, 2])
+    );
+    assert_eq!(
+        mana_payment(
+            &cost(&[Colored(Color::G), Colored(Color::G), Colored(Color::G)]),
+            &[g, u, dual]
+        ),
+        None
+    );
+    assert_eq!(
+        mana_payment(
+            &cost(&[Hybrid(Color::G, Color::U), Hybrid(Color::G, Color::U)]),
+            &[u, dual]
+        ),
+        Some(vec![0, 1])
+    );
+
+    let two_brid = cost(&[TwoBrid(Color::W)]);
+    assert_eq!(mana_payment(&two_brid, &[ColorSet::WHITE]), Some(vec![0]));
+    assert_eq!(mana_payment(&two_brid, &[g, g]), Some(vec![0, 1]));
+    assert_eq!(mana_payment(&two_brid, &[g]), None);
+
+    let phyrexian = cost(&[one, Phyrexian(Color::B)]);
+    assert_eq!(mana_payment(&phyrexian, &[ColorSet::BLACK]), Some(vec![0]));
+    assert_eq!(
+        mana_payment(&phyrexian, &[g, ColorSet::BLACK]),
+        Some(vec![0, 1])
+    );
+
+    let colorless = cost(&[ManaSymbol::Colorless]);
+    assert_eq!(mana_payment(&colorless, &[g]), None);
+    assert_eq!(
+        mana_payment(&colorless, &[ColorSet::empty()]),
+        Some(vec![0])
+    );
+
+    let x_spell = cost(&[ManaSymbol::Variable(VarSym::X), Colored(Color::R)]);
+    assert_eq!(mana_payment(&x_spell, &[ColorSet::RED]), Some(vec![0]));
+}
+
+#[test]
+fn test_goldfish_two_drops() {
+    let forest = card("Basic Land — Forest", &[]);
+    let bear = card(
+        "Creature — Bear",
+        &[Generic(GenericCost::new(1)), Colored(Color::G)],
+    );
+    let (deck, cards) = deck(&[(&forest, 17), (&bear, 23)]);
+    let simulator = GoldfishSimulator::new(&deck, &cards);
+    let config = GoldfishConfig {
+        track: vec![bear.oracle_id],
+        ..GoldfishConfig::default()
+    };
+
+    let report = simulator.run(&config, 4_000, 11);
+    assert_eq!(report, simulator.run(&config, 4_000, 11));
+    assert_eq!(report.turns.len(), 7);
+
+    // Nothing costs one, so turn 1 never spends mana.
+    assert_eq!(
+        report.turns[0].mana_spent.iter().collect::<Vec<_>>(),
+        vec![(&0, &4_000)]
+    );
+    for stats in &report.turns {
+        assert!(
+            stats
+                .mana_spent
+                .keys()
+                .all(|m| m % 2 == 0 && *m <= stats.turn)
+        );
+        assert!(stats.avg_lands <= f64::from(stats.turn));
+    }
+    assert!(report.turns[3].avg_mana_spent > report.turns[1].avg_mana_spent);
+
+    // Castable on turn 2: two lands and a bear among the first eight cards.
+    let bear_on_two = multivariate_hypergeometric(
+        40,
+        &[
+            Requirement {
+                successes: 17,
+                at_least: 2,
+            },
+            Requirement {
+                successes: 23,
+                at_least: 1,
+            },
+        ],
+        8,
+    );
+    let first = &report.first_castable[0];
+    assert_eq!(first.castable_by(1), 0.0);
+    assert!((first.castable_by(2) - bear_on_two).abs() < 0.03);
+
+    // Fewer than 3 of 10 cards are lands by turn 4; 7+ of 12 by turn 6.
+    assert!((report.screw_rate - hypergeometric_at_most(40, 17, 10, 2)).abs() < 0.03);
+    assert!((report.flood_rate - hypergeometric_cdf(40, 17, 12, 7)).abs() < 0.03);
+}
+
+#[test]
+fn test_goldfish_off_color_spells_are_never_cast() {
+    let forest = card("Basic Land — Forest", &[]);
+    let counterspell = card("Instant", &[Colored(Color::U), Colored(Color::U)]);
+    let (deck, cards) = deck(&[(&forest, 20), (&counterspell, 20)]);
+    let config = GoldfishConfig {
+        on_play: false,
+        track: vec![counterspell.oracle_id],
+        ..GoldfishConfig::default()
+    };
+
+    let report = GoldfishSimulator::new(&deck, &cards).run(&config, 200, 3);
+    assert!(!report.on_play);
+    assert_eq!(report.first_castable[0].never, 200);
+    assert!(report.turns.iter().all(|t| t.avg_mana_spent == 0.0));
+}
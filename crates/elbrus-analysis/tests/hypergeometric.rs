@@ -0,0 +1,165 @@
+use common::close;
+use elbrus_analysis::{
+    CardRequirement, Requirement, cards_seen, count_matching, draw_odds, draw_odds_all,
+    hypergeometric_at_most, hypergeometric_cdf, hypergeometric_pmf, multivariate_hypergeometric,
+};
+use elbrus_core::{
+    CardFace, CardLayout, CardType, ColorSet, OracleCard, legality::Legalities, oracle::OracleText,
+    types::TypeLine,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod common;
+
+#[test]
+fn test_pmf_and_cdf() {
+    // One copy of a 4-of in a 60-card opening hand.
+    close(hypergeometric_pmf(60, 4, 7, 1), 0.336_280_209_582_987_3);
+    close(hypergeometric_cdf(60, 4, 7, 1), 0.399_499_625_744_665_6);
+    close(
+        hypergeometric_at_most(60, 4, 7, 0) + hypergeometric_cdf(60, 4, 7, 1),
+        1.0,
+    );
+
+    close(hypergeometric_cdf(60, 4, 7, 0), 1.0);
+    close(hypergeometric_pmf(60, 4, 7, 5), 0.0);
+    close(hypergeometric_pmf(10, 10, 3, 3), 1.0);
+    // Drawing past the population draws all of it.
+    close(hypergeometric_pmf(5, 2, 9, 2), 1.0);
+}
+
+#[test]
+fn test_large_population() {
+    let total: f64 = (0..=40).map(|k| hypergeometric_pmf(250, 40, 100, k)).sum();
+    close(total, 1.0);
+    let p = hypergeometric_cdf(250, 40, 100, 16);
+    assert!(p.is_finite() && p > 0.0 && p < 1.0);
+}
+
+#[test]
+fn test_multivariate() {
+    // A single requirement is the ordinary hypergeometric.
+    close(
+        multivariate_hypergeometric(
+            60,
+            &[Requirement {
+                successes: 4,
+                at_least: 1,
+            }],
+            7,
+        ),
+        hypergeometric_cdf(60, 4, 7, 1),
+    );
+
+    // At least 1 of a 4-of AND 2 of 24 lands in 9 cards.
+    close(
+        multivariate_hypergeometric(
+            60,
+            &[
+                Requirement {
+                    successes: 4,
+                    at_least: 1,
+                },
+                Requirement {
+                    successes: 24,
+                    at_least: 2,
+                },
+            ],
+            9,
+        ),
+        0.451_004_970_549_340_6,
+    );
+
+    let overfull = [Requirement {
+        successes: 61,
+        at_least: 1,
+    }];
+    close(multivariate_hypergeometric(60, &overfull, 7), 0.0);
+}
+
+fn card(types: &[CardType], colors: ColorSet) -> OracleCard {
+    OracleCard {
+        oracle_id: Uuid::new_v4(),
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: "Test".into(),
+            mana_cost: None,
+            type_line: TypeLine {
+                card_types: types.iter().cloned().collect(),
+                ..TypeLine::default()
+            },
+            oracle_text: OracleText::default(),
+            colors,
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: colors,
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    }
+}
+
+fn entry(quantity: u32, resolved: Option<Uuid>) -> DeckEntry {
+    DeckEntry {
+        quantity,
+        card_name: "Test".into(),
+        resolved,
+        set_hint: None,
+        collector_number: None,
+        foil: false,
+    }
+}
+
+#[test]
+fn test_deck_draw_odds() {
+    let land = card(&[CardType::Land], ColorSet::empty());
+    let bolt = card(&[CardType::Instant], ColorSet::RED);
+    let bear = card(&[CardType::Creature], ColorSet::GREEN);
+    let deck = Deck {
+        name: None,
+        format: None,
+        mainboard: vec![
+            entry(24, Some(land.oracle_id)),
+            entry(4, Some(bolt.oracle_id)),
+            entry(28, Some(bear.oracle_id)),
+            entry(4, None),
+        ],
+        sideboard: vec![],
+        commander: vec![],
+        companion: None,
+    };
+    let cards: HashMap<Uuid, OracleCard> = [land, bolt, bear]
+        .into_iter()
+        .map(|c| (c.oracle_id, c))
+        .collect();
+
+    let is_land = |c: &OracleCard| c.primary_face().type_line.is_land();
+    let is_red = |c: &OracleCard| c.color_identity.contains(ColorSet::RED);
+
+    assert_eq!(count_matching(&deck, &cards, is_land), 24);
+    assert_eq!(cards_seen(2, true), 8);
+    assert_eq!(cards_seen(2, false), 9);
+
+    // Two lands by turn 2 on the play.
+    close(
+        draw_odds(&deck, &cards, is_land, 2, 2, true),
+        0.909_871_499_542_168_4,
+    );
+    assert!(
+        draw_odds(&deck, &cards, is_land, 2, 2, false)
+            > draw_odds(&deck, &cards, is_land, 2, 2, true)
+    );
+
+    let requirements: [CardRequirement<'_>; 2] = [(&is_red, 1), (&is_land, 2)];
+    close(
+        draw_odds_all(&deck, &cards, &requirements, 2, false),
+        0.451_004_970_549_340_6,
+    );
+}
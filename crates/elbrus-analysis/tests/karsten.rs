@@ -0,0 +1,184 @@
+use common::{generic, oracle};
+use elbrus_analysis::{
+    ManaBaseAnalysis, color_source_report, lands_for_target, pip_groups, recommended_land_count,
+    sources_needed,
+};
+use elbrus_core::{
+    CardFace, CardLayout, Color, ColorSet, ManaCost, ManaSymbol, OracleCard, oracle::OracleText,
+    types::TypeLine,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use smallvec::smallvec;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use ManaSymbol::{Colored, Hybrid, Phyrexian};
+
+mod common;
+
+fn face(name: &str, type_line: &str, cost: &[ManaSymbol]) -> CardFace {
+    CardFace {
+        name: name.into(),
+        mana_cost: (!cost.is_empty()).then(|| ManaCost(cost.iter().cloned().collect())),
+        type_line: TypeLine::parse(type_line).unwrap(),
+        oracle_text: OracleText::default(),
+        colors: ColorSet::empty(),
+        power: None,
+        toughness: None,
+        loyalty: None,
+        defense: None,
+        flavor_text: None,
+    }
+}
+
+#[test]
+fn test_sources_needed() {
+    assert_eq!(sources_needed(60, 1, 1, true, 0.9), Some(16));
+    assert_eq!(sources_needed(60, 2, 2, true, 0.9), Some(24));
+    assert_eq!(sources_needed(60, 2, 2, false, 0.9), Some(22));
+    assert_eq!(sources_needed(60, 0, 1, true, 0.9), Some(0));
+    // Eight pips can't be seen in a seven-card hand.
+    assert_eq!(sources_needed(60, 8, 1, true, 0.9), None);
+    assert_eq!(lands_for_target(60, 3, 3, true, 0.9), Some(29));
+}
+
+#[test]
+fn test_pip_groups() {
+    let cost = ManaCost(smallvec![
+        generic(1),
+        Colored(Color::G),
+        Hybrid(Color::G, Color::W),
+        Phyrexian(Color::B),
+    ]);
+    assert_eq!(
+        pip_groups(&cost),
+        vec![(ColorSet::GREEN, 1), (ColorSet::GREEN | ColorSet::WHITE, 2)]
+    );
+}
+
+#[test]
+fn test_recommended_land_count() {
+    assert_eq!(recommended_land_count(60, 2.0, 0), 23);
+    assert_eq!(recommended_land_count(60, 2.0, 8), 21);
+    assert_eq!(recommended_land_count(40, 3.0, 0), 17);
+    assert_eq!(recommended_land_count(100, 3.0, 10), 38);
+    assert_eq!(recommended_land_count(10, 30.0, 0), 10);
+}
+
+#[test]
+fn test_color_source_report() {
+    let forest = oracle(
+        CardLayout::Normal,
+        vec![face("Forest", "Basic Land — Forest", &[])],
+    );
+    let plains = oracle(
+        CardLayout::Normal,
+        vec![face("Plains", "Basic Land — Plains", &[])],
+    );
+    let elves = oracle(
+        CardLayout::Normal,
+        vec![face("Elves", "Creature — Elf", &[Colored(Color::G)])],
+    );
+    let double_white = oracle(
+        CardLayout::Normal,
+        vec![face(
+            "Knight",
+            "Creature — Knight",
+            &[Colored(Color::W), Colored(Color::W)],
+        )],
+    );
+    let finks = oracle(
+        CardLayout::Normal,
+        vec![face(
+            "Finks",
+            "Creature — Ouphe",
+            &[
+                generic(1),
+                Hybrid(Color::G, Color::W),
+                Hybrid(Color::G, Color::W),
+            ],
+        )],
+    );
+    let split = oracle(
+        CardLayout::Split,
+        vec![
+            face("Grow", "Sorcery", &[generic(1), Colored(Color::G)]),
+            face("Ice", "Instant", &[generic(1), Colored(Color::U)]),
+        ],
+    );
+    let dismember = oracle(
+        CardLayout::Normal,
+        vec![face(
+            "Dismember",
+            "Instant",
+            &[generic(1), Phyrexian(Color::B), Phyrexian(Color::B)],
+        )],
+    );
+
+    let counts = [
+        (&forest, 16),
+        (&plains, 8),
+        (&elves, 4),
+        (&double_white, 4),
+        (&finks, 4),
+        (&split, 4),
+        (&dismember, 2),
+    ];
+    let mut mainboard: Vec<DeckEntry> = counts
+        .iter()
+        .map(|(c, quantity)| DeckEntry {
+            quantity: *quantity,
+            card_name: c.name().into(),
+            resolved: Some(c.oracle_id),
+            set_hint: None,
+            collector_number: None,
+            foil: false,
+        })
+        .collect();
+    mainboard.push(DeckEntry {
+        quantity: 18,
+        card_name: "Unresolved".into(),
+        resolved: None,
+        set_hint: None,
+        collector_number: None,
+        foil: false,
+    });
+    let deck = Deck {
+        name: None,
+        format: None,
+        mainboard,
+        sideboard: vec![],
+        commander: vec![],
+        companion: None,
+    };
+    let cards: HashMap<Uuid, OracleCard> = counts
+        .iter()
+        .map(|(c, _)| (c.oracle_id, (*c).clone()))
+        .collect();
+
+    let report = color_source_report(&deck, &cards, true);
+    assert_eq!(report.deck_size, 60);
+    // Each split half is checked on its own.
+    assert_eq!(report.checks.len(), 6);
+
+    let check = |name: &str| report.checks.iter().find(|c| &*c.face == name).unwrap();
+    let knight = check("Knight");
+    assert_eq!(knight.turn, 2);
+    assert_eq!(knight.requirements[0].needed, Some(24));
+    assert_eq!(knight.requirements[0].available, 8);
+
+    let finks = check("Finks");
+    assert_eq!(finks.requirements.len(), 1);
+    assert_eq!(finks.requirements[0].needed, Some(22));
+    assert_eq!(finks.requirements[0].available, 24);
+    assert!(check("Dismember").requirements.is_empty());
+
+    let flagged: Vec<&str> = report.under_supported().map(|c| &*c.face).collect();
+    assert_eq!(flagged, vec!["Knight", "Ice"]);
+
+    let analysis = ManaBaseAnalysis::analyze(&deck, &cards);
+    assert_eq!(
+        analysis.recommended_lands,
+        recommended_land_count(60, analysis.avg_cmc, 0)
+    );
+}
@@ -0,0 +1,225 @@
+use common::{close, generic, oracle};
+use elbrus_analysis::{ManaBaseAnalysis, hypergeometric_cdf};
+use elbrus_core::{
+    CardFace, CardLayout, CardType, Color, ColorSet, ManaCost, ManaSymbol, OracleCard, Supertype,
+    VarSym, oracle::OracleText, oracle::OracleTextSegment, types::TypeLine,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use smallvec::smallvec;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use ManaSymbol::{Colored, Hybrid, Phyrexian};
+
+mod common;
+
+fn face(type_line: &str, cost: &[ManaSymbol], text: &str) -> CardFace {
+    CardFace {
+        name: "Test".into(),
+        mana_cost: (!cost.is_empty()).then(|| ManaCost(cost.iter().cloned().collect())),
+        type_line: TypeLine::parse(type_line).unwrap(),
+        oracle_text: OracleText(vec![OracleTextSegment::Text(text.into())]),
+        colors: ColorSet::empty(),
+        power: None,
+        toughness: None,
+        loyalty: None,
+        defense: None,
+        flavor_text: None,
+    }
+}
+
+fn normal(type_line: &str, cost: &[ManaSymbol], text: &str) -> OracleCard {
+    oracle(CardLayout::Normal, vec![face(type_line, cost, text)])
+}
+
+#[test]
+fn test_type_line_and_mana_value() {
+    let line = TypeLine::parse("Legendary Kindred Creature — Elf Druid").unwrap();
+    assert_eq!(line.supertypes.as_slice(), &[Supertype::Legendary]);
+    assert_eq!(
+        line.card_types.as_slice(),
+        &[CardType::Tribal, CardType::Creature]
+    );
+    assert_eq!(line.subtypes.len(), 2);
+    assert!(TypeLine::parse("Basic Land — Forest").unwrap().is_land());
+    assert!(TypeLine::parse("  ").is_err());
+
+    let cost = ManaCost(smallvec![
+        ManaSymbol::Variable(VarSym::X),
+        generic(2),
+        ManaSymbol::TwoBrid(Color::W),
+        Hybrid(Color::U, Color::B),
+        Phyrexian(Color::R),
+    ]);
+    assert_eq!(cost.cmc(), 6.0);
+    assert_eq!(
+        cost.color_identity(),
+        ColorSet::WHITE | ColorSet::BLUE | ColorSet::BLACK | ColorSet::RED
+    );
+}
+
+#[test]
+fn test_mana_base_analysis() {
+    let forest = normal("Basic Land — Forest", &[], "({T}: Add {G}.)");
+    let mountain = normal("Basic Land — Mountain", &[], "({T}: Add {R}.)");
+    let wilds = normal(
+        "Land",
+        &[],
+        "{T}, Sacrifice Evolving Wilds: Search your library for a basic land card, \
+         put it onto the battlefield tapped, then shuffle.",
+    );
+    let shock = normal(
+        "Land — Mountain Forest",
+        &[],
+        "As Stomping Ground enters, you may pay 2 life.",
+    );
+    let tower = normal(
+        "Land",
+        &[],
+        "{T}: Add one mana of any color in your commander's color identity.",
+    );
+    let mut azorius = normal("Land", &[], "");
+    azorius.faces[0].oracle_text = OracleText(vec![
+        OracleTextSegment::Text("{T}: Add".into()),
+        OracleTextSegment::ManaCost(ManaCost(smallvec![Colored(Color::W)])),
+        OracleTextSegment::Text(" or ".into()),
+        OracleTextSegment::ManaCost(ManaCost(smallvec![Colored(Color::U)])),
+        OracleTextSegment::Text(".".into()),
+    ]);
+    let valakut = oracle(
+        CardLayout::ModalDfc,
+        vec![
+            face("Instant", &[generic(2), Colored(Color::R)], "Draw cards."),
+            face(
+                "Land",
+                &[],
+                "As it enters, you may pay 3 life.\n{T}: Add {R}.",
+            ),
+        ],
+    );
+
+    let bolt = normal("Instant", &[Colored(Color::R)], "Deal 3 damage.");
+    let fire_ice = oracle(
+        CardLayout::Split,
+        vec![
+            face("Instant", &[generic(1), Colored(Color::R)], "Fire."),
+            face("Instant", &[generic(1), Colored(Color::U)], "Ice."),
+        ],
+    );
+    let giant = oracle(
+        CardLayout::Adventure,
+        vec![
+            face("Creature — Giant", &[generic(2), Colored(Color::R)], ""),
+            face("Instant — Adventure", &[generic(1), Colored(Color::R)], ""),
+        ],
+    );
+    let finks = normal(
+        "Creature — Ouphe",
+        &[
+            generic(1),
+            Hybrid(Color::G, Color::W),
+            Hybrid(Color::G, Color::W),
+        ],
+        "Persist",
+    );
+    // Mana creatures are not sources.
+    let elves = normal(
+        "Creature — Elf Druid",
+        &[Colored(Color::G)],
+        "{T}: Add {G}.",
+    );
+    let dismember = normal(
+        "Instant",
+        &[generic(1), Phyrexian(Color::B), Phyrexian(Color::B)],
+        "",
+    );
+
+    let counts = [
+        (&forest, 10),
+        (&mountain, 7),
+        (&wilds, 4),
+        (&shock, 2),
+        (&tower, 1),
+        (&azorius, 1),
+        (&valakut, 2),
+        (&bolt, 4),
+        (&fire_ice, 4),
+        (&giant, 4),
+        (&finks, 4),
+        (&elves, 4),
+        (&dismember, 2),
+    ];
+    let mut mainboard: Vec<DeckEntry> = counts
+        .iter()
+        .map(|(c, quantity)| DeckEntry {
+            quantity: *quantity,
+            card_name: "Test".into(),
+            resolved: Some(c.oracle_id),
+            set_hint: None,
+            collector_number: None,
+            foil: false,
+        })
+        .collect();
+    mainboard.push(DeckEntry {
+        quantity: 11,
+        card_name: "Unresolved".into(),
+        resolved: None,
+        set_hint: None,
+        collector_number: None,
+        foil: false,
+    });
+    let deck = Deck {
+        name: None,
+        format: None,
+        mainboard,
+        sideboard: vec![],
+        commander: vec![],
+        companion: None,
+    };
+    let cards: HashMap<Uuid, OracleCard> = counts
+        .iter()
+        .map(|(c, _)| (c.oracle_id, (*c).clone()))
+        .collect();
+
+    let analysis = ManaBaseAnalysis::analyze(&deck, &cards);
+    assert_eq!(analysis.deck_size, 60);
+    assert_eq!(analysis.land_count, 27);
+    assert_eq!(analysis.mdfc_lands, 2);
+
+    // Split and adventure cards sit at their cheapest half.
+    assert_eq!(analysis.curve.count(1), 8);
+    assert_eq!(analysis.curve.count(2), 8);
+    assert_eq!(analysis.curve.count(3), 6);
+    assert_eq!(analysis.curve.total(), 22);
+    close(analysis.avg_cmc, 42.0 / 22.0);
+
+    let pips = &analysis.color_requirements;
+    close(pips[&Color::R], 12.0);
+    close(pips[&Color::U], 4.0);
+    close(pips[&Color::G], 8.0);
+    close(pips[&Color::W], 4.0);
+    close(pips[&Color::B], 4.0);
+
+    let sources = &analysis.color_sources;
+    assert_eq!(sources[&Color::G], 17);
+    assert_eq!(sources[&Color::R], 16);
+    assert_eq!(sources[&Color::W], 2);
+    assert_eq!(sources[&Color::U], 2);
+    assert_eq!(sources[&Color::B], 1);
+
+    assert_eq!(analysis.on_curve_probability.len(), 7);
+    close(
+        analysis.on_curve_probability[0],
+        hypergeometric_cdf(60, 27, 7, 1),
+    );
+    close(
+        analysis.on_curve_probability[3],
+        hypergeometric_cdf(60, 27, 10, 4),
+    );
+    assert!(
+        analysis
+            .on_curve_probability
+            .windows(2)
+            .all(|w| w[0] >= w[1])
+    );
+}
@@ -0,0 +1,125 @@
+use chrono::NaiveDate;
+use common::{Pool, close};
+use elbrus_analysis::{
+    DatedDeck, SimilarityMetric, metagame_snapshot, staple_trends, staples_index,
+};
+use elbrus_core::Format;
+
+mod common;
+
+fn day(d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, d).unwrap()
+}
+
+#[test]
+fn test_staples_index() {
+    let mut pool = Pool::default();
+    let decks = vec![
+        pool.deck(
+            &[("Lightning Bolt", 4), ("Goblin Guide", 4), ("Mountain", 20)],
+            &[("Abrade", 2)],
+        ),
+        pool.deck(
+            &[("Lightning Bolt", 2), ("Abrade", 1), ("Mountain", 20)],
+            &[("Lightning Bolt", 2), ("Abrade", 2)],
+        ),
+        pool.deck(&[("Primeval Titan", 4), ("Forest", 20)], &[]),
+        pool.deck(&[("Lightning Bolt", 4)], &[]),
+    ];
+
+    let staples = staples_index(&decks);
+    let names: Vec<&str> = staples.iter().map(|s| &*s.name).collect();
+    assert_eq!(
+        names,
+        vec!["Lightning Bolt", "Abrade", "Goblin Guide", "Primeval Titan"]
+    );
+
+    let bolt = &staples[0];
+    assert_eq!((bolt.decks, bolt.main_decks, bolt.side_decks), (3, 3, 1));
+    close(bolt.inclusion_rate, 0.75);
+    close(bolt.avg_main_copies, 10.0 / 3.0);
+    close(bolt.avg_side_copies, 2.0);
+
+    let abrade = &staples[1];
+    assert_eq!(
+        (abrade.decks, abrade.main_decks, abrade.side_decks),
+        (2, 1, 2)
+    );
+    close(abrade.avg_side_copies, 2.0);
+
+    assert!(staples_index(&[]).is_empty());
+}
+
+#[test]
+fn test_snapshots_and_trends() {
+    let mut pool = Pool::default();
+    let burn = [("Lightning Bolt", 4), ("Goblin Guide", 4), ("Mountain", 20)];
+    let ramp = [("Llanowar Elves", 4), ("Primeval Titan", 4), ("Forest", 20)];
+    let decks = vec![
+        DatedDeck {
+            date: day(2),
+            deck: pool.deck(&burn, &[]),
+        },
+        DatedDeck {
+            date: day(3),
+            deck: pool.deck(&ramp, &[]),
+        },
+        DatedDeck {
+            date: day(9),
+            deck: pool.deck(&burn, &[]),
+        },
+        DatedDeck {
+            date: day(10),
+            deck: pool.deck(&burn, &[("Abrade", 2)]),
+        },
+        DatedDeck {
+            date: day(12),
+            deck: pool.deck(&ramp, &[]),
+        },
+        DatedDeck {
+            date: day(20),
+            deck: pool.deck(&ramp, &[]),
+        },
+    ];
+    let modern = Format("modern".into());
+
+    let first = metagame_snapshot(
+        modern.clone(),
+        day(1),
+        day(7),
+        &decks,
+        SimilarityMetric::WeightedJaccard,
+        0.5,
+    );
+    let second = metagame_snapshot(
+        modern,
+        day(8),
+        day(14),
+        &decks,
+        SimilarityMetric::WeightedJaccard,
+        0.5,
+    );
+    assert_eq!(first.deck_count, 2);
+    assert_eq!(second.deck_count, 3);
+    assert_eq!((second.start, second.end), (day(8), day(14)));
+
+    assert_eq!(second.archetypes.len(), 2);
+    assert_eq!(&*second.archetypes[0].name, "Goblin Guide / Lightning Bolt");
+    assert_eq!(second.archetypes[0].decks, 2);
+    close(second.archetypes[0].share, 2.0 / 3.0);
+
+    let trends = staple_trends(&first, &second);
+    assert_eq!(trends.len(), 5);
+    assert_eq!(&*trends[0].name, "Abrade");
+    close(trends[0].before, 0.0);
+    close(trends[0].change(), 1.0 / 3.0);
+    for trend in &trends[1..] {
+        close(trend.change().abs(), 1.0 / 6.0);
+    }
+    let bolt = trends
+        .iter()
+        .find(|t| &*t.name == "Lightning Bolt")
+        .unwrap();
+    close(bolt.before, 0.5);
+    close(bolt.after, 2.0 / 3.0);
+}
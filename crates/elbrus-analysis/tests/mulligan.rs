@@ -0,0 +1,136 @@
+use common::deck;
+use elbrus_analysis::{
+    DrawnCard, HasTwoDrop, KeepIf, KeepStrategy, LandWindow, MulliganSimulator, hypergeometric_pmf,
+    land_count,
+};
+use elbrus_core::{
+    CardFace, CardLayout, ColorSet, GenericCost, ManaCost, ManaSymbol, OracleCard,
+    legality::Legalities, oracle::OracleText, types::TypeLine,
+};
+use uuid::Uuid;
+
+mod common;
+
+fn card(type_line: &str, mana_value: u32) -> OracleCard {
+    OracleCard {
+        oracle_id: Uuid::new_v4(),
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: "Test".into(),
+            mana_cost: (mana_value > 0).then(|| {
+                ManaCost(smallvec::smallvec![ManaSymbol::Generic(GenericCost::new(
+                    mana_value
+                ))])
+            }),
+            type_line: TypeLine::parse(type_line).unwrap(),
+            oracle_text: OracleText::default(),
+            colors: ColorSet::empty(),
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    }
+}
+
+#[test]
+fn test_land_window_matches_hypergeometric() {
+    let land = card("Basic Land — Forest", 0);
+    let spell = card("Creature — Bear", 3);
+    let (deck, cards) = deck(&[(&land, 24), (&spell, 36)]);
+    let simulator = MulliganSimulator::new(&deck, &cards);
+
+    let report = simulator.run(&LandWindow::default(), 20_000, true, 7);
+    let expected: f64 = (2..=5).map(|k| hypergeometric_pmf(60, 24, 7, k)).sum();
+    assert!((report.keep_seven_rate() - expected).abs() < 0.01);
+
+    let depths = &report.depths;
+    assert_eq!(depths.len(), 4);
+    assert_eq!(depths[0].reached, 20_000);
+    assert_eq!(depths[1].reached, 20_000 - depths[0].kept);
+    assert_eq!(depths[3].keep_rate, 1.0);
+    assert_eq!(depths[2].hand_size, 5);
+    let kept: u32 = depths.iter().map(|d| d.kept).sum();
+    assert_eq!(kept, 20_000);
+    assert!(report.avg_hand_size > 6.5 && report.avg_hand_size < 7.0);
+}
+
+#[test]
+fn test_seeded_runs_are_reproducible() {
+    let land = card("Basic Land — Forest", 0);
+    let two = card("Creature — Bear", 2);
+    let four = card("Creature — Giant", 4);
+    let (deck, cards) = deck(&[(&land, 17), (&two, 8), (&four, 15)]);
+    let simulator = MulliganSimulator::new(&deck, &cards).with_max_mulligans(2);
+    let strategy = HasTwoDrop::default();
+
+    let play = simulator.run(&strategy, 1_000, true, 42);
+    assert_eq!(play, simulator.run(&strategy, 1_000, true, 42));
+    assert_ne!(play, simulator.run(&strategy, 1_000, true, 43));
+
+    let counts: Vec<(u32, u32)> = play.depths.iter().map(|d| (d.reached, d.kept)).collect();
+    assert_eq!(counts, vec![(1_000, 724), (276, 196), (80, 80)]);
+    assert!((play.avg_hand_size - 6.644).abs() < 1e-9);
+
+    // The built-ins ignore play/draw, but the strategy is told.
+    let on_draw =
+        KeepIf(|hand: &[DrawnCard<'_>], on_play: bool, _| !on_play && land_count(hand) >= 3);
+    let draw = simulator.run(&on_draw, 1_000, false, 42);
+    assert!(!draw.on_play);
+    assert!(draw.keep_seven_rate() > 0.0);
+    assert_eq!(
+        simulator.run(&on_draw, 1_000, true, 42).keep_seven_rate(),
+        0.0
+    );
+}
+
+#[test]
+fn test_default_bottom_balances_lands_and_spells() {
+    let land = card("Basic Land — Forest", 0);
+    let cheap = card("Instant", 1);
+    let pricey = card("Creature — Dragon", 6);
+    let strategy = LandWindow::default();
+
+    let flood: Vec<DrawnCard<'_>> = vec![
+        Some(&land),
+        Some(&land),
+        Some(&land),
+        Some(&land),
+        Some(&land),
+        Some(&cheap),
+        Some(&pricey),
+    ];
+    assert_eq!(strategy.bottom(&flood, 2), vec![0, 1]);
+
+    let curve: Vec<DrawnCard<'_>> = vec![
+        Some(&land),
+        Some(&pricey),
+        None,
+        Some(&cheap),
+        Some(&land),
+        Some(&land),
+        Some(&cheap),
+    ];
+    // The unresolved card first, then the most expensive spell.
+    assert_eq!(strategy.bottom(&curve, 2), vec![2, 1]);
+}
+
+#[test]
+fn test_max_mulligans_is_capped_at_hand_size() {
+    let land = card("Basic Land — Forest", 0);
+    let (deck, cards) = deck(&[(&land, 40)]);
+    let simulator = MulliganSimulator::new(&deck, &cards).with_max_mulligans(20);
+    assert_eq!(simulator.max_mulligans(), 7);
+
+    let never = KeepIf(|_: &[DrawnCard<'_>], _, _| false);
+    let report = simulator.run(&never, 10, true, 1);
+    assert_eq!(report.depths.len(), 8);
+    assert_eq!(report.depths[7].hand_size, 0);
+    assert_eq!(report.depths[7].kept, 10);
+}
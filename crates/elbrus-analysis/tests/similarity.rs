@@ -0,0 +1,136 @@
+use common::card;
+use elbrus_analysis::{OracleIndex, SimilarFilter};
+use elbrus_core::{ColorSet, OracleCard};
+use uuid::Uuid;
+
+mod common;
+
+fn pool() -> Vec<OracleCard> {
+    let (r, g, u) = (ColorSet::RED, ColorSet::GREEN, ColorSet::BLUE);
+    vec![
+        card(
+            "Shock",
+            "{R}",
+            "Instant",
+            "Shock deals 2 damage to any target.",
+            r,
+        ),
+        card(
+            "Burst Lightning",
+            "{R}",
+            "Instant",
+            "Kicker {4} (You may pay an additional {4} as you cast this spell.)\n\
+             Burst Lightning deals 2 damage to any target. If this spell was kicked, it deals 4 damage instead.",
+            r,
+        ),
+        card(
+            "Lightning Strike",
+            "{1}{R}",
+            "Instant",
+            "Lightning Strike deals 3 damage to any target.",
+            r,
+        ),
+        card(
+            "Lava Axe",
+            "{4}{R}",
+            "Sorcery",
+            "Lava Axe deals 5 damage to target player or planeswalker.",
+            r,
+        ),
+        card(
+            "Llanowar Elves",
+            "{G}",
+            "Creature — Elf Druid",
+            "{T}: Add {G}.",
+            g,
+        ),
+        card(
+            "Elvish Mystic",
+            "{G}",
+            "Creature — Elf Druid",
+            "{T}: Add {G}.",
+            g,
+        ),
+        card(
+            "Fyndhorn Elves",
+            "{G}",
+            "Creature — Elf Druid",
+            "{T}: Add {G}.",
+            g,
+        ),
+        card(
+            "Counterspell",
+            "{U}{U}",
+            "Instant",
+            "Counter target spell.",
+            u,
+        ),
+        card("Divination", "{2}{U}", "Sorcery", "Draw two cards.", u),
+        card(
+            "Electrostatic Field",
+            "{1}{R}",
+            "Creature — Wall",
+            "Defender\nWhenever you cast an instant or sorcery spell, this creature deals 1 damage to each opponent.",
+            r,
+        ),
+    ]
+}
+
+#[test]
+fn test_similar_cards() {
+    let cards = pool();
+    let id = |name: &str| cards.iter().find(|c| c.name() == name).unwrap().oracle_id;
+    let index = OracleIndex::build(&cards);
+    assert_eq!(index.len(), cards.len());
+
+    let similar = index.similar_cards(id("Shock"), 3, &SimilarFilter::default());
+    let names: Vec<&str> = similar.iter().map(|c| &*c.name).collect();
+    assert_eq!(names[..2], ["Burst Lightning", "Lightning Strike"]);
+    assert!(similar.windows(2).all(|w| w[0].score >= w[1].score));
+    assert!(similar[0].score > 0.5);
+
+    // Functional reprints score almost perfectly despite different names.
+    let mystic = index
+        .similarity(id("Llanowar Elves"), id("Elvish Mystic"))
+        .unwrap();
+    assert!(mystic > 0.99, "{mystic}");
+    let elves = index.similar_cards(id("Llanowar Elves"), 2, &SimilarFilter::default());
+    assert_eq!(&*elves[1].name, "Fyndhorn Elves");
+    assert!(index.similarity(id("Shock"), id("Divination")).unwrap() < 0.2);
+
+    let filter = SimilarFilter {
+        within_colors: Some(ColorSet::BLUE),
+        ..SimilarFilter::default()
+    };
+    let blue: Vec<_> = index.similar_cards(id("Shock"), 10, &filter);
+    assert_eq!(blue.len(), 2);
+    assert_eq!(&*blue[0].name, "Counterspell");
+
+    let filter = SimilarFilter {
+        same_card_type: true,
+        max_mana_value: Some(2),
+        ..SimilarFilter::default()
+    };
+    let cheap_instants = index.similar_cards(id("Shock"), 10, &filter);
+    assert!(cheap_instants.iter().all(|c| c.name.as_ref() != "Lava Axe"));
+    assert_eq!(cheap_instants.len(), 3);
+
+    assert!(
+        index
+            .similar_cards(Uuid::new_v4(), 5, &SimilarFilter::default())
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_index_round_trip() {
+    let cards = pool();
+    let index = OracleIndex::build(&cards);
+    let json = serde_json::to_string(&index).unwrap();
+    let loaded: OracleIndex = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, index);
+    assert_eq!(
+        loaded.similar_cards(cards[0].oracle_id, 3, &SimilarFilter::default()),
+        index.similar_cards(cards[0].oracle_id, 3, &SimilarFilter::default())
+    );
+}
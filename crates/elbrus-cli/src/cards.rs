@@ -0,0 +1,101 @@
+use crate::{DbArgs, open_db};
+use clap::Subcommand;
+use elbrus_analysis::{OracleIndex, SimilarFilter};
+use elbrus_core::{Color, ColorSet};
+use elbrus_db::repo::CardRepository;
+use elbrus_deck::DeckResolver;
+use std::path::PathBuf;
+
+#[derive(Subcommand)]
+pub enum CardsCommand {
+    /// Build the oracle-text similarity index from the database
+    Index {
+        /// Where to write the index as JSON
+        #[arg(short, long, default_value = "oracle-index.json")]
+        out: PathBuf,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Find cards that play like a given card
+    Similar {
+        /// Card name
+        name: String,
+
+        /// Number of cards to show
+        #[arg(short, default_value_t = 10)]
+        k: usize,
+
+        /// Only cards within this color identity, e.g. `RG`
+        #[arg(long, value_parser = parse_colors)]
+        colors: Option<ColorSet>,
+
+        /// Only cards with at most this mana value
+        #[arg(long)]
+        max_mana_value: Option<u32>,
+
+        /// Only cards sharing a card type with the given card
+        #[arg(long)]
+        same_type: bool,
+
+        /// Prebuilt index from `cards index`; built from the database if omitted
+        #[arg(long)]
+        index: Option<PathBuf>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+}
+
+pub(crate) fn parse_colors(s: &str) -> Result<ColorSet, String> {
+    s.chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'W' => Ok(Color::W),
+            'U' => Ok(Color::U),
+            'B' => Ok(Color::B),
+            'R' => Ok(Color::R),
+            'G' => Ok(Color::G),
+            _ => Err(format!("unknown color `{c}` (expected letters from WUBRG)")),
+        })
+        .try_fold(ColorSet::empty(), |set, color| Ok(set | color?.into()))
+}
+
+pub async fn run(command: CardsCommand) -> anyhow::Result<()> {
+    match command {
+        CardsCommand::Index { out, db } => {
+            let backend = open_db(&db.db).await?;
+            let index = OracleIndex::build(&backend.all_oracle_cards().await?);
+            std::fs::write(&out, serde_json::to_vec(&index)?)?;
+            println!("Indexed {} cards into {}", index.len(), out.display());
+        }
+        CardsCommand::Similar {
+            name,
+            k,
+            colors,
+            max_mana_value,
+            same_type,
+            index,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let Some(oracle_id) = DeckResolver::new(&backend).lookup(&name).await? else {
+                anyhow::bail!("no card named `{name}`");
+            };
+            let index = match index {
+                Some(path) => serde_json::from_slice(&std::fs::read(path)?)?,
+                None => OracleIndex::build(&backend.all_oracle_cards().await?),
+            };
+
+            let filter = SimilarFilter {
+                within_colors: colors,
+                max_mana_value,
+                same_card_type: same_type,
+            };
+            for card in index.similar_cards(oracle_id, k, &filter) {
+                println!("  {:.3}  {}", card.score, card.name);
+            }
+        }
+    }
+
+    Ok(())
+}
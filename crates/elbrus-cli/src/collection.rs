@@ -0,0 +1,351 @@
+use crate::{DbArgs, describe_printing, open_db, parse_currency};
+use clap::Subcommand;
+use elbrus_collection::{
+    CollectionValuation, CompletionBreakdown, ProposedTrade, Valuator, apply_trade, evaluate_trade,
+    set_completion, trade_binder,
+};
+use elbrus_core::{Condition, Currency, TradeCard};
+use elbrus_db::repo::{CollectionRepository, TradeRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub enum CollectionCommand {
+    /// Show how much of a set is owned and what is missing
+    Completion {
+        /// Set code, e.g. DMU
+        set: String,
+
+        /// Only count this collection (defaults to all collections)
+        #[arg(short, long)]
+        collection: Option<Uuid>,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        /// List missing cards
+        #[arg(short, long)]
+        missing: bool,
+
+        /// Only list missing cards found in boosters
+        #[arg(long, requires = "missing")]
+        booster: bool,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Put an entry in the trade binder, or take it out with --off
+    Tradeable {
+        collection: Uuid,
+        printing_id: Uuid,
+
+        #[arg(long, default_value = "nm", value_parser = parse_condition)]
+        condition: Condition,
+
+        #[arg(long)]
+        foil: bool,
+
+        /// The etched foil finish; implies --foil
+        #[arg(long)]
+        etched: bool,
+
+        #[arg(long)]
+        off: bool,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// List the trade binder with values
+    Binder {
+        collection: Uuid,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Evaluate a trade, and apply it with --apply.
+    ///
+    /// Cards are given as PRINTING_ID[:QTY[:CONDITION[:FINISH]]], e.g.
+    /// `<uuid>:2:lp:foil`. Conditions are nm, lp, mp, hp and dmg; finishes
+    /// are nonfoil, foil and etched.
+    Trade {
+        collection: Uuid,
+
+        /// Card given away (repeatable)
+        #[arg(long = "give", value_parser = parse_trade_card)]
+        given: Vec<TradeCard>,
+
+        /// Card received (repeatable)
+        #[arg(long = "get", value_parser = parse_trade_card)]
+        received: Vec<TradeCard>,
+
+        /// Update the collection and record the trade in the ledger
+        #[arg(long)]
+        apply: bool,
+
+        #[arg(long)]
+        note: Option<String>,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Show the trade ledger
+    Trades {
+        collection: Uuid,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+}
+
+fn parse_condition(s: &str) -> Result<Condition, String> {
+    match s.to_lowercase().as_str() {
+        "nm" | "near_mint" => Ok(Condition::NearMint),
+        "lp" | "lightly_played" => Ok(Condition::LightlyPlayed),
+        "mp" | "moderately_played" => Ok(Condition::ModeratelyPlayed),
+        "hp" | "heavy_played" => Ok(Condition::HeavyPlayed),
+        "dmg" | "damaged" => Ok(Condition::Damaged),
+        _ => Err(format!(
+            "unknown condition `{s}` (expected nm, lp, mp, hp or dmg)"
+        )),
+    }
+}
+
+fn parse_trade_card(s: &str) -> Result<TradeCard, String> {
+    let mut parts = s.split(':');
+    let printing_id = parts
+        .next()
+        .and_then(|p| Uuid::parse_str(p).ok())
+        .ok_or_else(|| format!("`{s}` does not start with a printing id"))?;
+    let quantity = match parts.next() {
+        Some(q) => q.parse().map_err(|_| format!("bad quantity `{q}`"))?,
+        None => 1,
+    };
+    let condition = match parts.next() {
+        Some(c) => parse_condition(c)?,
+        None => Condition::NearMint,
+    };
+    let (foil, etched) = match parts.next() {
+        None | Some("nonfoil") => (false, false),
+        Some("foil") => (true, false),
+        Some("etched") => (true, true),
+        Some(f) => {
+            return Err(format!(
+                "unknown finish `{f}` (expected nonfoil, foil or etched)"
+            ));
+        }
+    };
+    Ok(TradeCard {
+        printing_id,
+        quantity,
+        condition,
+        foil,
+        etched,
+    })
+}
+
+fn valuator(currency: Currency) -> Valuator {
+    Valuator {
+        currency,
+        ..Valuator::default()
+    }
+}
+
+async fn print_valuation(
+    backend: &SqliteBackend,
+    valuation: &CollectionValuation,
+) -> anyhow::Result<()> {
+    for entry in &valuation.entries {
+        println!(
+            "  {}x {} [{:?}{}]  {}",
+            entry.entry.quantity,
+            describe_printing(backend, entry.entry.printing_id).await?,
+            entry.entry.condition,
+            if entry.entry.foil { ", foil" } else { "" },
+            entry.value
+        );
+    }
+    for entry in &valuation.unpriced {
+        println!(
+            "  {}x {} [{:?}{}]  unpriced",
+            entry.quantity,
+            describe_printing(backend, entry.printing_id).await?,
+            entry.condition,
+            if entry.foil { ", foil" } else { "" },
+        );
+    }
+    println!("  Total: {} {:?}", valuation.total, valuation.currency);
+    Ok(())
+}
+
+pub async fn run(command: CollectionCommand) -> anyhow::Result<()> {
+    match command {
+        CollectionCommand::Completion {
+            set,
+            collection,
+            currency,
+            missing,
+            booster,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let completion =
+                set_completion(&backend, &set, collection, &valuator(currency)).await?;
+            let code = completion.set_code.to_uppercase();
+
+            print_breakdown(&format!("{code} (all printings)"), &completion.all);
+            print_breakdown(&format!("{code} (booster)"), &completion.booster);
+
+            if missing {
+                println!();
+                for card in completion.missing.iter().filter(|c| c.booster || !booster) {
+                    println!(
+                        "{:>6}  {:<40} {:<9} {}",
+                        card.collector_number,
+                        card.name,
+                        format!("{:?}", card.rarity),
+                        card.price.map_or_else(|| "-".to_owned(), |p| p.to_string()),
+                    );
+                }
+            }
+        }
+        CollectionCommand::Tradeable {
+            collection,
+            printing_id,
+            condition,
+            foil,
+            etched,
+            off,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            if !backend
+                .set_tradeable(
+                    collection,
+                    printing_id,
+                    condition,
+                    foil || etched,
+                    etched,
+                    !off,
+                )
+                .await?
+            {
+                anyhow::bail!(
+                    "No {condition:?} entry for {printing_id} in collection {collection}"
+                );
+            }
+        }
+        CollectionCommand::Binder {
+            collection,
+            currency,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let binder = trade_binder(&backend, collection, &valuator(currency)).await?;
+            println!("Trade binder:");
+            print_valuation(&backend, &binder).await?;
+        }
+        CollectionCommand::Trade {
+            collection,
+            given,
+            received,
+            apply,
+            note,
+            currency,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let trade = ProposedTrade {
+                collection_id: collection,
+                given,
+                received,
+                notes: note.map(Into::into),
+            };
+            let valuator = valuator(currency);
+            let evaluation = evaluate_trade(&backend, &valuator, &trade).await?;
+
+            println!("Giving:");
+            print_valuation(&backend, &evaluation.given).await?;
+            println!("Receiving:");
+            print_valuation(&backend, &evaluation.received).await?;
+            print!("Delta: {:+}", evaluation.delta);
+            if evaluation.has_unpriced() {
+                print!(" (some cards unpriced)");
+            }
+            println!();
+            for card in &evaluation.unavailable {
+                println!(
+                    "Not in trade binder: {}x {}",
+                    card.quantity,
+                    describe_printing(&backend, card.printing_id).await?
+                );
+            }
+
+            if apply {
+                let (record, _) = apply_trade(&backend, &valuator, &trade).await?;
+                println!("Trade {} recorded", record.id);
+            }
+        }
+        CollectionCommand::Trades { collection, db } => {
+            let backend = open_db(&db.db).await?;
+            for trade in backend.list_trades(collection).await? {
+                println!(
+                    "{}  gave {} / got {} {:?}  ({:+}){}",
+                    trade.traded_at.format("%Y-%m-%d %H:%M"),
+                    trade.given_value,
+                    trade.received_value,
+                    trade.currency,
+                    trade.received_value - trade.given_value,
+                    trade.notes.map(|n| format!("  {n}")).unwrap_or_default(),
+                );
+                for (side, cards) in [("-", &trade.given), ("+", &trade.received)] {
+                    for card in cards {
+                        println!(
+                            "  {side} {}x {}",
+                            card.quantity,
+                            describe_printing(&backend, card.printing_id).await?
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_breakdown(label: &str, breakdown: &CompletionBreakdown) {
+    let cards = breakdown.cards;
+    println!(
+        "{label}: {}/{} ({:.1}%), missing {}",
+        cards.owned,
+        cards.total,
+        cards.percent(),
+        cards.missing()
+    );
+    for (rarity, tally) in &breakdown.by_rarity {
+        println!(
+            "  {:<9} {}/{}",
+            format!("{rarity:?}"),
+            tally.owned,
+            tally.total
+        );
+    }
+    println!(
+        "  Non-foil  {}/{}\n  Foil      {}/{}",
+        breakdown.nonfoil.owned,
+        breakdown.nonfoil.total,
+        breakdown.foil.owned,
+        breakdown.foil.total
+    );
+    print!("  Cost to complete: {}", breakdown.cost_to_complete);
+    if breakdown.unpriced_missing > 0 {
+        print!(" (+{} unpriced)", breakdown.unpriced_missing);
+    }
+    println!();
+}
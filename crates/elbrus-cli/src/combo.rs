@@ -0,0 +1,243 @@
+use crate::cards::parse_colors;
+use crate::{DbArgs, open_db};
+use clap::Subcommand;
+use elbrus_combos::{
+    ComboDatabase, ComboFilter, SpellbookIngestor, SuggestOptions, combo_bracket, suggest_combos,
+};
+use elbrus_core::{ColorSet, Combo, Format, OracleCard};
+use elbrus_db::repo::{CardRepository, CollectionRepository};
+use elbrus_deck::{DeckResolver, parse_deck};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub enum ComboCommand {
+    /// Import combos from a Commander Spellbook `variants.json` export
+    Ingest {
+        /// Path to the export; cards must already be ingested
+        path: PathBuf,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Find the combos in a decklist or card pool, and those it is close to
+    Check {
+        /// Arena or MTGO decklist
+        path: PathBuf,
+
+        /// Also list combos missing up to this many pieces
+        #[arg(long, default_value_t = 1)]
+        near: usize,
+
+        /// Only combos within this color identity, e.g. `UB`; defaults to the
+        /// commander's identity for Commander decks
+        #[arg(long, value_parser = parse_colors)]
+        colors: Option<ColorSet>,
+
+        /// Only combos legal in this format, e.g. `commander`
+        #[arg(long)]
+        format: Option<String>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Suggest cards to add to a Commander deck to enable combos
+    Suggest {
+        /// Arena or MTGO decklist with a commander
+        path: PathBuf,
+
+        /// Most cards a suggested combo may need
+        #[arg(long, default_value_t = 2)]
+        max_missing: usize,
+
+        /// Leave out combos above this Commander bracket, and flag the deck's
+        /// own combos above it
+        #[arg(long)]
+        max_bracket: Option<u8>,
+
+        /// Number of suggestions to show
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+}
+
+fn describe(combo: &Combo) -> String {
+    let pieces: Vec<&str> = combo.pieces.iter().map(|p| &*p.name).collect();
+    let results: Vec<&str> = combo.results.iter().map(|r| &**r).collect();
+    format!("{} => {}", pieces.join(" + "), results.join(", "))
+}
+
+pub async fn run(command: ComboCommand) -> anyhow::Result<()> {
+    match command {
+        ComboCommand::Ingest { path, db } => {
+            let backend = open_db(&db.db).await?;
+            println!("Reading {}...", path.display());
+            let export = SpellbookIngestor::read_file(&path)?;
+            let stats = SpellbookIngestor::default()
+                .ingest_into_db(&export, &backend)
+                .await?;
+
+            println!("Ingest completed in {:?}", stats.duration);
+            println!("Variants processed: {}", stats.variants_processed);
+            println!("Combos stored: {}", stats.combos_stored);
+            println!("Unpublished variants: {}", stats.variants_unpublished);
+            println!("Skipped (unknown cards): {}", stats.skipped.len());
+            for skipped in stats.skipped.iter().take(10) {
+                println!("  {}: {}", skipped.id, skipped.unknown_cards.join(", "));
+            }
+            if stats.skipped.len() > 10 {
+                println!("  ... and {} more", stats.skipped.len() - 10);
+            }
+        }
+        ComboCommand::Check {
+            path,
+            near,
+            colors,
+            format,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let mut deck = parse_deck(&std::fs::read_to_string(&path)?)?;
+            let unresolved = DeckResolver::new(&backend).resolve(&mut deck).await?;
+            if !unresolved.is_empty() {
+                eprintln!("Unknown cards: {}", unresolved.join(", "));
+            }
+            let pool: Vec<Uuid> = deck.entries().filter_map(|e| e.resolved).collect();
+
+            let mut within_identity = colors;
+            if within_identity.is_none() && !deck.commander.is_empty() {
+                let mut identity = ColorSet::empty();
+                for id in deck.commander.iter().filter_map(|e| e.resolved) {
+                    if let Some(card) = backend.get_oracle(id).await? {
+                        identity |= card.color_identity;
+                    }
+                }
+                within_identity = Some(identity);
+            }
+            let legal_cards = match format {
+                Some(format) => Some(
+                    backend
+                        .legal_in_format(&Format(format.to_lowercase().into()))
+                        .await?
+                        .into_iter()
+                        .map(|c| c.oracle_id)
+                        .collect::<HashSet<_>>(),
+                ),
+                None => None,
+            };
+            let filter = ComboFilter {
+                within_identity,
+                legal_cards,
+            };
+
+            let combos = ComboDatabase::load(&backend).await?;
+            let enabled = combos.find_enabled_combos(&pool, &filter);
+            println!("{} combo(s) in {}:", enabled.len(), path.display());
+            for combo in enabled {
+                println!("  {}", describe(combo));
+            }
+
+            if near > 0 {
+                let owned = backend.owned_oracle_ids().await?;
+                let near = combos.find_near_combos(&pool, near, &filter, &owned);
+                println!("{} combo(s) within reach:", near.len());
+                for found in near {
+                    let missing: Vec<String> = found
+                        .combo
+                        .pieces
+                        .iter()
+                        .filter(|p| found.missing.contains(&p.oracle_id))
+                        .map(|p| {
+                            if found.owned.contains(&p.oracle_id) {
+                                format!("{} (owned)", p.name)
+                            } else {
+                                p.name.to_string()
+                            }
+                        })
+                        .collect();
+                    println!("  {}", describe(found.combo));
+                    println!("      missing: {}", missing.join(", "));
+                }
+            }
+        }
+        ComboCommand::Suggest {
+            path,
+            max_missing,
+            max_bracket,
+            top,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let mut deck = parse_deck(&std::fs::read_to_string(&path)?)?;
+            if deck.commander.is_empty() {
+                anyhow::bail!("{} has no commander", path.display());
+            }
+            let unresolved = DeckResolver::new(&backend).resolve(&mut deck).await?;
+            if !unresolved.is_empty() {
+                eprintln!("Unknown cards: {}", unresolved.join(", "));
+            }
+
+            let combos = ComboDatabase::load(&backend).await?;
+            let cards: HashMap<Uuid, OracleCard> = backend
+                .all_oracle_cards()
+                .await?
+                .into_iter()
+                .map(|c| (c.oracle_id, c))
+                .collect();
+            let name = |id: &Uuid| {
+                cards
+                    .get(id)
+                    .map_or_else(|| id.to_string(), |c| c.name().to_owned())
+            };
+
+            let pool: Vec<Uuid> = deck.entries().filter_map(|e| e.resolved).collect();
+            let enabled = combos.find_enabled_combos(&pool, &ComboFilter::default());
+            if !enabled.is_empty() {
+                println!("Combos already in the deck:");
+                for combo in enabled {
+                    let bracket = combo_bracket(combo, &cards);
+                    let flag = if max_bracket.is_some_and(|max| bracket > max) {
+                        "  [above bracket limit]"
+                    } else {
+                        ""
+                    };
+                    println!("  [{bracket}] {}{flag}", describe(combo));
+                }
+            }
+
+            let owned = backend.owned_oracle_ids().await?;
+            let options = SuggestOptions {
+                max_missing,
+                max_bracket,
+            };
+            let suggestions = suggest_combos(&combos, &deck, &cards, &owned, &options);
+            println!("Suggestions:");
+            for suggestion in suggestions.iter().take(top) {
+                let add: Vec<String> = suggestion
+                    .add
+                    .iter()
+                    .map(|id| {
+                        if suggestion.owned.contains(id) {
+                            format!("{} (owned)", name(id))
+                        } else {
+                            name(id)
+                        }
+                    })
+                    .collect();
+                println!(
+                    "  {:.2} [{}] add {}",
+                    suggestion.score,
+                    suggestion.bracket,
+                    add.join(", ")
+                );
+                println!("      {}", describe(suggestion.combo));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,442 @@
+use crate::{DbArgs, open_db, parse_currency};
+use chrono::NaiveDate;
+use clap::Subcommand;
+use elbrus_analysis::{
+    BracketLists, BudgetOptions, DatedDeck, OracleIndex, SimilarityMetric, budget_substitutions,
+    cluster_archetypes, estimate_bracket, metagame_snapshot, staple_trends,
+};
+use elbrus_core::{Currency, Format, MetagameSnapshot, OracleCard, StapleStat};
+use elbrus_db::repo::{CardRepository, MetagameRepository, PriceRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_deck::{Deck, DeckResolver, parse_deck};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub enum DeckCommand {
+    /// Cluster a directory of decklists into archetypes
+    Cluster {
+        /// Directory of Arena or MTGO decklists
+        dir: PathBuf,
+
+        /// Similarity measure: jaccard or cosine
+        #[arg(long, default_value = "jaccard", value_parser = parse_metric)]
+        metric: SimilarityMetric,
+
+        /// Minimum average similarity for decks to share an archetype
+        #[arg(long, default_value_t = 0.5)]
+        threshold: f64,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Build and store a metagame snapshot from dated decklists
+    ///
+    /// Each file name must contain the date the deck was played, e.g.
+    /// `2026-10-04-mono-red.txt`.
+    Metagame {
+        /// Directory of dated Arena or MTGO decklists
+        dir: PathBuf,
+
+        /// Format the decks were played in, e.g. `pioneer`
+        #[arg(long)]
+        format: String,
+
+        /// First day of the period, YYYY-MM-DD
+        #[arg(long)]
+        from: NaiveDate,
+
+        /// Last day of the period, YYYY-MM-DD
+        #[arg(long)]
+        to: NaiveDate,
+
+        /// Start of an earlier period to compare against
+        #[arg(long, requires = "compare_to")]
+        compare_from: Option<NaiveDate>,
+
+        /// End of the earlier period
+        #[arg(long, requires = "compare_from")]
+        compare_to: Option<NaiveDate>,
+
+        /// Similarity measure: jaccard or cosine
+        #[arg(long, default_value = "jaccard", value_parser = parse_metric)]
+        metric: SimilarityMetric,
+
+        /// Minimum average similarity for decks to share an archetype
+        #[arg(long, default_value_t = 0.5)]
+        threshold: f64,
+
+        /// Number of staples and trends to print
+        #[arg(long, default_value_t = 25)]
+        top: usize,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Estimate the Commander bracket of a decklist
+    Bracket {
+        /// Arena or MTGO decklist
+        path: PathBuf,
+
+        /// Bracket card lists to use instead of the bundled ones (JSON)
+        #[arg(long)]
+        lists: Option<PathBuf>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Suggest cheaper replacements for a deck's expensive cards
+    Budget {
+        /// Arena or MTGO decklist
+        path: PathBuf,
+
+        /// Replace every card costing more than this per copy
+        #[arg(long)]
+        max_card: Option<Decimal>,
+
+        /// Replace the most expensive cards until the deck costs at most this
+        #[arg(long)]
+        max_total: Option<Decimal>,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        /// Only suggest cards legal in this format, e.g. `commander`
+        #[arg(long)]
+        format: Option<String>,
+
+        /// Suggestions to list per card
+        #[arg(short, default_value_t = 3)]
+        k: usize,
+
+        /// Prebuilt index from `cards index`; built from the database if omitted
+        #[arg(long)]
+        index: Option<PathBuf>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Show the most played cards of a format's latest metagame snapshot
+    Staples {
+        /// Format, e.g. `pioneer`
+        #[arg(long)]
+        format: String,
+
+        /// Use the snapshot covering this date (YYYY-MM-DD) instead
+        #[arg(long)]
+        as_of: Option<NaiveDate>,
+
+        /// Number of cards to show
+        #[arg(long, default_value_t = 50)]
+        top: u32,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+}
+
+fn parse_metric(s: &str) -> Result<SimilarityMetric, String> {
+    match s.to_lowercase().as_str() {
+        "jaccard" => Ok(SimilarityMetric::WeightedJaccard),
+        "cosine" => Ok(SimilarityMetric::Cosine),
+        _ => Err(format!("unknown metric `{s}` (expected jaccard or cosine)")),
+    }
+}
+
+/// The first `YYYY-MM-DD` in a file name.
+fn file_date(path: &Path) -> Option<NaiveDate> {
+    let name = path.file_name()?.to_str()?;
+    (0..name.len().saturating_sub(9))
+        .filter_map(|i| name.get(i..i + 10))
+        .find_map(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+}
+
+/// Parse and resolve every decklist in `dir`, in file name order, warning
+/// about files that don't parse and cards that don't resolve.
+async fn load_decks(
+    dir: &Path,
+    resolver: &mut DeckResolver<'_, SqliteBackend>,
+) -> anyhow::Result<Vec<(PathBuf, Deck)>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .map(|e| e.map(|e| e.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|p| p.is_file());
+    paths.sort();
+
+    let mut decks = Vec::new();
+    for path in paths {
+        let mut deck = match parse_deck(&std::fs::read_to_string(&path)?) {
+            Ok(deck) => deck,
+            Err(e) => {
+                eprintln!("Skipping {}: {e}", path.display());
+                continue;
+            }
+        };
+        let unresolved = resolver.resolve(&mut deck).await?;
+        if !unresolved.is_empty() {
+            eprintln!(
+                "{}: unknown cards: {}",
+                path.display(),
+                unresolved.join(", ")
+            );
+        }
+        decks.push((path, deck));
+    }
+    Ok(decks)
+}
+
+/// The oracle cards a resolved deck refers to, by oracle id.
+async fn deck_cards(
+    backend: &SqliteBackend,
+    deck: &Deck,
+) -> anyhow::Result<HashMap<Uuid, OracleCard>> {
+    let mut cards = HashMap::new();
+    for id in deck.entries().filter_map(|e| e.resolved) {
+        if !cards.contains_key(&id)
+            && let Some(card) = backend.get_oracle(id).await?
+        {
+            cards.insert(id, card);
+        }
+    }
+    Ok(cards)
+}
+
+fn print_staples(staples: &[StapleStat]) {
+    println!("  {:>6}  {:>5}  {:>5}  Card", "Decks", "Main", "Side");
+    for s in staples {
+        let avg = |decks: u32, copies: f64| {
+            if decks == 0 {
+                "-".to_owned()
+            } else {
+                format!("{copies:.1}")
+            }
+        };
+        println!(
+            "  {:>5.1}%  {:>5}  {:>5}  {}",
+            s.inclusion_rate * 100.0,
+            avg(s.main_decks, s.avg_main_copies),
+            avg(s.side_decks, s.avg_side_copies),
+            s.name
+        );
+    }
+}
+
+fn print_snapshot(snapshot: &MetagameSnapshot, top: usize) {
+    println!(
+        "{} metagame, {} to {}: {} decks",
+        snapshot.format.0, snapshot.start, snapshot.end, snapshot.deck_count
+    );
+    println!("\nArchetypes:");
+    for a in &snapshot.archetypes {
+        let name = if a.name.is_empty() {
+            "(unnamed)"
+        } else {
+            &a.name
+        };
+        println!("  {:>5.1}%  {:>4}  {name}", a.share * 100.0, a.decks);
+    }
+    println!("\nStaples (average copies among decks playing them):");
+    print_staples(&snapshot.staples[..top.min(snapshot.staples.len())]);
+}
+
+pub async fn run(command: DeckCommand) -> anyhow::Result<()> {
+    match command {
+        DeckCommand::Cluster {
+            dir,
+            metric,
+            threshold,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let mut resolver = DeckResolver::new(&backend);
+            let (files, decks): (Vec<_>, Vec<_>) =
+                load_decks(&dir, &mut resolver).await?.into_iter().unzip();
+
+            println!("{} decks", decks.len());
+            for archetype in cluster_archetypes(&decks, metric, threshold) {
+                let name = if archetype.name.is_empty() {
+                    "(unnamed)"
+                } else {
+                    &archetype.name
+                };
+                println!(
+                    "\n{name} — {} decks ({:.1}%)",
+                    archetype.decks.len(),
+                    archetype.share * 100.0
+                );
+                for card in &archetype.signature {
+                    println!("  {:>5.1}%  {}", card.frequency * 100.0, card.name);
+                }
+                for &i in &archetype.decks {
+                    println!("  - {}", files[i].display());
+                }
+            }
+        }
+        DeckCommand::Metagame {
+            dir,
+            format,
+            from,
+            to,
+            compare_from,
+            compare_to,
+            metric,
+            threshold,
+            top,
+            db,
+        } => {
+            anyhow::ensure!(from <= to, "--from must not be after --to");
+            let backend = open_db(&db.db).await?;
+            let mut resolver = DeckResolver::new(&backend);
+
+            let mut decks = Vec::new();
+            for (path, deck) in load_decks(&dir, &mut resolver).await? {
+                match file_date(&path) {
+                    Some(date) => decks.push(DatedDeck { date, deck }),
+                    None => eprintln!("Skipping {}: no YYYY-MM-DD in file name", path.display()),
+                }
+            }
+
+            let format = Format(format.to_lowercase().into());
+            let snapshot = metagame_snapshot(format.clone(), from, to, &decks, metric, threshold);
+            anyhow::ensure!(snapshot.deck_count > 0, "no decks dated {from} to {to}");
+            backend.save_snapshot(&snapshot).await?;
+            print_snapshot(&snapshot, top);
+
+            if let (Some(start), Some(end)) = (compare_from, compare_to) {
+                let earlier = metagame_snapshot(format, start, end, &decks, metric, threshold);
+                if earlier.deck_count > 0 {
+                    backend.save_snapshot(&earlier).await?;
+                }
+                println!(
+                    "\nBiggest movers compared with {start} to {end} ({} decks):",
+                    earlier.deck_count
+                );
+                for t in staple_trends(&earlier, &snapshot).iter().take(top) {
+                    println!(
+                        "  {:>+6.1}%  {:>5.1}% -> {:>5.1}%  {}",
+                        t.change() * 100.0,
+                        t.before * 100.0,
+                        t.after * 100.0,
+                        t.name
+                    );
+                }
+            }
+        }
+        DeckCommand::Bracket { path, lists, db } => {
+            let lists = match lists {
+                Some(lists) => BracketLists::from_json(&std::fs::read_to_string(lists)?)?,
+                None => BracketLists::bundled(),
+            };
+            let backend = open_db(&db.db).await?;
+            let mut deck = parse_deck(&std::fs::read_to_string(&path)?)?;
+            let unresolved = DeckResolver::new(&backend).resolve(&mut deck).await?;
+            if !unresolved.is_empty() {
+                eprintln!("Unknown cards: {}", unresolved.join(", "));
+            }
+
+            let estimate = estimate_bracket(&deck, &deck_cards(&backend, &deck).await?, &lists);
+            println!(
+                "Bracket {} (average mana value {:.2})",
+                estimate.bracket, estimate.average_mana_value
+            );
+            for evidence in &estimate.evidence {
+                println!("  [{}] {}", evidence.min_bracket, evidence.reason);
+                for card in &evidence.cards {
+                    println!("      {card}");
+                }
+            }
+        }
+        DeckCommand::Budget {
+            path,
+            max_card,
+            max_total,
+            currency,
+            format,
+            k,
+            index,
+            db,
+        } => {
+            if max_card.is_none() && max_total.is_none() {
+                anyhow::bail!("give a ceiling with --max-card and/or --max-total");
+            }
+            let backend = open_db(&db.db).await?;
+            let mut deck = parse_deck(&std::fs::read_to_string(&path)?)?;
+            let unresolved = DeckResolver::new(&backend).resolve(&mut deck).await?;
+            if !unresolved.is_empty() {
+                eprintln!("Unknown cards: {}", unresolved.join(", "));
+            }
+
+            let cards = backend.all_oracle_cards().await?;
+            let index = match index {
+                Some(path) => serde_json::from_slice(&std::fs::read(path)?)?,
+                None => OracleIndex::build(&cards),
+            };
+            let pool: HashMap<Uuid, OracleCard> =
+                cards.into_iter().map(|c| (c.oracle_id, c)).collect();
+            let prices = backend.cheapest_prices(currency).await?;
+            let options = BudgetOptions {
+                max_card_price: max_card,
+                max_total,
+                format: format.map(|f| Format(f.to_lowercase().into())),
+                suggestions: k,
+                ..BudgetOptions::default()
+            };
+
+            let report = budget_substitutions(&deck, &pool, &index, &prices, &options);
+            for swap in &report.swaps {
+                println!(
+                    "{}x {} ({} {currency:?} each)",
+                    swap.quantity, swap.name, swap.price
+                );
+                for card in &swap.suggestions {
+                    println!("    {:.3}  {}  {}", card.score, card.price, card.name);
+                }
+            }
+            if !report.irreplaceable.is_empty() {
+                println!("No cheaper stand-in: {}", report.irreplaceable.join(", "));
+            }
+            if !report.unpriced.is_empty() {
+                println!("No price: {}", report.unpriced.join(", "));
+            }
+            println!(
+                "Total: {} -> {} {currency:?}{}",
+                report.total,
+                report.total_after,
+                if report.within(&options) {
+                    ""
+                } else {
+                    " (still over budget)"
+                }
+            );
+        }
+        DeckCommand::Staples {
+            format,
+            as_of,
+            top,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let format = Format(format.to_lowercase().into());
+            let snapshot = match as_of {
+                Some(date) => backend.snapshot_as_of(&format, date).await?,
+                None => backend.latest_snapshot(&format).await?,
+            };
+            let Some(snapshot) = snapshot else {
+                match as_of {
+                    Some(date) => println!("No {} metagame snapshot covers {date}.", format.0),
+                    None => println!("No metagame snapshots for {}.", format.0),
+                }
+                return Ok(());
+            };
+            println!(
+                "Top {top} {} cards, {} to {} ({} decks):",
+                format.0, snapshot.start, snapshot.end, snapshot.deck_count
+            );
+            print_staples(&backend.top_staples(&format, as_of, top).await?);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,708 @@
+use crate::cards::parse_colors;
+use crate::{DbArgs, open_db};
+use clap::Subcommand;
+use clap::builder::RangedU64ValueParser;
+use elbrus_core::{Color, ColorSet, OracleCard, Rarity};
+use elbrus_db::repo::CardRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_deck::{DeckParser, DeckResolver, MtgoParser, parse_deck};
+use elbrus_draft::{
+    BoosterConfig, BoosterKind, CardRating, CardStats, ColorCategory, ColorCommitPicker,
+    CsvRatings, Cube, CubeStats, Dataset, DraftCard, DraftConfig, DraftFormat, DraftState,
+    GridDraft, HeuristicRating, ImportedDraft, LimitedRatings, MIN_GAMES_IN_HAND, PickLog,
+    PickStrategy, RandomPicker, RarityPicker, RatingPicker, RochesterDraft, SEALED_BOOSTERS,
+    SealedBuilder, WINSTON_SEATS, WinstonDraft, cube_packs, load_card_stats, review_picks, sealed,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub enum DraftCommand {
+    /// Open boosters of a set
+    Booster {
+        /// Set code, e.g. `mkm`
+        set: String,
+
+        /// Booster type: draft, set, collector or play
+        #[arg(long, default_value = "play", value_parser = parse_kind)]
+        kind: BoosterKind,
+
+        /// Number of boosters to open
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+
+        /// Seed for reproducible packs; random if omitted
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Booster templates to use instead of the bundled ones
+        #[arg(long)]
+        templates: Option<PathBuf>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Simulate a draft between bots, or open sealed pools
+    Sim {
+        /// Set code, e.g. `mkm`
+        #[arg(required_unless_present = "cube")]
+        set: Option<String>,
+
+        /// Draft this CubeCobra list or CSV export instead of a set
+        #[arg(long, conflicts_with = "set")]
+        cube: Option<PathBuf>,
+
+        /// Cards per cube pack
+        #[arg(long, default_value_t = 15, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        pack_size: usize,
+
+        /// Booster type: draft, set, collector or play
+        #[arg(long, default_value = "play", value_parser = parse_kind)]
+        kind: BoosterKind,
+
+        /// Format: booster, rochester, winston, grid or sealed
+        #[arg(long, default_value = "booster", value_parser = parse_format)]
+        format: DraftFormat,
+
+        /// Players; 8 by default, 2 for winston and grid
+        #[arg(long)]
+        seats: Option<usize>,
+
+        /// Packs per player; 3 by default, 6 for sealed
+        #[arg(long)]
+        rounds: Option<u32>,
+
+        /// Bots by seat, repeated to fill the table: random, rarity,
+        /// color-commit or rating (17lands stats for the set if ingested)
+        #[arg(long, value_delimiter = ',', default_value = "color-commit")]
+        bots: Vec<String>,
+
+        /// Seed for reproducible drafts; random if omitted
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Write the pick log as JSON to this file
+        #[arg(long)]
+        log: Option<PathBuf>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Build a 40-card deck from a sealed pool
+    Sealed {
+        /// The pool as a decklist; opens six boosters of --set if omitted
+        #[arg(required_unless_present = "set")]
+        pool: Option<PathBuf>,
+
+        /// Set code to open a pool of, e.g. `mkm`
+        #[arg(long, conflicts_with = "pool")]
+        set: Option<String>,
+
+        /// Booster type: draft, set, collector or play
+        #[arg(long, default_value = "play", value_parser = parse_kind)]
+        kind: BoosterKind,
+
+        /// Seed for a reproducible pool; random if omitted
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// CSV of card ratings with Name and Rating columns. Without one,
+        /// 17lands stats for --set are used if ingested. Cards left unrated
+        /// are rated by rarity, type and mana value
+        #[arg(long)]
+        ratings: Option<PathBuf>,
+
+        /// Runner-up builds to list
+        #[arg(long, default_value_t = 3)]
+        alternatives: usize,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Ingest 17lands game data or draft data CSVs, replacing earlier
+    /// ingests of the same file names
+    Ingest {
+        /// Decompressed CSV files from the 17lands public datasets
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Per-card 17lands stats for a set: GIH WR, IWD, ALSA and ATA
+    Stats {
+        /// Set code, e.g. `mkm`
+        set: String,
+
+        /// 17lands event type, e.g. PremierDraft; every type if omitted
+        #[arg(long)]
+        event_type: Option<String>,
+
+        /// Only games in decks of exactly these main colors, e.g. `WU`
+        #[arg(long, value_parser = parse_colors)]
+        colors: Option<ColorSet>,
+
+        /// Sort by gih, iwd, alsa, ata or name
+        #[arg(long, default_value = "gih")]
+        sort: String,
+
+        /// Games in hand a card needs to be listed
+        #[arg(long, default_value_t = MIN_GAMES_IN_HAND)]
+        min_games: u64,
+
+        #[arg(long, default_value_t = 40)]
+        limit: usize,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Review each pick of a draft: the card taken, the cards passed and
+    /// how they rate
+    Review {
+        /// MTGO draft log, Arena-style JSON draft log, or a pick log written
+        /// by `draft sim --log`
+        log: PathBuf,
+
+        /// Seat to review, from 1; the drafter's own seat for MTGO and Arena
+        /// logs
+        #[arg(long)]
+        seat: Option<usize>,
+
+        /// CSV of card ratings with Name and Rating columns. Without one,
+        /// 17lands stats for --set are used if ingested. Cards left unrated
+        /// are rated by rarity, type and mana value
+        #[arg(long)]
+        ratings: Option<PathBuf>,
+
+        /// Set code for 17lands ratings; the log's first set if omitted
+        #[arg(long)]
+        set: Option<String>,
+
+        /// Passed cards to list per pick
+        #[arg(long, default_value_t = 3)]
+        alternatives: usize,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Color balance, curves and type counts of a cube
+    Cube {
+        /// CubeCobra card list or CSV export
+        path: PathBuf,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+}
+
+/// Read and resolve a cube, warning about cards not in the database.
+async fn load_cube(path: &Path, backend: &SqliteBackend) -> anyhow::Result<Vec<DraftCard>> {
+    let mut cube = Cube::parse(&std::fs::read_to_string(path)?)?;
+    let unresolved = cube.resolve(backend).await?;
+    if !unresolved.is_empty() {
+        let names: Vec<&str> = unresolved.iter().map(|n| &**n).collect();
+        eprintln!("Warning: not in the database: {}", names.join(", "));
+    }
+    Ok(cube.draft_cards(backend).await?)
+}
+
+/// Read a decklist as a sealed pool, warning about cards not in the
+/// database. Rarities aren't known from a decklist, so every card is common.
+async fn load_pool(path: &Path, backend: &SqliteBackend) -> anyhow::Result<Vec<DraftCard>> {
+    let mut deck = parse_deck(&std::fs::read_to_string(path)?)?;
+    let unresolved = DeckResolver::new(backend).resolve(&mut deck).await?;
+    if !unresolved.is_empty() {
+        let names: Vec<&str> = unresolved.iter().map(|n| &**n).collect();
+        eprintln!("Warning: not in the database: {}", names.join(", "));
+    }
+    let mut pool = Vec::new();
+    for entry in deck.entries() {
+        let Some(id) = entry.resolved else {
+            continue;
+        };
+        if let Some(card) = backend.get_oracle(id).await? {
+            let card = DraftCard::from_oracle(&card, Rarity::Common);
+            pool.extend(std::iter::repeat_n(card, entry.quantity as usize));
+        }
+    }
+    Ok(pool)
+}
+
+/// The oracle cards of `pool` and the five basic lands, by oracle id.
+async fn pool_oracles(
+    backend: &SqliteBackend,
+    pool: &[DraftCard],
+) -> anyhow::Result<HashMap<Uuid, OracleCard>> {
+    let mut ids: Vec<Uuid> = pool.iter().map(|c| c.oracle_id).collect();
+    let mut resolver = DeckResolver::new(backend);
+    for color in Color::ALL {
+        ids.extend(resolver.lookup(&color.land()).await?);
+    }
+    let mut cards = HashMap::new();
+    for id in ids {
+        if !cards.contains_key(&id)
+            && let Some(card) = backend.get_oracle(id).await?
+        {
+            cards.insert(id, card);
+        }
+    }
+    Ok(cards)
+}
+
+fn parse_kind(s: &str) -> Result<BoosterKind, String> {
+    serde_json::from_str(&format!("\"{}\"", s.to_lowercase()))
+        .map_err(|_| format!("unknown booster type `{s}` (expected draft, set, collector or play)"))
+}
+
+fn parse_format(s: &str) -> Result<DraftFormat, String> {
+    serde_json::from_str(&format!("\"{}\"", s.to_lowercase())).map_err(|_| {
+        format!("unknown format `{s}` (expected booster, rochester, winston, grid or sealed)")
+    })
+}
+
+fn parse_bot(s: &str, ratings: &Arc<dyn CardRating>) -> anyhow::Result<Box<dyn PickStrategy>> {
+    Ok(match s {
+        "random" => Box::new(RandomPicker),
+        "rarity" => Box::new(RarityPicker),
+        "color-commit" => Box::new(ColorCommitPicker::default()),
+        "rating" => Box::new(RatingPicker::new(ratings.clone())),
+        _ => anyhow::bail!("unknown bot `{s}` (expected random, rarity, color-commit or rating)"),
+    })
+}
+
+/// 17lands ratings for `set` if any stats are ingested, else the heuristic.
+async fn set_ratings(
+    backend: &SqliteBackend,
+    set: Option<&str>,
+) -> anyhow::Result<Arc<dyn CardRating>> {
+    if let Some(set) = set {
+        let stats = load_card_stats(backend, set, None, None).await?;
+        let ratings = LimitedRatings::new(&stats, MIN_GAMES_IN_HAND);
+        if !ratings.is_empty() {
+            return Ok(Arc::new(ratings));
+        }
+    }
+    Ok(Arc::new(HeuristicRating))
+}
+
+fn percent(rate: Option<f64>) -> String {
+    rate.map_or_else(|| "-".into(), |r| format!("{:.1}%", r * 100.0))
+}
+
+fn average(pick: Option<f64>) -> String {
+    pick.map_or_else(|| "-".into(), |p| format!("{p:.2}"))
+}
+
+fn seed_or_random(seed: Option<u64>) -> anyhow::Result<u64> {
+    Ok(match seed {
+        Some(seed) => seed,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+    })
+}
+
+fn color_letters(colors: ColorSet) -> String {
+    let letters: String = Color::ALL
+        .into_iter()
+        .zip(['W', 'U', 'B', 'R', 'G'])
+        .filter(|&(color, _)| colors.contains(color.into()))
+        .map(|(_, letter)| letter)
+        .collect();
+    if letters.is_empty() {
+        "C".into()
+    } else {
+        letters
+    }
+}
+
+fn rarity_letter(rarity: Rarity) -> char {
+    match rarity {
+        Rarity::Common => 'C',
+        Rarity::Uncommon => 'U',
+        Rarity::Rare => 'R',
+        Rarity::Mythic => 'M',
+        _ => 'S',
+    }
+}
+
+pub async fn run(command: DraftCommand) -> anyhow::Result<()> {
+    match command {
+        DraftCommand::Booster {
+            set,
+            kind,
+            count,
+            seed,
+            templates,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let config = match templates {
+                Some(path) => BoosterConfig::from_json(&std::fs::read_to_string(path)?)?,
+                None => BoosterConfig::bundled(),
+            };
+            let seed = seed_or_random(seed)?;
+            let packs = config.generate(&backend, &set, kind, count, seed).await?;
+
+            println!(
+                "{} {kind} booster x{count} (seed {seed})",
+                set.to_uppercase()
+            );
+            for (i, pack) in packs.iter().enumerate() {
+                println!();
+                println!("Pack {}:", i + 1);
+                for card in &pack.cards {
+                    let foil = if card.foil { " (foil)" } else { "" };
+                    println!("  {} {}{foil}", rarity_letter(card.rarity), card.name);
+                }
+            }
+        }
+        DraftCommand::Sim {
+            set,
+            cube,
+            pack_size,
+            kind,
+            format,
+            seats,
+            rounds,
+            bots,
+            seed,
+            log,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let ratings = set_ratings(&backend, set.as_deref()).await?;
+            let bots = bots
+                .iter()
+                .map(|b| parse_bot(b.trim(), &ratings))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            if bots.is_empty() {
+                anyhow::bail!("no bots given");
+            }
+            let seed = seed_or_random(seed)?;
+            let seats = seats.unwrap_or(match format {
+                DraftFormat::Winston | DraftFormat::Grid => WINSTON_SEATS,
+                _ => 8,
+            });
+            let rounds = rounds.unwrap_or(match format {
+                DraftFormat::Sealed => SEALED_BOOSTERS,
+                _ => 3,
+            });
+            if format == DraftFormat::Winston && seats != WINSTON_SEATS {
+                anyhow::bail!("winston drafts are for {WINSTON_SEATS} players");
+            }
+            let config = DraftConfig { seats, rounds };
+            let (title, packs) = match (&cube, &set) {
+                (Some(path), _) => {
+                    let cards = load_cube(path, &backend).await?;
+                    let title = format!("{} cube", path.display());
+                    (title, cube_packs(&cards, config, pack_size, seed)?)
+                }
+                (None, Some(set)) => {
+                    let packs = BoosterConfig::bundled()
+                        .generate(&backend, set, kind, config.packs_needed(), seed)
+                        .await?;
+                    (format!("{} {kind}", set.to_uppercase()), packs)
+                }
+                (None, None) => anyhow::bail!("give a set or --cube"),
+            };
+            let seated: Vec<&dyn PickStrategy> =
+                (0..seats).map(|i| &*bots[i % bots.len()]).collect();
+            let (pick_log, passed): (PickLog, Vec<Vec<DraftCard>>) = match format {
+                DraftFormat::Booster => {
+                    let mut draft = DraftState::new(config, packs, seed)?;
+                    draft.run(&seated)?;
+                    let passed = draft.players.iter().map(|p| p.passed.clone()).collect();
+                    (draft.into_log(), passed)
+                }
+                DraftFormat::Rochester => {
+                    let mut draft = RochesterDraft::new(config, packs, seed)?;
+                    draft.run(&seated)?;
+                    let passed = draft.players.iter().map(|p| p.passed.clone()).collect();
+                    (draft.into_log(), passed)
+                }
+                DraftFormat::Winston => {
+                    let mut draft = WinstonDraft::new(packs, seed);
+                    draft.run([seated[0], seated[1]])?;
+                    let passed = draft.players.iter().map(|p| p.passed.clone()).collect();
+                    (draft.into_log(), passed)
+                }
+                DraftFormat::Grid => {
+                    let mut draft = GridDraft::new(packs, seats, seed)?;
+                    draft.run(&seated)?;
+                    let passed = draft.players.iter().map(|p| p.passed.clone()).collect();
+                    (draft.into_log(), passed)
+                }
+                DraftFormat::Sealed => (sealed(config, packs, seed)?, vec![Vec::new(); seats]),
+            };
+
+            println!("{title} {format}, {seats} seats x {rounds} packs (seed {seed})");
+            for (seat, pool) in pick_log.pools().iter().enumerate() {
+                let colors = ColorCommitPicker::committed_colors(pool, &passed[seat]);
+                let rares = pool
+                    .iter()
+                    .filter(|c| matches!(c.rarity, Rarity::Rare | Rarity::Mythic))
+                    .count();
+                let player = match format {
+                    DraftFormat::Sealed => "sealed",
+                    _ => seated[seat].name(),
+                };
+                println!(
+                    "  Seat {}: {:<12} {:>2} cards, {:<2}  {rares} rares/mythics",
+                    seat + 1,
+                    player,
+                    pool.len(),
+                    color_letters(colors),
+                );
+            }
+            if let Some(path) = log {
+                std::fs::write(&path, pick_log.to_json()?)?;
+                println!("Pick log written to {}", path.display());
+            }
+        }
+        DraftCommand::Sealed {
+            pool,
+            set,
+            kind,
+            seed,
+            ratings,
+            alternatives,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let pool = match (&pool, &set) {
+                (Some(path), _) => load_pool(path, &backend).await?,
+                (None, Some(set)) => {
+                    let seed = seed_or_random(seed)?;
+                    println!("{} {kind} sealed pool (seed {seed})", set.to_uppercase());
+                    BoosterConfig::bundled()
+                        .generate(&backend, set, kind, SEALED_BOOSTERS as usize, seed)
+                        .await?
+                        .into_iter()
+                        .flat_map(|p| p.cards)
+                        .collect()
+                }
+                (None, None) => anyhow::bail!("give a pool or --set"),
+            };
+            let ratings: Arc<dyn CardRating> = match ratings {
+                Some(path) => Arc::new(CsvRatings::parse(&std::fs::read_to_string(path)?)?),
+                None => set_ratings(&backend, set.as_deref()).await?,
+            };
+            let cards = pool_oracles(&backend, &pool).await?;
+            let builder = SealedBuilder::default();
+
+            let builds = builder.builds(&pool, &*ratings, &cards);
+            println!("{} cards, rated by {}", pool.len(), ratings.name());
+            for build in builds.iter().take(alternatives + 1) {
+                let splash = build
+                    .splash
+                    .map(|c| format!("+{}", color_letters(c.into()).to_lowercase()))
+                    .unwrap_or_default();
+                println!(
+                    "  {:<5} {:>6.1}  {} spells",
+                    format!("{}{splash}", color_letters(build.colors)),
+                    build.score,
+                    build.spells.len()
+                );
+            }
+            let Some(sealed) = builder.build(&pool, &*ratings, &cards) else {
+                anyhow::bail!("the pool has no spells");
+            };
+            println!();
+            println!("{}", sealed.deck.name.as_deref().unwrap_or_default());
+            print!("{}", MtgoParser.serialize(&sealed.deck));
+            println!();
+            println!(
+                "Average mana value {:.2}, {} lands",
+                sealed.mana.avg_cmc, sealed.mana.land_count
+            );
+        }
+        DraftCommand::Ingest { files, db } => {
+            let backend = open_db(&db.db).await?;
+            for path in files {
+                let name = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let file = std::io::BufReader::new(std::fs::File::open(&path)?);
+                let dataset = Dataset::parse(file)?;
+                dataset.store(&backend, &name).await?;
+                let kind = match dataset {
+                    Dataset::Games(_) => "game",
+                    Dataset::Drafts(_) => "draft",
+                };
+                println!("{name}: {kind} data, {} card tallies", dataset.len());
+            }
+        }
+        DraftCommand::Stats {
+            set,
+            event_type,
+            colors,
+            sort,
+            min_games,
+            limit,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let mut stats: Vec<CardStats> =
+                load_card_stats(&backend, &set, event_type.as_deref(), colors)
+                    .await?
+                    .into_iter()
+                    .filter(|s| s.games_in_hand >= min_games)
+                    .collect();
+            if stats.is_empty() {
+                anyhow::bail!("no 17lands stats for {set}; ingest some with `draft ingest`");
+            }
+            let key: fn(&CardStats) -> Option<f64> = match sort.as_str() {
+                "gih" => |s| s.gih_win_rate,
+                "iwd" => |s| s.iwd,
+                "alsa" => |s| s.alsa.map(|a| -a),
+                "ata" => |s| s.ata.map(|a| -a),
+                "name" => |_| None,
+                _ => anyhow::bail!("unknown sort `{sort}` (expected gih, iwd, alsa, ata or name)"),
+            };
+            // Best first: highest win rates, earliest picks; missing values last.
+            stats.sort_by(|a, b| {
+                let (a, b) = (key(a), key(b));
+                b.is_some()
+                    .cmp(&a.is_some())
+                    .then_with(|| b.unwrap_or(0.0).total_cmp(&a.unwrap_or(0.0)))
+            });
+
+            println!(
+                "{:<32} {:>7} {:>7} {:>7} {:>5} {:>5}",
+                "Card", "Games", "GIH WR", "IWD", "ALSA", "ATA"
+            );
+            for s in stats.iter().take(limit) {
+                println!(
+                    "{:<32} {:>7} {:>7} {:>7} {:>5} {:>5}",
+                    s.name,
+                    s.games_in_hand,
+                    percent(s.gih_win_rate),
+                    s.iwd
+                        .map_or_else(|| "-".into(), |i| format!("{:+.1}pp", i * 100.0)),
+                    average(s.alsa),
+                    average(s.ata),
+                );
+            }
+        }
+        DraftCommand::Review {
+            log,
+            seat,
+            ratings,
+            set,
+            alternatives,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let text = std::fs::read_to_string(&log)?;
+            let (pick_log, drafter, log_set) = match PickLog::from_json(&text) {
+                Ok(pick_log) => (pick_log, None, None),
+                Err(_) => {
+                    let draft = ImportedDraft::parse(&text)?;
+                    let (pick_log, unresolved) = draft.resolve(&backend).await?;
+                    if !unresolved.is_empty() {
+                        let names: Vec<&str> = unresolved.iter().map(|n| &**n).collect();
+                        eprintln!("Warning: not in the database: {}", names.join(", "));
+                    }
+                    if let Some(event) = &draft.event {
+                        println!("Event {event}");
+                    }
+                    let drafter = (draft.seat, draft.players.get(draft.seat).cloned());
+                    (pick_log, Some(drafter), draft.sets.first().cloned())
+                }
+            };
+            let seat = match seat {
+                Some(0) => anyhow::bail!("seats count from 1"),
+                Some(seat) => seat - 1,
+                None => drafter.as_ref().map_or(0, |(seat, _)| *seat),
+            };
+            let ratings: Arc<dyn CardRating> = match ratings {
+                Some(path) => Arc::new(CsvRatings::parse(&std::fs::read_to_string(path)?)?),
+                None => set_ratings(&backend, set.or(log_set).as_deref()).await?,
+            };
+            let reviews = review_picks(&pick_log, seat, &*ratings)?;
+            if reviews.is_empty() {
+                anyhow::bail!("seat {} made no picks", seat + 1);
+            }
+
+            let player = drafter
+                .and_then(|(s, name)| (s == seat).then_some(name).flatten())
+                .map(|name| format!(" ({name})"))
+                .unwrap_or_default();
+            println!(
+                "Seat {}{player}: {} picks, rated by {}",
+                seat + 1,
+                reviews.len(),
+                ratings.name()
+            );
+            for review in &reviews {
+                println!();
+                println!(
+                    "P{}p{:<3} {} ({:.1})  #{} of {}",
+                    review.round,
+                    review.pick,
+                    review.taken.card.name,
+                    review.taken.rating,
+                    review.rank(),
+                    review.passed.len() + 1
+                );
+                let passed: Vec<String> = review
+                    .passed
+                    .iter()
+                    .take(alternatives)
+                    .map(|c| format!("{} ({:.1})", c.card.name, c.rating))
+                    .collect();
+                if !passed.is_empty() {
+                    println!("       passed {}", passed.join(", "));
+                }
+            }
+            let top = reviews.iter().filter(|r| r.rank() == 1).count();
+            let lost: f64 = reviews.iter().map(|r| r.rating_lost()).sum();
+            println!();
+            println!(
+                "{top} of {} picks were the top-rated card; {lost:.1} rating passed up",
+                reviews.len()
+            );
+        }
+        DraftCommand::Cube { path, db } => {
+            let backend = open_db(&db.db).await?;
+            let cards = load_cube(&path, &backend).await?;
+            let stats = CubeStats::new(&cards);
+
+            println!("{} cards", stats.total);
+            println!();
+            println!(
+                "{:<11} {:>5} {:>6}   0  1  2  3  4  5  6 7+",
+                "", "Cards", "Share"
+            );
+            for category in ColorCategory::ALL {
+                let curve = stats.curves.get(&category).copied().unwrap_or_default();
+                let curve: Vec<String> = curve.iter().map(|n| format!("{n:>2}")).collect();
+                println!(
+                    "{:<11} {:>5} {:>5.1}%  {}",
+                    format!("{category:?}"),
+                    stats.count(category),
+                    stats.share(category) * 100.0,
+                    curve.join(" ")
+                );
+            }
+            match stats.color_balance() {
+                Some(ratio) => println!("Largest color is {ratio:.2}x the smallest"),
+                None => println!("Some colors have no cards"),
+            }
+
+            let mut types: Vec<_> = stats.types.iter().collect();
+            types.sort_by(|a, b| {
+                b.1.cmp(a.1)
+                    .then_with(|| format!("{:?}", a.0).cmp(&format!("{:?}", b.0)))
+            });
+            println!();
+            for (card_type, count) in types {
+                println!("{:<12} {count:>4}", format!("{card_type:?}"));
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,17 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use elbrus_core::Currency;
+use elbrus_db::repo::CardRepository;
 use elbrus_db::sqlite::SqliteBackend;
 use elbrus_scryfall::bulk::BulkIngestor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+mod cards;
+mod collection;
+mod combo;
+mod deck;
+mod draft;
+mod prices;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -21,6 +31,72 @@ enum Commands {
         #[arg(short, long, default_value = "elbrus.db")]
         db: PathBuf,
     },
+    /// Card search tools
+    Cards {
+        #[command(subcommand)]
+        command: cards::CardsCommand,
+    },
+    /// Collection tools
+    Collection {
+        #[command(subcommand)]
+        command: collection::CollectionCommand,
+    },
+    /// Commander combo tools
+    Combo {
+        #[command(subcommand)]
+        command: combo::ComboCommand,
+    },
+    /// Decklist tools
+    Deck {
+        #[command(subcommand)]
+        command: deck::DeckCommand,
+    },
+    /// Booster and draft tools
+    Draft {
+        #[command(subcommand)]
+        command: draft::DraftCommand,
+    },
+    /// Price history tools
+    Prices {
+        #[command(subcommand)]
+        command: prices::PricesCommand,
+    },
+}
+
+#[derive(Args)]
+pub(crate) struct DbArgs {
+    /// Path to the SQLite database
+    #[arg(short, long, default_value = "elbrus.db")]
+    db: PathBuf,
+}
+
+pub(crate) fn parse_currency(s: &str) -> Result<Currency, String> {
+    serde_json::from_str(&format!("\"{}\"", s.to_lowercase()))
+        .map_err(|_| format!("unknown currency `{s}` (expected usd, eur or tix)"))
+}
+
+pub(crate) async fn open_db(db: &Path) -> anyhow::Result<SqliteBackend> {
+    let db_path = db
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid DB path"))?;
+    let conn_str = format!("sqlite://{db_path}");
+    Ok(SqliteBackend::open(&conn_str).await?)
+}
+
+/// "Name (SET #CN)" for a printing, or its id if it is not in the database.
+pub(crate) async fn describe_printing(db: &SqliteBackend, id: Uuid) -> anyhow::Result<String> {
+    let Some(printing) = db.get_by_id(id).await? else {
+        return Ok(id.to_string());
+    };
+    let name = db
+        .get_oracle(printing.oracle_id)
+        .await?
+        .map_or_else(|| printing.oracle_id.to_string(), |o| o.name().to_owned());
+    Ok(format!(
+        "{name} ({} #{})",
+        printing.set_code.to_uppercase(),
+        printing.collector_number
+    ))
 }
 
 #[tokio::main]
@@ -29,11 +105,7 @@ async fn main() -> anyhow::Result<()> {
 
     match cli.command {
         Commands::Ingest { path, db } => {
-            let db_path = db
-                .to_str()
-                .ok_or_else(|| anyhow::anyhow!("Invalid DB path"))?;
-            let conn_str = format!("sqlite://{db_path}");
-            let backend = SqliteBackend::open(&conn_str).await?;
+            let backend = open_db(&db).await?;
 
             let ingestor = BulkIngestor::default();
 
@@ -44,7 +116,15 @@ async fn main() -> anyhow::Result<()> {
             println!("Cards processed: {}", stats.cards_processed);
             println!("Cards inserted: {}", stats.cards_inserted);
             println!("Cards updated: {}", stats.cards_updated);
+            println!("Price snapshots recorded: {}", stats.snapshots_recorded);
+            println!("Price snapshots unchanged: {}", stats.snapshots_unchanged);
         }
+        Commands::Cards { command } => cards::run(command).await?,
+        Commands::Collection { command } => collection::run(command).await?,
+        Commands::Combo { command } => combo::run(command).await?,
+        Commands::Deck { command } => deck::run(command).await?,
+        Commands::Draft { command } => draft::run(command).await?,
+        Commands::Prices { command } => prices::run(command).await?,
     }
 
     Ok(())
@@ -0,0 +1,250 @@
+use crate::{DbArgs, describe_printing, open_db, parse_currency};
+use chrono::{NaiveDate, Utc};
+use clap::Subcommand;
+use elbrus_collection::{MoverQuery, MoverScope, check_watchlist, top_movers};
+use elbrus_core::{Currency, Format, WatchEntry};
+use elbrus_db::repo::WatchlistRepository;
+use elbrus_scryfall::bulk::BulkIngestor;
+use rust_decimal::Decimal;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Subcommand)]
+pub enum PricesCommand {
+    /// Record price history from a directory of dated `default-cards-*.json` dumps
+    Backfill {
+        /// Directory containing the bulk dumps
+        dir: PathBuf,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Printings with the largest price moves since a date
+    Movers {
+        /// Start date (YYYY-MM-DD)
+        #[arg(long)]
+        since: NaiveDate,
+
+        /// Only printings of cards legal in this format
+        #[arg(long, conflicts_with = "collections")]
+        format: Option<String>,
+
+        /// Only printings held in a collection
+        #[arg(long)]
+        collections: bool,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        #[arg(long)]
+        foil: bool,
+
+        /// Ignore printings cheaper than this at both ends
+        #[arg(long, default_value = "1")]
+        min_price: Decimal,
+
+        #[arg(short, long, default_value_t = 20)]
+        limit: usize,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Manage and check the price watchlist
+    Watch {
+        #[command(subcommand)]
+        command: WatchCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WatchCommand {
+    /// Watch a printing (replaces any existing thresholds for it)
+    Add {
+        printing_id: Uuid,
+
+        /// Alert on a move of at least this many percent either way
+        #[arg(long)]
+        pct: Option<Decimal>,
+
+        /// Alert when the price rises to or above this
+        #[arg(long)]
+        above: Option<Decimal>,
+
+        /// Alert when the price falls to or below this
+        #[arg(long)]
+        below: Option<Decimal>,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        #[arg(long)]
+        foil: bool,
+
+        #[arg(long)]
+        note: Option<String>,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Stop watching a printing
+    Remove {
+        printing_id: Uuid,
+
+        #[arg(long, default_value = "usd", value_parser = parse_currency)]
+        currency: Currency,
+
+        #[arg(long)]
+        foil: bool,
+
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// List watched printings
+    List {
+        #[command(flatten)]
+        db: DbArgs,
+    },
+    /// Show watched printings that crossed a threshold since the previous ingest
+    Check {
+        #[command(flatten)]
+        db: DbArgs,
+    },
+}
+
+pub async fn run(command: PricesCommand) -> anyhow::Result<()> {
+    match command {
+        PricesCommand::Backfill { dir, db } => {
+            let backend = open_db(&db.db).await?;
+            let ingestor = BulkIngestor::default();
+
+            println!("Backfilling prices from {}...", dir.display());
+            let stats = ingestor.backfill_prices(&dir, &backend).await?;
+
+            println!("Backfill completed in {:?}", stats.duration);
+            println!("Files processed: {}", stats.files_processed);
+            println!("Cards processed: {}", stats.cards_processed);
+            println!("Cards inserted: {}", stats.cards_inserted);
+            println!("Price snapshots recorded: {}", stats.snapshots_recorded);
+            println!("Price snapshots unchanged: {}", stats.snapshots_unchanged);
+        }
+        PricesCommand::Movers {
+            since,
+            format,
+            collections,
+            currency,
+            foil,
+            min_price,
+            limit,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            let scope = match format {
+                Some(f) => MoverScope::Format(Format(f.into())),
+                None if collections => MoverScope::Collections,
+                None => MoverScope::All,
+            };
+            let query = MoverQuery {
+                since: since.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc(),
+                currency,
+                foil,
+                scope,
+                min_price,
+                limit,
+            };
+
+            for mover in top_movers(&backend, &query).await? {
+                println!(
+                    "{:>+8}%  {:>8} -> {:<8}  {}",
+                    mover.change_pct,
+                    mover.before,
+                    mover.after,
+                    describe_printing(&backend, mover.printing_id).await?
+                );
+            }
+        }
+        PricesCommand::Watch { command } => run_watch(command).await?,
+    }
+
+    Ok(())
+}
+
+async fn run_watch(command: WatchCommand) -> anyhow::Result<()> {
+    match command {
+        WatchCommand::Add {
+            printing_id,
+            pct,
+            above,
+            below,
+            currency,
+            foil,
+            note,
+            db,
+        } => {
+            if pct.is_none() && above.is_none() && below.is_none() {
+                anyhow::bail!("Set at least one of --pct, --above or --below");
+            }
+            let backend = open_db(&db.db).await?;
+            backend
+                .upsert_watch(&WatchEntry {
+                    printing_id,
+                    currency,
+                    foil,
+                    threshold_pct: pct,
+                    price_above: above,
+                    price_below: below,
+                    notes: note.map(Into::into),
+                })
+                .await?;
+            println!(
+                "Watching {}",
+                describe_printing(&backend, printing_id).await?
+            );
+        }
+        WatchCommand::Remove {
+            printing_id,
+            currency,
+            foil,
+            db,
+        } => {
+            let backend = open_db(&db.db).await?;
+            backend.remove_watch(printing_id, currency, foil).await?;
+        }
+        WatchCommand::List { db } => {
+            let backend = open_db(&db.db).await?;
+            for entry in backend.list_watches().await? {
+                println!(
+                    "{}  {:?}{}  pct={}  above={}  below={}",
+                    describe_printing(&backend, entry.printing_id).await?,
+                    entry.currency,
+                    if entry.foil { " foil" } else { "" },
+                    display_opt(entry.threshold_pct),
+                    display_opt(entry.price_above),
+                    display_opt(entry.price_below),
+                );
+            }
+        }
+        WatchCommand::Check { db } => {
+            let backend = open_db(&db.db).await?;
+            let alerts = check_watchlist(&backend).await?;
+            if alerts.is_empty() {
+                println!("No watchlist alerts as of {}", Utc::now().date_naive());
+            }
+            for alert in alerts {
+                println!(
+                    "{}: {} -> {} ({}%) {:?}",
+                    describe_printing(&backend, alert.entry.printing_id).await?,
+                    alert.previous,
+                    alert.current,
+                    display_opt(alert.change_pct),
+                    alert.reasons,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn display_opt(value: Option<Decimal>) -> String {
+    value.map_or_else(|| "-".to_owned(), |v| v.to_string())
+}
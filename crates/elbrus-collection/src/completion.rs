@@ -0,0 +1,206 @@
+use crate::{CollectionError, Valuator};
+use elbrus_core::{CollectionEntry, Currency, Printing, Rarity};
+use elbrus_db::repo::{CardRepository, CollectionRepository, PriceRepository};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Owned vs. total collector numbers for one slice of a set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Tally {
+    pub owned: u32,
+    pub total: u32,
+}
+
+impl Tally {
+    fn count(&mut self, owned: bool) {
+        self.total += 1;
+        if owned {
+            self.owned += 1;
+        }
+    }
+
+    #[must_use]
+    pub fn missing(&self) -> u32 {
+        self.total - self.owned
+    }
+
+    #[must_use]
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        f64::from(self.owned) * 100.0 / f64::from(self.total)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CompletionBreakdown {
+    /// Owned in any finish.
+    pub cards: Tally,
+    pub by_rarity: BTreeMap<Rarity, Tally>,
+    pub nonfoil: Tally,
+    pub foil: Tally,
+    /// Sum of the non-foil prices of the missing cards.
+    pub cost_to_complete: Decimal,
+    /// Missing cards with no usable price, left out of `cost_to_complete`.
+    pub unpriced_missing: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingCard {
+    pub printing_id: Uuid,
+    pub name: Arc<str>,
+    pub collector_number: Arc<str>,
+    pub rarity: Rarity,
+    pub booster: bool,
+    pub price: Option<Decimal>,
+}
+
+/// How much of a set is owned, counted by collector number so that the same
+/// card in several languages only counts once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetCompletion {
+    pub set_code: Arc<str>,
+    pub currency: Currency,
+    /// Every collector number in the set.
+    pub all: CompletionBreakdown,
+    /// Only collector numbers that can be opened in the set's boosters.
+    pub booster: CompletionBreakdown,
+    /// Sorted by collector number.
+    pub missing: Vec<MissingCard>,
+}
+
+#[derive(Default)]
+struct Owned {
+    nonfoil: bool,
+    foil: bool,
+}
+
+/// Compare collector numbers numerically where they start with digits,
+/// so "9" sorts before "10" and "10a" after "10".
+fn collector_key(number: &str) -> (u32, &str) {
+    let digits = number
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(number.len());
+    (number[..digits].parse().unwrap_or(u32::MAX), number)
+}
+
+/// Completion of `set_code` against one collection, or every collection when
+/// `collection_id` is `None`. Missing cards are priced with `valuator`.
+pub async fn set_completion<R>(
+    db: &R,
+    set_code: &str,
+    collection_id: Option<Uuid>,
+    valuator: &Valuator,
+) -> Result<SetCompletion, CollectionError>
+where
+    R: CardRepository + CollectionRepository + PriceRepository + ?Sized,
+{
+    let set_code = set_code.to_lowercase();
+    let printings = db.cards_in_set(&set_code).await?;
+    if printings.is_empty() {
+        return Err(CollectionError::UnknownSet(set_code));
+    }
+
+    let collection_ids = match collection_id {
+        Some(id) => vec![id],
+        None => db.list_collections().await?.iter().map(|c| c.id).collect(),
+    };
+    let mut entries: Vec<CollectionEntry> = Vec::new();
+    for id in collection_ids {
+        let collection = db
+            .get_collection(id)
+            .await?
+            .ok_or(CollectionError::NotFound(id))?;
+        entries.extend(collection.entries);
+    }
+
+    let mut by_number: BTreeMap<(u32, &str), Vec<&Printing>> = BTreeMap::new();
+    for printing in &printings {
+        by_number
+            .entry(collector_key(&printing.collector_number))
+            .or_default()
+            .push(printing);
+    }
+    let number_of: HashMap<Uuid, &str> = printings
+        .iter()
+        .map(|p| (p.id, &*p.collector_number))
+        .collect();
+    let mut owned: HashMap<&str, Owned> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.quantity > 0) {
+        if let Some(number) = number_of.get(&entry.printing_id) {
+            let slot = owned.entry(number).or_default();
+            if entry.foil {
+                slot.foil = true;
+            } else {
+                slot.nonfoil = true;
+            }
+        }
+    }
+
+    let mut completion = SetCompletion {
+        set_code: set_code.as_str().into(),
+        currency: valuator.currency,
+        all: CompletionBreakdown::default(),
+        booster: CompletionBreakdown::default(),
+        missing: Vec::new(),
+    };
+
+    for ((_, number), group) in by_number {
+        let printing = group.iter().find(|p| &*p.lang == "en").unwrap_or(&group[0]);
+        let booster = group.iter().any(|p| p.booster);
+        let ownership = owned.get(number);
+        let is_owned = ownership.is_some();
+
+        let price = if is_owned {
+            None
+        } else {
+            let history = db.get_price_history(printing.id).await?;
+            valuator.unit_price(&history, false, false)
+        };
+
+        let breakdowns = if booster {
+            vec![&mut completion.all, &mut completion.booster]
+        } else {
+            vec![&mut completion.all]
+        };
+        for breakdown in breakdowns {
+            breakdown.cards.count(is_owned);
+            breakdown
+                .by_rarity
+                .entry(printing.rarity)
+                .or_default()
+                .count(is_owned);
+            breakdown
+                .nonfoil
+                .count(ownership.is_some_and(|o| o.nonfoil));
+            breakdown.foil.count(ownership.is_some_and(|o| o.foil));
+            if !is_owned {
+                match price {
+                    Some(price) => breakdown.cost_to_complete += price,
+                    None => breakdown.unpriced_missing += 1,
+                }
+            }
+        }
+
+        if !is_owned {
+            let name = db
+                .get_oracle(printing.oracle_id)
+                .await?
+                .map_or_else(|| Arc::from(""), |o| Arc::from(o.name()));
+            completion.missing.push(MissingCard {
+                printing_id: printing.id,
+                name,
+                collector_number: printing.collector_number.clone(),
+                rarity: printing.rarity,
+                booster,
+                price,
+            });
+        }
+    }
+
+    Ok(completion)
+}
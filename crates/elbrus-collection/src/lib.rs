@@ -1 +1,32 @@
 // Collection management
+use elbrus_db::backend::DbError;
+use rust_decimal::{Decimal, RoundingStrategy};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub mod completion;
+pub mod prices;
+pub mod trade;
+pub mod valuation;
+
+pub use completion::*;
+pub use prices::*;
+pub use trade::*;
+pub use valuation::*;
+
+#[derive(Error, Debug)]
+pub enum CollectionError {
+    #[error("Database error: {0}")]
+    Db(#[from] DbError),
+    #[error("Collection not found: {0}")]
+    NotFound(Uuid),
+    #[error("No printings found for set: {0}")]
+    UnknownSet(String),
+    #[error("Printing {0} is not in the trade binder in that quantity")]
+    NotTradeable(Uuid),
+}
+
+/// Round a currency amount to cents, halves away from zero.
+pub(crate) fn round_cents(value: Decimal) -> Decimal {
+    value.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+}
@@ -0,0 +1,335 @@
+use crate::{CollectionError, round_cents};
+use chrono::{DateTime, Duration, Utc};
+use elbrus_core::{Currency, Format, PriceSnapshot, WatchEntry};
+use elbrus_db::repo::{CardRepository, CollectionRepository, PriceRepository, WatchlistRepository};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// One printing's prices in a single currency and finish, oldest first.
+///
+/// Snapshots are only stored when prices change, so each point holds until
+/// the next one.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PriceSeries(pub Vec<(DateTime<Utc>, Decimal)>);
+
+impl PriceSeries {
+    #[must_use]
+    pub fn from_history(history: &[PriceSnapshot], currency: Currency, foil: bool) -> Self {
+        let mut points: Vec<_> = history
+            .iter()
+            .filter_map(|s| s.price(currency, foil, false).map(|p| (s.fetched_at, p)))
+            .collect();
+        points.sort_by_key(|(at, _)| *at);
+        Self(points)
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> Option<(DateTime<Utc>, Decimal)> {
+        self.0.last().copied()
+    }
+
+    /// Price in effect at `at`: the last point at or before it.
+    #[must_use]
+    pub fn price_as_of(&self, at: DateTime<Utc>) -> Option<Decimal> {
+        self.0
+            .iter()
+            .take_while(|(t, _)| *t <= at)
+            .last()
+            .map(|(_, p)| *p)
+    }
+
+    /// Percentage change from the price in effect at `since` to the latest price.
+    #[must_use]
+    pub fn change_since(&self, since: DateTime<Utc>) -> Option<Decimal> {
+        let before = self.price_as_of(since)?;
+        let (_, after) = self.latest()?;
+        percent_change(before, after)
+    }
+
+    /// Percentage change over the `days` days leading up to the latest point.
+    #[must_use]
+    pub fn change_pct(&self, days: u32) -> Option<Decimal> {
+        let (latest_at, _) = self.latest()?;
+        self.change_since(latest_at - Duration::days(i64::from(days)))
+    }
+
+    /// Points in effect at some time from `from` to `until`: the last one at or
+    /// before `from`, then every later one up to `until`.
+    #[must_use]
+    pub fn in_effect(
+        &self,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> &[(DateTime<Utc>, Decimal)] {
+        let end = self.0.partition_point(|(t, _)| *t <= until);
+        let start = self
+            .0
+            .partition_point(|(t, _)| *t <= from)
+            .saturating_sub(1);
+        &self.0[start.min(end)..end]
+    }
+
+    /// Mean price from `from` to `until`, weighting each point by how long it
+    /// held. A window that starts before the first point starts with it.
+    #[must_use]
+    pub fn average_between(&self, from: DateTime<Utc>, until: DateTime<Utc>) -> Option<Decimal> {
+        let points = self.in_effect(from, until);
+        let mut weighted = Decimal::ZERO;
+        let mut held = Decimal::ZERO;
+        for (i, (at, price)) in points.iter().enumerate() {
+            let next = points.get(i + 1).map_or(until, |(t, _)| *t);
+            let seconds = Decimal::from((next - (*at).max(from)).num_seconds());
+            weighted += price * seconds;
+            held += seconds;
+        }
+        if held.is_zero() {
+            // Only a point set at `until` itself, if any.
+            return points.last().map(|(_, p)| *p);
+        }
+        Some(round_cents(weighted / held))
+    }
+
+    /// Time-weighted mean over the `days` days leading up to each point.
+    #[must_use]
+    pub fn rolling_average(&self, days: u32) -> Vec<(DateTime<Utc>, Decimal)> {
+        let window = Duration::days(i64::from(days));
+        self.0
+            .iter()
+            .filter_map(|(at, _)| Some((*at, self.average_between(*at - window, *at)?)))
+            .collect()
+    }
+
+    /// Sample standard deviation of point-to-point percentage changes.
+    /// `None` with fewer than two changes to compare.
+    #[must_use]
+    pub fn volatility(&self) -> Option<f64> {
+        let returns: Vec<f64> = self
+            .0
+            .windows(2)
+            .filter_map(|w| percent_change(w[0].1, w[1].1))
+            .filter_map(|d| d.to_f64())
+            .collect();
+        if returns.len() < 2 {
+            return None;
+        }
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Some(variance.sqrt())
+    }
+
+    #[must_use]
+    pub fn all_time_high(&self) -> Option<(DateTime<Utc>, Decimal)> {
+        // Earliest point wins ties so the date is when the high was first reached.
+        self.0
+            .iter()
+            .copied()
+            .reduce(|best, p| if p.1 > best.1 { p } else { best })
+    }
+
+    #[must_use]
+    pub fn all_time_low(&self) -> Option<(DateTime<Utc>, Decimal)> {
+        self.0
+            .iter()
+            .copied()
+            .reduce(|best, p| if p.1 < best.1 { p } else { best })
+    }
+
+    /// Headline numbers for a printing, with changes measured over `days`.
+    #[must_use]
+    pub fn summary(&self, days: u32) -> Option<PriceSummary> {
+        let (_, latest) = self.latest()?;
+        Some(PriceSummary {
+            latest,
+            change_pct: self.change_pct(days),
+            average: self.rolling_average(days).last().map(|(_, p)| *p),
+            volatility: self.volatility(),
+            all_time_high: self.all_time_high()?,
+            all_time_low: self.all_time_low()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceSummary {
+    pub latest: Decimal,
+    pub change_pct: Option<Decimal>,
+    pub average: Option<Decimal>,
+    pub volatility: Option<f64>,
+    pub all_time_high: (DateTime<Utc>, Decimal),
+    pub all_time_low: (DateTime<Utc>, Decimal),
+}
+
+/// Which printings a movers query considers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MoverScope {
+    All,
+    /// Printings of cards legal in the format.
+    Format(Format),
+    /// Printings held in any collection.
+    Collections,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoverQuery {
+    pub since: DateTime<Utc>,
+    pub currency: Currency,
+    pub foil: bool,
+    pub scope: MoverScope,
+    /// Ignore printings below this price at both ends, to keep bulk commons
+    /// doubling from a few cents off the list.
+    pub min_price: Decimal,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceMover {
+    pub printing_id: Uuid,
+    pub before: Decimal,
+    pub after: Decimal,
+    pub change_pct: Decimal,
+}
+
+/// Printings with the largest percentage moves (up or down) since `query.since`.
+pub async fn top_movers<R>(db: &R, query: &MoverQuery) -> Result<Vec<PriceMover>, CollectionError>
+where
+    R: CardRepository + CollectionRepository + PriceRepository + ?Sized,
+{
+    let scope: Option<HashSet<Uuid>> = match &query.scope {
+        MoverScope::All => None,
+        MoverScope::Format(format) => {
+            Some(db.legal_printing_ids(format).await?.into_iter().collect())
+        }
+        MoverScope::Collections => {
+            let mut ids = HashSet::new();
+            for collection in db.list_collections().await? {
+                if let Some(full) = db.get_collection(collection.id).await? {
+                    ids.extend(full.entries.iter().map(|e| e.printing_id));
+                }
+            }
+            Some(ids)
+        }
+    };
+
+    let mut movers: Vec<PriceMover> = db
+        .get_price_histories_since(query.since)
+        .await?
+        .into_iter()
+        .filter(|(id, _)| scope.as_ref().is_none_or(|s| s.contains(id)))
+        .filter_map(|(printing_id, history)| {
+            let series = PriceSeries::from_history(&history, query.currency, query.foil);
+            let before = series.price_as_of(query.since)?;
+            let (_, after) = series.latest()?;
+            if before.max(after) < query.min_price {
+                return None;
+            }
+            Some(PriceMover {
+                printing_id,
+                before,
+                after,
+                change_pct: percent_change(before, after)?,
+            })
+        })
+        .filter(|m| !m.change_pct.is_zero())
+        .collect();
+
+    movers.sort_by(|a, b| {
+        b.change_pct
+            .abs()
+            .cmp(&a.change_pct.abs())
+            .then(a.printing_id.cmp(&b.printing_id))
+    });
+    movers.truncate(query.limit);
+    Ok(movers)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertReason {
+    /// Moved by at least `threshold_pct` in either direction.
+    Moved,
+    RoseAbove,
+    FellBelow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchAlert {
+    pub entry: WatchEntry,
+    pub previous: Decimal,
+    pub current: Decimal,
+    pub change_pct: Option<Decimal>,
+    pub reasons: Vec<AlertReason>,
+}
+
+/// Check `entry` against the prices in effect at two ingest times.
+#[must_use]
+pub fn watch_alert(
+    entry: &WatchEntry,
+    history: &[PriceSnapshot],
+    previous_at: DateTime<Utc>,
+    current_at: DateTime<Utc>,
+) -> Option<WatchAlert> {
+    let series = PriceSeries::from_history(history, entry.currency, entry.foil);
+    let previous = series.price_as_of(previous_at)?;
+    let current = series.price_as_of(current_at)?;
+    let change_pct = percent_change(previous, current);
+
+    let mut reasons = Vec::new();
+    if let (Some(threshold), Some(change)) = (entry.threshold_pct, change_pct)
+        && change.abs() >= threshold
+    {
+        reasons.push(AlertReason::Moved);
+    }
+    if let Some(above) = entry.price_above
+        && previous < above
+        && current >= above
+    {
+        reasons.push(AlertReason::RoseAbove);
+    }
+    if let Some(below) = entry.price_below
+        && previous > below
+        && current <= below
+    {
+        reasons.push(AlertReason::FellBelow);
+    }
+
+    (!reasons.is_empty()).then(|| WatchAlert {
+        entry: entry.clone(),
+        previous,
+        current,
+        change_pct,
+        reasons,
+    })
+}
+
+/// Watchlist entries whose thresholds were crossed between the previous ingest
+/// and the latest one.
+pub async fn check_watchlist<R>(db: &R) -> Result<Vec<WatchAlert>, CollectionError>
+where
+    R: PriceRepository + WatchlistRepository + ?Sized,
+{
+    let runs = db.ingest_runs(2).await?;
+    let [current_at, previous_at] = runs[..] else {
+        return Ok(Vec::new());
+    };
+
+    let mut alerts = Vec::new();
+    for entry in db.list_watches().await? {
+        let history = db.get_price_history(entry.printing_id).await?;
+        alerts.extend(watch_alert(&entry, &history, previous_at, current_at));
+    }
+    Ok(alerts)
+}
+
+fn percent_change(before: Decimal, after: Decimal) -> Option<Decimal> {
+    if before.is_zero() {
+        return None;
+    }
+    Some(round_cents(
+        (after - before) / before * Decimal::ONE_HUNDRED,
+    ))
+}
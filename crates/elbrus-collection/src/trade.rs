@@ -0,0 +1,155 @@
+use crate::valuation::load_histories;
+use crate::{CollectionError, CollectionValuation, Valuator};
+use chrono::Utc;
+use elbrus_core::{CollectionEntry, TradeCard, TradeRecord};
+use elbrus_db::repo::{CollectionRepository, PriceRepository, TradeRepository};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// A trade under consideration for one collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposedTrade {
+    pub collection_id: Uuid,
+    pub given: Vec<TradeCard>,
+    pub received: Vec<TradeCard>,
+    pub notes: Option<Arc<str>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeEvaluation {
+    pub given: CollectionValuation,
+    pub received: CollectionValuation,
+    /// `received.total - given.total`: positive when the trade comes out ahead.
+    pub delta: Decimal,
+    /// Given cards the collection does not hold, marked tradeable, in the
+    /// quantity being given.
+    pub unavailable: Vec<TradeCard>,
+}
+
+impl TradeEvaluation {
+    /// Whether either side has cards with no price, leaving `delta` incomplete.
+    #[must_use]
+    pub fn has_unpriced(&self) -> bool {
+        !self.given.unpriced.is_empty() || !self.received.unpriced.is_empty()
+    }
+}
+
+fn to_entries(collection_id: Uuid, cards: &[TradeCard]) -> Vec<CollectionEntry> {
+    cards.iter().map(|c| c.to_entry(collection_id)).collect()
+}
+
+/// Given cards not covered by tradeable entries, with duplicate lines merged.
+fn unavailable(entries: &[CollectionEntry], given: &[TradeCard]) -> Vec<TradeCard> {
+    let mut wanted: Vec<TradeCard> = Vec::new();
+    for card in given {
+        match wanted.iter_mut().find(|w| {
+            w.printing_id == card.printing_id
+                && w.condition == card.condition
+                && w.foil == card.foil
+                && w.etched == card.etched
+        }) {
+            Some(w) => w.quantity += card.quantity,
+            None => wanted.push(card.clone()),
+        }
+    }
+
+    wanted
+        .into_iter()
+        .filter(|w| {
+            !entries.iter().any(|e| {
+                e.tradeable
+                    && e.printing_id == w.printing_id
+                    && e.condition == w.condition
+                    && e.foil == w.foil
+                    && e.etched == w.etched
+                    && e.quantity >= w.quantity
+            })
+        })
+        .collect()
+}
+
+/// The collection's tradeable entries, valued.
+pub async fn trade_binder<R>(
+    db: &R,
+    collection_id: Uuid,
+    valuator: &Valuator,
+) -> Result<CollectionValuation, CollectionError>
+where
+    R: CollectionRepository + PriceRepository + ?Sized,
+{
+    let collection = db
+        .get_collection(collection_id)
+        .await?
+        .ok_or(CollectionError::NotFound(collection_id))?;
+    let binder: Vec<CollectionEntry> = collection
+        .entries
+        .into_iter()
+        .filter(|e| e.tradeable)
+        .collect();
+
+    let histories = load_histories(db, &binder).await?;
+    Ok(valuator.value_entries(&binder, &histories))
+}
+
+/// Value both sides of `trade` and check the given cards are up for trade.
+pub async fn evaluate_trade<R>(
+    db: &R,
+    valuator: &Valuator,
+    trade: &ProposedTrade,
+) -> Result<TradeEvaluation, CollectionError>
+where
+    R: CollectionRepository + PriceRepository + ?Sized,
+{
+    let collection = db
+        .get_collection(trade.collection_id)
+        .await?
+        .ok_or(CollectionError::NotFound(trade.collection_id))?;
+
+    let given = to_entries(trade.collection_id, &trade.given);
+    let received = to_entries(trade.collection_id, &trade.received);
+    let mut all = given.clone();
+    all.extend(received.iter().cloned());
+    let histories = load_histories(db, &all).await?;
+
+    let given = valuator.value_entries(&given, &histories);
+    let received = valuator.value_entries(&received, &histories);
+    Ok(TradeEvaluation {
+        delta: received.total - given.total,
+        given,
+        received,
+        unavailable: unavailable(&collection.entries, &trade.given),
+    })
+}
+
+/// Evaluate `trade`, then update the collection and record it in the ledger
+/// in one transaction. Refuses trades that give away cards not in the trade
+/// binder.
+pub async fn apply_trade<R>(
+    db: &R,
+    valuator: &Valuator,
+    trade: &ProposedTrade,
+) -> Result<(TradeRecord, TradeEvaluation), CollectionError>
+where
+    R: CollectionRepository + PriceRepository + TradeRepository + ?Sized,
+{
+    let evaluation = evaluate_trade(db, valuator, trade).await?;
+    if let Some(card) = evaluation.unavailable.first() {
+        return Err(CollectionError::NotTradeable(card.printing_id));
+    }
+
+    let record = TradeRecord {
+        id: Uuid::new_v4(),
+        collection_id: trade.collection_id,
+        traded_at: Utc::now(),
+        given: trade.given.clone(),
+        received: trade.received.clone(),
+        currency: valuator.currency,
+        given_value: evaluation.given.total,
+        received_value: evaluation.received.total,
+        notes: trade.notes.clone(),
+    };
+    db.record_trade(&record).await?;
+    Ok((record, evaluation))
+}
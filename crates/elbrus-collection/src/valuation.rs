@@ -0,0 +1,202 @@
+use crate::{CollectionError, PriceSeries, round_cents};
+use chrono::{DateTime, Duration, Utc};
+use elbrus_core::{CollectionEntry, Condition, Currency, PriceSnapshot};
+use elbrus_db::repo::{CollectionRepository, PriceRepository};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, hash_map::Entry};
+use uuid::Uuid;
+
+/// How a single market price is derived from a printing's snapshot history.
+///
+/// Snapshots are only stored when a price changes, so each one stays in effect
+/// until the next. Windows end at the valuation time and start with whichever
+/// price was in effect `days` days before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingPolicy {
+    /// Most recent snapshot that has a price in the requested currency.
+    Latest,
+    /// Mean of the prices in effect over the last `days` days, weighted by how
+    /// long each one held.
+    Average { days: u32 },
+    /// Lowest price in effect over the last `days` days.
+    Minimum { days: u32 },
+}
+
+/// Fraction of the market price a copy in each condition is worth.
+/// Conditions missing from the map are valued at full price.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConditionMultipliers(pub HashMap<Condition, Decimal>);
+
+impl ConditionMultipliers {
+    #[must_use]
+    pub fn get(&self, condition: Condition) -> Decimal {
+        self.0.get(&condition).copied().unwrap_or(Decimal::ONE)
+    }
+}
+
+impl Default for ConditionMultipliers {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (Condition::NearMint, Decimal::ONE),
+            (Condition::LightlyPlayed, Decimal::new(85, 2)),
+            (Condition::ModeratelyPlayed, Decimal::new(70, 2)),
+            (Condition::HeavyPlayed, Decimal::new(50, 2)),
+            (Condition::Damaged, Decimal::new(30, 2)),
+        ]))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryValuation {
+    pub entry: CollectionEntry,
+    /// Market price for one copy, before the condition multiplier.
+    pub unit_price: Decimal,
+    pub multiplier: Decimal,
+    /// `unit_price * multiplier * quantity`, rounded to cents.
+    pub value: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollectionValuation {
+    pub currency: Currency,
+    pub entries: Vec<EntryValuation>,
+    /// Entries with no usable price under the chosen currency and policy.
+    pub unpriced: Vec<CollectionEntry>,
+    pub total: Decimal,
+    pub priced_cards: u32,
+    pub unpriced_cards: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Valuator {
+    pub currency: Currency,
+    pub policy: PricingPolicy,
+    pub conditions: ConditionMultipliers,
+    /// When to value at; `None` means now. Later snapshots are ignored.
+    #[serde(default)]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+impl Default for Valuator {
+    fn default() -> Self {
+        Self {
+            currency: Currency::Usd,
+            policy: PricingPolicy::Latest,
+            conditions: ConditionMultipliers::default(),
+            as_of: None,
+        }
+    }
+}
+
+impl Valuator {
+    /// Market price for one copy of a printing, given its snapshot history in any order.
+    #[must_use]
+    pub fn unit_price(
+        &self,
+        history: &[PriceSnapshot],
+        foil: bool,
+        etched: bool,
+    ) -> Option<Decimal> {
+        let now = self.as_of.unwrap_or_else(Utc::now);
+        let mut points: Vec<_> = history
+            .iter()
+            .filter_map(|s| {
+                s.price(self.currency, foil, etched)
+                    .map(|p| (s.fetched_at, p))
+            })
+            .collect();
+        points.sort_by_key(|(at, _)| *at);
+        let series = PriceSeries(points);
+
+        match self.policy {
+            PricingPolicy::Latest => series.price_as_of(now),
+            PricingPolicy::Average { days } => {
+                series.average_between(now - Duration::days(i64::from(days)), now)
+            }
+            PricingPolicy::Minimum { days } => series
+                .in_effect(now - Duration::days(i64::from(days)), now)
+                .iter()
+                .map(|(_, p)| *p)
+                .min(),
+        }
+    }
+
+    /// Value `entries` against price histories keyed by printing id.
+    #[must_use]
+    pub fn value_entries(
+        &self,
+        entries: &[CollectionEntry],
+        histories: &HashMap<Uuid, Vec<PriceSnapshot>>,
+    ) -> CollectionValuation {
+        let mut valuation = CollectionValuation {
+            currency: self.currency,
+            entries: Vec::new(),
+            unpriced: Vec::new(),
+            total: Decimal::ZERO,
+            priced_cards: 0,
+            unpriced_cards: 0,
+        };
+
+        for entry in entries {
+            let unit_price = histories
+                .get(&entry.printing_id)
+                .and_then(|h| self.unit_price(h, entry.foil, entry.etched));
+
+            let Some(unit_price) = unit_price else {
+                valuation.unpriced_cards += entry.quantity;
+                valuation.unpriced.push(entry.clone());
+                continue;
+            };
+
+            let multiplier = self.conditions.get(entry.condition);
+            let value = round_cents(unit_price * multiplier * Decimal::from(entry.quantity));
+            valuation.total += value;
+            valuation.priced_cards += entry.quantity;
+            valuation.entries.push(EntryValuation {
+                entry: entry.clone(),
+                unit_price,
+                multiplier,
+                value,
+            });
+        }
+
+        valuation
+    }
+
+    /// Load a collection and its price histories from `db` and value it.
+    pub async fn value_collection<R>(
+        &self,
+        db: &R,
+        collection_id: Uuid,
+    ) -> Result<CollectionValuation, CollectionError>
+    where
+        R: CollectionRepository + PriceRepository + ?Sized,
+    {
+        let collection = db
+            .get_collection(collection_id)
+            .await?
+            .ok_or(CollectionError::NotFound(collection_id))?;
+
+        let histories = load_histories(db, &collection.entries).await?;
+        Ok(self.value_entries(&collection.entries, &histories))
+    }
+}
+
+/// Price histories for every printing in `entries`, keyed by printing id.
+pub(crate) async fn load_histories<R>(
+    db: &R,
+    entries: &[CollectionEntry],
+) -> Result<HashMap<Uuid, Vec<PriceSnapshot>>, CollectionError>
+where
+    R: PriceRepository + ?Sized,
+{
+    let mut histories = HashMap::new();
+    for entry in entries {
+        if let Entry::Vacant(slot) = histories.entry(entry.printing_id) {
+            slot.insert(db.get_price_history(entry.printing_id).await?);
+        }
+    }
+    Ok(histories)
+}
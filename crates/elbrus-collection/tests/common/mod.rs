@@ -0,0 +1,93 @@
+// Shared fixtures; each test binary uses its own subset.
+#![allow(dead_code)]
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use elbrus_core::{
+    CardFace, CardLayout, OracleCard, PriceSnapshot, Printing, Rarity, color::ColorSet,
+    legality::Legalities, oracle::OracleText, types::TypeLine,
+};
+use elbrus_db::repo::CardRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use uuid::Uuid;
+
+pub fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+/// `n` days after 2026-06-01.
+pub fn day(n: i64) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap() + Duration::days(n)
+}
+
+/// A snapshot with only a USD price.
+pub fn snapshot(at: DateTime<Utc>, usd: &str) -> PriceSnapshot {
+    PriceSnapshot {
+        usd: Some(dec(usd)),
+        usd_foil: None,
+        usd_etched: None,
+        eur: None,
+        eur_foil: None,
+        tix: None,
+        fetched_at: at,
+    }
+}
+
+/// Store a colorless, typeless oracle card.
+pub async fn insert_oracle(db: &SqliteBackend, name: &str, legalities: Legalities) -> Uuid {
+    let oracle_id = Uuid::new_v4();
+    db.upsert_oracle(&OracleCard {
+        oracle_id,
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: name.into(),
+            mana_cost: None,
+            type_line: TypeLine::default(),
+            oracle_text: OracleText::default(),
+            colors: ColorSet::empty(),
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities,
+        edh_rank: None,
+        reserved: false,
+    })
+    .await
+    .unwrap();
+    oracle_id
+}
+
+/// An English booster rare, `tst` #1, of `oracle_id`.
+pub fn printing(oracle_id: Uuid) -> Printing {
+    Printing {
+        id: Uuid::new_v4(),
+        oracle_id,
+        set_code: "tst".into(),
+        collector_number: "1".into(),
+        rarity: Rarity::Rare,
+        lang: "en".into(),
+        released_at: chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+        image_uris: None,
+        promo: false,
+        digital: false,
+        full_art: false,
+        textless: false,
+        reprint: false,
+        booster: true,
+        prices: None,
+    }
+}
+
+/// Store a card named `name` and one [`printing`] of it.
+pub async fn insert_printing(db: &SqliteBackend, name: &str) -> Uuid {
+    let oracle_id = insert_oracle(db, name, Legalities::default()).await;
+    let printing = printing(oracle_id);
+    db.upsert_printing(&printing).await.unwrap();
+    printing.id
+}
@@ -0,0 +1,117 @@
+use common::dec;
+use elbrus_collection::{CollectionError, Tally, Valuator, set_completion};
+use elbrus_core::{CollectionEntry, Condition, Printing, Rarity, legality::Legalities};
+use elbrus_db::repo::{CardRepository, CollectionRepository, PriceRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+mod common;
+
+async fn insert_printing(
+    db: &SqliteBackend,
+    oracle_id: Uuid,
+    number: &str,
+    rarity: Rarity,
+    lang: &str,
+    booster: bool,
+) -> Uuid {
+    let printing = Printing {
+        collector_number: number.into(),
+        rarity,
+        lang: lang.into(),
+        booster,
+        ..common::printing(oracle_id)
+    };
+    db.upsert_printing(&printing).await.unwrap();
+    printing.id
+}
+
+async fn own(db: &SqliteBackend, collection_id: Uuid, printing_id: Uuid, foil: bool) {
+    db.upsert_card(&CollectionEntry {
+        collection_id,
+        printing_id,
+        quantity: 1,
+        condition: Condition::NearMint,
+        foil,
+        etched: false,
+        tradeable: false,
+        notes: None,
+    })
+    .await
+    .unwrap();
+}
+
+async fn price(db: &SqliteBackend, printing_id: Uuid, usd: &str) {
+    db.insert_snapshot(printing_id, &common::snapshot(common::day(0), usd))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_set_completion() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+
+    let first = common::insert_oracle(&db, "First", Legalities::default()).await;
+    let first_en = insert_printing(&db, first, "1", Rarity::Common, "en", true).await;
+    let first_ja = insert_printing(&db, first, "1", Rarity::Common, "ja", true).await;
+    let second = common::insert_oracle(&db, "Second", Legalities::default()).await;
+    let second = insert_printing(&db, second, "2", Rarity::Common, "en", true).await;
+    let third = common::insert_oracle(&db, "Third", Legalities::default()).await;
+    let third = insert_printing(&db, third, "3", Rarity::Rare, "en", true).await;
+    let promo = common::insert_oracle(&db, "Promo", Legalities::default()).await;
+    let promo = insert_printing(&db, promo, "10", Rarity::Mythic, "en", false).await;
+    let unpriced = common::insert_oracle(&db, "Unpriced", Legalities::default()).await;
+    insert_printing(&db, unpriced, "9", Rarity::Uncommon, "en", true).await;
+
+    price(&db, first_en, "0.10").await;
+    price(&db, third, "2.00").await;
+    price(&db, promo, "5.00").await;
+
+    let binder = db.create_collection("Binder", None).await.unwrap();
+    let box_ = db.create_collection("Box", None).await.unwrap();
+    own(&db, binder.id, first_ja, true).await;
+    own(&db, box_.id, second, false).await;
+
+    let all = set_completion(&db, "TST", None, &Valuator::default())
+        .await
+        .unwrap();
+    assert_eq!(&*all.set_code, "tst");
+    assert_eq!(all.all.cards, Tally { owned: 2, total: 5 });
+    assert_eq!(all.booster.cards, Tally { owned: 2, total: 4 });
+    assert_eq!(
+        all.all.by_rarity[&Rarity::Common],
+        Tally { owned: 2, total: 2 }
+    );
+    assert_eq!(
+        all.all.by_rarity[&Rarity::Mythic],
+        Tally { owned: 0, total: 1 }
+    );
+    assert!(!all.booster.by_rarity.contains_key(&Rarity::Mythic));
+    assert_eq!(all.all.foil, Tally { owned: 1, total: 5 });
+    assert_eq!(all.all.nonfoil, Tally { owned: 1, total: 5 });
+    assert_eq!(all.all.cost_to_complete, dec("7.00"));
+    assert_eq!(all.booster.cost_to_complete, dec("2.00"));
+    assert_eq!(all.all.unpriced_missing, 1);
+
+    let missing: Vec<_> = all
+        .missing
+        .iter()
+        .map(|m| (&*m.collector_number, &*m.name))
+        .collect();
+    assert_eq!(
+        missing,
+        vec![("3", "Third"), ("9", "Unpriced"), ("10", "Promo")]
+    );
+
+    let binder_only = set_completion(&db, "tst", Some(binder.id), &Valuator::default())
+        .await
+        .unwrap();
+    assert_eq!(binder_only.all.cards, Tally { owned: 1, total: 5 });
+    assert_eq!(binder_only.all.cost_to_complete, dec("7.00"));
+    assert_eq!(binder_only.missing[0].printing_id, second);
+
+    assert!(matches!(
+        set_completion(&db, "nope", None, &Valuator::default()).await,
+        Err(CollectionError::UnknownSet(_))
+    ));
+}
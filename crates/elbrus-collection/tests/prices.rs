@@ -0,0 +1,180 @@
+use common::{day, dec, snapshot};
+use elbrus_collection::{
+    AlertReason, MoverQuery, MoverScope, PriceSeries, check_watchlist, top_movers,
+};
+use elbrus_core::{
+    CollectionEntry, Condition, Currency, Format, LegalityStatus, WatchEntry, legality::Legalities,
+};
+use elbrus_db::repo::{CardRepository, CollectionRepository, PriceRepository, WatchlistRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod common;
+
+async fn insert_printing(db: &SqliteBackend, name: &str, modern_legal: bool) -> Uuid {
+    let status = if modern_legal {
+        LegalityStatus::Legal
+    } else {
+        LegalityStatus::NotLegal
+    };
+    let legalities = Legalities(HashMap::from([(Format("modern".into()), status)]));
+    let oracle_id = common::insert_oracle(db, name, legalities).await;
+    let printing = common::printing(oracle_id);
+    db.upsert_printing(&printing).await.unwrap();
+    printing.id
+}
+
+async fn record(db: &SqliteBackend, printing_id: Uuid, prices: &[(i64, &str)]) {
+    for (at, usd) in prices {
+        db.insert_snapshot(printing_id, &snapshot(day(*at), usd))
+            .await
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_price_series_stats() {
+    let history: Vec<_> = [(0, "10.00"), (5, "12.00"), (10, "9.00"), (20, "15.00")]
+        .into_iter()
+        .map(|(d, p)| snapshot(day(d), p))
+        .rev()
+        .collect();
+    let series = PriceSeries::from_history(&history, Currency::Usd, false);
+
+    assert_eq!(series.latest(), Some((day(20), dec("15.00"))));
+    assert_eq!(series.price_as_of(day(7)), Some(dec("12.00")));
+    assert_eq!(series.price_as_of(day(-1)), None);
+    assert_eq!(series.change_since(day(0)), Some(dec("50.00")));
+    // 15 days back lands on day 5, where 12.00 was in effect.
+    assert_eq!(series.change_pct(15), Some(dec("25.00")));
+    assert_eq!(series.all_time_high(), Some((day(20), dec("15.00"))));
+    assert_eq!(series.all_time_low(), Some((day(10), dec("9.00"))));
+
+    let rolling = series.rolling_average(10);
+    assert_eq!(rolling[0], (day(0), dec("10.00")));
+    assert_eq!(rolling[2], (day(10), dec("11.00"))); // 10 for 5 days, 12 for 5
+    assert_eq!(rolling[3], (day(20), dec("9.00"))); // 9 held all 10 days
+
+    // Returns: +20%, -25%, +66.67%
+    let volatility = series.volatility().unwrap();
+    assert!((volatility - 45.84).abs() < 0.01, "{volatility}");
+
+    let summary = series.summary(15).unwrap();
+    assert_eq!(summary.latest, dec("15.00"));
+    assert_eq!(summary.change_pct, Some(dec("25.00")));
+
+    assert_eq!(PriceSeries::default().volatility(), None);
+    assert_eq!(PriceSeries::default().summary(7), None);
+}
+
+#[tokio::test]
+async fn test_top_movers() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let riser = insert_printing(&db, "Riser", true).await;
+    let faller = insert_printing(&db, "Faller", false).await;
+    let bulk = insert_printing(&db, "Bulk", true).await;
+    let flat = insert_printing(&db, "Flat", true).await;
+
+    record(&db, riser, &[(0, "2.00"), (10, "5.00")]).await;
+    record(&db, faller, &[(0, "20.00"), (5, "12.00"), (10, "8.00")]).await;
+    record(&db, bulk, &[(0, "0.10"), (10, "0.50")]).await;
+    record(&db, flat, &[(0, "3.00")]).await;
+
+    let mut query = MoverQuery {
+        since: day(1),
+        currency: Currency::Usd,
+        foil: false,
+        scope: MoverScope::All,
+        min_price: Decimal::ONE,
+        limit: 10,
+    };
+
+    let movers = top_movers(&db, &query).await.unwrap();
+    let ids: Vec<_> = movers.iter().map(|m| m.printing_id).collect();
+    assert_eq!(ids, vec![riser, faller]);
+    assert_eq!(movers[0].change_pct, dec("150.00"));
+    assert_eq!(movers[1].before, dec("20.00"));
+    assert_eq!(movers[1].change_pct, dec("-60.00"));
+
+    query.scope = MoverScope::Format(Format("modern".into()));
+    let movers = top_movers(&db, &query).await.unwrap();
+    assert_eq!(movers.len(), 1);
+    assert_eq!(movers[0].printing_id, riser);
+
+    let coll = db.create_collection("Binder", None).await.unwrap();
+    db.upsert_card(&CollectionEntry {
+        collection_id: coll.id,
+        printing_id: faller,
+        quantity: 1,
+        condition: Condition::NearMint,
+        foil: false,
+        etched: false,
+        tradeable: false,
+        notes: None,
+    })
+    .await
+    .unwrap();
+    query.scope = MoverScope::Collections;
+    let movers = top_movers(&db, &query).await.unwrap();
+    assert_eq!(movers.len(), 1);
+    assert_eq!(movers[0].printing_id, faller);
+}
+
+#[tokio::test]
+async fn test_check_watchlist() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let spiking = insert_printing(&db, "Spiking", true).await;
+    let steady = insert_printing(&db, "Steady", true).await;
+
+    record(&db, spiking, &[(0, "4.00"), (1, "4.00"), (2, "6.00")]).await;
+    record(&db, steady, &[(0, "10.00"), (1, "10.20")]).await;
+
+    let watch = |printing_id| WatchEntry {
+        printing_id,
+        currency: Currency::Usd,
+        foil: false,
+        threshold_pct: Some(dec("20")),
+        price_above: None,
+        price_below: None,
+        notes: None,
+    };
+    db.upsert_watch(&WatchEntry {
+        price_above: Some(dec("5.00")),
+        ..watch(spiking)
+    })
+    .await
+    .unwrap();
+    db.upsert_watch(&watch(steady)).await.unwrap();
+    assert_eq!(db.list_watches().await.unwrap().len(), 2);
+
+    // A single ingest has nothing to compare against.
+    db.record_ingest_run(day(1)).await.unwrap();
+    assert!(check_watchlist(&db).await.unwrap().is_empty());
+    db.record_ingest_run(day(2)).await.unwrap();
+
+    let alerts = check_watchlist(&db).await.unwrap();
+    assert_eq!(alerts.len(), 1);
+    let alert = &alerts[0];
+    assert_eq!(alert.entry.printing_id, spiking);
+    assert_eq!(alert.previous, dec("4.00"));
+    assert_eq!(alert.current, dec("6.00"));
+    assert_eq!(alert.change_pct, Some(dec("50.00")));
+    assert_eq!(
+        alert.reasons,
+        vec![AlertReason::Moved, AlertReason::RoseAbove]
+    );
+
+    // An ingest where nothing moved writes no snapshots, but still ends the
+    // window the spike fell in.
+    db.record_ingest_run(day(3)).await.unwrap();
+    assert!(check_watchlist(&db).await.unwrap().is_empty());
+    db.record_ingest_run(day(2)).await.unwrap();
+    assert_eq!(db.ingest_runs(5).await.unwrap(), [day(3), day(2), day(1)]);
+
+    db.remove_watch(spiking, Currency::Usd, false)
+        .await
+        .unwrap();
+    assert_eq!(db.list_watches().await.unwrap(), vec![watch(steady)]);
+}
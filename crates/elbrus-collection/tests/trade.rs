@@ -0,0 +1,258 @@
+use chrono::Utc;
+use common::dec;
+use elbrus_collection::{
+    CollectionError, ProposedTrade, Valuator, apply_trade, evaluate_trade, trade_binder,
+};
+use elbrus_core::{CollectionEntry, Condition, Currency, TradeCard, TradeRecord};
+use elbrus_db::backend::DbError;
+use elbrus_db::repo::{CollectionRepository, PriceRepository, TradeRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+mod common;
+
+async fn insert_priced_printing(db: &SqliteBackend, name: &str, usd: &str) -> Uuid {
+    let printing_id = common::insert_printing(db, name).await;
+    db.insert_snapshot(printing_id, &common::snapshot(common::day(0), usd))
+        .await
+        .unwrap();
+    printing_id
+}
+
+fn card(printing_id: Uuid, quantity: u32, condition: Condition) -> TradeCard {
+    TradeCard {
+        printing_id,
+        quantity,
+        condition,
+        foil: false,
+        etched: false,
+    }
+}
+
+fn quantity(entries: &[CollectionEntry], printing_id: Uuid) -> u32 {
+    entries
+        .iter()
+        .filter(|e| e.printing_id == printing_id)
+        .map(|e| e.quantity)
+        .sum()
+}
+
+#[tokio::test]
+async fn test_evaluate_and_apply_trade() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let staple = insert_priced_printing(&db, "Staple", "10.00").await;
+    let bulk = insert_priced_printing(&db, "Bulk Rare", "0.50").await;
+    let wanted = insert_priced_printing(&db, "Wanted", "8.00").await;
+
+    let coll = db.create_collection("Trade Binder", None).await.unwrap();
+    db.upsert_card(&card(staple, 3, Condition::NearMint).to_entry(coll.id))
+        .await
+        .unwrap();
+    db.upsert_card(&card(bulk, 4, Condition::NearMint).to_entry(coll.id))
+        .await
+        .unwrap();
+
+    let trade = ProposedTrade {
+        collection_id: coll.id,
+        given: vec![
+            card(staple, 1, Condition::NearMint),
+            card(bulk, 4, Condition::NearMint),
+        ],
+        received: vec![card(wanted, 2, Condition::LightlyPlayed)],
+        notes: Some("FNM".into()),
+    };
+    let valuator = Valuator::default();
+
+    // Nothing is in the binder yet.
+    let evaluation = evaluate_trade(&db, &valuator, &trade).await.unwrap();
+    assert_eq!(evaluation.given.total, dec("12.00"));
+    assert_eq!(evaluation.received.total, dec("13.60")); // 2 * 8.00 * 0.85
+    assert_eq!(evaluation.delta, dec("1.60"));
+    assert_eq!(evaluation.unavailable.len(), 2);
+    assert!(!evaluation.has_unpriced());
+    assert!(matches!(
+        apply_trade(&db, &valuator, &trade).await,
+        Err(CollectionError::NotTradeable(_))
+    ));
+
+    for printing_id in [staple, bulk] {
+        assert!(
+            db.set_tradeable(
+                coll.id,
+                printing_id,
+                Condition::NearMint,
+                false,
+                false,
+                true
+            )
+            .await
+            .unwrap()
+        );
+    }
+    assert_eq!(
+        trade_binder(&db, coll.id, &valuator).await.unwrap().total,
+        dec("32.00")
+    );
+
+    let (record, evaluation) = apply_trade(&db, &valuator, &trade).await.unwrap();
+    assert!(evaluation.unavailable.is_empty());
+    assert_eq!(record.given_value, dec("12.00"));
+    assert_eq!(record.received_value, dec("13.60"));
+
+    let entries = db.get_collection(coll.id).await.unwrap().unwrap().entries;
+    assert_eq!(quantity(&entries, staple), 2);
+    assert_eq!(quantity(&entries, bulk), 0);
+    assert_eq!(quantity(&entries, wanted), 2);
+    let received = entries.iter().find(|e| e.printing_id == wanted).unwrap();
+    assert_eq!(received.condition, Condition::LightlyPlayed);
+    assert!(!received.tradeable);
+
+    let ledger = db.list_trades(coll.id).await.unwrap();
+    assert_eq!(ledger, vec![record]);
+}
+
+#[tokio::test]
+async fn test_record_trade_is_atomic() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let owned = insert_priced_printing(&db, "Owned", "1.00").await;
+    let missing = insert_priced_printing(&db, "Missing", "1.00").await;
+    let coll = db.create_collection("Binder", None).await.unwrap();
+    let mut entry = card(owned, 2, Condition::NearMint).to_entry(coll.id);
+    entry.tradeable = true;
+    db.upsert_card(&entry).await.unwrap();
+
+    let result = db
+        .record_trade(&TradeRecord {
+            id: Uuid::new_v4(),
+            collection_id: coll.id,
+            traded_at: Utc::now(),
+            given: vec![
+                card(owned, 2, Condition::NearMint),
+                card(missing, 1, Condition::NearMint),
+            ],
+            received: vec![],
+            currency: Currency::Usd,
+            given_value: dec("3.00"),
+            received_value: Decimal::ZERO,
+            notes: None,
+        })
+        .await;
+    assert!(matches!(result, Err(DbError::Constraint(_))));
+
+    let entries = db.get_collection(coll.id).await.unwrap().unwrap().entries;
+    assert_eq!(quantity(&entries, owned), 2);
+    assert!(db.list_trades(coll.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_record_trade_touches_only_traded_entries() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let traded = insert_priced_printing(&db, "Traded", "1.00").await;
+    let kept = insert_priced_printing(&db, "Kept", "1.00").await;
+    let coll = db.create_collection("Binder", None).await.unwrap();
+    let mut binder = card(traded, 1, Condition::NearMint).to_entry(coll.id);
+    binder.tradeable = true;
+    db.upsert_card(&binder).await.unwrap();
+    let etched = CollectionEntry {
+        etched: true,
+        foil: true,
+        quantity: 3,
+        tradeable: false,
+        ..binder.clone()
+    };
+    db.upsert_card(&etched).await.unwrap();
+    db.upsert_card(&card(kept, 0, Condition::NearMint).to_entry(coll.id))
+        .await
+        .unwrap();
+
+    let record = |given: TradeCard| TradeRecord {
+        id: Uuid::new_v4(),
+        collection_id: coll.id,
+        traded_at: Utc::now(),
+        given: vec![given],
+        received: vec![],
+        currency: Currency::Usd,
+        given_value: dec("1.00"),
+        received_value: Decimal::ZERO,
+        notes: None,
+    };
+    let etched_card = TradeCard {
+        etched: true,
+        foil: true,
+        ..card(traded, 1, Condition::NearMint)
+    };
+    assert!(
+        matches!(
+            db.record_trade(&record(etched_card)).await,
+            Err(DbError::Constraint(_))
+        ),
+        "etched copies aren't in the binder"
+    );
+
+    db.record_trade(&record(card(traded, 1, Condition::NearMint)))
+        .await
+        .unwrap();
+    let entries = db.get_collection(coll.id).await.unwrap().unwrap().entries;
+    let left: Vec<(Uuid, bool, u32)> = entries
+        .iter()
+        .map(|e| (e.printing_id, e.etched, e.quantity))
+        .collect();
+    assert_eq!(left.len(), 2);
+    assert!(left.contains(&(traded, true, 3)));
+    assert!(left.contains(&(kept, false, 0)), "untraded entries survive");
+}
+
+#[tokio::test]
+async fn test_set_tradeable_matches_finish() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let printing_id = insert_priced_printing(&db, "Foiled", "1.00").await;
+    let coll = db.create_collection("Binder", None).await.unwrap();
+    let foil = TradeCard {
+        foil: true,
+        ..card(printing_id, 1, Condition::NearMint)
+    };
+    let etched = TradeCard {
+        etched: true,
+        ..foil.clone()
+    };
+    db.upsert_card(&foil.to_entry(coll.id)).await.unwrap();
+    db.upsert_card(&etched.to_entry(coll.id)).await.unwrap();
+
+    let tradeable = |entries: &[CollectionEntry]| -> Vec<(bool, bool)> {
+        let mut finishes: Vec<(bool, bool)> = entries
+            .iter()
+            .filter(|e| e.tradeable)
+            .map(|e| (e.foil, e.etched))
+            .collect();
+        finishes.sort_unstable();
+        finishes
+    };
+    assert!(
+        db.set_tradeable(coll.id, printing_id, Condition::NearMint, true, false, true)
+            .await
+            .unwrap()
+    );
+    let entries = db.get_collection(coll.id).await.unwrap().unwrap().entries;
+    assert_eq!(tradeable(&entries), [(true, false)]);
+
+    assert!(
+        db.set_tradeable(coll.id, printing_id, Condition::NearMint, true, true, true)
+            .await
+            .unwrap()
+    );
+    assert!(
+        db.set_tradeable(
+            coll.id,
+            printing_id,
+            Condition::NearMint,
+            true,
+            false,
+            false
+        )
+        .await
+        .unwrap()
+    );
+    let entries = db.get_collection(coll.id).await.unwrap().unwrap().entries;
+    assert_eq!(tradeable(&entries), [(true, true)]);
+}
@@ -0,0 +1,141 @@
+use chrono::{Duration, TimeZone, Utc};
+use common::{day, dec, insert_printing, snapshot};
+use elbrus_collection::{PricingPolicy, Valuator};
+use elbrus_core::{CollectionEntry, Condition, Currency, PriceSnapshot};
+use elbrus_db::repo::{CollectionRepository, PriceRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+mod common;
+
+fn entry(collection_id: Uuid, printing_id: Uuid, quantity: u32) -> CollectionEntry {
+    CollectionEntry {
+        collection_id,
+        printing_id,
+        quantity,
+        condition: Condition::NearMint,
+        foil: false,
+        etched: false,
+        tradeable: false,
+        notes: None,
+    }
+}
+
+#[tokio::test]
+async fn test_value_collection() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let priced = insert_printing(&db, "Priced Card").await;
+    let unpriced = insert_printing(&db, "Unpriced Card").await;
+
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+    for (days_ago, usd) in [(10, "4.00"), (3, "2.00"), (0, "3.00")] {
+        db.insert_snapshot(
+            priced,
+            &PriceSnapshot {
+                usd: Some(dec(usd)),
+                usd_foil: Some(dec("8.00")),
+                usd_etched: Some(dec("12.00")),
+                eur: Some(dec("2.50")),
+                eur_foil: None,
+                tix: Some(dec("0.10")),
+                fetched_at: now - Duration::days(days_ago),
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let coll = db.create_collection("Binder", None).await.unwrap();
+    db.upsert_card(&entry(coll.id, priced, 2)).await.unwrap();
+    db.upsert_card(&CollectionEntry {
+        condition: Condition::LightlyPlayed,
+        ..entry(coll.id, priced, 1)
+    })
+    .await
+    .unwrap();
+    db.upsert_card(&CollectionEntry {
+        foil: true,
+        etched: true,
+        ..entry(coll.id, priced, 1)
+    })
+    .await
+    .unwrap();
+    db.upsert_card(&entry(coll.id, unpriced, 3)).await.unwrap();
+
+    // Latest USD: 2 * 3.00 + 1 * 3.00 * 0.85 + 1 * 12.00 (etched)
+    let latest = Valuator::default()
+        .value_collection(&db, coll.id)
+        .await
+        .unwrap();
+    assert_eq!(latest.total, dec("20.55"));
+    assert_eq!(latest.priced_cards, 4);
+    assert_eq!(latest.unpriced_cards, 3);
+    assert_eq!(latest.unpriced.len(), 1);
+    assert_eq!(latest.unpriced[0].printing_id, unpriced);
+
+    // EUR has no foil price, so the etched copy falls back to non-foil.
+    let eur = Valuator {
+        currency: Currency::Eur,
+        ..Valuator::default()
+    };
+    let eur = eur.value_collection(&db, coll.id).await.unwrap();
+    assert_eq!(eur.total, dec("9.63")); // 2.50 * 2 + 2.125 + 2.50
+
+    // The 10-day-old 4.00 held for the first 4 days of a 7-day window and
+    // 2.00 for the last 3: (4 * 4.00 + 3 * 2.00) / 7.
+    let avg = Valuator {
+        policy: PricingPolicy::Average { days: 7 },
+        as_of: Some(now),
+        ..Valuator::default()
+    };
+    let history = db.get_price_history(priced).await.unwrap();
+    assert_eq!(avg.unit_price(&history, false, false), Some(dec("3.14")));
+    let min = Valuator {
+        policy: PricingPolicy::Minimum { days: 30 },
+        as_of: Some(now),
+        ..Valuator::default()
+    };
+    assert_eq!(min.unit_price(&history, false, false), Some(dec("2.00")));
+}
+
+#[test]
+fn test_unit_price_weights_deduplicated_history() {
+    // $10 for a month, then $2 from yesterday; only changes are stored.
+    let history = [snapshot(day(0), "10.00"), snapshot(day(30), "2.00")];
+    let at = |policy| Valuator {
+        policy,
+        as_of: Some(day(31)),
+        ..Valuator::default()
+    };
+
+    // The $10 seeds the window at day 1 and holds 29 of its 30 days.
+    let avg = at(PricingPolicy::Average { days: 30 });
+    assert_eq!(avg.unit_price(&history, false, false), Some(dec("9.73")));
+    let min = at(PricingPolicy::Minimum { days: 7 });
+    assert_eq!(min.unit_price(&history, false, false), Some(dec("2.00")));
+
+    // A window entirely before the drop still sees the $10 in effect.
+    let before = Valuator {
+        as_of: Some(day(20)),
+        ..at(PricingPolicy::Average { days: 7 })
+    };
+    assert_eq!(
+        before.unit_price(&history, false, false),
+        Some(dec("10.00"))
+    );
+    let latest = Valuator {
+        as_of: Some(day(20)),
+        ..at(PricingPolicy::Latest)
+    };
+    assert_eq!(
+        latest.unit_price(&history, false, false),
+        Some(dec("10.00"))
+    );
+
+    // Nothing priced yet at the valuation time.
+    let early = Valuator {
+        as_of: Some(day(-1)),
+        ..at(PricingPolicy::Average { days: 7 })
+    };
+    assert_eq!(early.unit_price(&history, false, false), None);
+}
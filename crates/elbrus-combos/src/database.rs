@@ -0,0 +1,193 @@
+//! Checking card pools against a set of combos.
+//!
+//! [`ComboDatabase`] keeps an inverted index from oracle id to the combos
+//! using that card, so a pool is matched by walking only the combos its
+//! cards appear in rather than every combo.
+
+use crate::ComboError;
+use elbrus_core::{ColorSet, Combo};
+use elbrus_db::repo::ComboRepository;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComboFilter {
+    /// Only combos whose color identity fits within this one.
+    pub within_identity: Option<ColorSet>,
+    /// Only combos made entirely of these cards, e.g. the oracle ids legal in
+    /// a format from `CardRepository::legal_in_format`.
+    pub legal_cards: Option<HashSet<Uuid>>,
+}
+
+impl ComboFilter {
+    #[must_use]
+    pub fn allows(&self, combo: &Combo) -> bool {
+        self.within_identity
+            .is_none_or(|identity| identity.contains(combo.identity))
+            && self
+                .legal_cards
+                .as_ref()
+                .is_none_or(|legal| combo.piece_ids().all(|id| legal.contains(&id)))
+    }
+}
+
+/// A combo a pool is a few cards short of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearCombo<'a> {
+    pub combo: &'a Combo,
+    /// Pieces not in the pool, in the combo's order.
+    pub missing: Vec<Uuid>,
+    /// The missing pieces found in the owned cards passed in.
+    pub owned: Vec<Uuid>,
+}
+
+impl NearCombo<'_> {
+    /// Whether every missing piece is already owned.
+    #[must_use]
+    pub fn completable(&self) -> bool {
+        self.owned.len() == self.missing.len()
+    }
+}
+
+/// An in-memory set of combos to check card pools against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComboDatabase {
+    combos: Vec<Combo>,
+    /// Oracle id to the positions in `combos` of the combos using it.
+    by_card: HashMap<Uuid, Vec<usize>>,
+    /// Distinct pieces per combo.
+    sizes: Vec<usize>,
+}
+
+impl ComboDatabase {
+    #[must_use]
+    pub fn new(combos: Vec<Combo>) -> Self {
+        let mut by_card: HashMap<Uuid, Vec<usize>> = HashMap::new();
+        let mut sizes = Vec::with_capacity(combos.len());
+        for (i, combo) in combos.iter().enumerate() {
+            let pieces: HashSet<Uuid> = combo.piece_ids().collect();
+            for id in &pieces {
+                by_card.entry(*id).or_default().push(i);
+            }
+            sizes.push(pieces.len());
+        }
+        Self {
+            combos,
+            by_card,
+            sizes,
+        }
+    }
+
+    /// Every combo stored in `db`.
+    pub async fn load<R>(db: &R) -> Result<Self, ComboError>
+    where
+        R: ComboRepository + ?Sized,
+    {
+        Ok(Self::new(db.all_combos().await?))
+    }
+
+    #[must_use]
+    pub fn combos(&self) -> &[Combo] {
+        &self.combos
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Combo> {
+        self.combos.iter().find(|c| &*c.id == id)
+    }
+
+    /// Combos using `oracle_id` as a piece.
+    pub fn combos_with(&self, oracle_id: Uuid) -> impl Iterator<Item = &Combo> {
+        self.by_card
+            .get(&oracle_id)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.combos[i])
+    }
+
+    /// How many distinct pieces of each touched combo `pool` holds, by
+    /// position in `combos`.
+    fn hits(&self, pool: &HashSet<Uuid>) -> HashMap<usize, usize> {
+        let mut hits: HashMap<usize, usize> = HashMap::new();
+        for id in pool {
+            for &i in self.by_card.get(id).into_iter().flatten() {
+                *hits.entry(i).or_default() += 1;
+            }
+        }
+        hits
+    }
+
+    /// All combos completable with the cards in `pool`, most popular first.
+    #[must_use]
+    pub fn find_enabled_combos(&self, pool: &[Uuid], filter: &ComboFilter) -> Vec<&Combo> {
+        let pool: HashSet<Uuid> = pool.iter().copied().collect();
+        let mut enabled: Vec<&Combo> = self
+            .hits(&pool)
+            .into_iter()
+            .filter(|&(i, n)| n == self.sizes[i])
+            .map(|(i, _)| &self.combos[i])
+            .filter(|combo| filter.allows(combo))
+            .collect();
+        enabled.sort_by(|a, b| b.popularity.cmp(&a.popularity).then(a.id.cmp(&b.id)));
+        enabled
+    }
+
+    /// Combos missing between one and `pieces_missing` pieces from `pool`,
+    /// fewest missing first, then most popular. Only combos with at least one
+    /// piece in the pool are considered. Missing pieces that appear in `owned`
+    /// (say, the cards in the user's collections) are listed in
+    /// [`NearCombo::owned`].
+    #[must_use]
+    pub fn find_near_combos(
+        &self,
+        pool: &[Uuid],
+        pieces_missing: usize,
+        filter: &ComboFilter,
+        owned: &HashSet<Uuid>,
+    ) -> Vec<NearCombo<'_>> {
+        let pool: HashSet<Uuid> = pool.iter().copied().collect();
+        let mut near: Vec<NearCombo<'_>> = self
+            .hits(&pool)
+            .into_iter()
+            .filter(|&(i, n)| n < self.sizes[i] && self.sizes[i] - n <= pieces_missing)
+            .map(|(i, _)| &self.combos[i])
+            .filter(|combo| filter.allows(combo))
+            .map(|combo| {
+                let mut missing: Vec<Uuid> = Vec::new();
+                for id in combo.piece_ids() {
+                    if !pool.contains(&id) && !missing.contains(&id) {
+                        missing.push(id);
+                    }
+                }
+                let owned = missing
+                    .iter()
+                    .copied()
+                    .filter(|id| owned.contains(id))
+                    .collect();
+                NearCombo {
+                    combo,
+                    missing,
+                    owned,
+                }
+            })
+            .collect();
+        near.sort_by(|a, b| {
+            a.missing
+                .len()
+                .cmp(&b.missing.len())
+                .then(b.combo.popularity.cmp(&a.combo.popularity))
+                .then(a.combo.id.cmp(&b.combo.id))
+        });
+        near
+    }
+}
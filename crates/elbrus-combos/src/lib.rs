@@ -1 +1,23 @@
 // Combo piece detection
+use elbrus_db::backend::DbError;
+use thiserror::Error;
+
+pub mod database;
+pub mod spellbook;
+pub mod suggest;
+
+pub use database::{ComboDatabase, ComboFilter, NearCombo};
+pub use spellbook::{IngestStats, SpellbookIngestor};
+pub use suggest::{ComboSuggestion, SuggestOptions, combo_bracket, suggest_combos};
+
+#[derive(Error, Debug)]
+pub enum ComboError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Database error: {0}")]
+    Db(#[from] DbError),
+    #[error("Deck error: {0}")]
+    Deck(#[from] elbrus_deck::DeckError),
+}
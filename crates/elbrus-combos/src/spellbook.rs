@@ -0,0 +1,220 @@
+//! Commander Spellbook variants export (`variants.json`).
+//!
+//! Only the fields elbrus stores are modelled; everything else in the export
+//! is ignored. Card names are resolved to oracle ids against the card
+//! database, so cards must be ingested before combos.
+
+use crate::ComboError;
+use elbrus_core::{Color, ColorSet, Combo, ComboPiece};
+use elbrus_db::repo::{CardRepository, ComboRepository};
+use elbrus_deck::DeckResolver;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct SpellbookExport {
+    pub variants: Vec<SpellbookVariant>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellbookVariant {
+    pub id: String,
+    /// `OK` for published combos; absent in older exports.
+    #[serde(default)]
+    pub status: Option<String>,
+    pub uses: Vec<CardUse>,
+    #[serde(default)]
+    pub requires: Vec<TemplateUse>,
+    #[serde(default)]
+    pub produces: Vec<FeatureUse>,
+    /// Color identity in WUBRG letters, `C` for colorless.
+    pub identity: String,
+    #[serde(default)]
+    pub mana_needed: Option<String>,
+    #[serde(default)]
+    pub easy_prerequisites: Option<String>,
+    #[serde(default)]
+    pub notable_prerequisites: Option<String>,
+    /// Older exports put all prerequisites here.
+    #[serde(default)]
+    pub other_prerequisites: Option<String>,
+    /// The steps to perform the combo.
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub popularity: Option<u32>,
+    #[serde(default)]
+    pub bracket_tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CardUse {
+    pub card: Named,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateUse {
+    pub template: Named,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeatureUse {
+    pub feature: Named,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Named {
+    pub name: String,
+}
+
+fn parse_identity(identity: &str) -> ColorSet {
+    identity
+        .chars()
+        .filter_map(|c| match c.to_ascii_uppercase() {
+            'W' => Some(Color::W),
+            'U' => Some(Color::U),
+            'B' => Some(Color::B),
+            'R' => Some(Color::R),
+            'G' => Some(Color::G),
+            _ => None,
+        })
+        .fold(ColorSet::empty(), |set, color| set | color.into())
+}
+
+fn non_empty(text: Option<&str>) -> Option<Arc<str>> {
+    text.map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(Into::into)
+}
+
+impl SpellbookVariant {
+    /// Template requirements first, then mana needed, then the free-text
+    /// prerequisites one line each.
+    #[must_use]
+    pub fn prerequisites(&self) -> Vec<Arc<str>> {
+        let mut prerequisites: Vec<Arc<str>> = self
+            .requires
+            .iter()
+            .map(|r| r.template.name.as_str().into())
+            .collect();
+        if let Some(mana) = non_empty(self.mana_needed.as_deref()) {
+            prerequisites.push(format!("{mana} available").into());
+        }
+        for text in [
+            &self.easy_prerequisites,
+            &self.notable_prerequisites,
+            &self.other_prerequisites,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            prerequisites.extend(text.lines().filter_map(|l| non_empty(Some(l))));
+        }
+        prerequisites
+    }
+}
+
+/// A variant left out because some of its cards are not in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedCombo {
+    pub id: Arc<str>,
+    pub unknown_cards: Vec<Arc<str>>,
+}
+
+#[derive(Debug, Default)]
+pub struct IngestStats {
+    pub variants_processed: u64,
+    pub combos_stored: u64,
+    /// Variants not marked `OK`, e.g. drafts or needing review.
+    pub variants_unpublished: u64,
+    pub skipped: Vec<SkippedCombo>,
+    pub duration: std::time::Duration,
+}
+
+pub struct SpellbookIngestor {
+    pub chunk_size: usize, // combos per transaction
+}
+
+impl Default for SpellbookIngestor {
+    fn default() -> Self {
+        Self { chunk_size: 1000 }
+    }
+}
+
+impl SpellbookIngestor {
+    pub fn read_file(path: &Path) -> Result<SpellbookExport, ComboError> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Resolve the export's variants against the cards in `db` and store them
+    /// as combos, replacing earlier imports of the same variant ids.
+    pub async fn ingest_into_db<R>(
+        &self,
+        export: &SpellbookExport,
+        db: &R,
+    ) -> Result<IngestStats, ComboError>
+    where
+        R: CardRepository + ComboRepository + ?Sized,
+    {
+        let start = std::time::Instant::now();
+        let mut stats = IngestStats::default();
+        let mut resolver = DeckResolver::new(db);
+        let mut batch = Vec::with_capacity(self.chunk_size);
+
+        for variant in &export.variants {
+            stats.variants_processed += 1;
+            if variant.status.as_deref().is_some_and(|s| s != "OK") {
+                stats.variants_unpublished += 1;
+                continue;
+            }
+
+            let mut pieces = Vec::with_capacity(variant.uses.len());
+            let mut unknown_cards = Vec::new();
+            for card_use in &variant.uses {
+                let name = &card_use.card.name;
+                match resolver.lookup(name).await? {
+                    Some(oracle_id) => pieces.push(ComboPiece {
+                        oracle_id,
+                        name: name.as_str().into(),
+                    }),
+                    None => unknown_cards.push(name.as_str().into()),
+                }
+            }
+            if !unknown_cards.is_empty() {
+                stats.skipped.push(SkippedCombo {
+                    id: variant.id.as_str().into(),
+                    unknown_cards,
+                });
+                continue;
+            }
+
+            batch.push(Combo {
+                id: variant.id.as_str().into(),
+                pieces,
+                prerequisites: variant.prerequisites(),
+                results: variant
+                    .produces
+                    .iter()
+                    .map(|p| p.feature.name.as_str().into())
+                    .collect(),
+                steps: non_empty(variant.description.as_deref()),
+                identity: parse_identity(&variant.identity),
+                popularity: variant.popularity,
+                bracket_tag: non_empty(variant.bracket_tag.as_deref()),
+            });
+            if batch.len() >= self.chunk_size {
+                db.upsert_combos(&batch).await?;
+                stats.combos_stored += batch.len() as u64;
+                batch.clear();
+            }
+        }
+        db.upsert_combos(&batch).await?;
+        stats.combos_stored += batch.len() as u64;
+
+        stats.duration = start.elapsed();
+        Ok(stats)
+    }
+}
@@ -0,0 +1,148 @@
+//! "Add these cards to enable a combo" suggestions for Commander decks.
+
+use crate::database::{ComboDatabase, ComboFilter};
+use elbrus_core::{ColorSet, Combo, OracleCard};
+use elbrus_deck::Deck;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Two-card combos whose pieces cost this much or less in total count as
+/// early-game combos.
+pub const EARLY_COMBO_MANA_VALUE: f32 = 4.0;
+
+/// The lowest Commander bracket a deck containing `combo` fits in. Commander
+/// Spellbook's [`bracket_tag`](Combo::bracket_tag) decides when it has one:
+/// casual combos fit bracket 1, precon-appropriate and oddball ones 2,
+/// powerful and spicy ones 3 and ruthless ones 4.
+///
+/// Untagged combos follow the official guidelines: brackets 1–2 have no
+/// two-card infinite combos and bracket 3 has no early-game ones. Two-card
+/// combos are 3, or 4 when their pieces' front faces total at most
+/// [`EARLY_COMBO_MANA_VALUE`]; combos of three or more cards are 2. Pieces
+/// missing from `cards` count as free.
+#[must_use]
+pub fn combo_bracket(combo: &Combo, cards: &HashMap<Uuid, OracleCard>) -> u8 {
+    if let Some(bracket) = combo.bracket_tag.as_deref().and_then(tag_bracket) {
+        return bracket;
+    }
+    if combo.pieces.len() > 2 {
+        return 2;
+    }
+    let mana_value: f32 = combo
+        .piece_ids()
+        .filter_map(|id| cards.get(&id))
+        .filter_map(|c| c.primary_face().mana_cost.as_ref().map(|m| m.cmc()))
+        .sum();
+    if mana_value <= EARLY_COMBO_MANA_VALUE {
+        4
+    } else {
+        3
+    }
+}
+
+/// The bracket a Commander Spellbook bracket tag stands for.
+fn tag_bracket(tag: &str) -> Option<u8> {
+    match tag {
+        "C" => Some(1),
+        "PA" | "O" => Some(2),
+        "P" | "S" => Some(3),
+        "R" => Some(4),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestOptions {
+    /// Suggest combos needing at most this many cards the deck lacks.
+    pub max_missing: usize,
+    /// Leave out combos above this bracket (see [`combo_bracket`]).
+    pub max_bracket: Option<u8>,
+}
+
+impl Default for SuggestOptions {
+    fn default() -> Self {
+        Self {
+            max_missing: 2,
+            max_bracket: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComboSuggestion<'a> {
+    pub combo: &'a Combo,
+    /// Cards to add, in the combo's order.
+    pub add: Vec<Uuid>,
+    /// The cards to add that are already owned.
+    pub owned: Vec<Uuid>,
+    /// Combo pieces already in the deck.
+    pub in_deck: usize,
+    pub bracket: u8,
+    pub score: f64,
+}
+
+/// The combo's rank among suggestions: the share of its pieces already in the
+/// deck, scaled up by the log of its popularity so a widely played combo beats
+/// an obscure one with the same pieces in hand.
+fn score(in_deck: usize, pieces: usize, popularity: Option<u32>) -> f64 {
+    let participation = in_deck as f64 / pieces.max(1) as f64;
+    participation * (1.0 + f64::from(popularity.unwrap_or(0)).ln_1p())
+}
+
+/// Combos within the commander's color identity that `deck` is one to
+/// `options.max_missing` cards short of, best first. Only the commander,
+/// companion and mainboard count towards a combo. `cards` must hold the
+/// commander and every combo piece, for color identity and mana values;
+/// missing pieces found in `owned` are listed in [`ComboSuggestion::owned`].
+#[must_use]
+pub fn suggest_combos<'a>(
+    combos: &'a ComboDatabase,
+    deck: &Deck,
+    cards: &HashMap<Uuid, OracleCard>,
+    owned: &HashSet<Uuid>,
+    options: &SuggestOptions,
+) -> Vec<ComboSuggestion<'a>> {
+    let identity = deck
+        .commander
+        .iter()
+        .filter_map(|e| e.resolved.and_then(|id| cards.get(&id)))
+        .fold(ColorSet::empty(), |set, c| set | c.color_identity);
+    let filter = ComboFilter {
+        within_identity: Some(identity),
+        ..ComboFilter::default()
+    };
+    let pool: Vec<Uuid> = deck
+        .commander
+        .iter()
+        .chain(&deck.companion)
+        .chain(&deck.mainboard)
+        .filter_map(|e| e.resolved)
+        .collect();
+
+    let mut suggestions: Vec<ComboSuggestion<'a>> = combos
+        .find_near_combos(&pool, options.max_missing, &filter, owned)
+        .into_iter()
+        .filter_map(|near| {
+            let bracket = combo_bracket(near.combo, cards);
+            if options.max_bracket.is_some_and(|max| bracket > max) {
+                return None;
+            }
+            let pieces = near.combo.piece_ids().collect::<HashSet<_>>().len();
+            let in_deck = pieces - near.missing.len();
+            Some(ComboSuggestion {
+                combo: near.combo,
+                score: score(in_deck, pieces, near.combo.popularity),
+                add: near.missing,
+                owned: near.owned,
+                in_deck,
+                bracket,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.combo.id.cmp(&b.combo.id))
+    });
+    suggestions
+}
@@ -0,0 +1,120 @@
+use elbrus_combos::{ComboDatabase, ComboFilter};
+use elbrus_core::{ColorSet, Combo, ComboPiece};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+fn combo(id: &str, pieces: &[Uuid], identity: ColorSet, popularity: u32) -> Combo {
+    Combo {
+        id: id.into(),
+        pieces: pieces
+            .iter()
+            .map(|&oracle_id| ComboPiece {
+                oracle_id,
+                name: oracle_id.to_string().into(),
+            })
+            .collect(),
+        prerequisites: vec![],
+        results: vec!["Infinite mana".into()],
+        steps: None,
+        identity,
+        popularity: Some(popularity),
+        bracket_tag: None,
+    }
+}
+
+#[test]
+fn test_enabled_and_near_combos() {
+    let [a, b, c, d, e] = [(); 5].map(|()| Uuid::new_v4());
+    let (ub, u, r) = (
+        ColorSet::BLUE | ColorSet::BLACK,
+        ColorSet::BLUE,
+        ColorSet::RED,
+    );
+    let db = ComboDatabase::new(vec![
+        combo("ab", &[a, b], ub, 100),
+        combo("ac", &[a, c], u, 500),
+        combo("acd", &[a, c, d], u, 50),
+        combo("de", &[d, e], r, 10),
+        combo("bcd", &[b, c, d], ub, 20),
+    ]);
+    assert_eq!(db.len(), 5);
+    assert_eq!(db.combos_with(a).count(), 3);
+
+    let any = ComboFilter::default();
+    let enabled: Vec<&str> = db
+        .find_enabled_combos(&[a, b, c], &any)
+        .iter()
+        .map(|c| &*c.id)
+        .collect();
+    assert_eq!(enabled, ["ac", "ab"]);
+
+    let owned = HashSet::from([d]);
+    let near = db.find_near_combos(&[a, b], 1, &any, &owned);
+    let ids: Vec<&str> = near.iter().map(|n| &*n.combo.id).collect();
+    assert_eq!(ids, ["ac"]);
+    assert_eq!(near[0].missing, vec![c]);
+    assert!(near[0].owned.is_empty());
+    assert!(!near[0].completable());
+
+    let near = db.find_near_combos(&[a, b], 2, &any, &owned);
+    let ids: Vec<&str> = near.iter().map(|n| &*n.combo.id).collect();
+    // "de" has no piece in the pool, so it is not near.
+    assert_eq!(ids, ["ac", "acd", "bcd"]);
+    assert_eq!(near[1].missing, vec![c, d]);
+    assert_eq!(near[1].owned, vec![d]);
+
+    // Color identity and legality filters.
+    let mono_blue = ComboFilter {
+        within_identity: Some(u),
+        ..ComboFilter::default()
+    };
+    let enabled = db.find_enabled_combos(&[a, b, c], &mono_blue);
+    assert_eq!(enabled.len(), 1);
+    assert_eq!(&*enabled[0].id, "ac");
+    let legal = ComboFilter {
+        legal_cards: Some(HashSet::from([a, b, c])),
+        ..ComboFilter::default()
+    };
+    let near = db.find_near_combos(&[a], 2, &legal, &HashSet::new());
+    let ids: Vec<&str> = near.iter().map(|n| &*n.combo.id).collect();
+    assert_eq!(ids, ["ac", "ab"]);
+
+    // Duplicated pool cards and pieces count once.
+    let db = ComboDatabase::new(vec![combo("aa", &[a, a, b], u, 1)]);
+    assert_eq!(db.find_enabled_combos(&[a, a, b], &any).len(), 1);
+    assert_eq!(
+        db.find_near_combos(&[a, a], 1, &any, &owned)[0].missing,
+        vec![b]
+    );
+}
+
+#[test]
+fn test_large_database() {
+    // 30,000 two- and three-card combos over 3,000 cards, checked against a
+    // 100-card pool.
+    let cards: Vec<Uuid> = (0..3000).map(|_| Uuid::new_v4()).collect();
+    let combos = (0..30_000)
+        .map(|i| {
+            let pieces: Vec<Uuid> = (0..2 + i % 2)
+                .map(|k| cards[(i * 7 + k * 131) % cards.len()])
+                .collect();
+            combo(&i.to_string(), &pieces, ColorSet::empty(), 0)
+        })
+        .collect();
+    let db = ComboDatabase::new(combos);
+    let pool = &cards[..100];
+    let pool_set: HashSet<Uuid> = pool.iter().copied().collect();
+
+    let expected = db
+        .combos()
+        .iter()
+        .filter(|c| c.piece_ids().all(|id| pool_set.contains(&id)))
+        .count();
+    assert_eq!(
+        db.find_enabled_combos(pool, &ComboFilter::default()).len(),
+        expected
+    );
+    let near = db.find_near_combos(pool, 1, &ComboFilter::default(), &HashSet::new());
+    assert!(!near.is_empty());
+    assert!(near.iter().all(|n| n.missing.len() == 1));
+}
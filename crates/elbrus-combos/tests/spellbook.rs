@@ -0,0 +1,156 @@
+use elbrus_combos::{ComboDatabase, SpellbookIngestor, spellbook::SpellbookExport};
+use elbrus_core::{
+    CardFace, CardLayout, OracleCard, color::ColorSet, legality::Legalities, oracle::OracleText,
+    types::TypeLine,
+};
+use elbrus_db::repo::{CardRepository, ComboRepository};
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+const EXPORT: &str = r#"{
+  "timestamp": "2026-10-01T00:00:00Z",
+  "variants": [
+    {
+      "id": "1414-2730",
+      "status": "OK",
+      "uses": [
+        {"card": {"id": 1414, "name": "Thassa's Oracle"}, "zoneLocations": ["H"], "quantity": 1},
+        {"card": {"id": 2730, "name": "Demonic Consultation"}, "zoneLocations": ["H"], "quantity": 1}
+      ],
+      "requires": [],
+      "produces": [
+        {"feature": {"id": 2, "name": "Win the game"}, "quantity": 1},
+        {"feature": {"id": 7, "name": "Exile your library"}, "quantity": 1}
+      ],
+      "identity": "UB",
+      "manaNeeded": "{U}{U}{B}",
+      "easyPrerequisites": "",
+      "notablePrerequisites": "",
+      "description": "Cast Demonic Consultation, naming a card not in your deck.\nCast Thassa's Oracle.",
+      "popularity": 31000,
+      "bracketTag": "R",
+      "legalities": {"commander": true}
+    },
+    {
+      "id": "5-6-7",
+      "status": "OK",
+      "uses": [
+        {"card": {"id": 5, "name": "Kiki-Jiki, Mirror Breaker"}},
+        {"card": {"id": 6, "name": "Zealous Conscripts"}}
+      ],
+      "requires": [{"template": {"id": 3, "name": "A creature with haste"}, "quantity": 1}],
+      "produces": [{"feature": {"id": 4, "name": "Infinite hasty tokens"}}],
+      "identity": "R",
+      "notablePrerequisites": "Kiki-Jiki on the battlefield untapped.\nZealous Conscripts in hand.",
+      "popularity": null
+    },
+    {
+      "id": "8-9",
+      "status": "OK",
+      "uses": [
+        {"card": {"id": 8, "name": "Dramatic Reversal"}},
+        {"card": {"id": 9, "name": "Isochron Scepter"}}
+      ],
+      "produces": [{"feature": {"id": 5, "name": "Infinite mana"}}],
+      "identity": "U"
+    },
+    {
+      "id": "10-11",
+      "status": "NR",
+      "uses": [{"card": {"id": 10, "name": "Thassa's Oracle"}}],
+      "identity": "U"
+    }
+  ]
+}"#;
+
+async fn insert(db: &SqliteBackend, name: &str) -> Uuid {
+    let oracle_id = Uuid::new_v4();
+    db.upsert_oracle(&OracleCard {
+        oracle_id,
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: name.into(),
+            mana_cost: None,
+            type_line: TypeLine::default(),
+            oracle_text: OracleText::default(),
+            colors: ColorSet::empty(),
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    })
+    .await
+    .unwrap();
+    oracle_id
+}
+
+#[tokio::test]
+async fn test_ingest_spellbook() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let oracle = insert(&db, "Thassa's Oracle").await;
+    let consultation = insert(&db, "Demonic Consultation").await;
+    let kiki = insert(&db, "Kiki-Jiki, Mirror Breaker").await;
+    insert(&db, "Zealous Conscripts").await;
+    insert(&db, "Isochron Scepter").await;
+
+    let export: SpellbookExport = serde_json::from_str(EXPORT).unwrap();
+    let ingestor = SpellbookIngestor::default();
+    let stats = ingestor.ingest_into_db(&export, &db).await.unwrap();
+    assert_eq!(stats.variants_processed, 4);
+    assert_eq!(stats.combos_stored, 2);
+    assert_eq!(stats.variants_unpublished, 1);
+    assert_eq!(stats.skipped.len(), 1);
+    assert_eq!(&*stats.skipped[0].id, "8-9");
+    assert_eq!(
+        stats.skipped[0].unknown_cards,
+        vec!["Dramatic Reversal".into()]
+    );
+
+    let thoracle = db.get_combo("1414-2730").await.unwrap().unwrap();
+    assert_eq!(
+        thoracle.piece_ids().collect::<Vec<_>>(),
+        vec![oracle, consultation]
+    );
+    assert_eq!(
+        thoracle.results,
+        vec!["Win the game".into(), "Exile your library".into()]
+    );
+    assert_eq!(thoracle.prerequisites, vec!["{U}{U}{B} available".into()]);
+    assert!(
+        thoracle
+            .steps
+            .as_deref()
+            .unwrap()
+            .starts_with("Cast Demonic")
+    );
+    assert_eq!(thoracle.identity, ColorSet::BLUE | ColorSet::BLACK);
+    assert_eq!(thoracle.popularity, Some(31000));
+    assert_eq!(thoracle.bracket_tag.as_deref(), Some("R"));
+
+    let kiki_combo = db.get_combo("5-6-7").await.unwrap().unwrap();
+    assert_eq!(
+        kiki_combo.prerequisites,
+        vec![
+            "A creature with haste".into(),
+            "Kiki-Jiki on the battlefield untapped.".into(),
+            "Zealous Conscripts in hand.".into(),
+        ]
+    );
+    assert_eq!(kiki_combo.steps, None);
+    assert_eq!(kiki_combo.popularity, None);
+    assert_eq!(db.combos_with_card(kiki).await.unwrap(), vec![kiki_combo]);
+
+    // Re-importing replaces rather than duplicates.
+    ingestor.ingest_into_db(&export, &db).await.unwrap();
+    assert_eq!(db.combo_count().await.unwrap(), 2);
+    let combos = ComboDatabase::load(&db).await.unwrap();
+    assert_eq!(combos.len(), 2);
+    assert_eq!(combos.get("1414-2730"), Some(&thoracle));
+}
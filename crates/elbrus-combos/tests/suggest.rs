@@ -0,0 +1,203 @@
+use elbrus_combos::{ComboDatabase, SuggestOptions, combo_bracket, suggest_combos};
+use elbrus_core::{
+    CardFace, CardLayout, ColorSet, Combo, ComboPiece, OracleCard, legality::Legalities,
+    oracle::OracleText, types::TypeLine,
+};
+use elbrus_deck::{Deck, DeckEntry};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct Fixture {
+    cards: HashMap<Uuid, OracleCard>,
+    combos: Vec<Combo>,
+}
+
+impl Fixture {
+    fn card(&mut self, name: &str, cost: &str, identity: ColorSet) -> &mut Self {
+        let card = OracleCard {
+            oracle_id: Uuid::new_v4(),
+            layout: CardLayout::Normal,
+            faces: smallvec::smallvec![CardFace {
+                name: name.into(),
+                mana_cost: Some(elbrus_parser::mana_cost::parse(cost).unwrap()),
+                type_line: TypeLine::default(),
+                oracle_text: OracleText::default(),
+                colors: identity,
+                power: None,
+                toughness: None,
+                loyalty: None,
+                defense: None,
+                flavor_text: None,
+            }],
+            color_identity: identity,
+            keywords: vec![],
+            legalities: Legalities::default(),
+            edh_rank: None,
+            reserved: false,
+        };
+        self.cards.insert(card.oracle_id, card);
+        self
+    }
+
+    fn id(&self, name: &str) -> Uuid {
+        self.cards
+            .values()
+            .find(|c| c.name() == name)
+            .unwrap()
+            .oracle_id
+    }
+
+    fn combo(&mut self, id: &str, pieces: &[&str], popularity: u32) -> &mut Self {
+        let pieces: Vec<ComboPiece> = pieces
+            .iter()
+            .map(|&name| ComboPiece {
+                oracle_id: self.id(name),
+                name: name.into(),
+            })
+            .collect();
+        let identity = pieces.iter().fold(ColorSet::empty(), |set, p| {
+            set | self.cards[&p.oracle_id].color_identity
+        });
+        self.combos.push(Combo {
+            id: id.into(),
+            pieces,
+            prerequisites: vec![],
+            results: vec!["Win the game".into()],
+            steps: None,
+            identity,
+            popularity: Some(popularity),
+            bracket_tag: None,
+        });
+        self
+    }
+
+    fn deck(&self, commander: &str, cards: &[&str]) -> Deck {
+        let entry = |name: &str| {
+            let mut entry = DeckEntry::new(1, name);
+            entry.resolved = Some(self.id(name));
+            entry
+        };
+        Deck {
+            commander: vec![entry(commander)],
+            mainboard: cards.iter().map(|&n| entry(n)).collect(),
+            ..Deck::default()
+        }
+    }
+}
+
+fn fixture() -> Fixture {
+    let (u, b, r) = (ColorSet::BLUE, ColorSet::BLACK, ColorSet::RED);
+    let mut f = Fixture::default();
+    f.card("Yuriko, the Tiger's Shadow", "{1}{U}{B}", u | b)
+        .card("Thassa's Oracle", "{U}{U}", u)
+        .card("Demonic Consultation", "{B}", b)
+        .card("Tainted Pact", "{1}{B}", b)
+        .card("Dramatic Reversal", "{1}{U}", u)
+        .card("Isochron Scepter", "{2}", ColorSet::empty())
+        .card("Exquisite Blood", "{4}{B}", b)
+        .card("Sanguine Bond", "{3}{B}{B}", b)
+        .card("Kiki-Jiki, Mirror Breaker", "{2}{R}{R}{R}", r)
+        .card("Zealous Conscripts", "{4}{R}", r)
+        .card("Ashnod's Altar", "{3}", ColorSet::empty())
+        .card("Nim Deathmantle", "{2}", ColorSet::empty())
+        .card("Grave Titan", "{4}{B}{B}", b);
+    f.combo(
+        "thoracle",
+        &["Thassa's Oracle", "Demonic Consultation"],
+        30_000,
+    )
+    .combo("pact", &["Thassa's Oracle", "Tainted Pact"], 20_000)
+    .combo(
+        "scepter",
+        &["Dramatic Reversal", "Isochron Scepter"],
+        15_000,
+    )
+    .combo("blood", &["Exquisite Blood", "Sanguine Bond"], 8_000)
+    .combo(
+        "kiki",
+        &["Kiki-Jiki, Mirror Breaker", "Zealous Conscripts"],
+        40_000,
+    )
+    .combo(
+        "titan",
+        &["Ashnod's Altar", "Nim Deathmantle", "Grave Titan"],
+        5_000,
+    );
+    f
+}
+
+#[test]
+fn test_combo_bracket() {
+    let f = fixture();
+    let bracket =
+        |id: &str| combo_bracket(f.combos.iter().find(|c| &*c.id == id).unwrap(), &f.cards);
+    assert_eq!(bracket("thoracle"), 4);
+    assert_eq!(bracket("scepter"), 4);
+    assert_eq!(bracket("blood"), 3);
+    assert_eq!(bracket("titan"), 2);
+
+    let tagged = |id: &str, tag: &str| {
+        let mut combo = f.combos.iter().find(|c| &*c.id == id).unwrap().clone();
+        combo.bracket_tag = Some(tag.into());
+        combo_bracket(&combo, &f.cards)
+    };
+    assert_eq!(tagged("blood", "R"), 4);
+    assert_eq!(tagged("thoracle", "PA"), 2);
+    assert_eq!(tagged("titan", "C"), 1);
+    assert_eq!(tagged("scepter", "?"), 4, "unknown tags fall back");
+}
+
+#[test]
+fn test_suggest_combos() {
+    let f = fixture();
+    let combos = ComboDatabase::new(f.combos.clone());
+    let deck = f.deck(
+        "Yuriko, the Tiger's Shadow",
+        &[
+            "Thassa's Oracle",
+            "Isochron Scepter",
+            "Sanguine Bond",
+            "Ashnod's Altar",
+            "Zealous Conscripts",
+        ],
+    );
+    let owned = HashSet::from([f.id("Demonic Consultation")]);
+
+    let suggestions = suggest_combos(&combos, &deck, &f.cards, &owned, &SuggestOptions::default());
+    let ids: Vec<&str> = suggestions.iter().map(|s| &*s.combo.id).collect();
+    // Kiki-Jiki is off-color despite being the most popular.
+    assert_eq!(ids, ["thoracle", "pact", "scepter", "blood", "titan"]);
+    assert!(suggestions.windows(2).all(|w| w[0].score >= w[1].score));
+    assert_eq!(suggestions[0].add, vec![f.id("Demonic Consultation")]);
+    assert_eq!(suggestions[0].owned, suggestions[0].add);
+    assert_eq!(suggestions[0].in_deck, 1);
+    assert_eq!(suggestions[0].bracket, 4);
+    assert!(suggestions[1].owned.is_empty());
+    assert_eq!(suggestions[4].add.len(), 2);
+    assert_eq!(suggestions[4].in_deck, 1);
+
+    let options = SuggestOptions {
+        max_missing: 1,
+        max_bracket: Some(3),
+    };
+    let suggestions = suggest_combos(&combos, &deck, &f.cards, &owned, &options);
+    let ids: Vec<&str> = suggestions.iter().map(|s| &*s.combo.id).collect();
+    assert_eq!(ids, ["blood"]);
+
+    let options = SuggestOptions {
+        max_bracket: Some(2),
+        ..SuggestOptions::default()
+    };
+    let suggestions = suggest_combos(&combos, &deck, &f.cards, &owned, &options);
+    let ids: Vec<&str> = suggestions.iter().map(|s| &*s.combo.id).collect();
+    assert_eq!(ids, ["titan"]);
+    // Sideboard cards aren't in the deck.
+    let mut deck = deck;
+    let mut consultation = DeckEntry::new(1, "Demonic Consultation");
+    consultation.resolved = Some(f.id("Demonic Consultation"));
+    deck.sideboard.push(consultation);
+    let suggestions = suggest_combos(&combos, &deck, &f.cards, &owned, &SuggestOptions::default());
+    assert_eq!(&*suggestions[0].combo.id, "thoracle");
+    assert_eq!(suggestions[0].add, vec![f.id("Demonic Consultation")]);
+}
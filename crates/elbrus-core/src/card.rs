@@ -70,6 +70,15 @@ impl OracleCard {
     pub fn name(&self) -> &str {
         &self.primary_face().name
     }
+    /// Every face's name joined as `A // B`, as printed on split and MDFC cards.
+    #[must_use]
+    pub fn full_name(&self) -> String {
+        self.faces
+            .iter()
+            .map(|f| &*f.name)
+            .collect::<Vec<_>>()
+            .join(" // ")
+    }
     #[must_use]
     pub fn cmc(&self) -> f32 {
         self.primary_face()
@@ -96,10 +105,14 @@ pub struct Printing {
     pub full_art: bool,
     pub textless: bool,
     pub reprint: bool,
+    /// Opened from the set's boosters, as opposed to only being available
+    /// in precons, promos or other supplemental products.
+    #[serde(default)]
+    pub booster: bool,
     pub prices: Option<PriceSnapshot>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[non_exhaustive]
 pub enum Rarity {
@@ -125,7 +138,68 @@ pub struct ImageUris {
 pub struct PriceSnapshot {
     pub usd: Option<rust_decimal::Decimal>,
     pub usd_foil: Option<rust_decimal::Decimal>,
+    #[serde(default)]
+    pub usd_etched: Option<rust_decimal::Decimal>,
     pub eur: Option<rust_decimal::Decimal>,
+    #[serde(default)]
+    pub eur_foil: Option<rust_decimal::Decimal>,
     pub tix: Option<rust_decimal::Decimal>,
     pub fetched_at: chrono::DateTime<chrono::Utc>,
 }
+
+impl PriceSnapshot {
+    /// Whether two snapshots carry identical prices, ignoring when they were fetched.
+    #[must_use]
+    pub fn same_prices(&self, other: &Self) -> bool {
+        self.usd == other.usd
+            && self.usd_foil == other.usd_foil
+            && self.usd_etched == other.usd_etched
+            && self.eur == other.eur
+            && self.eur_foil == other.eur_foil
+            && self.tix == other.tix
+    }
+
+    /// Price for one copy in `currency` with the given finish.
+    ///
+    /// Etched falls back to foil, and foil falls back to non-foil, when the
+    /// more specific price is missing. MTGO (`tix`) has a single price per printing.
+    #[must_use]
+    pub fn price(
+        &self,
+        currency: Currency,
+        foil: bool,
+        etched: bool,
+    ) -> Option<rust_decimal::Decimal> {
+        match currency {
+            Currency::Usd => {
+                let foil_price = if etched {
+                    self.usd_etched.or(self.usd_foil)
+                } else {
+                    self.usd_foil
+                };
+                if foil || etched {
+                    foil_price.or(self.usd)
+                } else {
+                    self.usd
+                }
+            }
+            Currency::Eur => {
+                if foil || etched {
+                    self.eur_foil.or(self.eur)
+                } else {
+                    self.eur
+                }
+            }
+            Currency::Tix => self.tix,
+        }
+    }
+}
+
+/// Currencies Scryfall reports prices in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Currency {
+    Usd,
+    Eur,
+    Tix,
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[non_exhaustive]
 pub enum Condition {
@@ -20,6 +20,12 @@ pub struct CollectionEntry {
     pub quantity: u32,
     pub condition: Condition,
     pub foil: bool,
+    /// Foil-etched finish. Only meaningful together with `foil`.
+    #[serde(default)]
+    pub etched: bool,
+    /// In the trade binder: available to give away in a trade.
+    #[serde(default)]
+    pub tradeable: bool,
     pub notes: Option<Arc<str>>,
 }
 
@@ -30,3 +36,60 @@ pub struct Collection {
     pub description: Option<Arc<str>>,
     pub entries: Vec<CollectionEntry>,
 }
+
+/// A printing to alert on when its price crosses a threshold between ingests.
+/// Any combination of thresholds may be set; an alert fires if any is crossed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchEntry {
+    pub printing_id: Uuid,
+    pub currency: crate::card::Currency,
+    pub foil: bool,
+    /// Absolute percentage move, e.g. `20` for ±20%.
+    pub threshold_pct: Option<rust_decimal::Decimal>,
+    pub price_above: Option<rust_decimal::Decimal>,
+    pub price_below: Option<rust_decimal::Decimal>,
+    pub notes: Option<Arc<str>>,
+}
+
+/// Copies of one printing changing hands in a trade.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeCard {
+    pub printing_id: Uuid,
+    pub quantity: u32,
+    pub condition: Condition,
+    pub foil: bool,
+    #[serde(default)]
+    pub etched: bool,
+}
+
+impl TradeCard {
+    /// The collection entry these copies occupy (or will occupy) in `collection_id`.
+    #[must_use]
+    pub fn to_entry(&self, collection_id: Uuid) -> CollectionEntry {
+        CollectionEntry {
+            collection_id,
+            printing_id: self.printing_id,
+            quantity: self.quantity,
+            condition: self.condition,
+            foil: self.foil,
+            etched: self.etched,
+            tradeable: false,
+            notes: None,
+        }
+    }
+}
+
+/// A completed trade as kept in the ledger, with both sides valued at the
+/// time it was made.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub id: Uuid,
+    pub collection_id: Uuid,
+    pub traded_at: chrono::DateTime<chrono::Utc>,
+    pub given: Vec<TradeCard>,
+    pub received: Vec<TradeCard>,
+    pub currency: crate::card::Currency,
+    pub given_value: rust_decimal::Decimal,
+    pub received_value: rust_decimal::Decimal,
+    pub notes: Option<Arc<str>>,
+}
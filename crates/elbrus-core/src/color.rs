@@ -35,6 +35,8 @@ pub enum Color {
 }
 
 impl Color {
+    pub const ALL: [Self; 5] = [Self::W, Self::U, Self::B, Self::R, Self::G];
+
     pub fn land(self) -> String {
         match self {
             Self::W => "Plains".to_owned(),
@@ -45,3 +47,15 @@ impl Color {
         }
     }
 }
+
+impl From<Color> for ColorSet {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::W => Self::WHITE,
+            Color::U => Self::BLUE,
+            Color::B => Self::BLACK,
+            Color::R => Self::RED,
+            Color::G => Self::GREEN,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use crate::color::ColorSet;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ComboPiece {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+}
+
+/// A combo from Commander Spellbook: a set of cards that together produce
+/// the listed results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Combo {
+    /// Commander Spellbook variant id, e.g. `"1414-2730"`.
+    pub id: Arc<str>,
+    pub pieces: Vec<ComboPiece>,
+    /// What must hold beyond having the pieces: board states, mana available,
+    /// permanents of a given type.
+    pub prerequisites: Vec<Arc<str>>,
+    pub results: Vec<Arc<str>>,
+    pub steps: Option<Arc<str>>,
+    pub identity: ColorSet,
+    /// Decks on EDHREC playing the combo, as reported by Commander Spellbook.
+    pub popularity: Option<u32>,
+    /// Commander Spellbook's bracket tag, e.g. `"R"` for ruthless.
+    pub bracket_tag: Option<Arc<str>>,
+}
+
+impl Combo {
+    pub fn piece_ids(&self) -> impl Iterator<Item = Uuid> + '_ {
+        self.pieces.iter().map(|p| p.oracle_id)
+    }
+}
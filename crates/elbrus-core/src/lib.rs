@@ -1,11 +1,14 @@
 pub mod card;
 pub mod collection;
 pub mod color;
+pub mod combo;
 pub mod error;
 pub mod game;
 pub mod keyword;
 pub mod legality;
+pub mod limited;
 pub mod mana;
+pub mod metagame;
 pub mod oracle;
 pub mod ruling;
 pub mod types;
@@ -13,11 +16,14 @@ pub mod types;
 pub use card::*;
 pub use collection::*;
 pub use color::*;
+pub use combo::*;
 pub use error::*;
 pub use game::*;
 pub use keyword::*;
 pub use legality::*;
+pub use limited::*;
 pub use mana::*;
+pub use metagame::*;
 pub use oracle::*;
 pub use ruling::*;
 pub use types::*;
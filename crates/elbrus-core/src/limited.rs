@@ -0,0 +1,97 @@
+use crate::color::ColorSet;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// How one card fared in games of one expansion and event type, in decks of
+/// one set of main colors, as tallied from 17lands game data. Each count is
+/// of games, however many copies were played.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardGameCounts {
+    pub expansion: Arc<str>,
+    /// 17lands event type, e.g. `PremierDraft`.
+    pub event_type: Arc<str>,
+    /// Main colors of the decks, splashes aside.
+    pub colors: ColorSet,
+    pub name: Arc<str>,
+    /// Games with the card in the main deck.
+    pub games_in_deck: u64,
+    pub wins_in_deck: u64,
+    /// Games the card was in the opening hand or drawn.
+    pub games_in_hand: u64,
+    pub wins_in_hand: u64,
+    /// Games with the card in the deck but never seen.
+    pub games_not_seen: u64,
+    pub wins_not_seen: u64,
+}
+
+impl CardGameCounts {
+    /// Win rate when the card was in hand (GIH WR).
+    #[must_use]
+    pub fn gih_win_rate(&self) -> Option<f64> {
+        ratio(self.wins_in_hand, self.games_in_hand)
+    }
+
+    /// Win rate when the card was in the deck but not seen (GNS WR).
+    #[must_use]
+    pub fn gns_win_rate(&self) -> Option<f64> {
+        ratio(self.wins_not_seen, self.games_not_seen)
+    }
+
+    /// Improvement when drawn (IWD): GIH WR less GNS WR.
+    #[must_use]
+    pub fn iwd(&self) -> Option<f64> {
+        Some(self.gih_win_rate()? - self.gns_win_rate()?)
+    }
+
+    /// Add `other`'s games to these.
+    pub fn merge(&mut self, other: &Self) {
+        self.games_in_deck += other.games_in_deck;
+        self.wins_in_deck += other.wins_in_deck;
+        self.games_in_hand += other.games_in_hand;
+        self.wins_in_hand += other.wins_in_hand;
+        self.games_not_seen += other.games_not_seen;
+        self.wins_not_seen += other.wins_not_seen;
+    }
+}
+
+/// How one card went in drafts of one expansion and event type, as tallied
+/// from 17lands draft data. Pick numbers are 1-based.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardDraftCounts {
+    pub expansion: Arc<str>,
+    pub event_type: Arc<str>,
+    pub name: Arc<str>,
+    /// Packs the card was seen in, counting each pack once.
+    pub packs_seen: u64,
+    /// Sum over those packs of the last pick the card was seen at.
+    pub last_seen_total: u64,
+    pub times_picked: u64,
+    /// Sum of the pick numbers it was taken at.
+    pub pick_total: u64,
+}
+
+impl CardDraftCounts {
+    /// Average last seen at (ALSA).
+    #[must_use]
+    pub fn alsa(&self) -> Option<f64> {
+        ratio(self.last_seen_total, self.packs_seen)
+    }
+
+    /// Average taken at (ATA).
+    #[must_use]
+    pub fn ata(&self) -> Option<f64> {
+        ratio(self.pick_total, self.times_picked)
+    }
+
+    /// Add `other`'s drafts to these.
+    pub fn merge(&mut self, other: &Self) {
+        self.packs_seen += other.packs_seen;
+        self.last_seen_total += other.last_seen_total;
+        self.times_picked += other.times_picked;
+        self.pick_total += other.pick_total;
+    }
+}
+
+fn ratio(total: u64, count: u64) -> Option<f64> {
+    (count > 0).then(|| total as f64 / count as f64)
+}
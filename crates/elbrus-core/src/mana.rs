@@ -71,12 +71,42 @@ pub enum ManaSymbol {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub struct ManaCost(pub SmallVec<[ManaSymbol; 8]>);
 
+impl ManaSymbol {
+    /// Contribution to mana value. X and unrecognised symbols count as 0.
+    pub fn mana_value(&self) -> f32 {
+        match self {
+            Self::Generic(n) => n.value() as f32,
+            Self::TwoBrid(_) => 2.0,
+            Self::Colored(_)
+            | Self::Colorless
+            | Self::Snow
+            | Self::Hybrid(..)
+            | Self::Phyrexian(_)
+            | Self::HybridPhyrexian(..) => 1.0,
+            Self::Variable(_) | Self::Tap | Self::Unknown(_) => 0.0,
+        }
+    }
+
+    /// Colors that can pay for this symbol.
+    pub fn colors(&self) -> ColorSet {
+        match self {
+            Self::Colored(c) | Self::TwoBrid(c) | Self::Phyrexian(c) => ColorSet::from(*c),
+            Self::Hybrid(a, b) | Self::HybridPhyrexian(a, b) => {
+                ColorSet::from(*a) | ColorSet::from(*b)
+            }
+            _ => ColorSet::empty(),
+        }
+    }
+}
+
 impl ManaCost {
     pub fn cmc(&self) -> f32 {
-        todo!()
+        self.0.iter().map(ManaSymbol::mana_value).sum()
     }
     pub fn color_identity(&self) -> ColorSet {
-        todo!()
+        self.0
+            .iter()
+            .fold(ColorSet::empty(), |acc, s| acc | s.colors())
     }
     pub fn parse(_s: &str) -> Result<Self, crate::error::CoreError> {
         Err(crate::error::CoreError::ParseError(
@@ -0,0 +1,45 @@
+use crate::legality::Format;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How widely one card is played across the decks of a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StapleStat {
+    pub oracle_id: Uuid,
+    pub name: Arc<str>,
+    /// Decks playing the card in the main deck or sideboard.
+    pub decks: u32,
+    pub main_decks: u32,
+    pub side_decks: u32,
+    /// Share of all decks in the snapshot playing the card.
+    pub inclusion_rate: f64,
+    /// Average main-deck copies among decks playing it main.
+    pub avg_main_copies: f64,
+    /// Average sideboard copies among decks playing it in the sideboard.
+    pub avg_side_copies: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchetypeShare {
+    pub name: Arc<str>,
+    pub decks: u32,
+    pub share: f64,
+}
+
+/// The decks of a format played between two dates, summarised as a staples
+/// index and an archetype breakdown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetagameSnapshot {
+    pub id: Uuid,
+    pub format: Format,
+    /// First and last day covered, inclusive.
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub deck_count: u32,
+    /// Most played first.
+    pub staples: Vec<StapleStat>,
+    /// Largest first.
+    pub archetypes: Vec<ArchetypeShare>,
+}
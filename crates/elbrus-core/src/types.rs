@@ -34,6 +34,43 @@ pub enum CardType {
     Unknown(Arc<str>),
 }
 
+impl Supertype {
+    fn from_word(word: &str) -> Option<Self> {
+        Some(match word {
+            "Basic" => Self::Basic,
+            "Legendary" => Self::Legendary,
+            "Snow" => Self::Snow,
+            "World" => Self::World,
+            "Token" => Self::Token,
+            "Elite" | "Host" | "Ongoing" => Self::Unknown(Arc::from(word)),
+            _ => return None,
+        })
+    }
+}
+
+impl CardType {
+    fn from_word(word: &str) -> Self {
+        match word {
+            "Artifact" => Self::Artifact,
+            "Battle" => Self::Battle,
+            "Conspiracy" => Self::Conspiracy,
+            "Creature" => Self::Creature,
+            "Dungeon" => Self::Dungeon,
+            "Enchantment" => Self::Enchantment,
+            "Instant" => Self::Instant,
+            "Land" => Self::Land,
+            "Phenomenon" => Self::Phenomenon,
+            "Plane" => Self::Plane,
+            "Planeswalker" => Self::Planeswalker,
+            "Scheme" => Self::Scheme,
+            "Sorcery" => Self::Sorcery,
+            "Tribal" | "Kindred" => Self::Tribal,
+            "Vanguard" => Self::Vanguard,
+            other => Self::Unknown(Arc::from(other)),
+        }
+    }
+}
+
 /// Subtype is an open set — Wizards adds creature types, land types, spell types freely.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Subtype(pub Arc<str>);
@@ -49,6 +86,9 @@ impl TypeLine {
     pub fn is_creature(&self) -> bool {
         self.card_types.contains(&CardType::Creature)
     }
+    pub fn is_land(&self) -> bool {
+        self.card_types.contains(&CardType::Land)
+    }
     pub fn is_permanent(&self) -> bool {
         self.card_types.iter().any(|t| {
             matches!(
@@ -62,9 +102,28 @@ impl TypeLine {
             )
         })
     }
-    pub fn parse(_s: &str) -> Result<Self, crate::error::CoreError> {
-        Err(crate::error::CoreError::ParseError(
-            "not implemented".into(),
-        ))
+    /// Parse a single face's type line, e.g. `Legendary Creature — Elf Druid`.
+    /// Words that are neither known supertypes nor card types become
+    /// `CardType::Unknown`.
+    pub fn parse(s: &str) -> Result<Self, crate::error::CoreError> {
+        let (types, subtypes) = s.split_once('—').unwrap_or((s, ""));
+        let mut line = Self::default();
+        for word in types.split_whitespace() {
+            match Supertype::from_word(word) {
+                Some(supertype) => line.supertypes.push(supertype),
+                None => line.card_types.push(CardType::from_word(word)),
+            }
+        }
+        line.subtypes = subtypes
+            .split_whitespace()
+            .map(|w| Subtype(Arc::from(w)))
+            .collect();
+
+        if line.card_types.is_empty() && line.supertypes.is_empty() {
+            return Err(crate::error::CoreError::ParseError(format!(
+                "no types in type line: {s:?}"
+            )));
+        }
+        Ok(line)
     }
 }
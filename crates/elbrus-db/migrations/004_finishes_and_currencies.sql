@@ -0,0 +1,25 @@
+ALTER TABLE price_snapshots ADD COLUMN usd_etched TEXT;
+ALTER TABLE price_snapshots ADD COLUMN eur_foil TEXT;
+
+-- Etched foils are a separate finish from regular foils, so `etched` joins the
+-- primary key. SQLite can't change a primary key in place: rebuild the table.
+CREATE TABLE collection_entries_new (
+    collection_id TEXT NOT NULL,
+    printing_id TEXT NOT NULL,
+    quantity INTEGER NOT NULL DEFAULT 1,
+    condition TEXT NOT NULL,
+    foil BOOLEAN NOT NULL DEFAULT FALSE,
+    notes TEXT,
+    etched BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (collection_id, printing_id, condition, foil, etched),
+    FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE,
+    FOREIGN KEY (printing_id) REFERENCES printings(id) ON DELETE CASCADE
+);
+
+INSERT INTO collection_entries_new (collection_id, printing_id, quantity, condition, foil, notes)
+SELECT collection_id, printing_id, quantity, condition, foil, notes FROM collection_entries;
+
+DROP TABLE collection_entries;
+ALTER TABLE collection_entries_new RENAME TO collection_entries;
+
+CREATE INDEX idx_collection_entries_printing_id ON collection_entries(printing_id);
@@ -0,0 +1,13 @@
+CREATE TABLE watchlist (
+    printing_id TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    foil BOOLEAN NOT NULL DEFAULT FALSE,
+    threshold_pct TEXT,
+    price_above TEXT,
+    price_below TEXT,
+    notes TEXT,
+    PRIMARY KEY (printing_id, currency, foil),
+    FOREIGN KEY (printing_id) REFERENCES printings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_price_snapshots_fetched_at ON price_snapshots(fetched_at);
@@ -0,0 +1,3 @@
+-- Printings stored before this column existed keep counting as booster
+-- cards, as they did before, until the next ingest sets the real value.
+ALTER TABLE printings ADD COLUMN booster BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,28 @@
+ALTER TABLE collection_entries ADD COLUMN tradeable BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE trades (
+    id TEXT PRIMARY KEY,
+    collection_id TEXT NOT NULL,
+    traded_at TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    given_value TEXT NOT NULL,
+    received_value TEXT NOT NULL,
+    notes TEXT,
+    FOREIGN KEY (collection_id) REFERENCES collections(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_trades_collection_id ON trades(collection_id, traded_at);
+
+CREATE TABLE trade_cards (
+    trade_id TEXT NOT NULL,
+    side TEXT NOT NULL, -- 'given' or 'received'
+    printing_id TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    condition TEXT NOT NULL,
+    foil BOOLEAN NOT NULL,
+    etched BOOLEAN NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (trade_id) REFERENCES trades(id) ON DELETE CASCADE,
+    FOREIGN KEY (printing_id) REFERENCES printings(id)
+);
+
+CREATE INDEX idx_trade_cards_trade_id ON trade_cards(trade_id);
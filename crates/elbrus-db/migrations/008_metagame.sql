@@ -0,0 +1,37 @@
+CREATE TABLE metagame_snapshots (
+    id TEXT PRIMARY KEY,
+    format TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    end_date TEXT NOT NULL,
+    deck_count INTEGER NOT NULL,
+    UNIQUE (format, start_date, end_date)
+);
+
+CREATE INDEX idx_metagame_snapshots_format ON metagame_snapshots(format, end_date);
+
+CREATE TABLE metagame_staples (
+    snapshot_id TEXT NOT NULL,
+    oracle_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    decks INTEGER NOT NULL,
+    main_decks INTEGER NOT NULL,
+    side_decks INTEGER NOT NULL,
+    inclusion_rate REAL NOT NULL,
+    avg_main_copies REAL NOT NULL,
+    avg_side_copies REAL NOT NULL,
+    PRIMARY KEY (snapshot_id, oracle_id),
+    FOREIGN KEY (snapshot_id) REFERENCES metagame_snapshots(id) ON DELETE CASCADE,
+    FOREIGN KEY (oracle_id) REFERENCES oracle_cards(oracle_id)
+);
+
+CREATE INDEX idx_metagame_staples_oracle_id ON metagame_staples(oracle_id);
+
+CREATE TABLE metagame_archetypes (
+    snapshot_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    decks INTEGER NOT NULL,
+    share REAL NOT NULL,
+    PRIMARY KEY (snapshot_id, position),
+    FOREIGN KEY (snapshot_id) REFERENCES metagame_snapshots(id) ON DELETE CASCADE
+);
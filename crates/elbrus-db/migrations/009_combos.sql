@@ -0,0 +1,35 @@
+CREATE TABLE combos (
+    id TEXT PRIMARY KEY,
+    identity INTEGER NOT NULL,
+    steps TEXT,
+    popularity INTEGER,
+    bracket_tag TEXT
+);
+
+CREATE TABLE combo_pieces (
+    combo_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    oracle_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (combo_id, position),
+    FOREIGN KEY (combo_id) REFERENCES combos(id) ON DELETE CASCADE,
+    FOREIGN KEY (oracle_id) REFERENCES oracle_cards(oracle_id)
+);
+
+CREATE INDEX idx_combo_pieces_oracle_id ON combo_pieces(oracle_id);
+
+CREATE TABLE combo_prerequisites (
+    combo_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    prerequisite TEXT NOT NULL,
+    PRIMARY KEY (combo_id, position),
+    FOREIGN KEY (combo_id) REFERENCES combos(id) ON DELETE CASCADE
+);
+
+CREATE TABLE combo_results (
+    combo_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    result TEXT NOT NULL,
+    PRIMARY KEY (combo_id, position),
+    FOREIGN KEY (combo_id) REFERENCES combos(id) ON DELETE CASCADE
+);
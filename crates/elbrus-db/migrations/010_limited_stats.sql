@@ -0,0 +1,32 @@
+-- Per-card tallies from 17lands public datasets, one set of rows per file
+-- ingested so that re-ingesting a file replaces its rows.
+CREATE TABLE limited_game_counts (
+    dataset TEXT NOT NULL,
+    expansion TEXT NOT NULL COLLATE NOCASE,
+    event_type TEXT NOT NULL,
+    colors INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    games_in_deck INTEGER NOT NULL,
+    wins_in_deck INTEGER NOT NULL,
+    games_in_hand INTEGER NOT NULL,
+    wins_in_hand INTEGER NOT NULL,
+    games_not_seen INTEGER NOT NULL,
+    wins_not_seen INTEGER NOT NULL,
+    PRIMARY KEY (dataset, expansion, event_type, colors, name)
+);
+
+CREATE INDEX idx_limited_game_counts_expansion ON limited_game_counts(expansion, event_type);
+
+CREATE TABLE limited_draft_counts (
+    dataset TEXT NOT NULL,
+    expansion TEXT NOT NULL COLLATE NOCASE,
+    event_type TEXT NOT NULL,
+    name TEXT NOT NULL,
+    packs_seen INTEGER NOT NULL,
+    last_seen_total INTEGER NOT NULL,
+    times_picked INTEGER NOT NULL,
+    pick_total INTEGER NOT NULL,
+    PRIMARY KEY (dataset, expansion, event_type, name)
+);
+
+CREATE INDEX idx_limited_draft_counts_expansion ON limited_draft_counts(expansion, event_type);
@@ -0,0 +1,5 @@
+-- Snapshot times are compared as strings: rewrite them as fixed-width UTC with
+-- milliseconds, the format the price repository now writes. A row that lands
+-- on the same millisecond as another for its printing is a duplicate.
+UPDATE OR IGNORE price_snapshots SET fetched_at = strftime('%Y-%m-%dT%H:%M:%fZ', fetched_at);
+DELETE FROM price_snapshots WHERE fetched_at != strftime('%Y-%m-%dT%H:%M:%fZ', fetched_at);
@@ -0,0 +1,7 @@
+-- Bulk ingests by the time their prices were fetched. Unchanged prices write
+-- no snapshot and backfills write older ones, so snapshot times can't stand in
+-- for these.
+CREATE TABLE ingest_runs (
+    fetched_at TEXT PRIMARY KEY,
+    ingested_at TEXT NOT NULL
+);
@@ -6,6 +6,8 @@ use thiserror::Error;
 pub enum DbError {
     #[error("SQL error: {0}")]
     Sql(String),
+    #[error("Constraint violated: {0}")]
+    Constraint(String),
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
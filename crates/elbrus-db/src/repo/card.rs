@@ -14,6 +14,10 @@ pub trait CardRepository: Send + Sync {
     async fn search_fts(&self, q: &str, limit: u32) -> Result<Vec<OracleCard>, DbError>;
     async fn cards_in_set(&self, set_code: &str) -> Result<Vec<Printing>, DbError>;
     async fn legal_in_format(&self, format: &Format) -> Result<Vec<OracleCard>, DbError>;
+    /// Every oracle card with its faces, for building offline indexes.
+    async fn all_oracle_cards(&self) -> Result<Vec<OracleCard>, DbError>;
+    /// Ids of every printing whose oracle card is legal in `format`.
+    async fn legal_printing_ids(&self, format: &Format) -> Result<Vec<Uuid>, DbError>;
 }
 
 fn parse_oracle_card(row: &sqlx::sqlite::SqliteRow) -> Result<OracleCard, DbError> {
@@ -162,6 +166,9 @@ fn parse_printing(row: &sqlx::sqlite::SqliteRow) -> Result<Printing, DbError> {
     let reprint: bool = row
         .try_get("reprint")
         .map_err(|e| DbError::Sql(e.to_string()))?;
+    let booster: bool = row
+        .try_get("booster")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
     let prices_str: Option<String> = row
         .try_get("prices")
         .map_err(|e| DbError::Sql(e.to_string()))?;
@@ -198,6 +205,7 @@ fn parse_printing(row: &sqlx::sqlite::SqliteRow) -> Result<Printing, DbError> {
         full_art,
         textless,
         reprint,
+        booster,
         prices,
     })
 }
@@ -265,15 +273,16 @@ impl CardRepository for SqliteBackend {
 
     async fn upsert_printing(&self, p: &Printing) -> Result<(), DbError> {
         sqlx::query(
-            "INSERT INTO printings (id, oracle_id, set_code, collector_number, rarity, lang, released_at, image_uris, promo, digital, full_art, textless, reprint, prices) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+            "INSERT INTO printings (id, oracle_id, set_code, collector_number, rarity, lang, released_at, image_uris, promo, digital, full_art, textless, reprint, booster, prices) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
              ON CONFLICT (id) DO UPDATE SET \
              oracle_id = excluded.oracle_id, set_code = excluded.set_code, \
              collector_number = excluded.collector_number, rarity = excluded.rarity, \
              lang = excluded.lang, released_at = excluded.released_at, \
              image_uris = excluded.image_uris, promo = excluded.promo, \
              digital = excluded.digital, full_art = excluded.full_art, \
-             textless = excluded.textless, reprint = excluded.reprint, prices = excluded.prices"
+             textless = excluded.textless, reprint = excluded.reprint, \
+             booster = excluded.booster, prices = excluded.prices"
         )
         .bind(p.id.to_string())
         .bind(p.oracle_id.to_string())
@@ -288,6 +297,7 @@ impl CardRepository for SqliteBackend {
         .bind(p.full_art)
         .bind(p.textless)
         .bind(p.reprint)
+        .bind(p.booster)
         .bind(p.prices.as_ref().map(|pr| serde_json::to_string(pr).unwrap()))
         .execute(&self.pool)
         .await
@@ -456,4 +466,52 @@ impl CardRepository for SqliteBackend {
 
         Ok(cards)
     }
+
+    async fn all_oracle_cards(&self) -> Result<Vec<OracleCard>, DbError> {
+        let rows = sqlx::query(
+            "SELECT o.*, f.name, f.mana_cost, f.type_line, f.oracle_text, f.colors, f.power, f.toughness, f.loyalty, f.defense, f.flavor_text \
+             FROM oracle_cards o \
+             JOIN card_faces f ON o.oracle_id = f.oracle_id \
+             ORDER BY o.oracle_id, f.face_index ASC"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut cards: Vec<OracleCard> = Vec::new();
+        for row in rows {
+            let row_oracle_id_str: String = row.try_get("oracle_id").unwrap();
+            let row_oracle_id = Uuid::parse_str(&row_oracle_id_str).unwrap();
+
+            let is_new = cards.last().is_none_or(|c| c.oracle_id != row_oracle_id);
+            if is_new {
+                cards.push(parse_oracle_card(&row)?);
+            }
+
+            let face = parse_card_face(&row)?;
+            cards.last_mut().unwrap().faces.push(face);
+        }
+
+        Ok(cards)
+    }
+
+    async fn legal_printing_ids(&self, format: &Format) -> Result<Vec<Uuid>, DbError> {
+        let path = format!("$.{}", format.0);
+        let rows = sqlx::query(
+            "SELECT p.id FROM printings p \
+             JOIN oracle_cards o ON p.oracle_id = o.oracle_id \
+             WHERE json_extract(o.legalities, ?) = 'legal'",
+        )
+        .bind(&path)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut ids = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id_str: String = row.try_get("id").map_err(|e| DbError::Sql(e.to_string()))?;
+            ids.push(Uuid::parse_str(&id_str).map_err(|e| DbError::Unknown(e.to_string()))?);
+        }
+        Ok(ids)
+    }
 }
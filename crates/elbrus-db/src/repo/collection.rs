@@ -2,6 +2,7 @@ use crate::backend::DbError;
 use crate::sqlite::SqliteBackend;
 use elbrus_core::{Collection, CollectionEntry, Condition};
 use sqlx::Row;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 #[async_trait::async_trait]
@@ -21,7 +22,21 @@ pub trait CollectionRepository: Send + Sync {
         printing_id: Uuid,
         condition: Condition,
         foil: bool,
+        etched: bool,
     ) -> Result<(), DbError>;
+    /// Mark an entry as available (or not) for trade. Returns `false` if no
+    /// such entry exists.
+    async fn set_tradeable(
+        &self,
+        collection_id: Uuid,
+        printing_id: Uuid,
+        condition: Condition,
+        foil: bool,
+        etched: bool,
+        tradeable: bool,
+    ) -> Result<bool, DbError>;
+    /// Oracle ids of every card in any collection.
+    async fn owned_oracle_ids(&self) -> Result<HashSet<Uuid>, DbError>;
 }
 
 #[async_trait::async_trait]
@@ -97,7 +112,7 @@ impl CollectionRepository for SqliteBackend {
             let desc_str: Option<String> = row.try_get("description").unwrap_or(None);
 
             let entry_rows = sqlx::query(
-                "SELECT collection_id, printing_id, quantity, condition, foil, notes \
+                "SELECT collection_id, printing_id, quantity, condition, foil, etched, tradeable, notes \
                  FROM collection_entries WHERE collection_id = ?",
             )
             .bind(id.to_string())
@@ -112,6 +127,8 @@ impl CollectionRepository for SqliteBackend {
                 let quantity = u32::try_from(quantity_i64).unwrap_or(0);
                 let cond_str: String = erow.try_get("condition").unwrap();
                 let foil: bool = erow.try_get("foil").unwrap();
+                let etched: bool = erow.try_get("etched").unwrap();
+                let tradeable: bool = erow.try_get("tradeable").unwrap();
                 let notes: Option<String> = erow.try_get("notes").unwrap_or(None);
 
                 let condition: Condition =
@@ -123,6 +140,8 @@ impl CollectionRepository for SqliteBackend {
                     quantity,
                     condition,
                     foil,
+                    etched,
+                    tradeable,
                     notes: notes.map(Into::into),
                 });
             }
@@ -146,16 +165,18 @@ impl CollectionRepository for SqliteBackend {
             .trim_matches('"')
             .to_string();
         sqlx::query(
-            "INSERT INTO collection_entries (collection_id, printing_id, quantity, condition, foil, notes) \
-             VALUES (?, ?, ?, ?, ?, ?) \
-             ON CONFLICT (collection_id, printing_id, condition, foil) DO UPDATE SET \
-             quantity = excluded.quantity, notes = excluded.notes"
+            "INSERT INTO collection_entries (collection_id, printing_id, quantity, condition, foil, etched, tradeable, notes) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (collection_id, printing_id, condition, foil, etched) DO UPDATE SET \
+             quantity = excluded.quantity, tradeable = excluded.tradeable, notes = excluded.notes"
         )
         .bind(entry.collection_id.to_string())
         .bind(entry.printing_id.to_string())
         .bind(i64::from(entry.quantity))
         .bind(cond_str)
         .bind(entry.foil)
+        .bind(entry.etched)
+        .bind(entry.tradeable)
         .bind(entry.notes.as_ref().map(ToString::to_string))
         .execute(&self.pool)
         .await
@@ -170,6 +191,7 @@ impl CollectionRepository for SqliteBackend {
         printing_id: Uuid,
         condition: Condition,
         foil: bool,
+        etched: bool,
     ) -> Result<(), DbError> {
         let cond_str = serde_json::to_string(&condition)
             .unwrap()
@@ -177,16 +199,68 @@ impl CollectionRepository for SqliteBackend {
             .to_string();
         sqlx::query(
             "DELETE FROM collection_entries \
-             WHERE collection_id = ? AND printing_id = ? AND condition = ? AND foil = ?",
+             WHERE collection_id = ? AND printing_id = ? AND condition = ? AND foil = ? \
+             AND etched = ?",
         )
         .bind(collection_id.to_string())
         .bind(printing_id.to_string())
         .bind(cond_str)
         .bind(foil)
+        .bind(etched)
         .execute(&self.pool)
         .await
         .map_err(|e| DbError::Sql(e.to_string()))?;
 
         Ok(())
     }
+
+    async fn set_tradeable(
+        &self,
+        collection_id: Uuid,
+        printing_id: Uuid,
+        condition: Condition,
+        foil: bool,
+        etched: bool,
+        tradeable: bool,
+    ) -> Result<bool, DbError> {
+        let cond_str = serde_json::to_string(&condition)
+            .unwrap()
+            .trim_matches('"')
+            .to_string();
+        let result = sqlx::query(
+            "UPDATE collection_entries SET tradeable = ? \
+             WHERE collection_id = ? AND printing_id = ? AND condition = ? AND foil = ? \
+             AND etched = ?",
+        )
+        .bind(tradeable)
+        .bind(collection_id.to_string())
+        .bind(printing_id.to_string())
+        .bind(cond_str)
+        .bind(foil)
+        .bind(etched)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn owned_oracle_ids(&self) -> Result<HashSet<Uuid>, DbError> {
+        let rows = sqlx::query(
+            "SELECT DISTINCT p.oracle_id FROM collection_entries ce \
+             INNER JOIN printings p ON p.id = ce.printing_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                let oracle_id: String = row
+                    .try_get("oracle_id")
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+                Uuid::parse_str(&oracle_id).map_err(|e| DbError::Unknown(e.to_string()))
+            })
+            .collect()
+    }
 }
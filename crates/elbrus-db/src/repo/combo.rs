@@ -0,0 +1,226 @@
+use crate::backend::DbError;
+use crate::sqlite::SqliteBackend;
+use elbrus_core::{ColorSet, Combo, ComboPiece};
+use sqlx::Row;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[async_trait::async_trait]
+pub trait ComboRepository: Send + Sync {
+    /// Store combos, replacing any already stored under the same ids.
+    async fn upsert_combos(&self, combos: &[Combo]) -> Result<(), DbError>;
+    async fn get_combo(&self, id: &str) -> Result<Option<Combo>, DbError>;
+    /// Every stored combo, ordered by id.
+    async fn all_combos(&self) -> Result<Vec<Combo>, DbError>;
+    /// Combos with `oracle_id` among their pieces, ordered by id.
+    async fn combos_with_card(&self, oracle_id: Uuid) -> Result<Vec<Combo>, DbError>;
+    async fn combo_count(&self) -> Result<u64, DbError>;
+}
+
+const CHILD_TABLES: [&str; 3] = ["combo_pieces", "combo_prerequisites", "combo_results"];
+
+impl SqliteBackend {
+    /// Load the combos matching `filter`, a condition on the combo id written
+    /// as `{id}` and taking `arg` as its only parameter, if any.
+    async fn load_combos(&self, filter: &str, arg: Option<&str>) -> Result<Vec<Combo>, DbError> {
+        let sql = format!(
+            "SELECT id, identity, steps, popularity, bracket_tag FROM combos \
+             WHERE {} ORDER BY id",
+            filter.replace("{id}", "id")
+        );
+        let mut query = sqlx::query(&sql);
+        if let Some(arg) = arg {
+            query = query.bind(arg);
+        }
+        let rows = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        let mut combos = Vec::with_capacity(rows.len());
+        let mut positions: HashMap<String, usize> = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let id: String = row.try_get("id").map_err(|e| DbError::Sql(e.to_string()))?;
+            let identity: i64 = row
+                .try_get("identity")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let steps: Option<String> = row
+                .try_get("steps")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let popularity: Option<i64> = row
+                .try_get("popularity")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let bracket_tag: Option<String> = row
+                .try_get("bracket_tag")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            positions.insert(id.clone(), combos.len());
+            combos.push(Combo {
+                id: id.into(),
+                pieces: Vec::new(),
+                prerequisites: Vec::new(),
+                results: Vec::new(),
+                steps: steps.map(Into::into),
+                identity: ColorSet::from_bits_truncate(u8::try_from(identity).unwrap_or(0)),
+                popularity: popularity
+                    .map(u32::try_from)
+                    .transpose()
+                    .map_err(|e| DbError::Unknown(e.to_string()))?,
+                bracket_tag: bracket_tag.map(Into::into),
+            });
+        }
+
+        let child_filter = filter.replace("{id}", "combo_id");
+        for table in CHILD_TABLES {
+            let sql =
+                format!("SELECT * FROM {table} WHERE {child_filter} ORDER BY combo_id, position");
+            let mut query = sqlx::query(&sql);
+            if let Some(arg) = arg {
+                query = query.bind(arg);
+            }
+            let rows = query
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            for row in rows {
+                let combo_id: String = row
+                    .try_get("combo_id")
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+                let Some(&i) = positions.get(&combo_id) else {
+                    continue;
+                };
+                let combo = &mut combos[i];
+                match table {
+                    "combo_pieces" => {
+                        let oracle_id: String = row
+                            .try_get("oracle_id")
+                            .map_err(|e| DbError::Sql(e.to_string()))?;
+                        let name: String = row
+                            .try_get("name")
+                            .map_err(|e| DbError::Sql(e.to_string()))?;
+                        combo.pieces.push(ComboPiece {
+                            oracle_id: Uuid::parse_str(&oracle_id)
+                                .map_err(|e| DbError::Unknown(e.to_string()))?,
+                            name: name.into(),
+                        });
+                    }
+                    "combo_prerequisites" => {
+                        let text: String = row
+                            .try_get("prerequisite")
+                            .map_err(|e| DbError::Sql(e.to_string()))?;
+                        combo.prerequisites.push(text.into());
+                    }
+                    _ => {
+                        let text: String = row
+                            .try_get("result")
+                            .map_err(|e| DbError::Sql(e.to_string()))?;
+                        combo.results.push(text.into());
+                    }
+                }
+            }
+        }
+
+        Ok(combos)
+    }
+}
+
+#[async_trait::async_trait]
+impl ComboRepository for SqliteBackend {
+    async fn upsert_combos(&self, combos: &[Combo]) -> Result<(), DbError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        for combo in combos {
+            let id = &*combo.id;
+            for table in CHILD_TABLES {
+                sqlx::query(&format!("DELETE FROM {table} WHERE combo_id = ?"))
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+            }
+            sqlx::query(
+                "INSERT INTO combos (id, identity, steps, popularity, bracket_tag) \
+                 VALUES (?, ?, ?, ?, ?) \
+                 ON CONFLICT(id) DO UPDATE SET identity = excluded.identity, \
+                 steps = excluded.steps, popularity = excluded.popularity, \
+                 bracket_tag = excluded.bracket_tag",
+            )
+            .bind(id)
+            .bind(i64::from(combo.identity.bits()))
+            .bind(combo.steps.as_deref())
+            .bind(combo.popularity.map(i64::from))
+            .bind(combo.bracket_tag.as_deref())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+
+            for (position, piece) in combo.pieces.iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO combo_pieces (combo_id, position, oracle_id, name) \
+                     VALUES (?, ?, ?, ?)",
+                )
+                .bind(id)
+                .bind(position as i64)
+                .bind(piece.oracle_id.to_string())
+                .bind(&*piece.name)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            }
+            for (position, text) in combo.prerequisites.iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO combo_prerequisites (combo_id, position, prerequisite) \
+                     VALUES (?, ?, ?)",
+                )
+                .bind(id)
+                .bind(position as i64)
+                .bind(&**text)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            }
+            for (position, text) in combo.results.iter().enumerate() {
+                sqlx::query(
+                    "INSERT INTO combo_results (combo_id, position, result) VALUES (?, ?, ?)",
+                )
+                .bind(id)
+                .bind(position as i64)
+                .bind(&**text)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            }
+        }
+
+        tx.commit().await.map_err(|e| DbError::Sql(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_combo(&self, id: &str) -> Result<Option<Combo>, DbError> {
+        Ok(self.load_combos("{id} = ?", Some(id)).await?.pop())
+    }
+
+    async fn all_combos(&self) -> Result<Vec<Combo>, DbError> {
+        self.load_combos("1 = 1", None).await
+    }
+
+    async fn combos_with_card(&self, oracle_id: Uuid) -> Result<Vec<Combo>, DbError> {
+        self.load_combos(
+            "{id} IN (SELECT combo_id FROM combo_pieces WHERE oracle_id = ?)",
+            Some(&oracle_id.to_string()),
+        )
+        .await
+    }
+
+    async fn combo_count(&self) -> Result<u64, DbError> {
+        let count: i64 = sqlx::query("SELECT COUNT(*) AS n FROM combos")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?
+            .try_get("n")
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        u64::try_from(count).map_err(|e| DbError::Unknown(e.to_string()))
+    }
+}
@@ -0,0 +1,222 @@
+use crate::backend::DbError;
+use crate::sqlite::SqliteBackend;
+use elbrus_core::{CardDraftCounts, CardGameCounts, ColorSet};
+use sqlx::Row;
+
+#[async_trait::async_trait]
+pub trait LimitedStatsRepository: Send + Sync {
+    /// Store the counts tallied from one 17lands game data file, replacing
+    /// any stored before under the same `dataset` name.
+    async fn replace_game_counts(
+        &self,
+        dataset: &str,
+        counts: &[CardGameCounts],
+    ) -> Result<(), DbError>;
+    /// Store the counts tallied from one 17lands draft data file, replacing
+    /// any stored before under the same `dataset` name.
+    async fn replace_draft_counts(
+        &self,
+        dataset: &str,
+        counts: &[CardDraftCounts],
+    ) -> Result<(), DbError>;
+    /// Game counts for `expansion` by card name, summed over datasets, over
+    /// event types unless `event_type` is given and over deck colors unless
+    /// `colors` is. Summed-over fields come back empty.
+    async fn game_counts(
+        &self,
+        expansion: &str,
+        event_type: Option<&str>,
+        colors: Option<ColorSet>,
+    ) -> Result<Vec<CardGameCounts>, DbError>;
+    /// Draft counts for `expansion` by card name, summed like
+    /// [`game_counts`](Self::game_counts).
+    async fn draft_counts(
+        &self,
+        expansion: &str,
+        event_type: Option<&str>,
+    ) -> Result<Vec<CardDraftCounts>, DbError>;
+    /// Names of the datasets stored, in order.
+    async fn limited_datasets(&self) -> Result<Vec<String>, DbError>;
+}
+
+fn count(row: &sqlx::sqlite::SqliteRow, column: &str) -> Result<u64, DbError> {
+    let n: i64 = row
+        .try_get(column)
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    u64::try_from(n).map_err(|e| DbError::Unknown(e.to_string()))
+}
+
+fn to_i64(n: u64) -> Result<i64, DbError> {
+    i64::try_from(n).map_err(|e| DbError::Unknown(e.to_string()))
+}
+
+#[async_trait::async_trait]
+impl LimitedStatsRepository for SqliteBackend {
+    async fn replace_game_counts(
+        &self,
+        dataset: &str,
+        counts: &[CardGameCounts],
+    ) -> Result<(), DbError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        sqlx::query("DELETE FROM limited_game_counts WHERE dataset = ?")
+            .bind(dataset)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        for c in counts {
+            sqlx::query(
+                "INSERT INTO limited_game_counts (dataset, expansion, event_type, colors, name, \
+                 games_in_deck, wins_in_deck, games_in_hand, wins_in_hand, games_not_seen, \
+                 wins_not_seen) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(dataset)
+            .bind(&*c.expansion)
+            .bind(&*c.event_type)
+            .bind(i64::from(c.colors.bits()))
+            .bind(&*c.name)
+            .bind(to_i64(c.games_in_deck)?)
+            .bind(to_i64(c.wins_in_deck)?)
+            .bind(to_i64(c.games_in_hand)?)
+            .bind(to_i64(c.wins_in_hand)?)
+            .bind(to_i64(c.games_not_seen)?)
+            .bind(to_i64(c.wins_not_seen)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+        tx.commit().await.map_err(|e| DbError::Sql(e.to_string()))
+    }
+
+    async fn replace_draft_counts(
+        &self,
+        dataset: &str,
+        counts: &[CardDraftCounts],
+    ) -> Result<(), DbError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        sqlx::query("DELETE FROM limited_draft_counts WHERE dataset = ?")
+            .bind(dataset)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        for c in counts {
+            sqlx::query(
+                "INSERT INTO limited_draft_counts (dataset, expansion, event_type, name, \
+                 packs_seen, last_seen_total, times_picked, pick_total) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(dataset)
+            .bind(&*c.expansion)
+            .bind(&*c.event_type)
+            .bind(&*c.name)
+            .bind(to_i64(c.packs_seen)?)
+            .bind(to_i64(c.last_seen_total)?)
+            .bind(to_i64(c.times_picked)?)
+            .bind(to_i64(c.pick_total)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+        tx.commit().await.map_err(|e| DbError::Sql(e.to_string()))
+    }
+
+    async fn game_counts(
+        &self,
+        expansion: &str,
+        event_type: Option<&str>,
+        colors: Option<ColorSet>,
+    ) -> Result<Vec<CardGameCounts>, DbError> {
+        let colors_bits = colors.map(|c| i64::from(c.bits()));
+        let rows = sqlx::query(
+            "SELECT name, SUM(games_in_deck) AS games_in_deck, \
+             SUM(wins_in_deck) AS wins_in_deck, SUM(games_in_hand) AS games_in_hand, \
+             SUM(wins_in_hand) AS wins_in_hand, SUM(games_not_seen) AS games_not_seen, \
+             SUM(wins_not_seen) AS wins_not_seen FROM limited_game_counts \
+             WHERE expansion = ? AND (?2 IS NULL OR event_type = ?2) \
+             AND (?3 IS NULL OR colors = ?3) GROUP BY name ORDER BY name",
+        )
+        .bind(expansion)
+        .bind(event_type)
+        .bind(colors_bits)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+        rows.iter()
+            .map(|row| {
+                let name: String = row
+                    .try_get("name")
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+                Ok(CardGameCounts {
+                    expansion: expansion.into(),
+                    event_type: event_type.unwrap_or_default().into(),
+                    colors: colors.unwrap_or_else(ColorSet::empty),
+                    name: name.into(),
+                    games_in_deck: count(row, "games_in_deck")?,
+                    wins_in_deck: count(row, "wins_in_deck")?,
+                    games_in_hand: count(row, "games_in_hand")?,
+                    wins_in_hand: count(row, "wins_in_hand")?,
+                    games_not_seen: count(row, "games_not_seen")?,
+                    wins_not_seen: count(row, "wins_not_seen")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn draft_counts(
+        &self,
+        expansion: &str,
+        event_type: Option<&str>,
+    ) -> Result<Vec<CardDraftCounts>, DbError> {
+        let rows = sqlx::query(
+            "SELECT name, SUM(packs_seen) AS packs_seen, \
+             SUM(last_seen_total) AS last_seen_total, SUM(times_picked) AS times_picked, \
+             SUM(pick_total) AS pick_total FROM limited_draft_counts \
+             WHERE expansion = ? AND (?2 IS NULL OR event_type = ?2) \
+             GROUP BY name ORDER BY name",
+        )
+        .bind(expansion)
+        .bind(event_type)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+        rows.iter()
+            .map(|row| {
+                let name: String = row
+                    .try_get("name")
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+                Ok(CardDraftCounts {
+                    expansion: expansion.into(),
+                    event_type: event_type.unwrap_or_default().into(),
+                    name: name.into(),
+                    packs_seen: count(row, "packs_seen")?,
+                    last_seen_total: count(row, "last_seen_total")?,
+                    times_picked: count(row, "times_picked")?,
+                    pick_total: count(row, "pick_total")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn limited_datasets(&self) -> Result<Vec<String>, DbError> {
+        let rows = sqlx::query(
+            "SELECT dataset FROM limited_game_counts UNION \
+             SELECT dataset FROM limited_draft_counts ORDER BY dataset",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+        rows.iter()
+            .map(|row| {
+                row.try_get("dataset")
+                    .map_err(|e| DbError::Sql(e.to_string()))
+            })
+            .collect()
+    }
+}
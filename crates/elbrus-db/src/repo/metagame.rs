@@ -0,0 +1,309 @@
+use crate::backend::DbError;
+use crate::sqlite::SqliteBackend;
+use chrono::NaiveDate;
+use elbrus_core::{ArchetypeShare, Format, MetagameSnapshot, StapleStat};
+use sqlx::Row;
+use uuid::Uuid;
+
+#[async_trait::async_trait]
+pub trait MetagameRepository: Send + Sync {
+    /// Store a snapshot, replacing any earlier one for the same format and
+    /// dates.
+    async fn save_snapshot(&self, snapshot: &MetagameSnapshot) -> Result<(), DbError>;
+    async fn get_snapshot(&self, id: Uuid) -> Result<Option<MetagameSnapshot>, DbError>;
+    /// The snapshot of `format` with the latest end date.
+    async fn latest_snapshot(&self, format: &Format) -> Result<Option<MetagameSnapshot>, DbError>;
+    /// The snapshot of `format` whose dates cover `date`, the one ending
+    /// latest if several do.
+    async fn snapshot_as_of(
+        &self,
+        format: &Format,
+        date: NaiveDate,
+    ) -> Result<Option<MetagameSnapshot>, DbError>;
+    /// The `limit` most played cards of `format` in the snapshot
+    /// [covering](Self::snapshot_as_of) `as_of`, or the latest snapshot if
+    /// `None`.
+    async fn top_staples(
+        &self,
+        format: &Format,
+        as_of: Option<NaiveDate>,
+        limit: u32,
+    ) -> Result<Vec<StapleStat>, DbError>;
+}
+
+const STAPLE_COLUMNS: &str = "oracle_id, name, decks, main_decks, side_decks, \
+     inclusion_rate, avg_main_copies, avg_side_copies";
+
+// Matches the ordering produced by `elbrus_analysis::staples_index`.
+const STAPLE_ORDER: &str = "decks DESC, main_decks DESC, name";
+
+// Picks a format's snapshot covering a date, or its latest with a NULL date.
+// Binds the format, then the date twice.
+const SNAPSHOT_AS_OF: &str = "SELECT id, format, start_date, end_date, deck_count \
+     FROM metagame_snapshots WHERE format = ? \
+     AND (? IS NULL OR ? BETWEEN start_date AND end_date) \
+     ORDER BY end_date DESC, start_date DESC LIMIT 1";
+
+fn parse_date(s: &str) -> Result<NaiveDate, DbError> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| DbError::Unknown(e.to_string()))
+}
+
+fn parse_staple(row: &sqlx::sqlite::SqliteRow) -> Result<StapleStat, DbError> {
+    let oracle_id_str: String = row
+        .try_get("oracle_id")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let name: String = row
+        .try_get("name")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let count = |column: &str| -> Result<u32, DbError> {
+        let n: i64 = row
+            .try_get(column)
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        u32::try_from(n).map_err(|e| DbError::Unknown(e.to_string()))
+    };
+    let real = |column: &str| -> Result<f64, DbError> {
+        row.try_get(column).map_err(|e| DbError::Sql(e.to_string()))
+    };
+
+    Ok(StapleStat {
+        oracle_id: Uuid::parse_str(&oracle_id_str).map_err(|e| DbError::Unknown(e.to_string()))?,
+        name: name.into(),
+        decks: count("decks")?,
+        main_decks: count("main_decks")?,
+        side_decks: count("side_decks")?,
+        inclusion_rate: real("inclusion_rate")?,
+        avg_main_copies: real("avg_main_copies")?,
+        avg_side_copies: real("avg_side_copies")?,
+    })
+}
+
+impl SqliteBackend {
+    async fn find_snapshot(
+        &self,
+        format: &Format,
+        as_of: Option<NaiveDate>,
+    ) -> Result<Option<MetagameSnapshot>, DbError> {
+        let as_of = as_of.map(|d| d.format("%Y-%m-%d").to_string());
+        let row = sqlx::query(SNAPSHOT_AS_OF)
+            .bind(&*format.0)
+            .bind(&as_of)
+            .bind(&as_of)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        match row {
+            Some(row) => Ok(Some(self.load_snapshot(&row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn load_snapshot(
+        &self,
+        row: &sqlx::sqlite::SqliteRow,
+    ) -> Result<MetagameSnapshot, DbError> {
+        let id_str: String = row.try_get("id").map_err(|e| DbError::Sql(e.to_string()))?;
+        let format: String = row
+            .try_get("format")
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        let start_str: String = row
+            .try_get("start_date")
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        let end_str: String = row
+            .try_get("end_date")
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        let deck_count: i64 = row
+            .try_get("deck_count")
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let staple_rows = sqlx::query(&format!(
+            "SELECT {STAPLE_COLUMNS} FROM metagame_staples WHERE snapshot_id = ? \
+             ORDER BY {STAPLE_ORDER}"
+        ))
+        .bind(&id_str)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+        let staples = staple_rows
+            .iter()
+            .map(parse_staple)
+            .collect::<Result<_, _>>()?;
+
+        let archetype_rows = sqlx::query(
+            "SELECT name, decks, share FROM metagame_archetypes WHERE snapshot_id = ? \
+             ORDER BY position",
+        )
+        .bind(&id_str)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+        let mut archetypes = Vec::with_capacity(archetype_rows.len());
+        for row in archetype_rows {
+            let name: String = row
+                .try_get("name")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let decks: i64 = row
+                .try_get("decks")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            archetypes.push(ArchetypeShare {
+                name: name.into(),
+                decks: u32::try_from(decks).map_err(|e| DbError::Unknown(e.to_string()))?,
+                share: row
+                    .try_get("share")
+                    .map_err(|e| DbError::Sql(e.to_string()))?,
+            });
+        }
+
+        Ok(MetagameSnapshot {
+            id: Uuid::parse_str(&id_str).map_err(|e| DbError::Unknown(e.to_string()))?,
+            format: Format(format.into()),
+            start: parse_date(&start_str)?,
+            end: parse_date(&end_str)?,
+            deck_count: u32::try_from(deck_count).map_err(|e| DbError::Unknown(e.to_string()))?,
+            staples,
+            archetypes,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl MetagameRepository for SqliteBackend {
+    async fn save_snapshot(&self, snapshot: &MetagameSnapshot) -> Result<(), DbError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        let id = snapshot.id.to_string();
+        let start = snapshot.start.format("%Y-%m-%d").to_string();
+        let end = snapshot.end.format("%Y-%m-%d").to_string();
+
+        let replaced = sqlx::query(
+            "SELECT id FROM metagame_snapshots \
+             WHERE id = ? OR (format = ? AND start_date = ? AND end_date = ?)",
+        )
+        .bind(&id)
+        .bind(&*snapshot.format.0)
+        .bind(&start)
+        .bind(&end)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+        for row in replaced {
+            let old_id: String = row.try_get("id").map_err(|e| DbError::Sql(e.to_string()))?;
+            for table in ["metagame_staples", "metagame_archetypes"] {
+                sqlx::query(&format!("DELETE FROM {table} WHERE snapshot_id = ?"))
+                    .bind(&old_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+            }
+            sqlx::query("DELETE FROM metagame_snapshots WHERE id = ?")
+                .bind(&old_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+
+        sqlx::query(
+            "INSERT INTO metagame_snapshots (id, format, start_date, end_date, deck_count) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&*snapshot.format.0)
+        .bind(&start)
+        .bind(&end)
+        .bind(i64::from(snapshot.deck_count))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        for staple in &snapshot.staples {
+            sqlx::query(&format!(
+                "INSERT INTO metagame_staples (snapshot_id, {STAPLE_COLUMNS}) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+            ))
+            .bind(&id)
+            .bind(staple.oracle_id.to_string())
+            .bind(&*staple.name)
+            .bind(i64::from(staple.decks))
+            .bind(i64::from(staple.main_decks))
+            .bind(i64::from(staple.side_decks))
+            .bind(staple.inclusion_rate)
+            .bind(staple.avg_main_copies)
+            .bind(staple.avg_side_copies)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+
+        for (position, archetype) in snapshot.archetypes.iter().enumerate() {
+            sqlx::query(
+                "INSERT INTO metagame_archetypes (snapshot_id, position, name, decks, share) \
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&id)
+            .bind(position as i64)
+            .bind(&*archetype.name)
+            .bind(i64::from(archetype.decks))
+            .bind(archetype.share)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+
+        tx.commit().await.map_err(|e| DbError::Sql(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_snapshot(&self, id: Uuid) -> Result<Option<MetagameSnapshot>, DbError> {
+        let row = sqlx::query(
+            "SELECT id, format, start_date, end_date, deck_count \
+             FROM metagame_snapshots WHERE id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        match row {
+            Some(row) => Ok(Some(self.load_snapshot(&row).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn latest_snapshot(&self, format: &Format) -> Result<Option<MetagameSnapshot>, DbError> {
+        self.find_snapshot(format, None).await
+    }
+
+    async fn snapshot_as_of(
+        &self,
+        format: &Format,
+        date: NaiveDate,
+    ) -> Result<Option<MetagameSnapshot>, DbError> {
+        self.find_snapshot(format, Some(date)).await
+    }
+
+    async fn top_staples(
+        &self,
+        format: &Format,
+        as_of: Option<NaiveDate>,
+        limit: u32,
+    ) -> Result<Vec<StapleStat>, DbError> {
+        let as_of = as_of.map(|d| d.format("%Y-%m-%d").to_string());
+        let rows = sqlx::query(&format!(
+            "SELECT {STAPLE_COLUMNS} FROM metagame_staples \
+             WHERE snapshot_id = (SELECT id FROM ({SNAPSHOT_AS_OF})) \
+             ORDER BY {STAPLE_ORDER} LIMIT ?"
+        ))
+        .bind(&*format.0)
+        .bind(&as_of)
+        .bind(&as_of)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        rows.iter().map(parse_staple).collect()
+    }
+}
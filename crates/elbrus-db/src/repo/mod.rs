@@ -1,7 +1,17 @@
 pub mod card;
 pub mod collection;
+pub mod combo;
+pub mod limited;
+pub mod metagame;
 pub mod price;
+pub mod trade;
+pub mod watchlist;
 
 pub use card::*;
 pub use collection::*;
+pub use combo::*;
+pub use limited::*;
+pub use metagame::*;
 pub use price::*;
+pub use trade::*;
+pub use watchlist::*;
@@ -1,8 +1,9 @@
 use crate::backend::DbError;
 use crate::sqlite::SqliteBackend;
-use chrono::{DateTime, Utc};
-use elbrus_core::card::PriceSnapshot;
+use chrono::{DateTime, SecondsFormat, Utc};
+use elbrus_core::card::{Currency, PriceSnapshot};
 use sqlx::Row;
+use std::collections::HashMap;
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -14,11 +15,41 @@ pub trait PriceRepository: Send + Sync {
         snapshot: &PriceSnapshot,
     ) -> Result<(), DbError>;
     async fn get_latest_price(&self, printing_id: Uuid) -> Result<Option<PriceSnapshot>, DbError>;
+    /// Most recent snapshot fetched at or before `at`.
+    async fn get_price_as_of(
+        &self,
+        printing_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<Option<PriceSnapshot>, DbError>;
     async fn get_price_history(&self, printing_id: Uuid) -> Result<Vec<PriceSnapshot>, DbError>;
+    /// Histories of every printing with a snapshot after `since`, newest first.
+    /// Each history also includes the last snapshot at or before `since`, if any,
+    /// so callers can measure movement from that baseline.
+    async fn get_price_histories_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, Vec<PriceSnapshot>>, DbError>;
+    /// Note that a bulk ingest of prices fetched at `fetched_at` has finished.
+    async fn record_ingest_run(&self, fetched_at: DateTime<Utc>) -> Result<(), DbError>;
+    /// Fetch times of recorded bulk ingests, newest first.
+    async fn ingest_runs(&self, limit: u32) -> Result<Vec<DateTime<Utc>>, DbError>;
     async fn get_collection_value(
         &self,
         collection_id: Uuid,
     ) -> Result<rust_decimal::Decimal, DbError>;
+    /// The cheapest current non-foil price of each oracle card across its
+    /// printings, by oracle id. Cards with no price in `currency` are left out.
+    async fn cheapest_prices(
+        &self,
+        currency: Currency,
+    ) -> Result<HashMap<Uuid, rust_decimal::Decimal>, DbError>;
+}
+
+/// Fixed-width UTC with milliseconds, as SQLite's
+/// `strftime('%Y-%m-%dT%H:%M:%fZ')` writes it, so stored snapshot times
+/// compare chronologically as strings.
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Millis, true)
 }
 
 fn parse_price_snapshot(row: &sqlx::sqlite::SqliteRow) -> Result<PriceSnapshot, DbError> {
@@ -31,9 +62,15 @@ fn parse_price_snapshot(row: &sqlx::sqlite::SqliteRow) -> Result<PriceSnapshot,
     let usd_foil_str: Option<String> = row
         .try_get("usd_foil")
         .map_err(|e| DbError::Sql(e.to_string()))?;
+    let usd_etched_str: Option<String> = row
+        .try_get("usd_etched")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
     let eur_str: Option<String> = row
         .try_get("eur")
         .map_err(|e| DbError::Sql(e.to_string()))?;
+    let eur_foil_str: Option<String> = row
+        .try_get("eur_foil")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
     let tix_str: Option<String> = row
         .try_get("tix")
         .map_err(|e| DbError::Sql(e.to_string()))?;
@@ -50,7 +87,9 @@ fn parse_price_snapshot(row: &sqlx::sqlite::SqliteRow) -> Result<PriceSnapshot,
     Ok(PriceSnapshot {
         usd: parse_dec(usd_str),
         usd_foil: parse_dec(usd_foil_str),
+        usd_etched: parse_dec(usd_etched_str),
         eur: parse_dec(eur_str),
+        eur_foil: parse_dec(eur_foil_str),
         tix: parse_dec(tix_str),
         fetched_at,
     })
@@ -63,19 +102,22 @@ impl PriceRepository for SqliteBackend {
         printing_id: Uuid,
         snapshot: &PriceSnapshot,
     ) -> Result<(), DbError> {
-        let fetched_at_str = snapshot.fetched_at.to_rfc3339();
+        let fetched_at_str = timestamp(snapshot.fetched_at);
 
         sqlx::query(
-            "INSERT INTO price_snapshots (printing_id, fetched_at, usd, usd_foil, eur, tix) \
-             VALUES (?, ?, ?, ?, ?, ?) \
+            "INSERT INTO price_snapshots (printing_id, fetched_at, usd, usd_foil, usd_etched, eur, eur_foil, tix) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?) \
              ON CONFLICT (printing_id, fetched_at) DO UPDATE SET \
-             usd = excluded.usd, usd_foil = excluded.usd_foil, eur = excluded.eur, tix = excluded.tix"
+             usd = excluded.usd, usd_foil = excluded.usd_foil, usd_etched = excluded.usd_etched, \
+             eur = excluded.eur, eur_foil = excluded.eur_foil, tix = excluded.tix"
         )
         .bind(printing_id.to_string())
         .bind(fetched_at_str)
         .bind(snapshot.usd.map(|d| d.to_string()))
         .bind(snapshot.usd_foil.map(|d| d.to_string()))
+        .bind(snapshot.usd_etched.map(|d| d.to_string()))
         .bind(snapshot.eur.map(|d| d.to_string()))
+        .bind(snapshot.eur_foil.map(|d| d.to_string()))
         .bind(snapshot.tix.map(|d| d.to_string()))
         .execute(&self.pool)
         .await
@@ -86,7 +128,7 @@ impl PriceRepository for SqliteBackend {
 
     async fn get_latest_price(&self, printing_id: Uuid) -> Result<Option<PriceSnapshot>, DbError> {
         let row_opt = sqlx::query(
-            "SELECT fetched_at, usd, usd_foil, eur, tix FROM price_snapshots \
+            "SELECT fetched_at, usd, usd_foil, usd_etched, eur, eur_foil, tix FROM price_snapshots \
              WHERE printing_id = ? ORDER BY fetched_at DESC LIMIT 1",
         )
         .bind(printing_id.to_string())
@@ -100,9 +142,30 @@ impl PriceRepository for SqliteBackend {
         }
     }
 
+    async fn get_price_as_of(
+        &self,
+        printing_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<Option<PriceSnapshot>, DbError> {
+        let row_opt = sqlx::query(
+            "SELECT fetched_at, usd, usd_foil, usd_etched, eur, eur_foil, tix FROM price_snapshots \
+             WHERE printing_id = ? AND fetched_at <= ? ORDER BY fetched_at DESC LIMIT 1",
+        )
+        .bind(printing_id.to_string())
+        .bind(timestamp(at))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        match row_opt {
+            Some(row) => Ok(Some(parse_price_snapshot(&row)?)),
+            None => Ok(None),
+        }
+    }
+
     async fn get_price_history(&self, printing_id: Uuid) -> Result<Vec<PriceSnapshot>, DbError> {
         let rows = sqlx::query(
-            "SELECT fetched_at, usd, usd_foil, eur, tix FROM price_snapshots \
+            "SELECT fetched_at, usd, usd_foil, usd_etched, eur, eur_foil, tix FROM price_snapshots \
              WHERE printing_id = ? ORDER BY fetched_at DESC",
         )
         .bind(printing_id.to_string())
@@ -117,6 +180,78 @@ impl PriceRepository for SqliteBackend {
         Ok(snapshots)
     }
 
+    async fn get_price_histories_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, Vec<PriceSnapshot>>, DbError> {
+        let since_str = timestamp(since);
+        let rows = sqlx::query(
+            "SELECT s.printing_id, s.fetched_at, s.usd, s.usd_foil, s.usd_etched, s.eur, s.eur_foil, s.tix \
+             FROM price_snapshots s \
+             WHERE s.printing_id IN (SELECT printing_id FROM price_snapshots WHERE fetched_at > ?) \
+             AND s.fetched_at >= COALESCE( \
+                 (SELECT MAX(b.fetched_at) FROM price_snapshots b \
+                  WHERE b.printing_id = s.printing_id AND b.fetched_at <= ?), \
+                 ?) \
+             ORDER BY s.printing_id, s.fetched_at DESC",
+        )
+        .bind(&since_str)
+        .bind(&since_str)
+        .bind(&since_str)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut histories: HashMap<Uuid, Vec<PriceSnapshot>> = HashMap::new();
+        for row in rows {
+            let printing_id_str: String = row
+                .try_get("printing_id")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let printing_id =
+                Uuid::parse_str(&printing_id_str).map_err(|e| DbError::Unknown(e.to_string()))?;
+            histories
+                .entry(printing_id)
+                .or_default()
+                .push(parse_price_snapshot(&row)?);
+        }
+        Ok(histories)
+    }
+
+    async fn record_ingest_run(&self, fetched_at: DateTime<Utc>) -> Result<(), DbError> {
+        sqlx::query(
+            "INSERT INTO ingest_runs (fetched_at, ingested_at) VALUES (?, ?) \
+             ON CONFLICT (fetched_at) DO UPDATE SET ingested_at = excluded.ingested_at",
+        )
+        .bind(timestamp(fetched_at))
+        .bind(timestamp(Utc::now()))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn ingest_runs(&self, limit: u32) -> Result<Vec<DateTime<Utc>>, DbError> {
+        let rows =
+            sqlx::query("SELECT fetched_at FROM ingest_runs ORDER BY fetched_at DESC LIMIT ?")
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut times = Vec::with_capacity(rows.len());
+        for row in rows {
+            let fetched_at_str: String = row
+                .try_get("fetched_at")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let fetched_at = DateTime::parse_from_rfc3339(&fetched_at_str)
+                .map(|d| d.with_timezone(&Utc))
+                .map_err(|e| DbError::Unknown(e.to_string()))?;
+            times.push(fetched_at);
+        }
+        Ok(times)
+    }
+
     async fn get_collection_value(
         &self,
         collection_id: Uuid,
@@ -163,4 +298,40 @@ impl PriceRepository for SqliteBackend {
         }
         Ok(total)
     }
+
+    async fn cheapest_prices(
+        &self,
+        currency: Currency,
+    ) -> Result<HashMap<Uuid, rust_decimal::Decimal>, DbError> {
+        let rows = sqlx::query(
+            "SELECT pr.oracle_id, s.fetched_at, s.usd, s.usd_foil, s.usd_etched, s.eur, s.eur_foil, s.tix \
+             FROM price_snapshots s \
+             INNER JOIN ( \
+                 SELECT printing_id, MAX(fetched_at) as max_fetched \
+                 FROM price_snapshots \
+                 GROUP BY printing_id \
+             ) latest ON s.printing_id = latest.printing_id AND s.fetched_at = latest.max_fetched \
+             INNER JOIN printings pr ON pr.id = s.printing_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut prices: HashMap<Uuid, rust_decimal::Decimal> = HashMap::new();
+        for row in rows {
+            let oracle_id_str: String = row
+                .try_get("oracle_id")
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            let oracle_id =
+                Uuid::parse_str(&oracle_id_str).map_err(|e| DbError::Unknown(e.to_string()))?;
+            let Some(price) = parse_price_snapshot(&row)?.price(currency, false, false) else {
+                continue;
+            };
+            prices
+                .entry(oracle_id)
+                .and_modify(|p| *p = (*p).min(price))
+                .or_insert(price);
+        }
+        Ok(prices)
+    }
 }
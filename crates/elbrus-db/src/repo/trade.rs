@@ -0,0 +1,245 @@
+use crate::backend::DbError;
+use crate::sqlite::SqliteBackend;
+use chrono::{DateTime, Utc};
+use elbrus_core::{Condition, Currency, TradeCard, TradeRecord};
+use rust_decimal::Decimal;
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[async_trait::async_trait]
+pub trait TradeRepository: Send + Sync {
+    /// Move the traded cards in and out of the trade's collection and add it
+    /// to the ledger, all in one transaction. Fails without changing anything
+    /// if the collection holds fewer tradeable copies of a given card than the
+    /// trade gives away.
+    async fn record_trade(&self, trade: &TradeRecord) -> Result<(), DbError>;
+    /// Trades for a collection, most recent first.
+    async fn list_trades(&self, collection_id: Uuid) -> Result<Vec<TradeRecord>, DbError>;
+}
+
+fn enum_str<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value)
+        .unwrap()
+        .trim_matches('"')
+        .to_string()
+}
+
+fn parse_trade_card(row: &sqlx::sqlite::SqliteRow) -> Result<TradeCard, DbError> {
+    let printing_id_str: String = row
+        .try_get("printing_id")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let quantity: i64 = row
+        .try_get("quantity")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let condition_str: String = row
+        .try_get("condition")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let foil: bool = row
+        .try_get("foil")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let etched: bool = row
+        .try_get("etched")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+    let condition: Condition = serde_json::from_str(&format!("\"{condition_str}\""))
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+    Ok(TradeCard {
+        printing_id: Uuid::parse_str(&printing_id_str)
+            .map_err(|e| DbError::Unknown(e.to_string()))?,
+        quantity: u32::try_from(quantity).map_err(|e| DbError::Unknown(e.to_string()))?,
+        condition,
+        foil,
+        etched,
+    })
+}
+
+fn parse_trade(row: &sqlx::sqlite::SqliteRow) -> Result<TradeRecord, DbError> {
+    let id_str: String = row.try_get("id").map_err(|e| DbError::Sql(e.to_string()))?;
+    let collection_id_str: String = row
+        .try_get("collection_id")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let traded_at_str: String = row
+        .try_get("traded_at")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let currency_str: String = row
+        .try_get("currency")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let given_value_str: String = row
+        .try_get("given_value")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let received_value_str: String = row
+        .try_get("received_value")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let notes: Option<String> = row
+        .try_get("notes")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+    let currency: Currency = serde_json::from_str(&format!("\"{currency_str}\""))
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+    let traded_at = DateTime::parse_from_rfc3339(&traded_at_str)
+        .map_err(|e| DbError::Unknown(e.to_string()))?
+        .with_timezone(&Utc);
+
+    Ok(TradeRecord {
+        id: Uuid::parse_str(&id_str).map_err(|e| DbError::Unknown(e.to_string()))?,
+        collection_id: Uuid::parse_str(&collection_id_str)
+            .map_err(|e| DbError::Unknown(e.to_string()))?,
+        traded_at,
+        given: Vec::new(),
+        received: Vec::new(),
+        currency,
+        given_value: Decimal::from_str(&given_value_str)
+            .map_err(|e| DbError::Unknown(e.to_string()))?,
+        received_value: Decimal::from_str(&received_value_str)
+            .map_err(|e| DbError::Unknown(e.to_string()))?,
+        notes: notes.map(Into::into),
+    })
+}
+
+#[async_trait::async_trait]
+impl TradeRepository for SqliteBackend {
+    async fn record_trade(&self, trade: &TradeRecord) -> Result<(), DbError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        let collection_id = trade.collection_id.to_string();
+
+        for card in &trade.given {
+            let result = sqlx::query(
+                "UPDATE collection_entries SET quantity = quantity - ? \
+                 WHERE collection_id = ? AND printing_id = ? AND condition = ? AND foil = ? \
+                 AND etched = ? AND tradeable = TRUE AND quantity >= ?",
+            )
+            .bind(i64::from(card.quantity))
+            .bind(&collection_id)
+            .bind(card.printing_id.to_string())
+            .bind(enum_str(&card.condition))
+            .bind(card.foil)
+            .bind(card.etched)
+            .bind(i64::from(card.quantity))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+
+            if result.rows_affected() == 0 {
+                // Dropping `tx` rolls back anything already applied.
+                return Err(DbError::Constraint(format!(
+                    "collection {} holds fewer than {} tradeable copies of printing {}",
+                    trade.collection_id, card.quantity, card.printing_id
+                )));
+            }
+
+            sqlx::query(
+                "DELETE FROM collection_entries \
+                 WHERE collection_id = ? AND printing_id = ? AND condition = ? AND foil = ? \
+                 AND etched = ? AND quantity <= 0",
+            )
+            .bind(&collection_id)
+            .bind(card.printing_id.to_string())
+            .bind(enum_str(&card.condition))
+            .bind(card.foil)
+            .bind(card.etched)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+
+        for card in &trade.received {
+            sqlx::query(
+                "INSERT INTO collection_entries (collection_id, printing_id, quantity, condition, foil, etched) \
+                 VALUES (?, ?, ?, ?, ?, ?) \
+                 ON CONFLICT (collection_id, printing_id, condition, foil, etched) DO UPDATE SET \
+                 quantity = quantity + excluded.quantity",
+            )
+            .bind(&collection_id)
+            .bind(card.printing_id.to_string())
+            .bind(i64::from(card.quantity))
+            .bind(enum_str(&card.condition))
+            .bind(card.foil)
+            .bind(card.etched)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        }
+
+        sqlx::query(
+            "INSERT INTO trades (id, collection_id, traded_at, currency, given_value, received_value, notes) \
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(trade.id.to_string())
+        .bind(&collection_id)
+        .bind(trade.traded_at.to_rfc3339())
+        .bind(enum_str(&trade.currency))
+        .bind(trade.given_value.to_string())
+        .bind(trade.received_value.to_string())
+        .bind(trade.notes.as_ref().map(ToString::to_string))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let sides = [("given", &trade.given), ("received", &trade.received)];
+        for (side, cards) in sides {
+            for card in cards {
+                sqlx::query(
+                    "INSERT INTO trade_cards (trade_id, side, printing_id, quantity, condition, foil, etched) \
+                     VALUES (?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(trade.id.to_string())
+                .bind(side)
+                .bind(card.printing_id.to_string())
+                .bind(i64::from(card.quantity))
+                .bind(enum_str(&card.condition))
+                .bind(card.foil)
+                .bind(card.etched)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| DbError::Sql(e.to_string()))?;
+            }
+        }
+
+        tx.commit().await.map_err(|e| DbError::Sql(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn list_trades(&self, collection_id: Uuid) -> Result<Vec<TradeRecord>, DbError> {
+        let rows = sqlx::query(
+            "SELECT id, collection_id, traded_at, currency, given_value, received_value, notes \
+             FROM trades WHERE collection_id = ? ORDER BY traded_at DESC",
+        )
+        .bind(collection_id.to_string())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut trades = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut trade = parse_trade(&row)?;
+            let card_rows = sqlx::query(
+                "SELECT side, printing_id, quantity, condition, foil, etched \
+                 FROM trade_cards WHERE trade_id = ? ORDER BY rowid",
+            )
+            .bind(trade.id.to_string())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+
+            for card_row in card_rows {
+                let side: String = card_row
+                    .try_get("side")
+                    .map_err(|e| DbError::Sql(e.to_string()))?;
+                let card = parse_trade_card(&card_row)?;
+                match side.as_str() {
+                    "given" => trade.given.push(card),
+                    "received" => trade.received.push(card),
+                    other => return Err(DbError::Unknown(format!("Unknown trade side: {other}"))),
+                }
+            }
+            trades.push(trade);
+        }
+        Ok(trades)
+    }
+}
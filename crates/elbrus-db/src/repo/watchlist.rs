@@ -0,0 +1,127 @@
+use crate::backend::DbError;
+use crate::sqlite::SqliteBackend;
+use elbrus_core::{Currency, WatchEntry};
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+#[async_trait::async_trait]
+pub trait WatchlistRepository: Send + Sync {
+    async fn upsert_watch(&self, entry: &WatchEntry) -> Result<(), DbError>;
+    async fn remove_watch(
+        &self,
+        printing_id: Uuid,
+        currency: Currency,
+        foil: bool,
+    ) -> Result<(), DbError>;
+    async fn list_watches(&self) -> Result<Vec<WatchEntry>, DbError>;
+}
+
+fn parse_watch_entry(row: &sqlx::sqlite::SqliteRow) -> Result<WatchEntry, DbError> {
+    let printing_id_str: String = row
+        .try_get("printing_id")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let currency_str: String = row
+        .try_get("currency")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let foil: bool = row
+        .try_get("foil")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let threshold_pct_str: Option<String> = row
+        .try_get("threshold_pct")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let price_above_str: Option<String> = row
+        .try_get("price_above")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let price_below_str: Option<String> = row
+        .try_get("price_below")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+    let notes: Option<String> = row
+        .try_get("notes")
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+    let printing_id =
+        Uuid::parse_str(&printing_id_str).map_err(|e| DbError::Unknown(e.to_string()))?;
+    let currency = serde_json::from_str(&format!("\"{currency_str}\""))
+        .map_err(|e| DbError::Unknown(e.to_string()))?;
+
+    let parse_dec = |opt: Option<String>| {
+        opt.as_ref()
+            .and_then(|s| rust_decimal::Decimal::from_str(s).ok())
+    };
+
+    Ok(WatchEntry {
+        printing_id,
+        currency,
+        foil,
+        threshold_pct: parse_dec(threshold_pct_str),
+        price_above: parse_dec(price_above_str),
+        price_below: parse_dec(price_below_str),
+        notes: notes.map(Into::into),
+    })
+}
+
+#[async_trait::async_trait]
+impl WatchlistRepository for SqliteBackend {
+    async fn upsert_watch(&self, entry: &WatchEntry) -> Result<(), DbError> {
+        let currency_str = serde_json::to_string(&entry.currency)
+            .unwrap()
+            .trim_matches('"')
+            .to_string();
+        sqlx::query(
+            "INSERT INTO watchlist (printing_id, currency, foil, threshold_pct, price_above, price_below, notes) \
+             VALUES (?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT (printing_id, currency, foil) DO UPDATE SET \
+             threshold_pct = excluded.threshold_pct, price_above = excluded.price_above, \
+             price_below = excluded.price_below, notes = excluded.notes"
+        )
+        .bind(entry.printing_id.to_string())
+        .bind(currency_str)
+        .bind(entry.foil)
+        .bind(entry.threshold_pct.map(|d| d.to_string()))
+        .bind(entry.price_above.map(|d| d.to_string()))
+        .bind(entry.price_below.map(|d| d.to_string()))
+        .bind(entry.notes.as_ref().map(ToString::to_string))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn remove_watch(
+        &self,
+        printing_id: Uuid,
+        currency: Currency,
+        foil: bool,
+    ) -> Result<(), DbError> {
+        let currency_str = serde_json::to_string(&currency)
+            .unwrap()
+            .trim_matches('"')
+            .to_string();
+        sqlx::query("DELETE FROM watchlist WHERE printing_id = ? AND currency = ? AND foil = ?")
+            .bind(printing_id.to_string())
+            .bind(currency_str)
+            .bind(foil)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DbError::Sql(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn list_watches(&self) -> Result<Vec<WatchEntry>, DbError> {
+        let rows = sqlx::query(
+            "SELECT printing_id, currency, foil, threshold_pct, price_above, price_below, notes \
+             FROM watchlist",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| DbError::Sql(e.to_string()))?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            entries.push(parse_watch_entry(&row)?);
+        }
+        Ok(entries)
+    }
+}
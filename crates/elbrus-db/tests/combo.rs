@@ -0,0 +1,81 @@
+use elbrus_core::{ColorSet, Combo, ComboPiece};
+use elbrus_db::repo::ComboRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+mod common;
+
+fn combo(id: &str, pieces: &[(Uuid, &str)]) -> Combo {
+    Combo {
+        id: id.into(),
+        pieces: pieces
+            .iter()
+            .map(|&(oracle_id, name)| ComboPiece {
+                oracle_id,
+                name: name.into(),
+            })
+            .collect(),
+        prerequisites: vec!["All permanents untapped".into()],
+        results: vec!["Infinite mana".into(), "Infinite storm count".into()],
+        steps: Some("Cast Dramatic Reversal.".into()),
+        identity: ColorSet::BLUE,
+        popularity: Some(12_000),
+        bracket_tag: None,
+    }
+}
+
+#[tokio::test]
+async fn test_combo_repository() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let (reversal, scepter, rings) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+    for (oracle_id, name) in [
+        (reversal, "Dramatic Reversal"),
+        (scepter, "Isochron Scepter"),
+        (rings, "Sol Ring"),
+    ] {
+        common::insert_oracle(&db, oracle_id, name).await;
+    }
+    let scepter_combo = combo(
+        "8-9",
+        &[
+            (reversal, "Dramatic Reversal"),
+            (scepter, "Isochron Scepter"),
+        ],
+    );
+    let rings_combo = combo(
+        "1-8",
+        &[(reversal, "Dramatic Reversal"), (rings, "Sol Ring")],
+    );
+    db.upsert_combos(&[scepter_combo.clone(), rings_combo.clone()])
+        .await
+        .unwrap();
+
+    assert_eq!(db.combo_count().await.unwrap(), 2);
+    assert_eq!(
+        db.get_combo("8-9").await.unwrap(),
+        Some(scepter_combo.clone())
+    );
+    assert_eq!(db.get_combo("missing").await.unwrap(), None);
+    assert_eq!(
+        db.all_combos().await.unwrap(),
+        vec![rings_combo.clone(), scepter_combo.clone()]
+    );
+    assert_eq!(db.combos_with_card(reversal).await.unwrap().len(), 2);
+    assert_eq!(
+        db.combos_with_card(scepter).await.unwrap(),
+        vec![scepter_combo]
+    );
+
+    // Upserting replaces pieces, prerequisites and results wholesale.
+    let mut updated = combo("8-9", &[(reversal, "Dramatic Reversal")]);
+    updated.prerequisites.clear();
+    updated.results.truncate(1);
+    updated.popularity = None;
+    db.upsert_combos(std::slice::from_ref(&updated))
+        .await
+        .unwrap();
+    assert_eq!(db.combo_count().await.unwrap(), 2);
+    assert_eq!(db.get_combo("8-9").await.unwrap(), Some(updated));
+    assert!(db.combos_with_card(scepter).await.unwrap().is_empty());
+    assert_eq!(db.combos_with_card(rings).await.unwrap(), vec![rings_combo]);
+}
@@ -0,0 +1,66 @@
+// Shared fixtures; each test binary uses its own subset.
+#![allow(dead_code)]
+
+use elbrus_core::{
+    CardFace, CardLayout, OracleCard, Printing, Rarity, color::ColorSet, legality::Legalities,
+    oracle::OracleText, types::TypeLine,
+};
+use elbrus_db::repo::CardRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+/// Store a bare oracle card so rows that reference it satisfy their foreign
+/// keys.
+pub async fn insert_oracle(db: &SqliteBackend, oracle_id: Uuid, name: &str) {
+    db.upsert_oracle(&OracleCard {
+        oracle_id,
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: name.into(),
+            mana_cost: None,
+            type_line: TypeLine::default(),
+            oracle_text: OracleText::default(),
+            colors: ColorSet::empty(),
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    })
+    .await
+    .unwrap();
+}
+
+/// Store a bare oracle card with one printing in `set_code`, returning the
+/// printing id.
+pub async fn insert_printing(db: &SqliteBackend, name: &str, set_code: &str) -> Uuid {
+    let oracle_id = Uuid::new_v4();
+    insert_oracle(db, oracle_id, name).await;
+    let id = Uuid::new_v4();
+    db.upsert_printing(&Printing {
+        id,
+        oracle_id,
+        set_code: set_code.into(),
+        collector_number: "1".into(),
+        rarity: Rarity::Common,
+        lang: "en".into(),
+        released_at: chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+        image_uris: None,
+        promo: false,
+        digital: false,
+        full_art: false,
+        textless: false,
+        reprint: false,
+        booster: true,
+        prices: None,
+    })
+    .await
+    .unwrap();
+    id
+}
@@ -1,7 +1,7 @@
 use chrono::Utc;
 use elbrus_core::{
-    CardFace, CardLayout, CollectionEntry, Condition, OracleCard, PriceSnapshot, Printing, Rarity,
-    color::ColorSet, legality::Legalities, oracle::OracleText, types::TypeLine,
+    CardFace, CardLayout, CollectionEntry, Condition, Currency, OracleCard, PriceSnapshot,
+    Printing, Rarity, color::ColorSet, legality::Legalities, oracle::OracleText, types::TypeLine,
 };
 use elbrus_db::repo::{CardRepository, CollectionRepository, PriceRepository};
 use elbrus_db::sqlite::SqliteBackend;
@@ -9,6 +9,8 @@ use rust_decimal::Decimal;
 use std::str::FromStr;
 use uuid::Uuid;
 
+mod common;
+
 #[tokio::test]
 async fn test_db_integration() {
     let db = SqliteBackend::open_in_memory().await.unwrap();
@@ -55,6 +57,7 @@ async fn test_db_integration() {
         full_art: false,
         textless: false,
         reprint: false,
+        booster: true,
         prices: None,
     };
 
@@ -72,7 +75,9 @@ async fn test_db_integration() {
     let snapshot = PriceSnapshot {
         usd: Some(Decimal::from_str("1.50").unwrap()),
         usd_foil: Some(Decimal::from_str("3.00").unwrap()),
+        usd_etched: None,
         eur: None,
+        eur_foil: None,
         tix: None,
         fetched_at: Utc::now(),
     };
@@ -88,6 +93,30 @@ async fn test_db_integration() {
     assert_eq!(history.len(), 1);
     assert_eq!(history[0].usd_foil, snapshot.usd_foil);
 
+    // Cheapest price per oracle card across printings
+    let reprint_id = Uuid::new_v4();
+    db.upsert_printing(&Printing {
+        id: reprint_id,
+        set_code: "TS2".into(),
+        ..printing.clone()
+    })
+    .await
+    .unwrap();
+    db.insert_snapshot(
+        reprint_id,
+        &PriceSnapshot {
+            usd: Some(Decimal::from_str("0.75").unwrap()),
+            usd_foil: None,
+            ..snapshot.clone()
+        },
+    )
+    .await
+    .unwrap();
+    let cheapest = db.cheapest_prices(Currency::Usd).await.unwrap();
+    assert_eq!(cheapest.len(), 1);
+    assert_eq!(cheapest[&oracle_id], Decimal::from_str("0.75").unwrap());
+    assert!(db.cheapest_prices(Currency::Eur).await.unwrap().is_empty());
+
     // 3. Collection CRUD
     // Create collection
     let coll = db
@@ -102,10 +131,17 @@ async fn test_db_integration() {
         quantity: 4,
         condition: Condition::NearMint,
         foil: false,
+        etched: false,
+        tradeable: false,
         notes: Some("Playset".into()),
     };
 
+    assert!(db.owned_oracle_ids().await.unwrap().is_empty());
     db.upsert_card(&entry).await.unwrap();
+    assert_eq!(
+        db.owned_oracle_ids().await.unwrap(),
+        std::collections::HashSet::from([oracle_id])
+    );
 
     let mut fetched_coll = db.get_collection(coll.id).await.unwrap().unwrap();
     assert_eq!(fetched_coll.entries.len(), 1);
@@ -120,6 +156,8 @@ async fn test_db_integration() {
         quantity: 2,
         condition: Condition::NearMint,
         foil: true,
+        etched: false,
+        tradeable: false,
         notes: None,
     };
 
@@ -129,19 +167,88 @@ async fn test_db_integration() {
     // 4 * 1.50 + 2 * 3.00 = 6.00 + 6.00 = 12.00
     assert_eq!(coll_value2, Decimal::from_str("12.00").unwrap());
 
+    // Etched foils are stored beside regular foils, not merged into them.
+    db.upsert_card(&CollectionEntry {
+        quantity: 1,
+        etched: true,
+        ..entry_foil.clone()
+    })
+    .await
+    .unwrap();
+    db.upsert_card(&entry_foil).await.unwrap();
+    let finishes: Vec<(bool, bool, u32)> = db
+        .get_collection(coll.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .entries
+        .iter()
+        .map(|e| (e.foil, e.etched, e.quantity))
+        .collect();
+    assert_eq!(finishes.len(), 3);
+    assert!(finishes.contains(&(true, false, 2)));
+    assert!(finishes.contains(&(true, true, 1)));
+
     // List collections
     let collist = db.list_collections().await.unwrap();
     assert_eq!(collist.len(), 1);
 
     // Remove card
-    db.remove_card(coll.id, printing_id, Condition::NearMint, false)
+    db.remove_card(coll.id, printing_id, Condition::NearMint, false, false)
+        .await
+        .unwrap();
+    fetched_coll = db.get_collection(coll.id).await.unwrap().unwrap();
+    assert_eq!(fetched_coll.entries.len(), 2); // foils are still there
+
+    // Removing the foil leaves the etched foil alone.
+    db.remove_card(coll.id, printing_id, Condition::NearMint, true, false)
         .await
         .unwrap();
     fetched_coll = db.get_collection(coll.id).await.unwrap().unwrap();
-    assert_eq!(fetched_coll.entries.len(), 1); // foil is still there
+    let finishes: Vec<(bool, bool)> = fetched_coll
+        .entries
+        .iter()
+        .map(|e| (e.foil, e.etched))
+        .collect();
+    assert_eq!(finishes, [(true, true)]);
 
     // Delete collection
     db.delete_collection(coll.id).await.unwrap();
     let collist_after = db.list_collections().await.unwrap();
     assert_eq!(collist_after.len(), 0);
 }
+
+#[tokio::test]
+async fn test_snapshot_times_are_fixed_width() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let printing_id = common::insert_printing(&db, "Test Card", "TST").await;
+    let second = chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 3, 1, 12, 0, 0).unwrap();
+    let at = second + chrono::Duration::nanoseconds(123_456_789);
+    db.insert_snapshot(
+        printing_id,
+        &PriceSnapshot {
+            usd: Some(Decimal::ONE),
+            usd_foil: None,
+            usd_etched: None,
+            eur: None,
+            eur_foil: None,
+            tix: None,
+            fetched_at: at,
+        },
+    )
+    .await
+    .unwrap();
+
+    let stored: String = sqlx::query_scalar("SELECT fetched_at FROM price_snapshots")
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, "2026-03-01T12:00:00.123Z");
+    assert!(db.get_price_as_of(printing_id, at).await.unwrap().is_some());
+    assert!(
+        db.get_price_as_of(printing_id, second)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
@@ -0,0 +1,101 @@
+use elbrus_core::{CardDraftCounts, CardGameCounts, ColorSet};
+use elbrus_db::repo::LimitedStatsRepository;
+use elbrus_db::sqlite::SqliteBackend;
+
+fn game(event_type: &str, colors: ColorSet, name: &str, games: u64, wins: u64) -> CardGameCounts {
+    CardGameCounts {
+        expansion: "MKM".into(),
+        event_type: event_type.into(),
+        colors,
+        name: name.into(),
+        games_in_deck: games,
+        wins_in_deck: wins,
+        games_in_hand: games / 2,
+        wins_in_hand: wins / 2,
+        games_not_seen: games / 2,
+        wins_not_seen: wins / 4,
+    }
+}
+
+fn draft(name: &str, picked: u64, pick_total: u64) -> CardDraftCounts {
+    CardDraftCounts {
+        expansion: "MKM".into(),
+        event_type: "PremierDraft".into(),
+        name: name.into(),
+        packs_seen: picked * 2,
+        last_seen_total: pick_total * 3,
+        times_picked: picked,
+        pick_total,
+    }
+}
+
+#[tokio::test]
+async fn test_game_counts_filters_and_sums() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let wu = ColorSet::WHITE | ColorSet::BLUE;
+    db.replace_game_counts(
+        "game_data_a",
+        &[
+            game("PremierDraft", wu, "Bolt", 100, 60),
+            game("PremierDraft", ColorSet::RED, "Bolt", 40, 20),
+        ],
+    )
+    .await
+    .unwrap();
+    db.replace_game_counts("game_data_b", &[game("TradDraft", wu, "Bolt", 10, 8)])
+        .await
+        .unwrap();
+
+    let all = db.game_counts("mkm", None, None).await.unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].games_in_deck, 150);
+    assert_eq!(all[0].wins_in_deck, 88);
+    assert_eq!(all[0].colors, ColorSet::empty());
+
+    let premier_wu = db
+        .game_counts("MKM", Some("PremierDraft"), Some(wu))
+        .await
+        .unwrap();
+    assert_eq!(premier_wu[0].games_in_deck, 100);
+    assert_eq!(premier_wu[0].colors, wu);
+    assert_eq!(&*premier_wu[0].event_type, "PremierDraft");
+    assert!(db.game_counts("OTJ", None, None).await.unwrap().is_empty());
+
+    // Re-ingesting a dataset replaces its rows.
+    db.replace_game_counts("game_data_a", &[game("PremierDraft", wu, "Bolt", 2, 1)])
+        .await
+        .unwrap();
+    let all = db.game_counts("MKM", None, None).await.unwrap();
+    assert_eq!(all[0].games_in_deck, 12);
+}
+
+#[tokio::test]
+async fn test_draft_counts() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    db.replace_draft_counts(
+        "draft_data_a",
+        &[draft("Bolt", 4, 8), draft("Shock", 2, 10)],
+    )
+    .await
+    .unwrap();
+    db.replace_draft_counts("draft_data_b", &[draft("Bolt", 1, 4)])
+        .await
+        .unwrap();
+
+    let counts = db.draft_counts("MKM", Some("PremierDraft")).await.unwrap();
+    let names: Vec<&str> = counts.iter().map(|c| &*c.name).collect();
+    assert_eq!(names, ["Bolt", "Shock"]);
+    assert_eq!(counts[0].times_picked, 5);
+    assert_eq!(counts[0].pick_total, 12);
+    assert_eq!(counts[0].ata(), Some(2.4));
+    assert!(
+        db.draft_counts("MKM", Some("Sealed"))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        db.limited_datasets().await.unwrap(),
+        ["draft_data_a", "draft_data_b"]
+    );
+}
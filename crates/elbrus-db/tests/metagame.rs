@@ -0,0 +1,122 @@
+use chrono::NaiveDate;
+use elbrus_core::{ArchetypeShare, Format, MetagameSnapshot, StapleStat};
+use elbrus_db::repo::MetagameRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use uuid::Uuid;
+
+mod common;
+
+fn staple(name: &str, decks: u32, main_decks: u32, deck_count: u32) -> StapleStat {
+    StapleStat {
+        oracle_id: Uuid::new_v4(),
+        name: name.into(),
+        decks,
+        main_decks,
+        side_decks: decks - main_decks,
+        inclusion_rate: f64::from(decks) / f64::from(deck_count),
+        avg_main_copies: if main_decks > 0 { 3.5 } else { 0.0 },
+        avg_side_copies: if main_decks < decks { 2.0 } else { 0.0 },
+    }
+}
+
+fn snapshot(format: &Format, month: u32, staples: Vec<StapleStat>) -> MetagameSnapshot {
+    MetagameSnapshot {
+        id: Uuid::new_v4(),
+        format: format.clone(),
+        start: NaiveDate::from_ymd_opt(2026, month, 1).unwrap(),
+        end: NaiveDate::from_ymd_opt(2026, month, 28).unwrap(),
+        deck_count: 8,
+        staples,
+        archetypes: vec![
+            ArchetypeShare {
+                name: "Goblin Guide / Eidolon of the Great Revel".into(),
+                decks: 5,
+                share: 0.625,
+            },
+            ArchetypeShare {
+                name: "Primeval Titan".into(),
+                decks: 3,
+                share: 0.375,
+            },
+        ],
+    }
+}
+
+/// Store the snapshot's cards, then the snapshot.
+async fn save(db: &SqliteBackend, snapshot: &MetagameSnapshot) {
+    for staple in &snapshot.staples {
+        common::insert_oracle(db, staple.oracle_id, &staple.name).await;
+    }
+    db.save_snapshot(snapshot).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_metagame_snapshots() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let pioneer = Format("pioneer".into());
+    let modern = Format("modern".into());
+
+    assert!(db.latest_snapshot(&pioneer).await.unwrap().is_none());
+    assert!(db.top_staples(&pioneer, None, 50).await.unwrap().is_empty());
+
+    let september = snapshot(&pioneer, 9, vec![staple("Fatal Push", 6, 6, 8)]);
+    let october = snapshot(
+        &pioneer,
+        10,
+        vec![
+            staple("Thoughtseize", 7, 7, 8),
+            staple("Fatal Push", 5, 4, 8),
+            staple("Abrade", 5, 0, 8),
+        ],
+    );
+    save(&db, &september).await;
+    save(&db, &october).await;
+    save(
+        &db,
+        &snapshot(&modern, 11, vec![staple("Lightning Bolt", 8, 8, 8)]),
+    )
+    .await;
+
+    assert_eq!(
+        db.get_snapshot(september.id).await.unwrap(),
+        Some(september.clone())
+    );
+    assert_eq!(
+        db.latest_snapshot(&pioneer).await.unwrap(),
+        Some(october.clone())
+    );
+
+    let top: Vec<_> = db.top_staples(&pioneer, None, 2).await.unwrap();
+    assert_eq!(top, october.staples[..2].to_vec());
+
+    let date = |month, day| NaiveDate::from_ymd_opt(2026, month, day).unwrap();
+    assert_eq!(
+        db.snapshot_as_of(&pioneer, date(10, 28)).await.unwrap(),
+        Some(october.clone())
+    );
+    let top = db
+        .top_staples(&pioneer, Some(date(9, 15)), 50)
+        .await
+        .unwrap();
+    assert_eq!(top, september.staples);
+    assert!(
+        db.snapshot_as_of(&pioneer, date(9, 30))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        db.top_staples(&pioneer, Some(date(11, 1)), 50)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Re-running the same period replaces the earlier snapshot.
+    let rerun = snapshot(&pioneer, 10, vec![staple("Thoughtseize", 8, 8, 8)]);
+    save(&db, &rerun).await;
+    assert!(db.get_snapshot(october.id).await.unwrap().is_none());
+    let top = db.top_staples(&pioneer, None, 50).await.unwrap();
+    assert_eq!(top.len(), 1);
+    assert_eq!(top[0].decks, 8);
+}
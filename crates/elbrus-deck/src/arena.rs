@@ -0,0 +1,48 @@
+//! MTG Arena export: `Deck`, `Sideboard`, `Commander` and `Companion`
+//! sections of `N Card Name (SET) CN` lines, optionally preceded by an
+//! `About` block naming the deck.
+
+use crate::text::{parse_body, parse_line, section_header, write_entries};
+use crate::{Deck, DeckError, DeckParser};
+
+pub struct ArenaParser;
+
+impl DeckParser for ArenaParser {
+    /// Arena exports have section headers or set codes after card names.
+    fn can_parse(&self, input: &str) -> bool {
+        input.lines().any(|line| {
+            section_header(line).is_some()
+                || parse_line(line, true).is_some_and(|e| e.set_hint.is_some())
+        })
+    }
+
+    fn parse(&self, input: &str) -> Result<Deck, DeckError> {
+        parse_body(input, true)
+    }
+
+    fn serialize(&self, deck: &Deck) -> String {
+        let mut out = String::new();
+        if let Some(name) = &deck.name {
+            out.push_str("About\nName ");
+            out.push_str(name);
+            out.push_str("\n\n");
+        }
+        if !deck.commander.is_empty() {
+            out.push_str("Commander\n");
+            write_entries(&mut out, &deck.commander, true);
+            out.push('\n');
+        }
+        if let Some(companion) = &deck.companion {
+            out.push_str("Companion\n");
+            write_entries(&mut out, std::slice::from_ref(companion), true);
+            out.push('\n');
+        }
+        out.push_str("Deck\n");
+        write_entries(&mut out, &deck.mainboard, true);
+        if !deck.sideboard.is_empty() {
+            out.push_str("\nSideboard\n");
+            write_entries(&mut out, &deck.sideboard, true);
+        }
+        out
+    }
+}
@@ -3,12 +3,27 @@ use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
+pub mod arena;
+pub mod mtgo;
+#[cfg(feature = "resolve")]
+pub mod resolve;
+mod text;
+
+pub use arena::ArenaParser;
+pub use mtgo::MtgoParser;
+#[cfg(feature = "resolve")]
+pub use resolve::DeckResolver;
+
 #[derive(Error, Debug)]
 pub enum DeckError {
     #[error("Parse error: {0}")]
     Parse(String),
+    #[cfg(feature = "resolve")]
+    #[error("Database error: {0}")]
+    Db(#[from] elbrus_db::backend::DbError),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Deck {
     pub name: Option<Arc<str>>,
     pub format: Option<Format>,
@@ -18,32 +33,63 @@ pub struct Deck {
     pub companion: Option<DeckEntry>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DeckEntry {
     pub quantity: u32,
     pub card_name: Arc<str>,
     pub resolved: Option<Uuid>, // filled after db lookup
     pub set_hint: Option<Arc<str>>,
+    pub collector_number: Option<Arc<str>>,
     pub foil: bool,
 }
 
+impl DeckEntry {
+    #[must_use]
+    pub fn new(quantity: u32, card_name: &str) -> Self {
+        Self {
+            quantity,
+            card_name: Arc::from(card_name),
+            resolved: None,
+            set_hint: None,
+            collector_number: None,
+            foil: false,
+        }
+    }
+}
+
+impl Deck {
+    /// Every entry: commander, companion, mainboard, then sideboard.
+    pub fn entries(&self) -> impl Iterator<Item = &DeckEntry> {
+        self.commander
+            .iter()
+            .chain(&self.companion)
+            .chain(&self.mainboard)
+            .chain(&self.sideboard)
+    }
+
+    pub fn entries_mut(&mut self) -> impl Iterator<Item = &mut DeckEntry> {
+        self.commander
+            .iter_mut()
+            .chain(&mut self.companion)
+            .chain(&mut self.mainboard)
+            .chain(&mut self.sideboard)
+    }
+}
+
 pub trait DeckParser: Send + Sync {
     fn can_parse(&self, input: &str) -> bool;
     fn parse(&self, input: &str) -> Result<Deck, DeckError>;
     fn serialize(&self, deck: &Deck) -> String;
 }
 
-pub struct ArenaParser;
-pub struct MtgoParser;
 pub struct MoxfieldParser; // URL-fetched or exported text
 
-impl DeckParser for ArenaParser {
-    fn can_parse(&self, _input: &str) -> bool {
-        todo!()
-    }
-    fn parse(&self, _input: &str) -> Result<Deck, DeckError> {
-        todo!()
-    }
-    fn serialize(&self, _deck: &Deck) -> String {
-        todo!()
-    }
+/// Parse `input` with the first parser that recognises it.
+pub fn parse_deck(input: &str) -> Result<Deck, DeckError> {
+    let parsers: [&dyn DeckParser; 2] = [&ArenaParser, &MtgoParser];
+    parsers
+        .into_iter()
+        .find(|p| p.can_parse(input))
+        .ok_or_else(|| DeckError::Parse("unrecognised deck format".into()))?
+        .parse(input)
 }
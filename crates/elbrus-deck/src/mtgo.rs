@@ -0,0 +1,83 @@
+//! Magic Online decklists: the plain text export (`N Card Name` lines, a
+//! blank line or `Sideboard` header before the sideboard) and `.dek` XML.
+
+use crate::text::{parse_body, parse_line, section_header, write_entries};
+use crate::{Deck, DeckEntry, DeckError, DeckParser};
+
+pub struct MtgoParser;
+
+impl DeckParser for MtgoParser {
+    fn can_parse(&self, input: &str) -> bool {
+        if is_dek(input) {
+            return true;
+        }
+        let mut lines = input.lines().map(str::trim).filter(|l| !l.is_empty());
+        lines.clone().next().is_some()
+            && lines.all(|l| {
+                section_header(l).is_some_and(|s| s == crate::text::Section::Side)
+                    || parse_line(l, false).is_some()
+            })
+    }
+
+    fn parse(&self, input: &str) -> Result<Deck, DeckError> {
+        if is_dek(input) {
+            parse_dek(input)
+        } else {
+            parse_body(input, false)
+        }
+    }
+
+    /// Text export. MTGO has no commander zone, so commanders lead the
+    /// sideboard.
+    fn serialize(&self, deck: &Deck) -> String {
+        let mut out = String::new();
+        write_entries(&mut out, &deck.mainboard, false);
+        if !deck.commander.is_empty() || !deck.sideboard.is_empty() {
+            out.push('\n');
+            write_entries(&mut out, &deck.commander, false);
+            write_entries(&mut out, &deck.sideboard, false);
+        }
+        out
+    }
+}
+
+fn is_dek(input: &str) -> bool {
+    let start = input.trim_start();
+    start.starts_with("<?xml") || start.starts_with("<Deck")
+}
+
+/// Read `<Cards Quantity=".." Sideboard=".." Name=".." />` elements.
+fn parse_dek(input: &str) -> Result<Deck, DeckError> {
+    let mut deck = Deck::default();
+    for element in input.split("<Cards ").skip(1) {
+        let element = element.split('>').next().unwrap_or_default();
+        let name = attribute(element, "Name")
+            .ok_or_else(|| DeckError::Parse(format!("card without a name: {element:?}")))?;
+        let quantity = attribute(element, "Quantity")
+            .and_then(|q| q.parse().ok())
+            .ok_or_else(|| DeckError::Parse(format!("bad quantity for {name}")))?;
+        let entry = DeckEntry::new(quantity, &name);
+        if attribute(element, "Sideboard").is_some_and(|s| s == "true") {
+            deck.sideboard.push(entry);
+        } else {
+            deck.mainboard.push(entry);
+        }
+    }
+    if deck.mainboard.is_empty() && deck.sideboard.is_empty() {
+        return Err(DeckError::Parse("no cards".into()));
+    }
+    Ok(deck)
+}
+
+fn attribute(element: &str, name: &str) -> Option<String> {
+    let start = element.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = element[start..].find('"')? + start;
+    Some(
+        element[start..end]
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
@@ -0,0 +1,80 @@
+use crate::{Deck, DeckError};
+use elbrus_db::repo::CardRepository;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Candidates fetched per name lookup.
+const SEARCH_LIMIT: u32 = 25;
+
+/// Resolves deck entry names to oracle ids, caching lookups so a batch of
+/// decks sharing staples only queries each name once.
+pub struct DeckResolver<'a, R: ?Sized> {
+    db: &'a R,
+    cache: HashMap<String, Option<Uuid>>,
+}
+
+/// Lowercase, collapse whitespace, straighten apostrophes and spell split
+/// cards as `A // B`.
+fn normalize(name: &str) -> String {
+    let name = name.replace(['\u{2019}', '\u{2018}'], "'").to_lowercase();
+    let halves: Vec<String> = name
+        .split('/')
+        .map(|h| h.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|h| !h.is_empty())
+        .collect();
+    halves.join(" // ")
+}
+
+impl<'a, R> DeckResolver<'a, R>
+where
+    R: CardRepository + ?Sized,
+{
+    pub fn new(db: &'a R) -> Self {
+        Self {
+            db,
+            cache: HashMap::new(),
+        }
+    }
+
+    /// The oracle id for a card name. Matches the full name, ignoring case,
+    /// or failing that any single face, so `Fire // Ice`, `fire/ice` and an
+    /// MDFC's front face name all resolve.
+    pub async fn lookup(&mut self, name: &str) -> Result<Option<Uuid>, DeckError> {
+        let key = normalize(name);
+        if let Some(hit) = self.cache.get(&key) {
+            return Ok(*hit);
+        }
+
+        let front = key.split(" // ").next().unwrap_or_default();
+        let candidates = self.db.search_name(front, SEARCH_LIMIT).await?;
+        let hit = candidates
+            .iter()
+            .find(|c| normalize(&c.full_name()) == key)
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .find(|c| c.faces.iter().any(|f| normalize(&f.name) == key))
+            })
+            .map(|c| c.oracle_id);
+
+        self.cache.insert(key, hit);
+        Ok(hit)
+    }
+
+    /// Resolve every entry in `deck`, returning the names that matched
+    /// nothing. Entries that are already resolved are left alone.
+    pub async fn resolve(&mut self, deck: &mut Deck) -> Result<Vec<Arc<str>>, DeckError> {
+        let mut unresolved = Vec::new();
+        for entry in deck.entries_mut() {
+            if entry.resolved.is_some() {
+                continue;
+            }
+            entry.resolved = self.lookup(&entry.card_name).await?;
+            if entry.resolved.is_none() {
+                unresolved.push(entry.card_name.clone());
+            }
+        }
+        Ok(unresolved)
+    }
+}
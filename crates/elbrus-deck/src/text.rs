@@ -0,0 +1,115 @@
+//! Line-oriented decklist text shared by the Arena and MTGO formats.
+
+use crate::{Deck, DeckEntry, DeckError};
+use std::fmt::Write;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Section {
+    About,
+    Main,
+    Side,
+    Commander,
+    Companion,
+}
+
+/// The section a header line such as `Sideboard` or `SIDEBOARD:` starts.
+pub(crate) fn section_header(line: &str) -> Option<Section> {
+    let header = line.trim().trim_end_matches(':').to_ascii_lowercase();
+    Some(match header.as_str() {
+        "about" => Section::About,
+        "deck" | "main" | "mainboard" => Section::Main,
+        "sideboard" => Section::Side,
+        "commander" => Section::Commander,
+        "companion" => Section::Companion,
+        _ => return None,
+    })
+}
+
+/// Parse `4 Lightning Bolt` or `4x Lightning Bolt`. With `with_printing`, a
+/// trailing `(SET)` or `(SET) CN` becomes the set and collector number hints.
+pub(crate) fn parse_line(line: &str, with_printing: bool) -> Option<DeckEntry> {
+    let (quantity, rest) = line.trim().split_once(' ')?;
+    let quantity: u32 = quantity.trim_end_matches(['x', 'X']).parse().ok()?;
+    let mut name = rest.trim();
+    let foil = name.ends_with(" *F*");
+    name = name.trim_end_matches(" *F*");
+
+    let mut entry = DeckEntry::new(quantity, name);
+    entry.foil = foil;
+    if with_printing
+        && let Some((card, printing)) = name.rsplit_once(" (")
+        && let Some((set, collector_number)) = printing.split_once(')')
+        && !set.is_empty()
+        && !set.contains(' ')
+        && !collector_number.trim().contains(' ')
+    {
+        entry.card_name = Arc::from(card.trim());
+        entry.set_hint = Some(Arc::from(set.to_ascii_lowercase()));
+        let collector_number = collector_number.trim();
+        entry.collector_number =
+            (!collector_number.is_empty()).then(|| Arc::from(collector_number));
+    }
+    (!entry.card_name.is_empty()).then_some(entry)
+}
+
+/// Parse a sectioned decklist. Without any section headers, a blank line
+/// after the mainboard starts the sideboard.
+pub(crate) fn parse_body(input: &str, with_printing: bool) -> Result<Deck, DeckError> {
+    let mut deck = Deck::default();
+    let mut section = Section::Main;
+    let mut headers = false;
+
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !headers && section == Section::Main && !deck.mainboard.is_empty() {
+                section = Section::Side;
+            }
+            continue;
+        }
+        if line.starts_with("//") || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = section_header(line) {
+            section = header;
+            headers = true;
+            continue;
+        }
+        if section == Section::About {
+            if let Some(name) = line.strip_prefix("Name ") {
+                deck.name = Some(Arc::from(name.trim()));
+            }
+            continue;
+        }
+
+        let entry = parse_line(line, with_printing)
+            .ok_or_else(|| DeckError::Parse(format!("line {}: {line:?}", number + 1)))?;
+        match section {
+            Section::Main | Section::About => deck.mainboard.push(entry),
+            Section::Side => deck.sideboard.push(entry),
+            Section::Commander => deck.commander.push(entry),
+            Section::Companion => {
+                deck.companion.get_or_insert(entry);
+            }
+        }
+    }
+
+    if deck.entries().next().is_none() {
+        return Err(DeckError::Parse("no cards".into()));
+    }
+    Ok(deck)
+}
+
+pub(crate) fn write_entries(out: &mut String, entries: &[DeckEntry], with_printing: bool) {
+    for entry in entries {
+        let _ = write!(out, "{} {}", entry.quantity, entry.card_name);
+        if with_printing && let Some(set) = &entry.set_hint {
+            let _ = write!(out, " ({})", set.to_ascii_uppercase());
+            if let Some(collector_number) = &entry.collector_number {
+                let _ = write!(out, " {collector_number}");
+            }
+        }
+        out.push('\n');
+    }
+}
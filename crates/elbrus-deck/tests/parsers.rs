@@ -0,0 +1,146 @@
+use elbrus_deck::{ArenaParser, DeckEntry, DeckError, DeckParser, MtgoParser, parse_deck};
+
+const ARENA: &str = "About
+Name Jund Sacrifice
+
+Commander
+1 Korvold, Fae-Cursed King (ELD) 329
+
+Companion
+1 Jegantha, the Wellspring (IKO) 222
+
+Deck
+4 Lightning Bolt (M11) 149
+2 Fire // Ice (MH2) 290
+20 Mountain (M21) 275
+1 Valakut Awakening
+
+Sideboard
+2 Abrade (DMU) 139
+";
+
+const MTGO: &str = "4 Lightning Bolt
+20 Mountain
+36 Goblin Guide
+
+2 Abrade
+";
+
+const DEK: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <NetDeckID>0</NetDeckID>
+  <PreconstructedDeckID>0</PreconstructedDeckID>
+  <Cards CatID="38501" Quantity="4" Sideboard="false" Name="Lightning Bolt" />
+  <Cards CatID="1" Quantity="1" Sideboard="false" Name="Jace, Vryn&apos;s Prodigy" />
+  <Cards CatID="2" Quantity="3" Sideboard="true" Name="Smash to Smithereens" />
+</Deck>
+"#;
+
+fn entry(quantity: u32, name: &str, set: Option<&str>, cn: Option<&str>) -> DeckEntry {
+    let mut e = DeckEntry::new(quantity, name);
+    e.set_hint = set.map(Into::into);
+    e.collector_number = cn.map(Into::into);
+    e
+}
+
+#[test]
+fn test_arena_sections() {
+    assert!(ArenaParser.can_parse(ARENA));
+    let deck = ArenaParser.parse(ARENA).unwrap();
+    assert_eq!(deck.name.as_deref(), Some("Jund Sacrifice"));
+    assert_eq!(
+        deck.commander,
+        vec![entry(
+            1,
+            "Korvold, Fae-Cursed King",
+            Some("eld"),
+            Some("329")
+        )]
+    );
+    assert_eq!(
+        deck.companion,
+        Some(entry(
+            1,
+            "Jegantha, the Wellspring",
+            Some("iko"),
+            Some("222")
+        ))
+    );
+    assert_eq!(
+        deck.mainboard,
+        vec![
+            entry(4, "Lightning Bolt", Some("m11"), Some("149")),
+            entry(2, "Fire // Ice", Some("mh2"), Some("290")),
+            entry(20, "Mountain", Some("m21"), Some("275")),
+            entry(1, "Valakut Awakening", None, None),
+        ]
+    );
+    assert_eq!(
+        deck.sideboard,
+        vec![entry(2, "Abrade", Some("dmu"), Some("139"))]
+    );
+}
+
+#[test]
+fn test_arena_round_trip() {
+    let deck = ArenaParser.parse(ARENA).unwrap();
+    let text = ArenaParser.serialize(&deck);
+    assert!(text.contains("4 Lightning Bolt (M11) 149\n"));
+    assert_eq!(ArenaParser.parse(&text).unwrap(), deck);
+}
+
+#[test]
+fn test_mtgo_text() {
+    assert!(!ArenaParser.can_parse(MTGO));
+    assert!(MtgoParser.can_parse(MTGO));
+    let deck = MtgoParser.parse(MTGO).unwrap();
+    assert_eq!(deck.mainboard.len(), 3);
+    assert_eq!(deck.sideboard, vec![DeckEntry::new(2, "Abrade")]);
+    assert_eq!(
+        MtgoParser.parse(&MtgoParser.serialize(&deck)).unwrap(),
+        deck
+    );
+
+    let with_header = "4x Lightning Bolt\nSIDEBOARD:\n2x Abrade\n";
+    assert!(MtgoParser.can_parse(with_header));
+    let deck = MtgoParser.parse(with_header).unwrap();
+    assert_eq!(deck.mainboard, vec![DeckEntry::new(4, "Lightning Bolt")]);
+    assert_eq!(deck.sideboard, vec![DeckEntry::new(2, "Abrade")]);
+}
+
+#[test]
+fn test_mtgo_dek() {
+    assert!(MtgoParser.can_parse(DEK));
+    let deck = MtgoParser.parse(DEK).unwrap();
+    assert_eq!(
+        deck.mainboard,
+        vec![
+            DeckEntry::new(4, "Lightning Bolt"),
+            DeckEntry::new(1, "Jace, Vryn's Prodigy"),
+        ]
+    );
+    assert_eq!(
+        deck.sideboard,
+        vec![DeckEntry::new(3, "Smash to Smithereens")]
+    );
+}
+
+#[test]
+fn test_parse_deck_dispatch() {
+    assert_eq!(parse_deck(ARENA).unwrap().commander.len(), 1);
+    assert_eq!(parse_deck(MTGO).unwrap().mainboard.len(), 3);
+    assert_eq!(parse_deck(DEK).unwrap().sideboard.len(), 1);
+
+    assert!(matches!(
+        parse_deck("just some notes"),
+        Err(DeckError::Parse(_))
+    ));
+    assert!(matches!(
+        ArenaParser.parse("Deck\n4 Lightning Bolt\nnot a card\n"),
+        Err(DeckError::Parse(msg)) if msg.starts_with("line 3")
+    ));
+    assert!(matches!(
+        ArenaParser.parse("Deck\n\n"),
+        Err(DeckError::Parse(_))
+    ));
+}
@@ -0,0 +1,84 @@
+use elbrus_core::{
+    CardFace, CardLayout, OracleCard, color::ColorSet, legality::Legalities, oracle::OracleText,
+    types::TypeLine,
+};
+use elbrus_db::repo::CardRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_deck::{DeckResolver, parse_deck};
+use uuid::Uuid;
+
+fn face(name: &str) -> CardFace {
+    CardFace {
+        name: name.into(),
+        mana_cost: None,
+        type_line: TypeLine::default(),
+        oracle_text: OracleText::default(),
+        colors: ColorSet::empty(),
+        power: None,
+        toughness: None,
+        loyalty: None,
+        defense: None,
+        flavor_text: None,
+    }
+}
+
+async fn insert(db: &SqliteBackend, layout: CardLayout, names: &[&str]) -> Uuid {
+    let oracle_id = Uuid::new_v4();
+    db.upsert_oracle(&OracleCard {
+        oracle_id,
+        layout,
+        faces: names.iter().map(|n| face(n)).collect(),
+        color_identity: ColorSet::empty(),
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    })
+    .await
+    .unwrap();
+    oracle_id
+}
+
+#[tokio::test]
+async fn test_resolve_deck() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let bolt = insert(&db, CardLayout::Normal, &["Lightning Bolt"]).await;
+    insert(&db, CardLayout::Normal, &["Lightning Bolt Jr."]).await;
+    let fire_ice = insert(&db, CardLayout::Split, &["Fire", "Ice"]).await;
+    let valakut = insert(
+        &db,
+        CardLayout::ModalDfc,
+        &["Valakut Awakening", "Valakut Stoneforge"],
+    )
+    .await;
+    let jace = insert(&db, CardLayout::Transform, &["Jace, Vryn's Prodigy"]).await;
+
+    let mut deck = parse_deck(
+        "Deck
+4 LIGHTNING BOLT
+2 fire/ice
+1 Valakut Awakening
+1 Jace, Vryn\u{2019}s Prodigy
+3 Nonexistent Card
+
+Sideboard
+2 Lightning Bolt
+",
+    )
+    .unwrap();
+
+    let mut resolver = DeckResolver::new(&db);
+    let unresolved = resolver.resolve(&mut deck).await.unwrap();
+    assert_eq!(unresolved, vec!["Nonexistent Card".into()]);
+
+    let resolved: Vec<Option<Uuid>> = deck.mainboard.iter().map(|e| e.resolved).collect();
+    assert_eq!(
+        resolved,
+        vec![Some(bolt), Some(fire_ice), Some(valakut), Some(jace), None]
+    );
+    assert_eq!(deck.sideboard[0].resolved, Some(bolt));
+    assert_eq!(
+        resolver.lookup("Valakut Stoneforge").await.unwrap(),
+        Some(valakut)
+    );
+}
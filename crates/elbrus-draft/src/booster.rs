@@ -0,0 +1,295 @@
+//! Booster generation from per-set slot templates.
+//!
+//! A [`BoosterTemplate`] is a list of slots, each drawing some number of
+//! cards from one of several weighted [`Sheet`]s: the set's commons, its
+//! mythics, its basic lands, another set such as The List, and so on. The
+//! templates and the sets using them live in `data/boosters.json`, so a new
+//! set or a corrected ratio is a data change. Sets missing from the file get
+//! the fallback template, a plain 10 common / 3 uncommon / 1 rare-or-mythic
+//! pack.
+
+use crate::{BoosterPack, DraftCard, DraftError};
+use elbrus_core::Rarity;
+use elbrus_db::repo::CardRepository;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// The templates bundled with this crate.
+pub const DEFAULT_BOOSTERS: &str = include_str!("../data/boosters.json");
+
+/// Draws made for a slot before accepting a card already in the pack.
+const DUPLICATE_RETRIES: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BoosterKind {
+    Draft,
+    Set,
+    Collector,
+    Play,
+}
+
+impl fmt::Display for BoosterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Draft => "draft",
+            Self::Set => "set",
+            Self::Collector => "collector",
+            Self::Play => "play",
+        })
+    }
+}
+
+/// Where a slot draws its cards from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sheet {
+    Common,
+    Uncommon,
+    Rare,
+    Mythic,
+    BasicLand,
+    /// Printings of the set outside its regular boosters, such as showcase
+    /// and extended-art cards, of one rarity.
+    Variant(Rarity),
+    /// Every card of another set, e.g. The List (`plst`) or Special Guests
+    /// (`spg`).
+    Set(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotOption {
+    pub weight: u32,
+    pub sheet: Sheet,
+    #[serde(default)]
+    pub foil: bool,
+}
+
+/// `count` cards, each drawn from one of `options` picked by weight. Options
+/// whose sheet is empty for the set are skipped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Slot {
+    pub count: u32,
+    pub options: Vec<SlotOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoosterTemplate {
+    pub slots: Vec<Slot>,
+}
+
+impl BoosterTemplate {
+    /// Other sets the template draws from, which [`SetSheets::load`] fetches
+    /// alongside the set itself.
+    #[must_use]
+    pub fn extra_sets(&self) -> Vec<&str> {
+        self.slots
+            .iter()
+            .flat_map(|s| &s.options)
+            .filter_map(|o| match &o.sheet {
+                Sheet::Set(code) => Some(code.as_str()),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Open `count` packs; the same seed and sheets always give the same
+    /// packs.
+    #[must_use]
+    pub fn generate(&self, sheets: &SetSheets, count: usize, seed: u64) -> Vec<BoosterPack> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        (0..count).map(|_| self.open(sheets, &mut rng)).collect()
+    }
+
+    fn open(&self, sheets: &SetSheets, rng: &mut impl Rng) -> BoosterPack {
+        let mut pack = BoosterPack::default();
+        for slot in &self.slots {
+            let options: Vec<&SlotOption> = slot
+                .options
+                .iter()
+                .filter(|o| o.weight > 0 && !sheets.sheet(&o.sheet).is_empty())
+                .collect();
+            let Ok(weights) = WeightedIndex::new(options.iter().map(|o| o.weight)) else {
+                continue;
+            };
+            for _ in 0..slot.count {
+                let option = options[weights.sample(rng)];
+                let sheet = sheets.sheet(&option.sheet);
+                let mut card = sheet.choose(rng);
+                for _ in 0..DUPLICATE_RETRIES {
+                    let duplicate =
+                        card.is_some_and(|c| pack.cards.iter().any(|p| p.oracle_id == c.oracle_id));
+                    if !duplicate || sheet.len() <= 1 {
+                        break;
+                    }
+                    card = sheet.choose(rng);
+                }
+                if let Some(card) = card {
+                    pack.cards.push(DraftCard {
+                        foil: option.foil,
+                        ..card.clone()
+                    });
+                }
+            }
+        }
+        pack
+    }
+}
+
+/// The cards of a set split into the sheets templates draw from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SetSheets {
+    pub set_code: String,
+    sheets: HashMap<Sheet, Vec<DraftCard>>,
+}
+
+impl SetSheets {
+    /// Sort a set's cards into sheets. `cards` pairs each card with whether
+    /// the printing is opened from the set's regular boosters.
+    #[must_use]
+    pub fn new(set_code: &str, cards: impl IntoIterator<Item = (DraftCard, bool)>) -> Self {
+        let mut sheets: HashMap<Sheet, Vec<DraftCard>> = HashMap::new();
+        for (card, booster) in cards {
+            let sheet = if card.is_basic_land() {
+                Sheet::BasicLand
+            } else if !booster {
+                Sheet::Variant(card.rarity)
+            } else {
+                match card.rarity {
+                    Rarity::Common => Sheet::Common,
+                    Rarity::Uncommon => Sheet::Uncommon,
+                    Rarity::Rare => Sheet::Rare,
+                    Rarity::Mythic => Sheet::Mythic,
+                    _ => Sheet::Variant(card.rarity),
+                }
+            };
+            sheets.entry(sheet).or_default().push(card);
+        }
+        Self {
+            set_code: set_code.to_lowercase(),
+            sheets,
+        }
+    }
+
+    /// Add another set's cards as [`Sheet::Set`].
+    pub fn add_set(&mut self, set_code: &str, cards: Vec<DraftCard>) {
+        self.sheets
+            .insert(Sheet::Set(set_code.to_lowercase()), cards);
+    }
+
+    #[must_use]
+    pub fn sheet(&self, sheet: &Sheet) -> &[DraftCard] {
+        self.sheets.get(sheet).map_or(&[], Vec::as_slice)
+    }
+
+    /// The printings of `set_code` and of every extra set `template` draws
+    /// from.
+    pub async fn load<R>(
+        db: &R,
+        set_code: &str,
+        template: &BoosterTemplate,
+    ) -> Result<Self, DraftError>
+    where
+        R: CardRepository + ?Sized,
+    {
+        let cards = set_cards(db, set_code).await?;
+        if cards.is_empty() {
+            return Err(DraftError::EmptySet(set_code.to_owned()));
+        }
+        let mut sheets = Self::new(set_code, cards);
+        for extra in template.extra_sets() {
+            let cards = set_cards(db, extra).await?;
+            sheets.add_set(extra, cards.into_iter().map(|(c, _)| c).collect());
+        }
+        Ok(sheets)
+    }
+}
+
+/// The set's English printings paired with [`Printing::booster`].
+///
+/// [`Printing::booster`]: elbrus_core::Printing::booster
+async fn set_cards<R>(db: &R, set_code: &str) -> Result<Vec<(DraftCard, bool)>, DraftError>
+where
+    R: CardRepository + ?Sized,
+{
+    let mut oracles = HashMap::new();
+    let mut cards = Vec::new();
+    for printing in db.cards_in_set(&set_code.to_lowercase()).await? {
+        if &*printing.lang != "en" {
+            continue;
+        }
+        if let Entry::Vacant(entry) = oracles.entry(printing.oracle_id) {
+            entry.insert(db.get_oracle(printing.oracle_id).await?);
+        }
+        if let Some(Some(card)) = oracles.get(&printing.oracle_id) {
+            cards.push((DraftCard::from_printing(&printing, card), printing.booster));
+        }
+    }
+    Ok(cards)
+}
+
+/// Booster templates and the sets using them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoosterConfig {
+    /// Template for sets not listed in `sets`.
+    pub fallback: String,
+    pub templates: HashMap<String, BoosterTemplate>,
+    /// Set code to the template name of each booster it was sold in.
+    pub sets: HashMap<String, HashMap<BoosterKind, String>>,
+}
+
+impl BoosterConfig {
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The templates in [`DEFAULT_BOOSTERS`].
+    #[must_use]
+    pub fn bundled() -> Self {
+        Self::from_json(DEFAULT_BOOSTERS).expect("bundled booster templates are valid JSON")
+    }
+
+    /// The template for `kind` boosters of `set_code`, or the fallback if the
+    /// set is not listed.
+    pub fn template(
+        &self,
+        set_code: &str,
+        kind: BoosterKind,
+    ) -> Result<&BoosterTemplate, DraftError> {
+        let name = match self.sets.get(&set_code.to_lowercase()) {
+            Some(kinds) => kinds.get(&kind).ok_or_else(|| DraftError::NoBooster {
+                set: set_code.to_owned(),
+                kind,
+            })?,
+            None => &self.fallback,
+        };
+        self.templates
+            .get(name)
+            .ok_or_else(|| DraftError::UnknownTemplate(name.clone()))
+    }
+
+    /// Load `set_code` from `db` and open `count` of its `kind` boosters.
+    pub async fn generate<R>(
+        &self,
+        db: &R,
+        set_code: &str,
+        kind: BoosterKind,
+        count: usize,
+        seed: u64,
+    ) -> Result<Vec<BoosterPack>, DraftError>
+    where
+        R: CardRepository + ?Sized,
+    {
+        let template = self.template(set_code, kind)?;
+        let sheets = SetSheets::load(db, set_code, template).await?;
+        Ok(template.generate(&sheets, count, seed))
+    }
+}
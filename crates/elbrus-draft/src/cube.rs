@@ -0,0 +1,400 @@
+//! Cubes: a fixed card list drafted without rarity slots.
+//!
+//! Cubes are read from CubeCobra exports, either the plain card name list or
+//! the CSV, and resolved against the card database before drafting.
+
+use crate::state::DraftConfig;
+use crate::{BoosterPack, DraftCard, DraftError};
+use elbrus_core::{CardType, ColorSet, Rarity};
+use elbrus_db::repo::CardRepository;
+use elbrus_deck::DeckResolver;
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeEntry {
+    pub name: Arc<str>,
+    /// Filled by [`Cube::resolve`].
+    pub oracle_id: Option<Uuid>,
+    /// Filled by [`Cube::resolve`] when the set and collector number match a
+    /// printing.
+    pub printing_id: Option<Uuid>,
+    pub set_hint: Option<Arc<str>>,
+    pub collector_number: Option<Arc<str>>,
+    pub rarity: Option<Rarity>,
+    pub foil: bool,
+    pub section: Option<Arc<str>>,
+    pub tags: Vec<Arc<str>>,
+}
+
+impl CubeEntry {
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.into(),
+            oracle_id: None,
+            printing_id: None,
+            set_hint: None,
+            collector_number: None,
+            rarity: None,
+            foil: false,
+            section: None,
+            tags: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cube {
+    pub name: Option<Arc<str>>,
+    /// One entry per copy.
+    pub cards: Vec<CubeEntry>,
+}
+
+/// A row of CubeCobra's CSV export; other columns are ignored.
+#[derive(Debug, Deserialize)]
+struct CsvRow {
+    name: String,
+    #[serde(rename = "Set", default)]
+    set: String,
+    #[serde(rename = "Collector Number", default)]
+    collector_number: String,
+    #[serde(rename = "Rarity", default)]
+    rarity: String,
+    #[serde(rename = "Finish", default)]
+    finish: String,
+    #[serde(default)]
+    maybeboard: String,
+    #[serde(default)]
+    tags: String,
+}
+
+fn non_empty(text: &str) -> Option<Arc<str>> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.into())
+}
+
+impl Cube {
+    /// Parse either CubeCobra export, telling them apart by the CSV header.
+    pub fn parse(input: &str) -> Result<Self, DraftError> {
+        let first = input
+            .lines()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or_default();
+        if first
+            .trim_start_matches('\u{feff}')
+            .trim_start_matches('"')
+            .to_ascii_lowercase()
+            .starts_with("name")
+            && first.contains(',')
+        {
+            Self::parse_csv(input)
+        } else {
+            Ok(Self::parse_text(input))
+        }
+    }
+
+    /// One card name per line. `# Heading` lines start a section, and a
+    /// leading count (`2 Island`, `2x Island`) and trailing printing
+    /// (`(DMU) 262`) are understood.
+    #[must_use]
+    pub fn parse_text(input: &str) -> Self {
+        let mut cube = Self::default();
+        let mut section = None;
+        for line in input.lines() {
+            let line = line.trim().trim_start_matches('\u{feff}');
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            if let Some(heading) = line.strip_prefix('#') {
+                section = non_empty(heading);
+                continue;
+            }
+
+            let (count, rest) = match line.split_once(' ') {
+                Some((count, rest)) => match count.trim_end_matches(['x', 'X']).parse::<u32>() {
+                    Ok(count) if (1..100).contains(&count) => (count, rest.trim()),
+                    _ => (1, line),
+                },
+                None => (1, line),
+            };
+            let mut entry = CubeEntry::new(rest);
+            if let Some((name, printing)) = rest.rsplit_once(" (")
+                && let Some((set, collector_number)) = printing.split_once(')')
+                && !set.is_empty()
+                && !set.contains(' ')
+            {
+                entry.name = name.trim().into();
+                entry.set_hint = Some(set.to_ascii_lowercase().into());
+                entry.collector_number = non_empty(collector_number);
+            }
+            entry.section = section.clone();
+            for _ in 0..count {
+                cube.cards.push(entry.clone());
+            }
+        }
+        cube
+    }
+
+    /// CubeCobra's CSV export. Maybeboard rows are left out.
+    pub fn parse_csv(input: &str) -> Result<Self, DraftError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(input.trim_start_matches('\u{feff}').as_bytes());
+        let mut cube = Self::default();
+        for row in reader.deserialize::<CsvRow>() {
+            let row = row?;
+            if row.maybeboard.eq_ignore_ascii_case("true") || row.name.trim().is_empty() {
+                continue;
+            }
+            let mut entry = CubeEntry::new(row.name.trim());
+            entry.set_hint = non_empty(&row.set.to_ascii_lowercase());
+            entry.collector_number = non_empty(&row.collector_number);
+            entry.rarity = serde_json::from_str(&format!("\"{}\"", row.rarity.to_lowercase())).ok();
+            entry.foil = row.finish.eq_ignore_ascii_case("foil");
+            entry.tags = row.tags.split([';', ',']).filter_map(non_empty).collect();
+            cube.cards.push(entry);
+        }
+        Ok(cube)
+    }
+
+    /// Distinct sections in order of first appearance.
+    #[must_use]
+    pub fn sections(&self) -> Vec<Arc<str>> {
+        let mut sections: Vec<Arc<str>> = Vec::new();
+        for section in self.cards.iter().filter_map(|c| c.section.as_ref()) {
+            if !sections.contains(section) {
+                sections.push(section.clone());
+            }
+        }
+        sections
+    }
+
+    /// Entries tagged `tag`, ignoring case.
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a CubeEntry> {
+        self.cards
+            .iter()
+            .filter(move |c| c.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Resolve entry names to oracle ids, and set and collector numbers to
+    /// printings, returning the names that matched nothing. Entries that are
+    /// already resolved are left alone.
+    pub async fn resolve<R>(&mut self, db: &R) -> Result<Vec<Arc<str>>, DraftError>
+    where
+        R: CardRepository + ?Sized,
+    {
+        let mut resolver = DeckResolver::new(db);
+        let mut sets: HashMap<Arc<str>, HashMap<Arc<str>, Uuid>> = HashMap::new();
+        let mut unresolved = Vec::new();
+        for entry in &mut self.cards {
+            if entry.oracle_id.is_none() {
+                entry.oracle_id = resolver.lookup(&entry.name).await?;
+            }
+            let Some(oracle_id) = entry.oracle_id else {
+                unresolved.push(entry.name.clone());
+                continue;
+            };
+            if entry.printing_id.is_none()
+                && let (Some(set), Some(number)) = (&entry.set_hint, &entry.collector_number)
+            {
+                if !sets.contains_key(set) {
+                    let printings = db.cards_in_set(set).await?;
+                    sets.insert(
+                        set.clone(),
+                        printings
+                            .into_iter()
+                            .filter(|p| p.lang.as_ref() == "en")
+                            .map(|p| (p.collector_number, p.id))
+                            .collect(),
+                    );
+                }
+                entry.printing_id = sets[set].get(number).copied();
+                if let Some(id) = entry.printing_id
+                    && db
+                        .get_by_id(id)
+                        .await?
+                        .is_some_and(|p| p.oracle_id != oracle_id)
+                {
+                    entry.printing_id = None;
+                }
+            }
+        }
+        Ok(unresolved)
+    }
+
+    /// The resolved entries as draft cards. Cards without a known printing
+    /// take their rarity from the export, or common.
+    pub async fn draft_cards<R>(&self, db: &R) -> Result<Vec<DraftCard>, DraftError>
+    where
+        R: CardRepository + ?Sized,
+    {
+        let mut oracles = HashMap::new();
+        let mut cards = Vec::with_capacity(self.cards.len());
+        for entry in &self.cards {
+            let Some(oracle_id) = entry.oracle_id else {
+                continue;
+            };
+            if let Entry::Vacant(slot) = oracles.entry(oracle_id) {
+                slot.insert(db.get_oracle(oracle_id).await?);
+            }
+            let Some(Some(oracle)) = oracles.get(&oracle_id) else {
+                continue;
+            };
+            let printing = match entry.printing_id {
+                Some(id) => db.get_by_id(id).await?,
+                None => None,
+            };
+            let mut card = match printing {
+                Some(printing) => DraftCard::from_printing(&printing, oracle),
+                None => DraftCard::from_oracle(oracle, entry.rarity.unwrap_or(Rarity::Common)),
+            };
+            card.foil = entry.foil;
+            cards.push(card);
+        }
+        Ok(cards)
+    }
+}
+
+/// Deal `config.packs_needed()` packs of `pack_size` from `cards`, shuffled
+/// under `seed`, in the order [`DraftState::new`] takes them. `pack_size` must
+/// be at least 1.
+///
+/// [`DraftState::new`]: crate::DraftState::new
+pub fn cube_packs(
+    cards: &[DraftCard],
+    config: DraftConfig,
+    pack_size: usize,
+    seed: u64,
+) -> Result<Vec<BoosterPack>, DraftError> {
+    if pack_size == 0 {
+        return Err(DraftError::EmptyPacks);
+    }
+    let needed = config.packs_needed() * pack_size;
+    if cards.len() < needed {
+        return Err(DraftError::CubeTooSmall {
+            needed,
+            found: cards.len(),
+        });
+    }
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut deck = cards.to_vec();
+    deck.shuffle(&mut rng);
+    Ok(deck[..needed]
+        .chunks(pack_size)
+        .map(|cards| BoosterPack {
+            cards: cards.to_vec(),
+        })
+        .collect())
+}
+
+/// CubeCobra's color categories, in its sort order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ColorCategory {
+    White,
+    Blue,
+    Black,
+    Red,
+    Green,
+    Multicolor,
+    Colorless,
+    /// Colorless lands.
+    Land,
+}
+
+impl ColorCategory {
+    pub const ALL: [Self; 8] = [
+        Self::White,
+        Self::Blue,
+        Self::Black,
+        Self::Red,
+        Self::Green,
+        Self::Multicolor,
+        Self::Colorless,
+        Self::Land,
+    ];
+
+    #[must_use]
+    pub fn of(card: &DraftCard) -> Self {
+        let colors = card.colors;
+        if colors.is_multicolor() {
+            Self::Multicolor
+        } else if colors == ColorSet::WHITE {
+            Self::White
+        } else if colors == ColorSet::BLUE {
+            Self::Blue
+        } else if colors == ColorSet::BLACK {
+            Self::Black
+        } else if colors == ColorSet::RED {
+            Self::Red
+        } else if colors == ColorSet::GREEN {
+            Self::Green
+        } else if card.type_line.is_land() {
+            Self::Land
+        } else {
+            Self::Colorless
+        }
+    }
+}
+
+/// Mana values of 7 or more share the curve's last bucket.
+pub const CURVE_BUCKETS: usize = 8;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CubeStats {
+    pub total: usize,
+    pub colors: HashMap<ColorCategory, usize>,
+    /// Non-land cards per mana value, by color category.
+    pub curves: HashMap<ColorCategory, [usize; CURVE_BUCKETS]>,
+    /// Cards of each type; a card with several types counts towards each.
+    pub types: HashMap<CardType, usize>,
+}
+
+impl CubeStats {
+    #[must_use]
+    pub fn new(cards: &[DraftCard]) -> Self {
+        let mut stats = Self {
+            total: cards.len(),
+            ..Self::default()
+        };
+        for card in cards {
+            let category = ColorCategory::of(card);
+            *stats.colors.entry(category).or_default() += 1;
+            if !card.type_line.is_land() {
+                let bucket = (card.mana_value.max(0.0) as usize).min(CURVE_BUCKETS - 1);
+                stats.curves.entry(category).or_default()[bucket] += 1;
+            }
+            for card_type in &card.type_line.card_types {
+                *stats.types.entry(card_type.clone()).or_default() += 1;
+            }
+        }
+        stats
+    }
+
+    #[must_use]
+    pub fn count(&self, category: ColorCategory) -> usize {
+        self.colors.get(&category).copied().unwrap_or(0)
+    }
+
+    /// The share of the cube in `category`.
+    #[must_use]
+    pub fn share(&self, category: ColorCategory) -> f64 {
+        self.count(category) as f64 / self.total.max(1) as f64
+    }
+
+    /// How far the five colors are from equal: the largest mono-colored
+    /// count over the smallest, or `None` if a color is missing.
+    #[must_use]
+    pub fn color_balance(&self) -> Option<f64> {
+        let counts = ColorCategory::ALL[..5].iter().map(|&c| self.count(c));
+        let max = counts.clone().max()?;
+        let min = counts.min()?;
+        (min > 0).then(|| max as f64 / min as f64)
+    }
+}
@@ -0,0 +1,263 @@
+//! Grid draft: nine cards laid out 3x3, taken a row or column at a time.
+//!
+//! Each grid is one round. Seats take turns choosing a row or column and
+//! taking whatever is left in it, the first chooser moving one seat along each
+//! grid. Cards left once every seat has chosen are discarded.
+
+use crate::state::{DraftFormat, DraftPlayer, Pick, PickLog};
+use crate::strategy::{PickContext, PickStrategy};
+use crate::{BoosterPack, DraftCard, DraftError};
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+pub const GRID_SIZE: usize = 3;
+pub const GRID_CARDS: usize = GRID_SIZE * GRID_SIZE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GridLine {
+    Row(usize),
+    Column(usize),
+}
+
+impl GridLine {
+    pub const ALL: [Self; 2 * GRID_SIZE] = [
+        Self::Row(0),
+        Self::Row(1),
+        Self::Row(2),
+        Self::Column(0),
+        Self::Column(1),
+        Self::Column(2),
+    ];
+
+    /// Row-major indices of the line's cells.
+    #[must_use]
+    pub fn cells(self) -> [usize; GRID_SIZE] {
+        match self {
+            Self::Row(r) => [r * GRID_SIZE, r * GRID_SIZE + 1, r * GRID_SIZE + 2],
+            Self::Column(c) => [c, GRID_SIZE + c, 2 * GRID_SIZE + c],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GridDraft {
+    pub players: Vec<DraftPlayer>,
+    /// 1-based grid number; past the last grid once the draft is finished.
+    pub round: u32,
+    /// 1-based turn within the grid.
+    pub pick: u32,
+    /// The current grid in row-major order; taken cells are `None`.
+    cells: [Option<DraftCard>; GRID_CARDS],
+    log: PickLog,
+    rng: ChaCha8Rng,
+}
+
+impl GridDraft {
+    /// Shuffle `packs` together under `seed` and deal them into grids of nine
+    /// for `seats` players, dropping any remainder.
+    pub fn new(packs: Vec<BoosterPack>, seats: usize, seed: u64) -> Result<Self, DraftError> {
+        let mut cards: Vec<DraftCard> = packs.into_iter().flat_map(|p| p.cards).collect();
+        cards.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+        let grids = cards
+            .chunks_exact(GRID_CARDS)
+            .map(|g| BoosterPack { cards: g.to_vec() })
+            .collect();
+        Self::with_grids(grids, seats, seed)
+    }
+
+    fn with_grids(grids: Vec<BoosterPack>, seats: usize, seed: u64) -> Result<Self, DraftError> {
+        if seats == 0 || seats > 2 * GRID_SIZE {
+            return Err(DraftError::InvalidPick(format!(
+                "grid drafts take 1 to {} seats, not {seats}",
+                2 * GRID_SIZE
+            )));
+        }
+        if let Some(grid) = grids.iter().find(|g| g.cards.len() != GRID_CARDS) {
+            return Err(DraftError::InvalidPick(format!(
+                "a grid has {} cards, not {GRID_CARDS}",
+                grid.cards.len()
+            )));
+        }
+        let mut draft = Self {
+            players: vec![DraftPlayer::default(); seats],
+            round: 0,
+            pick: 1,
+            cells: Default::default(),
+            log: PickLog {
+                format: DraftFormat::Grid,
+                seats,
+                rounds: u32::try_from(grids.len()).unwrap_or(u32::MAX),
+                seed,
+                packs: grids,
+                picks: Vec::new(),
+                seen: Vec::new(),
+            },
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        draft.next_grid();
+        Ok(draft)
+    }
+
+    /// Rebuild a draft from its log by replaying every turn.
+    pub fn replay(log: &PickLog) -> Result<Self, DraftError> {
+        log.expect_format(DraftFormat::Grid)?;
+        let mut draft = Self::with_grids(log.packs.clone(), log.seats, log.seed)?;
+        for turn in log.turns() {
+            let seat = turn[0].seat;
+            if Some(seat) != draft.current_seat() {
+                return Err(DraftError::InvalidPick(format!(
+                    "seat {seat} takes a turn out of order"
+                )));
+            }
+            let cards: Vec<&DraftCard> = turn.iter().map(|p| &p.card).collect();
+            let line = GridLine::ALL
+                .into_iter()
+                .find(|&l| draft.line(l).iter().collect::<Vec<_>>() == cards)
+                .ok_or_else(|| {
+                    DraftError::InvalidPick(format!(
+                        "grid {} turn {} matches no row or column",
+                        turn[0].round, turn[0].pick
+                    ))
+                })?;
+            draft.take_line(line)?;
+        }
+        Ok(draft)
+    }
+
+    #[must_use]
+    pub fn seats(&self) -> usize {
+        self.players.len()
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.round > self.log.rounds
+    }
+
+    #[must_use]
+    pub fn log(&self) -> &PickLog {
+        &self.log
+    }
+
+    #[must_use]
+    pub fn into_log(self) -> PickLog {
+        self.log
+    }
+
+    #[must_use]
+    pub fn cells(&self) -> &[Option<DraftCard>; GRID_CARDS] {
+        &self.cells
+    }
+
+    /// The cards left in `line`, in row-major order.
+    #[must_use]
+    pub fn line(&self, line: GridLine) -> Vec<DraftCard> {
+        line.cells()
+            .into_iter()
+            .filter_map(|i| self.cells[i].clone())
+            .collect()
+    }
+
+    /// Lines with at least one card left.
+    #[must_use]
+    pub fn open_lines(&self) -> Vec<GridLine> {
+        GridLine::ALL
+            .into_iter()
+            .filter(|&l| l.cells().iter().any(|&i| self.cells[i].is_some()))
+            .collect()
+    }
+
+    /// The seat choosing next; the first chooser moves along each grid.
+    #[must_use]
+    pub fn current_seat(&self) -> Option<usize> {
+        if self.is_finished() {
+            return None;
+        }
+        let first = (self.round as usize - 1) % self.seats();
+        Some((first + self.pick as usize - 1) % self.seats())
+    }
+
+    /// The current seat takes what is left in `line`.
+    pub fn take_line(&mut self, line: GridLine) -> Result<Vec<DraftCard>, DraftError> {
+        let seat = self
+            .current_seat()
+            .ok_or_else(|| DraftError::InvalidPick("the draft is over".into()))?;
+        let cards: Vec<DraftCard> = line
+            .cells()
+            .into_iter()
+            .filter_map(|i| self.cells[i].take())
+            .collect();
+        if cards.is_empty() {
+            return Err(DraftError::InvalidPick(format!("{line:?} is empty")));
+        }
+        for card in &cards {
+            self.players[seat].pool.push(card.clone());
+            self.log.picks.push(Pick {
+                seat,
+                round: self.round,
+                pick: self.pick,
+                card: card.clone(),
+            });
+        }
+        self.pick += 1;
+        if self.pick as usize > self.seats() || self.cells.iter().all(Option::is_none) {
+            self.next_grid();
+        }
+        Ok(cards)
+    }
+
+    /// Let `strategy` choose a line for the current seat.
+    pub fn bot_pick(&mut self, strategy: &dyn PickStrategy) -> Result<Vec<DraftCard>, DraftError> {
+        let seat = self
+            .current_seat()
+            .ok_or_else(|| DraftError::InvalidPick("the draft is over".into()))?;
+        let lines = self.open_lines();
+        let groups: Vec<Vec<DraftCard>> = lines.iter().map(|&l| self.line(l)).collect();
+        let pack: Vec<DraftCard> = self.cells.iter().flatten().cloned().collect();
+        let player = &self.players[seat];
+        let ctx = PickContext {
+            pack: &pack,
+            pool: &player.pool,
+            passed: &player.passed,
+            round: self.round,
+            pick: self.pick,
+        };
+        let choice = strategy.pick_group(&groups, &ctx, &mut self.rng);
+        self.take_line(lines[choice.min(lines.len() - 1)])
+    }
+
+    /// Run the rest of the draft with `bots[seat]` choosing for each seat.
+    pub fn run(&mut self, bots: &[&dyn PickStrategy]) -> Result<(), DraftError> {
+        if bots.len() != self.seats() {
+            return Err(DraftError::InvalidPick(format!(
+                "{} bots for {} seats",
+                bots.len(),
+                self.seats()
+            )));
+        }
+        while let Some(seat) = self.current_seat() {
+            self.bot_pick(bots[seat])?;
+        }
+        Ok(())
+    }
+
+    /// Discard what is left and lay out the next grid, showing it to every
+    /// seat.
+    fn next_grid(&mut self) {
+        self.round += 1;
+        self.pick = 1;
+        self.cells = Default::default();
+        let Some(grid) = self.log.packs.get(self.round as usize - 1) else {
+            return;
+        };
+        for (cell, card) in self.cells.iter_mut().zip(&grid.cards) {
+            *cell = Some(card.clone());
+        }
+        for player in &mut self.players {
+            player.passed.extend(grid.cards.iter().cloned());
+        }
+    }
+}
@@ -0,0 +1,344 @@
+//! Draft logs from MTGO and Arena.
+//!
+//! Both clients record one drafter's view: every pack they saw and the card
+//! they took from it. [`ImportedDraft`] holds that view by card name;
+//! [`ImportedDraft::resolve`] turns it into a [`PickLog`] whose
+//! [`seen`](PickLog::seen) packs stand in for the packs the log never had.
+
+use crate::state::{DraftFormat, Pick, PickLog};
+use crate::{BoosterPack, DraftCard, DraftError};
+use elbrus_core::{ColorSet, OracleCard, Printing, Rarity, TypeLine};
+use elbrus_db::repo::CardRepository;
+use elbrus_deck::DeckResolver;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Marks the drafter's own name and picks in an MTGO log.
+const MTGO_ARROW: &str = "-->";
+
+/// One pick as the drafter saw it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedPick {
+    /// 1-based round number.
+    pub round: u32,
+    /// 1-based pick number within the round.
+    pub pick: u32,
+    /// The cards on offer, `taken` included.
+    pub pack: Vec<Arc<str>>,
+    pub taken: Arc<str>,
+}
+
+/// A draft log read from MTGO or Arena, with cards still known only by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportedDraft {
+    pub event: Option<String>,
+    /// Every player by seat; empty if the log doesn't list them.
+    pub players: Vec<String>,
+    /// The drafter's seat.
+    pub seat: usize,
+    /// Set codes by round. Logs with a single set code list it once.
+    pub sets: Vec<String>,
+    pub picks: Vec<ImportedPick>,
+}
+
+/// A pick block of an MTGO log, before its marked card is known.
+struct MtgoPick {
+    round: u32,
+    pick: u32,
+    pack: Vec<Arc<str>>,
+    taken: Option<Arc<str>>,
+}
+
+#[derive(Deserialize)]
+struct ArenaLog {
+    #[serde(
+        default,
+        alias = "eventName",
+        alias = "EventName",
+        alias = "event_name"
+    )]
+    event: Option<String>,
+    #[serde(default, alias = "expansion", alias = "Expansion", alias = "setCode")]
+    set: Option<String>,
+    #[serde(alias = "Picks")]
+    picks: Vec<ArenaPick>,
+}
+
+#[derive(Deserialize)]
+struct ArenaPick {
+    #[serde(alias = "packNumber", alias = "PackNumber")]
+    pack_number: u32,
+    #[serde(alias = "pickNumber", alias = "PickNumber")]
+    pick_number: u32,
+    #[serde(default, alias = "cardsInPack", alias = "CardsInPack")]
+    pack: Vec<String>,
+    #[serde(alias = "pickedCard", alias = "PickedCard")]
+    pick: String,
+}
+
+impl ImportedDraft {
+    /// Read either log format: JSON is taken for an Arena log, anything else
+    /// for MTGO.
+    pub fn parse(input: &str) -> Result<Self, DraftError> {
+        if input.trim_start().starts_with('{') {
+            Self::parse_arena(input)
+        } else {
+            Self::parse_mtgo(input)
+        }
+    }
+
+    /// Read an MTGO draft log: the `Event #` header, the `Players:` list with
+    /// the drafter marked `-->`, a `------ SET ------` line per round, and a
+    /// `Pack 1 pick 1:` block per pick listing the pack with the pick marked.
+    pub fn parse_mtgo(input: &str) -> Result<Self, DraftError> {
+        let mut draft = Self::default();
+        let mut in_players = false;
+        let mut current: Option<MtgoPick> = None;
+        for (number, line) in input.lines().enumerate() {
+            let trimmed = line.trim();
+            let lower = trimmed.to_lowercase();
+            if trimmed.is_empty() {
+                in_players = false;
+                draft.finish_mtgo_pick(current.take(), number)?;
+            } else if let Some(event) = trimmed.strip_prefix("Event #:") {
+                draft.event = Some(event.trim().to_string());
+            } else if lower == "players:" {
+                in_players = true;
+            } else if in_players {
+                if let Some(name) = trimmed.strip_prefix(MTGO_ARROW) {
+                    draft.seat = draft.players.len();
+                    draft.players.push(name.trim().to_string());
+                } else {
+                    draft.players.push(trimmed.to_string());
+                }
+            } else if let Some(set) = trimmed
+                .strip_prefix("------")
+                .and_then(|s| s.strip_suffix("------"))
+            {
+                draft.sets.push(set.trim().to_string());
+            } else if let Some(header) = lower
+                .strip_prefix("pack ")
+                .filter(|h| h.ends_with(':') && h.contains(" pick "))
+            {
+                draft.finish_mtgo_pick(current.take(), number)?;
+                let numbers =
+                    header
+                        .trim_end_matches(':')
+                        .split_once(" pick ")
+                        .and_then(|(round, pick)| {
+                            Some((round.trim().parse().ok()?, pick.trim().parse().ok()?))
+                        });
+                let Some((round, pick)) = numbers else {
+                    return Err(DraftError::InvalidDraftLog(format!(
+                        "line {}: expected `Pack N pick N:`, got `{trimmed}`",
+                        number + 1
+                    )));
+                };
+                current = Some(MtgoPick {
+                    round,
+                    pick,
+                    pack: Vec::new(),
+                    taken: None,
+                });
+            } else if let Some(MtgoPick { pack, taken, .. }) = &mut current {
+                let name: Arc<str> = match trimmed.strip_prefix(MTGO_ARROW) {
+                    Some(name) => {
+                        let name: Arc<str> = name.trim().into();
+                        *taken = Some(name.clone());
+                        name
+                    }
+                    None => trimmed.into(),
+                };
+                pack.push(name);
+            }
+        }
+        draft.finish_mtgo_pick(current, input.lines().count())?;
+        if draft.picks.is_empty() {
+            return Err(DraftError::InvalidDraftLog("no picks found".into()));
+        }
+        Ok(draft)
+    }
+
+    fn finish_mtgo_pick(&mut self, pick: Option<MtgoPick>, line: usize) -> Result<(), DraftError> {
+        let Some(MtgoPick {
+            round,
+            pick,
+            pack,
+            taken,
+        }) = pick
+        else {
+            return Ok(());
+        };
+        let Some(taken) = taken else {
+            return Err(DraftError::InvalidDraftLog(format!(
+                "pack {round} pick {pick} (before line {}) has no card marked `{MTGO_ARROW}`",
+                line + 1
+            )));
+        };
+        self.picks.push(ImportedPick {
+            round,
+            pick,
+            pack,
+            taken,
+        });
+        Ok(())
+    }
+
+    /// Read an Arena-style JSON draft log: an object with an optional event
+    /// name and set code and a `picks` array of pack number, pick number, the
+    /// names of the cards in the pack and the name of the card picked.
+    /// camelCase and PascalCase keys as Arena writes them are accepted, and
+    /// numbers counted from 0, as Arena counts them, are shifted to count
+    /// from 1.
+    pub fn parse_arena(input: &str) -> Result<Self, DraftError> {
+        let log: ArenaLog = serde_json::from_str(input)?;
+        if log.picks.is_empty() {
+            return Err(DraftError::InvalidDraftLog("no picks found".into()));
+        }
+        let pack_base = u32::from(log.picks.iter().any(|p| p.pack_number == 0));
+        let pick_base = u32::from(log.picks.iter().any(|p| p.pick_number == 0));
+        let picks = log
+            .picks
+            .into_iter()
+            .map(|p| {
+                let (round, pick) = (p.pack_number + pack_base, p.pick_number + pick_base);
+                let mut pack: Vec<Arc<str>> = p.pack.iter().map(|n| n.trim().into()).collect();
+                let taken: Arc<str> = p.pick.trim().into();
+                if pack.is_empty() {
+                    pack.push(taken.clone());
+                } else if !pack.contains(&taken) {
+                    return Err(DraftError::InvalidDraftLog(format!(
+                        "pack {round} pick {pick} takes {taken}, which isn't in the pack"
+                    )));
+                }
+                Ok(ImportedPick {
+                    round,
+                    pick,
+                    pack,
+                    taken,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            event: log.event,
+            players: Vec::new(),
+            seat: 0,
+            sets: log.set.into_iter().collect(),
+            picks,
+        })
+    }
+
+    /// The set code for `round`, if the log names one.
+    #[must_use]
+    pub fn set_for(&self, round: u32) -> Option<&str> {
+        match self.sets.as_slice() {
+            [] => None,
+            [set] => Some(set),
+            sets => round
+                .checked_sub(1)
+                .and_then(|r| sets.get(r as usize))
+                .map(String::as_str),
+        }
+    }
+
+    /// Resolve card names against the database and build the pick log,
+    /// returning the names that matched nothing. Cards take their rarity from
+    /// a printing in the round's set when the log names one. Unmatched cards
+    /// stay in the log by name, with a nil oracle id and no other details,
+    /// so a review still shows them.
+    pub async fn resolve<R>(&self, db: &R) -> Result<(PickLog, Vec<Arc<str>>), DraftError>
+    where
+        R: CardRepository + ?Sized,
+    {
+        let mut resolver = DeckResolver::new(db);
+        let mut oracles: HashMap<Uuid, Option<OracleCard>> = HashMap::new();
+        let mut printings: HashMap<String, HashMap<Uuid, Printing>> = HashMap::new();
+        let mut cards: HashMap<(Option<&str>, Arc<str>), DraftCard> = HashMap::new();
+        let mut unresolved: Vec<Arc<str>> = Vec::new();
+
+        let mut picks = Vec::with_capacity(self.picks.len());
+        let mut seen = Vec::with_capacity(self.picks.len());
+        for imported in &self.picks {
+            let set = self.set_for(imported.round);
+            if let Some(set) = set
+                && !printings.contains_key(set)
+            {
+                let mut by_oracle = HashMap::new();
+                for printing in db.cards_in_set(set).await? {
+                    if printing.lang.as_ref() == "en" {
+                        by_oracle.entry(printing.oracle_id).or_insert(printing);
+                    }
+                }
+                printings.insert(set.to_string(), by_oracle);
+            }
+
+            let mut pack = Vec::with_capacity(imported.pack.len());
+            for name in &imported.pack {
+                let key = (set, name.clone());
+                if !cards.contains_key(&key) {
+                    let oracle = match resolver.lookup(name).await? {
+                        Some(id) => {
+                            if let Entry::Vacant(slot) = oracles.entry(id) {
+                                slot.insert(db.get_oracle(id).await?);
+                            }
+                            oracles[&id].as_ref()
+                        }
+                        None => None,
+                    };
+                    let card = match oracle {
+                        Some(oracle) => match set
+                            .and_then(|s| printings.get(s))
+                            .and_then(|p| p.get(&oracle.oracle_id))
+                        {
+                            Some(printing) => DraftCard::from_printing(printing, oracle),
+                            None => DraftCard::from_oracle(oracle, Rarity::Common),
+                        },
+                        None => {
+                            if !unresolved.contains(name) {
+                                unresolved.push(name.clone());
+                            }
+                            unknown_card(name)
+                        }
+                    };
+                    cards.insert(key.clone(), card);
+                }
+                pack.push(cards[&key].clone());
+            }
+            picks.push(Pick {
+                seat: self.seat,
+                round: imported.round,
+                pick: imported.pick,
+                card: cards[&(set, imported.taken.clone())].clone(),
+            });
+            seen.push(BoosterPack { cards: pack });
+        }
+
+        let log = PickLog {
+            format: DraftFormat::Booster,
+            seats: self.players.len().max(self.seat + 1),
+            rounds: self.picks.iter().map(|p| p.round).max().unwrap_or(0),
+            seed: 0,
+            packs: Vec::new(),
+            picks,
+            seen,
+        };
+        Ok((log, unresolved))
+    }
+}
+
+/// A placeholder for a card the database doesn't know.
+fn unknown_card(name: &Arc<str>) -> DraftCard {
+    DraftCard {
+        oracle_id: Uuid::nil(),
+        printing_id: None,
+        name: name.clone(),
+        rarity: Rarity::Common,
+        colors: ColorSet::empty(),
+        mana_value: 0.0,
+        type_line: TypeLine::default(),
+        foil: false,
+    }
+}
@@ -1 +1,131 @@
 // Draft state and picking strategies
+use elbrus_core::{CardFace, ColorSet, OracleCard, Printing, Rarity, Supertype, TypeLine};
+use elbrus_db::backend::DbError;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub mod booster;
+pub mod cube;
+pub mod grid;
+pub mod import;
+pub mod rating;
+pub mod review;
+pub mod rochester;
+pub mod sealed;
+pub mod seventeen_lands;
+pub mod state;
+pub mod strategy;
+pub mod winston;
+
+pub use booster::{
+    BoosterConfig, BoosterKind, BoosterTemplate, DEFAULT_BOOSTERS, SetSheets, Sheet, Slot,
+    SlotOption,
+};
+pub use cube::{ColorCategory, Cube, CubeEntry, CubeStats, cube_packs};
+pub use grid::{GRID_CARDS, GRID_SIZE, GridDraft, GridLine};
+pub use import::{ImportedDraft, ImportedPick};
+pub use rating::{CardRating, CsvRatings, HeuristicRating, RatedCard, rank_pool};
+pub use review::{PickReview, review_picks};
+pub use rochester::RochesterDraft;
+pub use sealed::{SEALED_BOOSTERS, SPLASH_SOURCES, SealedBuild, SealedBuilder, SealedDeck, sealed};
+pub use seventeen_lands::{
+    CardStats, Dataset, LimitedRatings, MIN_GAMES_IN_HAND, card_stats, load_card_stats,
+};
+pub use state::{Direction, DraftConfig, DraftFormat, DraftPlayer, DraftState, Pick, PickLog};
+pub use strategy::{
+    ColorCommitPicker, PickContext, PickStrategy, RandomPicker, RarityPicker, RatingPicker,
+};
+pub use winston::{WINSTON_PILES, WINSTON_SEATS, WinstonDraft};
+
+#[derive(Error, Debug)]
+pub enum DraftError {
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("Database error: {0}")]
+    Db(#[from] DbError),
+    #[error("Deck error: {0}")]
+    Deck(#[from] elbrus_deck::DeckError),
+    #[error("Unknown booster template `{0}`")]
+    UnknownTemplate(String),
+    #[error("{set} has no {kind} boosters")]
+    NoBooster { set: String, kind: BoosterKind },
+    #[error("No cards found for set `{0}`")]
+    EmptySet(String),
+    #[error("Expected {expected} packs, got {found}")]
+    PackCount { expected: usize, found: usize },
+    #[error("Cube needs {needed} cards, has {found}")]
+    CubeTooSmall { needed: usize, found: usize },
+    #[error("Packs need at least one card")]
+    EmptyPacks,
+    #[error("Expected a {expected} draft log, got {found}")]
+    WrongFormat {
+        expected: DraftFormat,
+        found: DraftFormat,
+    },
+    #[error("Invalid 17lands dataset: {0}")]
+    InvalidDataset(String),
+    #[error("Invalid ratings: {0}")]
+    InvalidRatings(String),
+    #[error("Invalid pick: {0}")]
+    InvalidPick(String),
+    #[error("Invalid draft log: {0}")]
+    InvalidDraftLog(String),
+}
+
+/// A card as it appears in a draft: enough of the oracle card and printing
+/// for picking and deckbuilding without going back to the database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftCard {
+    pub oracle_id: Uuid,
+    /// `None` for cards known only by oracle id, such as cube entries.
+    pub printing_id: Option<Uuid>,
+    pub name: Arc<str>,
+    pub rarity: Rarity,
+    pub colors: ColorSet,
+    pub mana_value: f32,
+    pub type_line: TypeLine,
+    #[serde(default)]
+    pub foil: bool,
+}
+
+impl DraftCard {
+    #[must_use]
+    pub fn from_oracle(card: &OracleCard, rarity: Rarity) -> Self {
+        Self {
+            oracle_id: card.oracle_id,
+            printing_id: None,
+            name: card.full_name().into(),
+            rarity,
+            colors: card
+                .faces
+                .iter()
+                .fold(ColorSet::empty(), |set, f: &CardFace| set | f.colors),
+            mana_value: card.cmc(),
+            type_line: card.primary_face().type_line.clone(),
+            foil: false,
+        }
+    }
+
+    #[must_use]
+    pub fn from_printing(printing: &Printing, card: &OracleCard) -> Self {
+        Self {
+            printing_id: Some(printing.id),
+            ..Self::from_oracle(card, printing.rarity)
+        }
+    }
+
+    #[must_use]
+    pub fn is_basic_land(&self) -> bool {
+        self.type_line.is_land() && self.type_line.supertypes.contains(&Supertype::Basic)
+    }
+}
+
+/// One opened booster, in slot order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoosterPack {
+    pub cards: Vec<DraftCard>,
+}
@@ -0,0 +1,188 @@
+//! Card ratings for deck building and pick review.
+//!
+//! Ratings use the 0 to 5 scale of limited set reviews, where 5 is an A+ bomb
+//! and 0 an unplayable F. A source may not know every card; callers fall back
+//! to [`HeuristicRating`] for those.
+
+use crate::{DraftCard, DraftError};
+use elbrus_core::{CardType, Rarity};
+use std::collections::HashMap;
+
+/// A source of card ratings.
+pub trait CardRating: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// The card's rating from 0 to 5, or `None` if the source doesn't rate it.
+    fn rate(&self, card: &DraftCard) -> Option<f64>;
+
+    /// [`rate`](Self::rate), falling back to [`HeuristicRating`].
+    fn rate_or_guess(&self, card: &DraftCard) -> f64 {
+        self.rate(card)
+            .unwrap_or_else(|| HeuristicRating::grade(card))
+    }
+}
+
+/// A guess from rarity, card type and mana value, for when no ratings are to
+/// hand: rarer cards and creatures rate higher, expensive and multicolored
+/// cards lower, and basic lands not at all.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicRating;
+
+impl HeuristicRating {
+    #[must_use]
+    pub fn grade(card: &DraftCard) -> f64 {
+        if card.is_basic_land() {
+            return 0.0;
+        }
+        let mut grade = match card.rarity {
+            Rarity::Common => 2.0,
+            Rarity::Uncommon => 2.5,
+            Rarity::Rare => 3.0,
+            Rarity::Mythic => 3.5,
+            _ => 2.5,
+        };
+        let types = &card.type_line.card_types;
+        if types.contains(&CardType::Land) {
+            return grade - 1.0;
+        }
+        if types.contains(&CardType::Creature) {
+            grade += 0.5;
+        }
+        if card.mana_value > 5.0 {
+            grade -= 0.5 * f64::from(card.mana_value - 5.0);
+        }
+        let colors = card.colors.bits().count_ones();
+        if colors > 1 {
+            grade -= 0.25 * f64::from(colors - 1);
+        }
+        grade.clamp(0.0, 5.0)
+    }
+}
+
+impl CardRating for HeuristicRating {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn rate(&self, card: &DraftCard) -> Option<f64> {
+        Some(Self::grade(card))
+    }
+}
+
+/// Ratings read from a CSV file with `Name` and `Rating` columns. Ratings are
+/// numbers on the 0 to 5 scale or letter grades (`A+` to `F`).
+#[derive(Debug, Clone, Default)]
+pub struct CsvRatings {
+    /// Keyed by lowercased card name.
+    ratings: HashMap<String, f64>,
+}
+
+impl CsvRatings {
+    pub fn parse(input: &str) -> Result<Self, DraftError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(input.as_bytes());
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| {
+            headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+                .ok_or_else(|| DraftError::InvalidRatings(format!("no `{name}` column")))
+        };
+        let (name, rating) = (column("name")?, column("rating")?);
+
+        let mut ratings = HashMap::new();
+        for (line, record) in reader.records().enumerate() {
+            let record = record?;
+            let (Some(card), Some(value)) = (record.get(name), record.get(rating)) else {
+                continue;
+            };
+            if card.trim().is_empty() || value.trim().is_empty() {
+                continue;
+            }
+            let value = parse_rating(value).ok_or_else(|| {
+                DraftError::InvalidRatings(format!("bad rating `{value}` on row {}", line + 1))
+            })?;
+            ratings.insert(card.trim().to_lowercase(), value);
+        }
+        Ok(Self { ratings })
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ratings.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ratings.is_empty()
+    }
+}
+
+impl CardRating for CsvRatings {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    /// Looks up the full name, then the front face of split and double-faced
+    /// cards.
+    fn rate(&self, card: &DraftCard) -> Option<f64> {
+        rate_by_name(&self.ratings, card)
+    }
+}
+
+/// Rating keyed by lowercased card name: the full name, then the front face
+/// of split and double-faced cards.
+pub(crate) fn rate_by_name(ratings: &HashMap<String, f64>, card: &DraftCard) -> Option<f64> {
+    let name = card.name.to_lowercase();
+    ratings.get(&name).copied().or_else(|| {
+        let (front, _) = name.split_once(" // ")?;
+        ratings.get(front).copied()
+    })
+}
+
+/// A number, or a letter grade with an optional `+` or `-`.
+fn parse_rating(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<f64>() {
+        return (0.0..=5.0).contains(&n).then_some(n);
+    }
+    let mut chars = value.chars();
+    let base = match chars.next()?.to_ascii_uppercase() {
+        'A' => 4.5,
+        'B' => 3.0,
+        'C' => 1.5,
+        'D' => 0.5,
+        'F' => return (value.len() == 1).then_some(0.0),
+        _ => return None,
+    };
+    let step = if base < 1.0 { 0.25 } else { 0.5 };
+    match chars.as_str() {
+        "" => Some(base),
+        "+" => Some(base + step),
+        "-" => Some(base - step),
+        _ => None,
+    }
+}
+
+/// A card with its rating.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatedCard {
+    pub card: DraftCard,
+    pub rating: f64,
+}
+
+/// Rate every card in `pool`, best first. Cards `ratings` doesn't know get
+/// the heuristic rating.
+#[must_use]
+pub fn rank_pool(pool: &[DraftCard], ratings: &dyn CardRating) -> Vec<RatedCard> {
+    let mut ranked: Vec<RatedCard> = pool
+        .iter()
+        .map(|card| RatedCard {
+            card: card.clone(),
+            rating: ratings.rate_or_guess(card),
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+    ranked
+}
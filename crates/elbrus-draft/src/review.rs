@@ -0,0 +1,110 @@
+//! Pick-by-pick review of a drafter's picks against a rating source.
+
+use crate::rating::{CardRating, RatedCard, rank_pool};
+use crate::state::{DraftConfig, DraftFormat, DraftState, Pick, PickLog};
+use crate::{DraftCard, DraftError};
+
+/// One pick with the pack it was made from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickReview {
+    /// 1-based round number.
+    pub round: u32,
+    /// 1-based pick number within the round.
+    pub pick: u32,
+    pub taken: RatedCard,
+    /// The rest of the pack, best first.
+    pub passed: Vec<RatedCard>,
+}
+
+impl PickReview {
+    /// The best-rated card passed, if any.
+    #[must_use]
+    pub fn best_alternative(&self) -> Option<&RatedCard> {
+        self.passed.first()
+    }
+
+    /// The pick's 1-based place in the pack by rating; ties go to the pick.
+    #[must_use]
+    pub fn rank(&self) -> usize {
+        1 + self
+            .passed
+            .iter()
+            .filter(|c| c.rating > self.taken.rating)
+            .count()
+    }
+
+    /// How far the best card passed is rated above the pick; 0 when the pick
+    /// rated highest.
+    #[must_use]
+    pub fn rating_lost(&self) -> f64 {
+        self.best_alternative()
+            .map_or(0.0, |best| (best.rating - self.taken.rating).max(0.0))
+    }
+}
+
+/// Review every pick `seat` made. The packs on offer come from the log's
+/// [`seen`](PickLog::seen) packs for imported logs, or by replaying a booster
+/// draft; other formats have no single pack to pick from.
+pub fn review_picks(
+    log: &PickLog,
+    seat: usize,
+    ratings: &dyn CardRating,
+) -> Result<Vec<PickReview>, DraftError> {
+    if !log.seen.is_empty() {
+        if log.seen.len() != log.picks.len() {
+            return Err(DraftError::InvalidPick(format!(
+                "{} packs seen for {} picks",
+                log.seen.len(),
+                log.picks.len()
+            )));
+        }
+        return log
+            .picks
+            .iter()
+            .zip(&log.seen)
+            .filter(|(pick, _)| pick.seat == seat)
+            .map(|(pick, pack)| review(pick, &pack.cards, ratings))
+            .collect();
+    }
+
+    log.expect_format(DraftFormat::Booster)?;
+    let config = DraftConfig {
+        seats: log.seats,
+        rounds: log.rounds,
+    };
+    let mut state = DraftState::new(config, log.packs.clone(), log.seed)?;
+    let mut reviews = Vec::new();
+    for pick in &log.picks {
+        if pick.seat == seat
+            && let Some(pack) = state.current_pack(seat)
+        {
+            reviews.push(review(pick, &pack.cards, ratings)?);
+        }
+        state.replay_pick(pick)?;
+    }
+    Ok(reviews)
+}
+
+fn review(
+    pick: &Pick,
+    pack: &[DraftCard],
+    ratings: &dyn CardRating,
+) -> Result<PickReview, DraftError> {
+    let index = pack.iter().position(|c| *c == pick.card).ok_or_else(|| {
+        DraftError::InvalidPick(format!(
+            "{} is not in the pack for round {} pick {}",
+            pick.card.name, pick.round, pick.pick
+        ))
+    })?;
+    let mut passed = pack.to_vec();
+    let taken = passed.remove(index);
+    Ok(PickReview {
+        round: pick.round,
+        pick: pick.pick,
+        taken: RatedCard {
+            rating: ratings.rate_or_guess(&taken),
+            card: taken,
+        },
+        passed: rank_pool(&passed, ratings),
+    })
+}
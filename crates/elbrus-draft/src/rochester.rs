@@ -0,0 +1,244 @@
+//! Rochester draft: packs opened face up one at a time, picked in snake order.
+//!
+//! Each round every seat opens one pack in turn. The opener picks first, then
+//! picks go around the table in the round's direction, and the last seat
+//! picks twice as the order snakes back. Everyone sees every pack, so each
+//! seat's `passed` cards hold everything opened so far.
+
+use crate::state::{Direction, DraftConfig, DraftFormat, DraftPlayer, Pick, PickLog};
+use crate::strategy::{PickContext, PickStrategy};
+use crate::{BoosterPack, DraftCard, DraftError};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+#[derive(Debug, Clone)]
+pub struct RochesterDraft {
+    pub players: Vec<DraftPlayer>,
+    /// 1-based; past the last round once the draft is finished.
+    pub round: u32,
+    /// 1-based pick number within the round, counting every seat's picks.
+    pub pick: u32,
+    pub direction: Direction,
+    /// Index into the round of the open pack; its opener is the seat with the
+    /// same number.
+    opener: usize,
+    /// Picks made from the open pack.
+    taken: usize,
+    log: PickLog,
+    rng: ChaCha8Rng,
+}
+
+impl RochesterDraft {
+    /// `packs` holds `config.packs_needed()` boosters, round by round and seat
+    /// by seat, like [`DraftState::new`](crate::DraftState::new).
+    pub fn new(
+        config: DraftConfig,
+        packs: Vec<BoosterPack>,
+        seed: u64,
+    ) -> Result<Self, DraftError> {
+        if config.seats == 0 {
+            return Err(DraftError::InvalidPick("no seats".into()));
+        }
+        if packs.len() != config.packs_needed() {
+            return Err(DraftError::PackCount {
+                expected: config.packs_needed(),
+                found: packs.len(),
+            });
+        }
+        let mut draft = Self {
+            players: vec![DraftPlayer::default(); config.seats],
+            round: 1,
+            pick: 1,
+            direction: Direction::for_round(1),
+            opener: 0,
+            taken: 0,
+            log: PickLog {
+                format: DraftFormat::Rochester,
+                seats: config.seats,
+                rounds: config.rounds,
+                seed,
+                packs,
+                picks: Vec::new(),
+                seen: Vec::new(),
+            },
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        draft.open_pack();
+        Ok(draft)
+    }
+
+    /// Rebuild a draft from its log by replaying every pick.
+    pub fn replay(log: &PickLog) -> Result<Self, DraftError> {
+        log.expect_format(DraftFormat::Rochester)?;
+        let config = DraftConfig {
+            seats: log.seats,
+            rounds: log.rounds,
+        };
+        let mut draft = Self::new(config, log.packs.clone(), log.seed)?;
+        for pick in &log.picks {
+            if Some(pick.seat) != draft.current_seat() {
+                return Err(DraftError::InvalidPick(format!(
+                    "seat {} picks out of turn",
+                    pick.seat
+                )));
+            }
+            let index = draft
+                .open_pack_cards()
+                .iter()
+                .position(|c| *c == pick.card)
+                .ok_or_else(|| {
+                    DraftError::InvalidPick(format!("{} is not in the open pack", pick.card.name))
+                })?;
+            draft.make_pick(index)?;
+        }
+        Ok(draft)
+    }
+
+    #[must_use]
+    pub fn seats(&self) -> usize {
+        self.players.len()
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.round > self.log.rounds
+    }
+
+    #[must_use]
+    pub fn log(&self) -> &PickLog {
+        &self.log
+    }
+
+    #[must_use]
+    pub fn into_log(self) -> PickLog {
+        self.log
+    }
+
+    /// The cards left in the pack being picked from.
+    #[must_use]
+    pub fn open_pack_cards(&self) -> &[DraftCard] {
+        self.players
+            .get(self.opener)
+            .and_then(|p| p.current_pack.as_ref())
+            .map_or(&[], |p| &p.cards)
+    }
+
+    /// The seat to pick next: `taken` steps along the snake from the opener.
+    #[must_use]
+    pub fn current_seat(&self) -> Option<usize> {
+        if self.is_finished() {
+            return None;
+        }
+        let seats = self.seats();
+        let lap = self.taken / seats;
+        let step = self.taken % seats;
+        let offset = if lap.is_multiple_of(2) {
+            step
+        } else {
+            seats - 1 - step
+        };
+        Some(match self.direction {
+            Direction::Left => (self.opener + offset) % seats,
+            Direction::Right => (self.opener + seats - offset) % seats,
+        })
+    }
+
+    /// The current seat takes the card at `index` of the open pack.
+    pub fn make_pick(&mut self, index: usize) -> Result<&DraftCard, DraftError> {
+        let seat = self
+            .current_seat()
+            .ok_or_else(|| DraftError::InvalidPick("the draft is over".into()))?;
+        let pack = self.players[self.opener]
+            .current_pack
+            .as_mut()
+            .expect("an unfinished draft has an open pack");
+        if index >= pack.cards.len() {
+            return Err(DraftError::InvalidPick(format!(
+                "pick {index} is past the end of a {}-card pack",
+                pack.cards.len()
+            )));
+        }
+        let card = pack.cards.remove(index);
+        self.players[seat].pool.push(card.clone());
+        self.log.picks.push(Pick {
+            seat,
+            round: self.round,
+            pick: self.pick,
+            card,
+        });
+        self.taken += 1;
+        self.pick += 1;
+        if self.open_pack_cards().is_empty() {
+            self.players[self.opener].current_pack = None;
+            self.next_opener();
+            self.open_pack();
+        }
+        Ok(self.players[seat]
+            .pool
+            .last()
+            .expect("the pick was just added"))
+    }
+
+    /// Let `strategy` pick for the current seat.
+    pub fn bot_pick(&mut self, strategy: &dyn PickStrategy) -> Result<&DraftCard, DraftError> {
+        let seat = self
+            .current_seat()
+            .ok_or_else(|| DraftError::InvalidPick("the draft is over".into()))?;
+        let player = &self.players[seat];
+        let pack = self.players[self.opener]
+            .current_pack
+            .as_ref()
+            .map_or(&[][..], |p| &p.cards);
+        let ctx = PickContext {
+            pack,
+            pool: &player.pool,
+            passed: &player.passed,
+            round: self.round,
+            pick: self.pick,
+        };
+        let index = strategy.pick(&ctx, &mut self.rng);
+        self.make_pick(index)
+    }
+
+    /// Run the rest of the draft with `bots[seat]` picking for each seat.
+    pub fn run(&mut self, bots: &[&dyn PickStrategy]) -> Result<(), DraftError> {
+        if bots.len() != self.seats() {
+            return Err(DraftError::InvalidPick(format!(
+                "{} bots for {} seats",
+                bots.len(),
+                self.seats()
+            )));
+        }
+        while let Some(seat) = self.current_seat() {
+            self.bot_pick(bots[seat])?;
+        }
+        Ok(())
+    }
+
+    /// Open the next non-empty pack, showing it to everyone.
+    fn open_pack(&mut self) {
+        while !self.is_finished() {
+            let index = (self.round as usize - 1) * self.seats() + self.opener;
+            let pack = self.log.packs[index].clone();
+            if !pack.cards.is_empty() {
+                for player in &mut self.players {
+                    player.passed.extend(pack.cards.iter().cloned());
+                }
+                self.players[self.opener].current_pack = Some(pack);
+                return;
+            }
+            self.next_opener();
+        }
+    }
+
+    fn next_opener(&mut self) {
+        self.opener += 1;
+        self.taken = 0;
+        if self.opener == self.seats() {
+            self.opener = 0;
+            self.round += 1;
+            self.pick = 1;
+            self.direction = Direction::for_round(self.round);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! Sealed: every player builds a deck from their own unopened boosters.
+
+use crate::rating::{CardRating, RatedCard, rank_pool};
+use crate::state::{DraftConfig, DraftFormat, Pick, PickLog};
+use crate::{BoosterPack, DraftCard, DraftError};
+use elbrus_analysis::{ManaBaseAnalysis, pip_groups};
+use elbrus_core::{Color, ColorSet, OracleCard, types::Supertype};
+use elbrus_deck::{Deck, DeckEntry};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Boosters per player in a standard sealed event.
+pub const SEALED_BOOSTERS: u32 = 6;
+
+impl DraftConfig {
+    /// `seats` players with [`SEALED_BOOSTERS`] boosters each.
+    #[must_use]
+    pub fn sealed(seats: usize) -> Self {
+        Self {
+            seats,
+            rounds: SEALED_BOOSTERS,
+        }
+    }
+}
+
+/// Hand out sealed pools: `packs` holds `config.packs_needed()` boosters, seat
+/// by seat, and each seat's boosters are logged as picks with the booster as
+/// the round and the card's place in it as the pick number.
+pub fn sealed(
+    config: DraftConfig,
+    packs: Vec<BoosterPack>,
+    seed: u64,
+) -> Result<PickLog, DraftError> {
+    if packs.len() != config.packs_needed() {
+        return Err(DraftError::PackCount {
+            expected: config.packs_needed(),
+            found: packs.len(),
+        });
+    }
+    let per_seat = config.rounds as usize;
+    let mut picks = Vec::new();
+    for (i, pack) in packs.iter().enumerate() {
+        for (n, card) in pack.cards.iter().enumerate() {
+            picks.push(Pick {
+                seat: i / per_seat,
+                round: (i % per_seat) as u32 + 1,
+                pick: n as u32 + 1,
+                card: card.clone(),
+            });
+        }
+    }
+    Ok(PickLog {
+        format: DraftFormat::Sealed,
+        seats: config.seats,
+        rounds: config.rounds,
+        seed,
+        packs,
+        picks,
+        seen: Vec::new(),
+    })
+}
+
+/// Spells a sealed deck sets aside for a splash color.
+pub const SPLASH_SOURCES: u32 = 3;
+
+/// Builds a deck from a sealed pool: picks the best two colors, perhaps with a
+/// light splash, takes the best-rated spells and nonbasic lands in them, and
+/// fills out the deck with basic lands split by the spells' color
+/// requirements.
+#[derive(Debug, Clone, Copy)]
+pub struct SealedBuilder {
+    pub deck_size: u32,
+    /// Lands played when the pool has enough spells; a pool short of them
+    /// plays extra basics to reach `deck_size`.
+    pub lands: u32,
+    /// Most cards of a third color to splash.
+    pub max_splash: usize,
+    /// Rating a splashed card must beat the card it replaces by.
+    pub splash_penalty: f64,
+}
+
+impl Default for SealedBuilder {
+    fn default() -> Self {
+        Self {
+            deck_size: 40,
+            lands: 17,
+            max_splash: 3,
+            splash_penalty: 0.5,
+        }
+    }
+}
+
+/// One way of building the pool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealedBuild {
+    /// The two main colors.
+    pub colors: ColorSet,
+    pub splash: Option<Color>,
+    /// Total rating of the spells, less the splash penalty per splashed card.
+    pub score: f64,
+    /// The spells played, best first.
+    pub spells: Vec<RatedCard>,
+    /// Nonbasic lands in the build's colors, played in place of basics.
+    pub lands: Vec<RatedCard>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealedDeck {
+    pub build: SealedBuild,
+    pub deck: Deck,
+    pub mana: ManaBaseAnalysis,
+}
+
+impl SealedBuilder {
+    /// Spells the deck plays alongside its lands.
+    #[must_use]
+    pub fn spell_count(&self) -> usize {
+        self.deck_size.saturating_sub(self.lands) as usize
+    }
+
+    /// Every two-color build of `pool` and its splashes, best first. `cards`
+    /// holds the pool's oracle cards by id; cards missing from it are never
+    /// splashed, as their pips are unknown.
+    #[must_use]
+    pub fn builds(
+        &self,
+        pool: &[DraftCard],
+        ratings: &dyn CardRating,
+        cards: &HashMap<Uuid, OracleCard>,
+    ) -> Vec<SealedBuild> {
+        let (lands, ranked): (Vec<RatedCard>, Vec<RatedCard>) = rank_pool(pool, ratings)
+            .into_iter()
+            .partition(|r| r.card.type_line.is_land());
+        let mut builds = Vec::new();
+        for (i, &first) in Color::ALL.iter().enumerate() {
+            for &second in &Color::ALL[i + 1..] {
+                let colors = ColorSet::from(first) | ColorSet::from(second);
+                let spells: Vec<RatedCard> = ranked
+                    .iter()
+                    .filter(|r| colors.contains(r.card.colors))
+                    .take(self.spell_count())
+                    .cloned()
+                    .collect();
+                let base = SealedBuild {
+                    colors,
+                    splash: None,
+                    score: spells.iter().map(|r| r.rating).sum(),
+                    spells,
+                    lands: Vec::new(),
+                };
+                for splash in Color::ALL {
+                    if !colors.contains(splash.into()) {
+                        builds.extend(self.splash(&base, splash, &ranked, cards));
+                    }
+                }
+                builds.push(base);
+            }
+        }
+        for build in &mut builds {
+            build.lands = self.nonbasic_lands(build, &lands, cards);
+        }
+        builds.sort_by(|a, b| b.score.total_cmp(&a.score));
+        builds
+    }
+
+    /// The best build of `pool` as a deck, or `None` if the pool has no
+    /// spells.
+    #[must_use]
+    pub fn build(
+        &self,
+        pool: &[DraftCard],
+        ratings: &dyn CardRating,
+        cards: &HashMap<Uuid, OracleCard>,
+    ) -> Option<SealedDeck> {
+        let build = self.builds(pool, ratings, cards).into_iter().next()?;
+        if build.spells.is_empty() {
+            return None;
+        }
+
+        let mut deck = Deck {
+            name: Some(format!("Sealed {}", build_name(&build)).into()),
+            ..Deck::default()
+        };
+        deck.mainboard = entries(build.spells.iter().map(|r| &r.card));
+        let spells = ManaBaseAnalysis::analyze(&deck, cards);
+        deck.mainboard
+            .extend(entries(build.lands.iter().map(|r| &r.card)));
+        for (color, count) in self.basics(&build, &spells, cards) {
+            let name = color.land();
+            let mut entry = DeckEntry::new(count, &name);
+            entry.resolved = cards
+                .values()
+                .find(|c| c.faces.len() == 1 && *c.faces[0].name == *name)
+                .map(|c| c.oracle_id);
+            deck.mainboard.push(entry);
+        }
+
+        let mut main: Vec<&DraftCard> = build
+            .spells
+            .iter()
+            .chain(&build.lands)
+            .map(|r| &r.card)
+            .collect();
+        let sideboard = pool.iter().filter(|card| {
+            match main.iter().position(|c| c.oracle_id == card.oracle_id) {
+                Some(i) => {
+                    main.swap_remove(i);
+                    false
+                }
+                None => true,
+            }
+        });
+        deck.sideboard = entries(sideboard);
+
+        let mana = ManaBaseAnalysis::analyze(&deck, cards);
+        Some(SealedDeck { build, deck, mana })
+    }
+
+    /// Swap the weakest spells of `base` for up to `max_splash` cards of
+    /// `splash` needing one pip of it, where each beats the card it replaces
+    /// by the splash penalty. `None` if nothing is worth splashing.
+    fn splash(
+        &self,
+        base: &SealedBuild,
+        splash: Color,
+        ranked: &[RatedCard],
+        cards: &HashMap<Uuid, OracleCard>,
+    ) -> Option<SealedBuild> {
+        let colors = base.colors | splash.into();
+        let candidates = ranked.iter().filter(|r| {
+            colors.contains(r.card.colors)
+                && r.card.colors.contains(splash.into())
+                && splash_pips(&r.card, base.colors, cards) == Some(1)
+        });
+        let mut spells = base.spells.clone();
+        let mut splashed = 0;
+        for candidate in candidates.take(self.max_splash) {
+            let bar = candidate.rating - self.splash_penalty;
+            if spells.len() < self.spell_count() {
+                spells.push(candidate.clone());
+            } else if spells.last().is_some_and(|weakest| weakest.rating < bar) {
+                spells.pop();
+                spells.push(candidate.clone());
+            } else {
+                break;
+            }
+            spells.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+            splashed += 1;
+        }
+        (splashed > 0).then(|| SealedBuild {
+            colors: base.colors,
+            splash: Some(splash),
+            score: spells.iter().map(|r| r.rating).sum::<f64>()
+                - self.splash_penalty * f64::from(splashed),
+            spells,
+            lands: Vec::new(),
+        })
+    }
+
+    /// Up to [`lands`](Self::lands) nonbasic lands from `lands` whose color
+    /// identity lies within the build's colors and splash, best first. Lands
+    /// with no color identity, or missing from `cards`, aren't played.
+    fn nonbasic_lands(
+        &self,
+        build: &SealedBuild,
+        lands: &[RatedCard],
+        cards: &HashMap<Uuid, OracleCard>,
+    ) -> Vec<RatedCard> {
+        let colors = build.colors | build.splash.map_or(ColorSet::empty(), ColorSet::from);
+        lands
+            .iter()
+            .filter(|r| {
+                !r.card.type_line.supertypes.contains(&Supertype::Basic)
+                    && cards.get(&r.card.oracle_id).is_some_and(|c| {
+                        !c.color_identity.is_empty() && colors.contains(c.color_identity)
+                    })
+            })
+            .take(self.lands as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Basic lands by color, as many as the spells and nonbasic lands leave
+    /// room for: enough to make [`SPLASH_SOURCES`] for a splash with the
+    /// nonbasics, and the rest split between the main colors by their pips.
+    fn basics(
+        &self,
+        build: &SealedBuild,
+        spells: &ManaBaseAnalysis,
+        cards: &HashMap<Uuid, OracleCard>,
+    ) -> Vec<(Color, u32)> {
+        let played = build.spells.len() + build.lands.len();
+        let mut lands = (self.deck_size as usize).saturating_sub(played) as u32;
+        let mut basics = Vec::new();
+        if let Some(splash) = build.splash {
+            let nonbasics = build
+                .lands
+                .iter()
+                .filter_map(|r| cards.get(&r.card.oracle_id))
+                .filter(|c| c.color_identity.contains(splash.into()))
+                .count() as u32;
+            let count = SPLASH_SOURCES.saturating_sub(nonbasics).min(lands);
+            basics.push((splash, count));
+            lands -= count;
+        }
+        let main: Vec<Color> = Color::ALL
+            .into_iter()
+            .filter(|&c| build.colors.contains(c.into()))
+            .collect();
+        let pips: Vec<f64> = main
+            .iter()
+            .map(|c| spells.color_requirements.get(c).copied().unwrap_or(0.0))
+            .collect();
+        let total: f64 = pips.iter().sum();
+        let mut counts: Vec<u32> = pips
+            .iter()
+            .map(|&p| {
+                let share = if total > 0.0 {
+                    p / total
+                } else {
+                    1.0 / main.len() as f64
+                };
+                (share * f64::from(lands)).round() as u32
+            })
+            .collect();
+        // Rounding can miss by one either way; settle it on the first color.
+        let assigned: u32 = counts.iter().sum();
+        counts[0] = (counts[0] + lands).saturating_sub(assigned);
+        basics.splice(0..0, main.into_iter().zip(counts));
+        basics.retain(|&(_, count)| count > 0);
+        basics
+    }
+}
+
+/// Pips of `card` that only a color outside `colors` can pay, or `None` if
+/// the oracle card isn't known.
+fn splash_pips(
+    card: &DraftCard,
+    colors: ColorSet,
+    cards: &HashMap<Uuid, OracleCard>,
+) -> Option<u32> {
+    let oracle = cards.get(&card.oracle_id)?;
+    let cost = oracle.primary_face().mana_cost.as_ref()?;
+    Some(
+        pip_groups(cost)
+            .into_iter()
+            .filter(|(group, _)| !group.intersects(colors))
+            .map(|(_, count)| count)
+            .sum(),
+    )
+}
+
+/// Deck entries for `cards`, one per oracle card with its count, in first
+/// appearance order.
+fn entries<'a>(cards: impl Iterator<Item = &'a DraftCard>) -> Vec<DeckEntry> {
+    let mut entries: Vec<DeckEntry> = Vec::new();
+    for card in cards {
+        match entries
+            .iter_mut()
+            .find(|e| e.resolved == Some(card.oracle_id))
+        {
+            Some(entry) => entry.quantity += 1,
+            None => {
+                let mut entry = DeckEntry::new(1, &card.name);
+                entry.resolved = Some(card.oracle_id);
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+/// `WU`, or `WU+b` with a splash.
+fn build_name(build: &SealedBuild) -> String {
+    let mut name: String = Color::ALL
+        .into_iter()
+        .filter(|&c| build.colors.contains(c.into()))
+        .map(color_letter)
+        .collect();
+    if let Some(splash) = build.splash {
+        name.push('+');
+        name.push(color_letter(splash).to_ascii_lowercase());
+    }
+    name
+}
+
+fn color_letter(color: Color) -> char {
+    match color {
+        Color::W => 'W',
+        Color::U => 'U',
+        Color::B => 'B',
+        Color::R => 'R',
+        Color::G => 'G',
+    }
+}
@@ -0,0 +1,410 @@
+//! 17lands public datasets: per-card tallies from the game and draft data
+//! files, and the stats drafters read off them.
+//!
+//! The files have one row per game or per pick, with a column per card in the
+//! set (`deck_<name>`, `drawn_<name>`, `pack_card_<name>` and so on). Rows are
+//! tallied as they are read, so only the per-card counts are kept.
+
+use crate::rating::{CardRating, rate_by_name};
+use crate::{DraftCard, DraftError};
+use elbrus_core::{CardDraftCounts, CardGameCounts, Color, ColorSet};
+use elbrus_db::repo::LimitedStatsRepository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+
+/// Games in hand a card needs before its win rate counts as a rating.
+pub const MIN_GAMES_IN_HAND: u64 = 200;
+
+/// The per-card tallies of one dataset file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dataset {
+    Games(Vec<CardGameCounts>),
+    Drafts(Vec<CardDraftCounts>),
+}
+
+impl Dataset {
+    /// Read a game data or draft data CSV, telling which from its columns.
+    pub fn parse(reader: impl Read) -> Result<Self, DraftError> {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers = reader.headers()?.clone();
+        let has = |name: &str| headers.iter().any(|h| h == name);
+        if has("won") {
+            parse_games(reader, &headers).map(Self::Games)
+        } else if has("pick_number") {
+            parse_drafts(reader, &headers).map(Self::Drafts)
+        } else {
+            Err(DraftError::InvalidDataset(
+                "neither game data (no `won` column) nor draft data (no `pick_number` column)"
+                    .into(),
+            ))
+        }
+    }
+
+    /// Store the tallies under `name`, replacing any stored before under it.
+    pub async fn store<R>(&self, db: &R, name: &str) -> Result<(), DraftError>
+    where
+        R: LimitedStatsRepository + ?Sized,
+    {
+        match self {
+            Self::Games(counts) => db.replace_game_counts(name, counts).await?,
+            Self::Drafts(counts) => db.replace_draft_counts(name, counts).await?,
+        }
+        Ok(())
+    }
+
+    /// Cards tallied.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Games(counts) => counts.len(),
+            Self::Drafts(counts) => counts.len(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn column(headers: &csv::StringRecord, name: &str) -> Result<usize, DraftError> {
+    headers
+        .iter()
+        .position(|h| h == name)
+        .ok_or_else(|| DraftError::InvalidDataset(format!("no `{name}` column")))
+}
+
+/// Card names with the index of their column for each prefix, in `deck_`
+/// column order.
+fn card_columns<const N: usize>(
+    headers: &csv::StringRecord,
+    prefixes: [&str; N],
+) -> Vec<(Arc<str>, [Option<usize>; N])> {
+    let index: HashMap<&str, usize> = headers.iter().enumerate().map(|(i, h)| (h, i)).collect();
+    headers
+        .iter()
+        .filter_map(|h| h.strip_prefix(prefixes[0]))
+        .map(|name| {
+            let columns = prefixes.map(|p| index.get(&*format!("{p}{name}")).copied());
+            (Arc::from(name), columns)
+        })
+        .collect()
+}
+
+fn number(record: &csv::StringRecord, index: Option<usize>) -> u64 {
+    index
+        .and_then(|i| record.get(i))
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .map_or(0, |v| v.max(0.0) as u64)
+}
+
+fn parse_colors(letters: &str) -> ColorSet {
+    letters
+        .chars()
+        .filter_map(|c| match c {
+            'W' => Some(Color::W),
+            'U' => Some(Color::U),
+            'B' => Some(Color::B),
+            'R' => Some(Color::R),
+            'G' => Some(Color::G),
+            _ => None,
+        })
+        .fold(ColorSet::empty(), |set, c| set | c.into())
+}
+
+type GameKey = (Arc<str>, Arc<str>, ColorSet);
+type DraftKey = (Arc<str>, Arc<str>);
+
+fn parse_games(
+    mut reader: csv::Reader<impl Read>,
+    headers: &csv::StringRecord,
+) -> Result<Vec<CardGameCounts>, DraftError> {
+    let expansion = column(headers, "expansion")?;
+    let event_type = column(headers, "event_type")?;
+    let main_colors = column(headers, "main_colors")?;
+    let won = column(headers, "won")?;
+    let cards = card_columns(headers, ["deck_", "opening_hand_", "drawn_"]);
+
+    // Per expansion, event type and colors: counts for each card, in
+    // `cards` order, as [deck games, deck wins, hand games, hand wins,
+    // unseen games, unseen wins].
+    let mut tallies: HashMap<GameKey, Vec<[u64; 6]>> = HashMap::new();
+    let mut record = csv::StringRecord::new();
+    while reader.read_record(&mut record)? {
+        let key = (
+            Arc::from(record.get(expansion).unwrap_or_default()),
+            Arc::from(record.get(event_type).unwrap_or_default()),
+            parse_colors(record.get(main_colors).unwrap_or_default()),
+        );
+        let win = u64::from(matches!(
+            record.get(won).map(str::trim),
+            Some("True" | "true" | "1")
+        ));
+        let counts = tallies
+            .entry(key)
+            .or_insert_with(|| vec![[0; 6]; cards.len()]);
+        for ((_, [deck, opening, drawn]), counts) in cards.iter().zip(counts.iter_mut()) {
+            if number(&record, *deck) == 0 {
+                continue;
+            }
+            counts[0] += 1;
+            counts[1] += win;
+            let seen = if number(&record, *opening) + number(&record, *drawn) > 0 {
+                2
+            } else {
+                4
+            };
+            counts[seen] += 1;
+            counts[seen + 1] += win;
+        }
+    }
+
+    let mut games = Vec::new();
+    for ((expansion, event_type, colors), counts) in tallies {
+        for ((name, _), c) in cards.iter().zip(counts) {
+            if c[0] > 0 {
+                games.push(CardGameCounts {
+                    expansion: expansion.clone(),
+                    event_type: event_type.clone(),
+                    colors,
+                    name: name.clone(),
+                    games_in_deck: c[0],
+                    wins_in_deck: c[1],
+                    games_in_hand: c[2],
+                    wins_in_hand: c[3],
+                    games_not_seen: c[4],
+                    wins_not_seen: c[5],
+                });
+            }
+        }
+    }
+    games.sort_by(|a, b| {
+        (&a.expansion, &a.event_type, a.colors.bits(), &a.name).cmp(&(
+            &b.expansion,
+            &b.event_type,
+            b.colors.bits(),
+            &b.name,
+        ))
+    });
+    Ok(games)
+}
+
+/// Draft data rows of one draft are contiguous, as in the published files;
+/// a card's last sighting in each pack number is settled when the draft ends.
+fn parse_drafts(
+    mut reader: csv::Reader<impl Read>,
+    headers: &csv::StringRecord,
+) -> Result<Vec<CardDraftCounts>, DraftError> {
+    let expansion = column(headers, "expansion")?;
+    let event_type = column(headers, "event_type")?;
+    let draft_id = column(headers, "draft_id")?;
+    let pack_number = column(headers, "pack_number")?;
+    let pick_number = column(headers, "pick_number")?;
+    let pick = column(headers, "pick")?;
+    let cards = card_columns(headers, ["pack_card_"]);
+    let by_name: HashMap<&str, usize> = cards
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| (&**name, i))
+        .collect();
+
+    // Per expansion and event type: [packs seen, last seen total, times
+    // picked, pick total] for each card in `cards` order.
+    let mut tallies: HashMap<DraftKey, Vec<[u64; 4]>> = HashMap::new();
+    // The current draft and each (pack number, card)'s last pick seen in it.
+    let mut current: Option<(String, DraftKey)> = None;
+    let mut last_seen: HashMap<(u64, usize), u64> = HashMap::new();
+
+    let mut record = csv::StringRecord::new();
+    while reader.read_record(&mut record)? {
+        let id = record.get(draft_id).unwrap_or_default();
+        if current.as_ref().is_none_or(|(current, _)| current != id) {
+            if let Some((_, previous)) = current.take() {
+                settle(&mut tallies, previous, &mut last_seen, cards.len());
+            }
+            let key = (
+                Arc::from(record.get(expansion).unwrap_or_default()),
+                Arc::from(record.get(event_type).unwrap_or_default()),
+            );
+            current = Some((id.to_owned(), key));
+        }
+        let pack = number(&record, Some(pack_number));
+        let pick_no = number(&record, Some(pick_number)) + 1;
+        for (i, (_, [column])) in cards.iter().enumerate() {
+            if number(&record, *column) > 0 {
+                let last = last_seen.entry((pack, i)).or_default();
+                *last = (*last).max(pick_no);
+            }
+        }
+        if let (Some(&card), Some((_, key))) =
+            (record.get(pick).and_then(|p| by_name.get(p)), &current)
+        {
+            let counts = tallies
+                .entry(key.clone())
+                .or_insert_with(|| vec![[0; 4]; cards.len()]);
+            counts[card][2] += 1;
+            counts[card][3] += pick_no;
+        }
+    }
+    if let Some((_, key)) = current {
+        settle(&mut tallies, key, &mut last_seen, cards.len());
+    }
+
+    let mut drafts = Vec::new();
+    for ((expansion, event_type), counts) in tallies {
+        for ((name, _), c) in cards.iter().zip(counts) {
+            if c[0] > 0 || c[2] > 0 {
+                drafts.push(CardDraftCounts {
+                    expansion: expansion.clone(),
+                    event_type: event_type.clone(),
+                    name: name.clone(),
+                    packs_seen: c[0],
+                    last_seen_total: c[1],
+                    times_picked: c[2],
+                    pick_total: c[3],
+                });
+            }
+        }
+    }
+    drafts.sort_by(|a, b| {
+        (&a.expansion, &a.event_type, &a.name).cmp(&(&b.expansion, &b.event_type, &b.name))
+    });
+    Ok(drafts)
+}
+
+/// Add a finished draft's last sightings to `tallies`.
+fn settle(
+    tallies: &mut HashMap<DraftKey, Vec<[u64; 4]>>,
+    key: DraftKey,
+    last_seen: &mut HashMap<(u64, usize), u64>,
+    cards: usize,
+) {
+    let counts = tallies.entry(key).or_insert_with(|| vec![[0; 4]; cards]);
+    for ((_, card), pick) in last_seen.drain() {
+        counts[card][0] += 1;
+        counts[card][1] += pick;
+    }
+}
+
+/// One card's stats for an expansion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardStats {
+    pub name: Arc<str>,
+    pub games_in_hand: u64,
+    /// Games-in-hand win rate.
+    pub gih_win_rate: Option<f64>,
+    /// Improvement when drawn.
+    pub iwd: Option<f64>,
+    /// Average last seen at.
+    pub alsa: Option<f64>,
+    /// Average taken at.
+    pub ata: Option<f64>,
+}
+
+/// Join game and draft counts by card name, in name order.
+#[must_use]
+pub fn card_stats(games: &[CardGameCounts], drafts: &[CardDraftCounts]) -> Vec<CardStats> {
+    let mut stats: HashMap<Arc<str>, CardStats> = HashMap::new();
+    let entry = |stats: &mut HashMap<Arc<str>, CardStats>, name: &Arc<str>| {
+        stats.entry(name.clone()).or_insert_with(|| CardStats {
+            name: name.clone(),
+            games_in_hand: 0,
+            gih_win_rate: None,
+            iwd: None,
+            alsa: None,
+            ata: None,
+        });
+    };
+    for game in games {
+        entry(&mut stats, &game.name);
+        let card = stats.get_mut(&game.name).expect("just inserted");
+        card.games_in_hand = game.games_in_hand;
+        card.gih_win_rate = game.gih_win_rate();
+        card.iwd = game.iwd();
+    }
+    for draft in drafts {
+        entry(&mut stats, &draft.name);
+        let card = stats.get_mut(&draft.name).expect("just inserted");
+        card.alsa = draft.alsa();
+        card.ata = draft.ata();
+    }
+    let mut stats: Vec<CardStats> = stats.into_values().collect();
+    stats.sort_by(|a, b| a.name.cmp(&b.name));
+    stats
+}
+
+/// Stats for `expansion` from the stored tallies. Game stats can be narrowed
+/// to one event type and to decks of exactly `colors`; draft stats cover
+/// every deck, as the draft data doesn't record what was built.
+pub async fn load_card_stats<R>(
+    db: &R,
+    expansion: &str,
+    event_type: Option<&str>,
+    colors: Option<ColorSet>,
+) -> Result<Vec<CardStats>, DraftError>
+where
+    R: LimitedStatsRepository + ?Sized,
+{
+    let games = db.game_counts(expansion, event_type, colors).await?;
+    let drafts = db.draft_counts(expansion, event_type).await?;
+    Ok(card_stats(&games, &drafts))
+}
+
+/// Ratings from games-in-hand win rates: a card at the average rates 2.5,
+/// and each standard deviation above or below moves it a point, much as
+/// 17lands grades cards. Cards with fewer than `min_games` games in hand go
+/// unrated.
+#[derive(Debug, Clone, Default)]
+pub struct LimitedRatings {
+    /// Keyed by lowercased card name.
+    ratings: HashMap<String, f64>,
+}
+
+impl LimitedRatings {
+    #[must_use]
+    pub fn new(stats: &[CardStats], min_games: u64) -> Self {
+        let rates: Vec<(&str, f64)> = stats
+            .iter()
+            .filter(|s| s.games_in_hand >= min_games)
+            .filter_map(|s| Some((&*s.name, s.gih_win_rate?)))
+            .collect();
+        if rates.is_empty() {
+            return Self::default();
+        }
+        let n = rates.len() as f64;
+        let mean = rates.iter().map(|(_, r)| r).sum::<f64>() / n;
+        let sd = (rates.iter().map(|(_, r)| (r - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let ratings = rates
+            .into_iter()
+            .map(|(name, rate)| {
+                let z = if sd > 0.0 { (rate - mean) / sd } else { 0.0 };
+                (name.to_lowercase(), (2.5 + z).clamp(0.0, 5.0))
+            })
+            .collect();
+        Self { ratings }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ratings.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ratings.is_empty()
+    }
+}
+
+impl CardRating for LimitedRatings {
+    fn name(&self) -> &str {
+        "17lands"
+    }
+
+    /// Looks up the full name, then the front face of split and double-faced
+    /// cards.
+    fn rate(&self, card: &DraftCard) -> Option<f64> {
+        rate_by_name(&self.ratings, card)
+    }
+}
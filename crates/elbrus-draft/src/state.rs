@@ -0,0 +1,437 @@
+//! The booster draft state machine: open packs, pick, pass, repeat.
+//!
+//! Every seat picks from its current pack, and once all have picked the packs
+//! move one seat over, left in odd rounds and right in even ones. When the
+//! packs run out the next round's are opened. Each pick is recorded in a
+//! [`PickLog`] holding everything needed to replay the draft; the other
+//! formats in this crate write the same log.
+
+use crate::strategy::{PickContext, PickStrategy};
+use crate::{BoosterPack, DraftCard, DraftError};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// To the next seat.
+    Left,
+    /// To the previous seat.
+    Right,
+}
+
+impl Direction {
+    #[must_use]
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// The direction packs travel in `round` (1-based).
+    #[must_use]
+    pub fn for_round(round: u32) -> Self {
+        if round % 2 == 1 {
+            Self::Left
+        } else {
+            Self::Right
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DraftConfig {
+    pub seats: usize,
+    pub rounds: u32,
+}
+
+impl Default for DraftConfig {
+    fn default() -> Self {
+        Self {
+            seats: 8,
+            rounds: 3,
+        }
+    }
+}
+
+impl DraftConfig {
+    #[must_use]
+    pub fn packs_needed(&self) -> usize {
+        self.seats * self.rounds as usize
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DraftPlayer {
+    pub pool: Vec<DraftCard>,
+    pub current_pack: Option<BoosterPack>,
+    /// Every card seen in packs passed to this seat, for reading signals.
+    pub passed: Vec<DraftCard>,
+    picked: bool,
+}
+
+impl DraftPlayer {
+    /// Whether the seat still has to pick from its current pack.
+    #[must_use]
+    pub fn is_waiting(&self) -> bool {
+        !self.picked
+            && self
+                .current_pack
+                .as_ref()
+                .is_some_and(|p| !p.cards.is_empty())
+    }
+}
+
+/// How a [`PickLog`] was drafted, which decides how its packs and picks read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DraftFormat {
+    /// Booster or cube draft: see [`DraftState`].
+    #[default]
+    Booster,
+    /// See [`RochesterDraft`](crate::RochesterDraft).
+    Rochester,
+    /// See [`WinstonDraft`](crate::WinstonDraft).
+    Winston,
+    /// See [`GridDraft`](crate::GridDraft).
+    Grid,
+    /// See [`sealed`](crate::sealed()).
+    Sealed,
+}
+
+impl fmt::Display for DraftFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Booster => "booster",
+            Self::Rochester => "rochester",
+            Self::Winston => "winston",
+            Self::Grid => "grid",
+            Self::Sealed => "sealed",
+        })
+    }
+}
+
+/// One card going to a seat. Formats where a seat takes several cards at
+/// once, such as a Winston pile, log one pick per card with the same round and
+/// pick number.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pick {
+    pub seat: usize,
+    /// 1-based round number.
+    pub round: u32,
+    /// 1-based pick number within the round.
+    pub pick: u32,
+    pub card: DraftCard,
+}
+
+/// A complete record of a draft: the packs as opened and every pick in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PickLog {
+    #[serde(default)]
+    pub format: DraftFormat,
+    pub seats: usize,
+    pub rounds: u32,
+    /// Seed for the bots' picks.
+    pub seed: u64,
+    /// Round by round, seat by seat.
+    pub packs: Vec<BoosterPack>,
+    pub picks: Vec<Pick>,
+    /// The pack on offer at each pick, taken card included, for logs
+    /// imported from a client that only shows one seat's packs. Empty for
+    /// simulated drafts, whose packs are rebuilt by replay.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub seen: Vec<BoosterPack>,
+}
+
+impl PickLog {
+    pub fn to_json(&self) -> Result<String, DraftError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, DraftError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// The picks made by `seat`, in order.
+    pub fn picks_by(&self, seat: usize) -> impl Iterator<Item = &Pick> {
+        self.picks.iter().filter(move |p| p.seat == seat)
+    }
+
+    /// Every seat's cards, in pick order.
+    #[must_use]
+    pub fn pools(&self) -> Vec<Vec<DraftCard>> {
+        let mut pools = vec![Vec::new(); self.seats];
+        for pick in &self.picks {
+            if let Some(pool) = pools.get_mut(pick.seat) {
+                pool.push(pick.card.clone());
+            }
+        }
+        pools
+    }
+
+    /// Picks grouped into turns: runs of the same seat, round and pick number.
+    pub fn turns(&self) -> impl Iterator<Item = &[Pick]> {
+        self.picks
+            .chunk_by(|a, b| (a.seat, a.round, a.pick) == (b.seat, b.round, b.pick))
+    }
+
+    pub(crate) fn expect_format(&self, format: DraftFormat) -> Result<(), DraftError> {
+        if self.format == format {
+            Ok(())
+        } else {
+            Err(DraftError::WrongFormat {
+                expected: format,
+                found: self.format,
+            })
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DraftState {
+    pub players: Vec<DraftPlayer>,
+    /// 1-based; past the last round once the draft is finished.
+    pub round: u32,
+    /// 1-based pick number within the round.
+    pub pick: u32,
+    pub direction: Direction,
+    log: PickLog,
+    rng: ChaCha8Rng,
+}
+
+impl DraftState {
+    /// Seat the players and open the first round. `packs` holds
+    /// `config.packs_needed()` boosters, round by round and seat by seat;
+    /// `seed` drives the bots.
+    pub fn new(
+        config: DraftConfig,
+        packs: Vec<BoosterPack>,
+        seed: u64,
+    ) -> Result<Self, DraftError> {
+        if packs.len() != config.packs_needed() {
+            return Err(DraftError::PackCount {
+                expected: config.packs_needed(),
+                found: packs.len(),
+            });
+        }
+        let mut state = Self {
+            players: vec![DraftPlayer::default(); config.seats],
+            round: 0,
+            pick: 0,
+            direction: Direction::Left,
+            log: PickLog {
+                format: DraftFormat::Booster,
+                seats: config.seats,
+                rounds: config.rounds,
+                seed,
+                packs,
+                picks: Vec::new(),
+                seen: Vec::new(),
+            },
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        state.open_round();
+        Ok(state)
+    }
+
+    /// Rebuild a draft from its log by replaying every pick.
+    pub fn replay(log: &PickLog) -> Result<Self, DraftError> {
+        log.expect_format(DraftFormat::Booster)?;
+        let config = DraftConfig {
+            seats: log.seats,
+            rounds: log.rounds,
+        };
+        let mut state = Self::new(config, log.packs.clone(), log.seed)?;
+        for pick in &log.picks {
+            state.replay_pick(pick)?;
+        }
+        Ok(state)
+    }
+
+    /// Make a logged pick, checking it is the one the draft is waiting for.
+    pub(crate) fn replay_pick(&mut self, pick: &Pick) -> Result<(), DraftError> {
+        if (pick.round, pick.pick) != (self.round, self.pick) {
+            return Err(DraftError::InvalidPick(format!(
+                "log has round {} pick {} where the draft is at round {} pick {}",
+                pick.round, pick.pick, self.round, self.pick
+            )));
+        }
+        let index = self
+            .current_pack(pick.seat)
+            .and_then(|p| p.cards.iter().position(|c| *c == pick.card))
+            .ok_or_else(|| {
+                DraftError::InvalidPick(format!(
+                    "{} is not in seat {}'s pack",
+                    pick.card.name, pick.seat
+                ))
+            })?;
+        self.make_pick(pick.seat, index)?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn seats(&self) -> usize {
+        self.players.len()
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.round > self.log.rounds
+    }
+
+    #[must_use]
+    pub fn log(&self) -> &PickLog {
+        &self.log
+    }
+
+    #[must_use]
+    pub fn into_log(self) -> PickLog {
+        self.log
+    }
+
+    /// The pack `seat` is picking from, if it has not picked from it yet.
+    #[must_use]
+    pub fn current_pack(&self, seat: usize) -> Option<&BoosterPack> {
+        self.players
+            .get(seat)
+            .filter(|p| p.is_waiting())
+            .and_then(|p| p.current_pack.as_ref())
+    }
+
+    /// Take the card at `index` of `seat`'s pack. Passes the packs once every
+    /// seat has picked.
+    pub fn make_pick(&mut self, seat: usize, index: usize) -> Result<&DraftCard, DraftError> {
+        let player = self
+            .players
+            .get_mut(seat)
+            .ok_or_else(|| DraftError::InvalidPick(format!("no seat {seat}")))?;
+        if !player.is_waiting() {
+            return Err(DraftError::InvalidPick(format!(
+                "seat {seat} has no pack to pick from"
+            )));
+        }
+        let pack = player
+            .current_pack
+            .as_mut()
+            .expect("waiting seats have a pack");
+        if index >= pack.cards.len() {
+            return Err(DraftError::InvalidPick(format!(
+                "pick {index} is past the end of a {}-card pack",
+                pack.cards.len()
+            )));
+        }
+        let card = pack.cards.remove(index);
+        player.pool.push(card.clone());
+        player.picked = true;
+        self.log.picks.push(Pick {
+            seat,
+            round: self.round,
+            pick: self.pick,
+            card,
+        });
+
+        if self.players.iter().all(|p| !p.is_waiting()) {
+            self.pass();
+        }
+        Ok(self.players[seat]
+            .pool
+            .last()
+            .expect("the pick was just added"))
+    }
+
+    /// Let `strategy` pick for `seat`.
+    pub fn bot_pick(
+        &mut self,
+        seat: usize,
+        strategy: &dyn PickStrategy,
+    ) -> Result<&DraftCard, DraftError> {
+        let Some(player) = self.players.get(seat).filter(|p| p.is_waiting()) else {
+            return Err(DraftError::InvalidPick(format!(
+                "seat {seat} has no pack to pick from"
+            )));
+        };
+        let ctx = PickContext {
+            pack: &player
+                .current_pack
+                .as_ref()
+                .expect("waiting seats have a pack")
+                .cards,
+            pool: &player.pool,
+            passed: &player.passed,
+            round: self.round,
+            pick: self.pick,
+        };
+        let index = strategy.pick(&ctx, &mut self.rng);
+        self.make_pick(seat, index)
+    }
+
+    /// Run the rest of the draft with `bots[seat]` picking for each seat.
+    pub fn run(&mut self, bots: &[&dyn PickStrategy]) -> Result<(), DraftError> {
+        if bots.len() != self.seats() {
+            return Err(DraftError::InvalidPick(format!(
+                "{} bots for {} seats",
+                bots.len(),
+                self.seats()
+            )));
+        }
+        while !self.is_finished() {
+            for (seat, bot) in bots.iter().enumerate() {
+                if self.players[seat].is_waiting() {
+                    self.bot_pick(seat, *bot)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn pass(&mut self) {
+        if self
+            .players
+            .iter()
+            .all(|p| p.current_pack.as_ref().is_none_or(|p| p.cards.is_empty()))
+        {
+            self.open_round();
+            return;
+        }
+        let mut packs: Vec<Option<BoosterPack>> = self
+            .players
+            .iter_mut()
+            .map(|p| p.current_pack.take())
+            .collect();
+        match self.direction {
+            Direction::Left => packs.rotate_right(1),
+            Direction::Right => packs.rotate_left(1),
+        }
+        for (player, pack) in self.players.iter_mut().zip(packs) {
+            if let Some(pack) = &pack {
+                player.passed.extend(pack.cards.iter().cloned());
+            }
+            player.current_pack = pack;
+            player.picked = false;
+        }
+        self.pick += 1;
+    }
+
+    /// Open the next round's packs, skipping rounds whose packs are all
+    /// empty.
+    fn open_round(&mut self) {
+        loop {
+            self.round += 1;
+            self.pick = 1;
+            self.direction = Direction::for_round(self.round);
+            let seats = self.seats();
+            let start = (self.round as usize - 1) * seats;
+            let packs = self.log.packs.get(start..start + seats);
+            for (seat, player) in self.players.iter_mut().enumerate() {
+                player.current_pack = packs.map(|p| p[seat].clone());
+                player.picked = false;
+            }
+            if self.is_finished() || self.players.iter().any(DraftPlayer::is_waiting) {
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Bot pick strategies.
+
+use crate::DraftCard;
+use crate::rating::CardRating;
+use elbrus_core::{Color, ColorSet, Rarity};
+use rand::{Rng, RngCore};
+use std::sync::Arc;
+
+/// What a drafter knows when making a pick.
+#[derive(Debug, Clone, Copy)]
+pub struct PickContext<'a> {
+    /// The pack to pick from; never empty.
+    pub pack: &'a [DraftCard],
+    /// Cards picked so far.
+    pub pool: &'a [DraftCard],
+    /// Every card seen in packs passed to this seat, including ones still in
+    /// them, so what the neighbours are leaving behind.
+    pub passed: &'a [DraftCard],
+    /// 1-based round number.
+    pub round: u32,
+    /// 1-based pick number within the round.
+    pub pick: u32,
+}
+
+/// A drafting bot. Strategies hold no state between picks; anything they need
+/// to remember is in the [`PickContext`], and any randomness must come from
+/// `rng` so drafts stay reproducible.
+pub trait PickStrategy: Send + Sync {
+    fn name(&self) -> &str;
+    /// Index into `ctx.pack` of the card to take.
+    fn pick(&self, ctx: &PickContext<'_>, rng: &mut dyn RngCore) -> usize;
+
+    /// Index into `groups` of the cards to take together, such as a row or
+    /// column of a Grid draft; `ctx.pack` holds every card on offer. By
+    /// default the largest group holding the card [`pick`](Self::pick)
+    /// chooses from `ctx.pack`.
+    fn pick_group(
+        &self,
+        groups: &[Vec<DraftCard>],
+        ctx: &PickContext<'_>,
+        rng: &mut dyn RngCore,
+    ) -> usize {
+        let card = &ctx.pack[self.pick(ctx, rng)];
+        (0..groups.len())
+            .filter(|&i| groups[i].contains(card))
+            .max_by_key(|&i| (groups[i].len(), std::cmp::Reverse(i)))
+            .unwrap_or(0)
+    }
+
+    /// Whether to take the Winston draft pile in `ctx.pack` rather than look
+    /// at the next one. By default piles of two or more cards are taken, and
+    /// single cards when rare or better.
+    fn take_pile(&self, ctx: &PickContext<'_>, _rng: &mut dyn RngCore) -> bool {
+        ctx.pack.len() >= 2 || ctx.pack.iter().any(|c| c.rarity >= Rarity::Rare)
+    }
+}
+
+/// Picks uniformly at random.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomPicker;
+
+impl PickStrategy for RandomPicker {
+    fn name(&self) -> &str {
+        "random"
+    }
+
+    fn pick(&self, ctx: &PickContext<'_>, rng: &mut dyn RngCore) -> usize {
+        rng.gen_range(0..ctx.pack.len())
+    }
+}
+
+fn rarity_rank(card: &DraftCard) -> u8 {
+    if card.is_basic_land() {
+        return 0;
+    }
+    match card.rarity {
+        Rarity::Common => 1,
+        Rarity::Uncommon => 2,
+        Rarity::Rare => 3,
+        Rarity::Mythic => 4,
+        _ => 2,
+    }
+}
+
+/// The index of the highest-scoring card, ties broken at random.
+fn best_by<K: Ord>(
+    pack: &[DraftCard],
+    rng: &mut dyn RngCore,
+    key: impl Fn(&DraftCard) -> K,
+) -> usize {
+    let keys: Vec<K> = pack.iter().map(key).collect();
+    let Some(best) = keys.iter().max() else {
+        return 0;
+    };
+    let tied: Vec<usize> = (0..keys.len()).filter(|&i| keys[i] == *best).collect();
+    tied[rng.gen_range(0..tied.len())]
+}
+
+/// Takes the rarest card, preferring foils among equals. Basic lands come
+/// last.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RarityPicker;
+
+impl PickStrategy for RarityPicker {
+    fn name(&self) -> &str {
+        "rarity"
+    }
+
+    fn pick(&self, ctx: &PickContext<'_>, rng: &mut dyn RngCore) -> usize {
+        best_by(ctx.pack, rng, |c| (rarity_rank(c), c.foil))
+    }
+}
+
+/// Drafts the best cards until `commit_after` picks, then settles into the
+/// two colors with the most weight and takes the best card in them.
+///
+/// A color's weight is two points per card of it already picked plus one per
+/// uncommon or better of it seen in passed packs: good cards coming around
+/// late mean the neighbours are not in that color.
+#[derive(Debug, Clone, Copy)]
+pub struct ColorCommitPicker {
+    pub commit_after: usize,
+}
+
+impl Default for ColorCommitPicker {
+    fn default() -> Self {
+        Self { commit_after: 5 }
+    }
+}
+
+impl ColorCommitPicker {
+    /// Each color's weight from the pool and passed packs, in WUBRG order.
+    #[must_use]
+    pub fn color_weights(pool: &[DraftCard], passed: &[DraftCard]) -> [u32; 5] {
+        let mut weights = [0; 5];
+        for (i, color) in Color::ALL.into_iter().enumerate() {
+            let color = ColorSet::from(color);
+            let picked = pool.iter().filter(|c| c.colors.contains(color)).count();
+            let signals = passed
+                .iter()
+                .filter(|c| c.colors.contains(color) && rarity_rank(c) >= 2)
+                .count();
+            weights[i] = 2 * picked as u32 + signals as u32;
+        }
+        weights
+    }
+
+    /// The two heaviest colors, earlier WUBRG colors winning ties.
+    #[must_use]
+    pub fn committed_colors(pool: &[DraftCard], passed: &[DraftCard]) -> ColorSet {
+        let weights = Self::color_weights(pool, passed);
+        let mut order: Vec<usize> = (0..5).collect();
+        order.sort_by(|&a, &b| weights[b].cmp(&weights[a]));
+        order[..2]
+            .iter()
+            .fold(ColorSet::empty(), |set, &i| set | Color::ALL[i].into())
+    }
+}
+
+impl PickStrategy for ColorCommitPicker {
+    fn name(&self) -> &str {
+        "color-commit"
+    }
+
+    fn pick(&self, ctx: &PickContext<'_>, rng: &mut dyn RngCore) -> usize {
+        if ctx.pool.len() < self.commit_after {
+            let weights = Self::color_weights(ctx.pool, ctx.passed);
+            return best_by(ctx.pack, rng, |c| {
+                let lean: u32 = Color::ALL
+                    .into_iter()
+                    .zip(weights)
+                    .filter(|&(color, _)| c.colors.contains(color.into()))
+                    .map(|(_, w)| w)
+                    .sum();
+                (rarity_rank(c), lean)
+            });
+        }
+        let colors = Self::committed_colors(ctx.pool, ctx.passed);
+        best_by(ctx.pack, rng, |c| {
+            let on_color = !c.is_basic_land() && colors.contains(c.colors);
+            (on_color, rarity_rank(c))
+        })
+    }
+}
+
+/// Takes the best-rated card, using [`CardRating`] with its heuristic
+/// fallback. After `commit_after` picks it settles into the same two colors
+/// as [`ColorCommitPicker`], and cards outside them lose `off_color_penalty`.
+#[derive(Clone)]
+pub struct RatingPicker {
+    pub ratings: Arc<dyn CardRating>,
+    pub commit_after: usize,
+    pub off_color_penalty: f64,
+}
+
+impl RatingPicker {
+    #[must_use]
+    pub fn new(ratings: Arc<dyn CardRating>) -> Self {
+        Self {
+            ratings,
+            commit_after: ColorCommitPicker::default().commit_after,
+            off_color_penalty: 1.0,
+        }
+    }
+}
+
+impl PickStrategy for RatingPicker {
+    fn name(&self) -> &str {
+        "rating"
+    }
+
+    fn pick(&self, ctx: &PickContext<'_>, rng: &mut dyn RngCore) -> usize {
+        let colors = (ctx.pool.len() >= self.commit_after)
+            .then(|| ColorCommitPicker::committed_colors(ctx.pool, ctx.passed));
+        best_by(ctx.pack, rng, |c| {
+            let mut rating = self.ratings.rate_or_guess(c);
+            if colors.is_some_and(|colors| !colors.contains(c.colors)) {
+                rating -= self.off_color_penalty;
+            }
+            // Ratings are compared to the thousandth so ties stay ties.
+            (rating * 1000.0).round() as i64
+        })
+    }
+}
@@ -0,0 +1,245 @@
+//! Winston draft: two players and three face-down piles.
+//!
+//! Each pile starts with one card from the top of the deck. On their turn a
+//! player looks at the first pile and either takes it, replacing it with a
+//! fresh card from the deck, or adds the top card of the deck to it and moves
+//! on to the next pile. Passing on the last pile takes the top card of the
+//! deck unseen. The draft ends when the deck and piles are empty.
+
+use crate::state::{DraftFormat, DraftPlayer, Pick, PickLog};
+use crate::strategy::{PickContext, PickStrategy};
+use crate::{BoosterPack, DraftCard, DraftError};
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+use std::collections::VecDeque;
+
+pub const WINSTON_SEATS: usize = 2;
+pub const WINSTON_PILES: usize = 3;
+
+#[derive(Debug, Clone)]
+pub struct WinstonDraft {
+    pub players: Vec<DraftPlayer>,
+    /// The seat whose turn it is.
+    pub active: usize,
+    /// 1-based turn number, across both players.
+    pub turn: u32,
+    piles: [Vec<DraftCard>; WINSTON_PILES],
+    /// The pile the active player is looking at.
+    pile: usize,
+    deck: VecDeque<DraftCard>,
+    log: PickLog,
+    rng: ChaCha8Rng,
+}
+
+impl WinstonDraft {
+    /// Shuffle `packs` together under `seed` into the deck; six boosters is
+    /// usual.
+    #[must_use]
+    pub fn new(packs: Vec<BoosterPack>, seed: u64) -> Self {
+        let mut cards: Vec<DraftCard> = packs.into_iter().flat_map(|p| p.cards).collect();
+        cards.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+        Self::with_deck(cards, seed)
+    }
+
+    /// Start with `deck` in draw order.
+    fn with_deck(deck: Vec<DraftCard>, seed: u64) -> Self {
+        let mut draft = Self {
+            players: vec![DraftPlayer::default(); WINSTON_SEATS],
+            active: 0,
+            turn: 1,
+            piles: Default::default(),
+            pile: 0,
+            deck: deck.iter().cloned().collect(),
+            log: PickLog {
+                format: DraftFormat::Winston,
+                seats: WINSTON_SEATS,
+                rounds: 1,
+                seed,
+                packs: vec![BoosterPack { cards: deck }],
+                picks: Vec::new(),
+                seen: Vec::new(),
+            },
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        for pile in &mut draft.piles {
+            pile.extend(draft.deck.pop_front());
+        }
+        draft.skip_empty_piles();
+        draft
+    }
+
+    /// Rebuild a draft from its log by replaying every turn.
+    pub fn replay(log: &PickLog) -> Result<Self, DraftError> {
+        log.expect_format(DraftFormat::Winston)?;
+        let deck = log
+            .packs
+            .first()
+            .map(|p| p.cards.clone())
+            .unwrap_or_default();
+        let mut draft = Self::with_deck(deck, log.seed);
+        for turn in log.turns() {
+            let seat = turn[0].seat;
+            if seat != draft.active || draft.is_finished() {
+                return Err(DraftError::InvalidPick(format!(
+                    "seat {seat} takes a turn out of order"
+                )));
+            }
+            let cards: Vec<&DraftCard> = turn.iter().map(|p| &p.card).collect();
+            loop {
+                let pile: Vec<&DraftCard> = draft.current_pile().iter().collect();
+                if pile == cards {
+                    draft.take()?;
+                    break;
+                }
+                if let Some(drawn) = draft.pass()? {
+                    if cards != [&drawn] {
+                        return Err(DraftError::InvalidPick(format!(
+                            "turn {} matches no pile",
+                            turn[0].pick
+                        )));
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(draft)
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.deck.is_empty() && self.piles.iter().all(Vec::is_empty)
+    }
+
+    #[must_use]
+    pub fn log(&self) -> &PickLog {
+        &self.log
+    }
+
+    #[must_use]
+    pub fn into_log(self) -> PickLog {
+        self.log
+    }
+
+    #[must_use]
+    pub fn deck_len(&self) -> usize {
+        self.deck.len()
+    }
+
+    /// Index of the pile the active player is looking at.
+    #[must_use]
+    pub fn pile_index(&self) -> usize {
+        self.pile
+    }
+
+    /// The cards in the pile the active player is looking at.
+    #[must_use]
+    pub fn current_pile(&self) -> &[DraftCard] {
+        &self.piles[self.pile]
+    }
+
+    /// Whether the active player may pass on the current pile. Passing on the
+    /// last pile needs two cards in the deck, one for the pile and one to
+    /// draw; once the deck is empty the last non-empty pile must be taken.
+    #[must_use]
+    pub fn can_pass(&self) -> bool {
+        if self.pile + 1 == WINSTON_PILES {
+            self.deck.len() >= 2
+        } else {
+            !self.deck.is_empty() || self.piles[self.pile + 1..].iter().any(|p| !p.is_empty())
+        }
+    }
+
+    /// The active player takes the current pile, which is refilled from the
+    /// deck, and the turn passes.
+    pub fn take(&mut self) -> Result<Vec<DraftCard>, DraftError> {
+        if self.is_finished() {
+            return Err(DraftError::InvalidPick("the draft is over".into()));
+        }
+        let cards = std::mem::take(&mut self.piles[self.pile]);
+        self.piles[self.pile].extend(self.deck.pop_front());
+        self.gain(&cards);
+        Ok(cards)
+    }
+
+    /// The active player adds the top card of the deck to the current pile
+    /// and moves on. Passing on the last pile draws the top card of the deck,
+    /// which is returned, and ends the turn.
+    pub fn pass(&mut self) -> Result<Option<DraftCard>, DraftError> {
+        if self.is_finished() || !self.can_pass() {
+            return Err(DraftError::InvalidPick(
+                "the last pile must be taken once the deck is empty".into(),
+            ));
+        }
+        if let Some(card) = self.deck.pop_front() {
+            self.players[self.active]
+                .passed
+                .extend(self.piles[self.pile].iter().cloned());
+            self.piles[self.pile].push(card);
+        }
+        self.pile += 1;
+        if self.pile == WINSTON_PILES {
+            let card = self.deck.pop_front().expect("can_pass checked the deck");
+            self.gain(std::slice::from_ref(&card));
+            return Ok(Some(card));
+        }
+        self.skip_empty_piles();
+        Ok(None)
+    }
+
+    /// Let `strategy` take the active player's whole turn.
+    pub fn bot_turn(&mut self, strategy: &dyn PickStrategy) -> Result<Vec<DraftCard>, DraftError> {
+        if self.is_finished() {
+            return Err(DraftError::InvalidPick("the draft is over".into()));
+        }
+        loop {
+            let player = &self.players[self.active];
+            let ctx = PickContext {
+                pack: &self.piles[self.pile],
+                pool: &player.pool,
+                passed: &player.passed,
+                round: 1,
+                pick: self.turn,
+            };
+            if !self.can_pass() || strategy.take_pile(&ctx, &mut self.rng) {
+                return self.take();
+            }
+            if let Some(card) = self.pass()? {
+                return Ok(vec![card]);
+            }
+        }
+    }
+
+    /// Run the rest of the draft with `bots[seat]` playing each seat.
+    pub fn run(&mut self, bots: [&dyn PickStrategy; WINSTON_SEATS]) -> Result<(), DraftError> {
+        while !self.is_finished() {
+            self.bot_turn(bots[self.active])?;
+        }
+        Ok(())
+    }
+
+    /// Add `cards` to the active player's pool and end the turn.
+    fn gain(&mut self, cards: &[DraftCard]) {
+        for card in cards {
+            self.players[self.active].pool.push(card.clone());
+            self.log.picks.push(Pick {
+                seat: self.active,
+                round: 1,
+                pick: self.turn,
+                card: card.clone(),
+            });
+        }
+        self.active = (self.active + 1) % WINSTON_SEATS;
+        self.turn += 1;
+        self.pile = 0;
+        self.skip_empty_piles();
+    }
+
+    /// Taken piles are refilled while the deck lasts, so piles only run dry
+    /// once it has; skip them.
+    fn skip_empty_piles(&mut self) {
+        while self.pile + 1 < WINSTON_PILES && self.piles[self.pile].is_empty() {
+            self.pile += 1;
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use common::{insert, oracle, printing};
+use elbrus_core::{CardType, Printing, Rarity, Supertype, color::ColorSet, types::TypeLine};
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_draft::{
+    BoosterConfig, BoosterKind, BoosterTemplate, DraftCard, DraftError, SetSheets, Sheet, Slot,
+    SlotOption,
+};
+use uuid::Uuid;
+
+mod common;
+
+async fn insert_card(
+    db: &SqliteBackend,
+    set_code: &str,
+    name: &str,
+    rarity: Rarity,
+    booster: bool,
+    type_line: TypeLine,
+) {
+    let mut card = oracle(name, "", ColorSet::empty(), CardType::Land);
+    card.faces[0].type_line = type_line;
+    let printing = Printing {
+        collector_number: name.into(),
+        booster,
+        ..printing(&card, set_code, rarity)
+    };
+    insert(db, &card, &[printing]).await;
+}
+
+/// 20 commons, 6 uncommons, 3 rares, 1 mythic, 5 basics and a showcase rare
+/// in `set_code`, plus 4 cards on The List.
+async fn setup(set_code: &str) -> SqliteBackend {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    for (prefix, rarity, count) in [
+        ("Common", Rarity::Common, 20),
+        ("Uncommon", Rarity::Uncommon, 6),
+        ("Rare", Rarity::Rare, 3),
+        ("Mythic", Rarity::Mythic, 1),
+    ] {
+        for i in 0..count {
+            insert_card(
+                &db,
+                set_code,
+                &format!("{prefix} {i}"),
+                rarity,
+                true,
+                TypeLine::default(),
+            )
+            .await;
+        }
+    }
+    for name in ["Plains", "Island", "Swamp", "Mountain", "Forest"] {
+        let type_line = TypeLine {
+            supertypes: smallvec::smallvec![Supertype::Basic],
+            card_types: smallvec::smallvec![CardType::Land],
+            ..TypeLine::default()
+        };
+        insert_card(&db, set_code, name, Rarity::Common, true, type_line).await;
+    }
+    insert_card(
+        &db,
+        set_code,
+        "Showcase Rare",
+        Rarity::Rare,
+        false,
+        TypeLine::default(),
+    )
+    .await;
+    for i in 0..4 {
+        insert_card(
+            &db,
+            "plst",
+            &format!("Listed {i}"),
+            Rarity::Rare,
+            false,
+            TypeLine::default(),
+        )
+        .await;
+    }
+    db
+}
+
+fn count(cards: &[DraftCard], prefix: &str) -> usize {
+    cards.iter().filter(|c| c.name.starts_with(prefix)).count()
+}
+
+#[tokio::test]
+async fn test_generic_fallback() {
+    let db = setup("zzz").await;
+    let config = BoosterConfig::bundled();
+    let packs = config
+        .generate(&db, "zzz", BoosterKind::Play, 24, 7)
+        .await
+        .unwrap();
+
+    assert_eq!(packs.len(), 24);
+    for pack in &packs {
+        assert_eq!(pack.cards.len(), 14);
+        assert_eq!(count(&pack.cards, "Common"), 10);
+        assert_eq!(count(&pack.cards, "Uncommon"), 3);
+        assert_eq!(count(&pack.cards, "Rare") + count(&pack.cards, "Mythic"), 1);
+        assert!(
+            pack.cards
+                .iter()
+                .all(|c| !c.foil && c.printing_id.is_some())
+        );
+
+        let mut ids: Vec<Uuid> = pack.cards.iter().map(|c| c.oracle_id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 14, "no duplicates within a pack");
+    }
+}
+
+#[tokio::test]
+async fn test_seed_reproducible() {
+    let db = setup("zzz").await;
+    let config = BoosterConfig::bundled();
+    let a = config
+        .generate(&db, "zzz", BoosterKind::Draft, 3, 42)
+        .await
+        .unwrap();
+    let b = config
+        .generate(&db, "zzz", BoosterKind::Draft, 3, 42)
+        .await
+        .unwrap();
+    let c = config
+        .generate(&db, "zzz", BoosterKind::Draft, 3, 43)
+        .await
+        .unwrap();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[tokio::test]
+async fn test_draft_booster_slots() {
+    let db = setup("dom").await;
+    let config = BoosterConfig::bundled();
+    let packs = config
+        .generate(&db, "dom", BoosterKind::Draft, 50, 1)
+        .await
+        .unwrap();
+
+    let mut foils = 0;
+    for pack in &packs {
+        assert_eq!(pack.cards.len(), 15);
+        assert!(pack.cards[0].rarity >= Rarity::Rare, "rare slot first");
+        assert!(pack.cards[14].is_basic_land(), "land slot last");
+        assert!(
+            pack.cards[..14].iter().all(|c| !c.is_basic_land()),
+            "basics only in the land slot"
+        );
+        assert_eq!(count(&pack.cards, "Showcase"), 0);
+        foils += pack.cards.iter().filter(|c| c.foil).count();
+    }
+    // One pack in three has a foil in place of a common.
+    assert!((5..=35).contains(&foils), "{foils} foils in 50 packs");
+
+    let err = config
+        .generate(&db, "dom", BoosterKind::Play, 1, 1)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DraftError::NoBooster {
+            kind: BoosterKind::Play,
+            ..
+        }
+    ));
+}
+
+#[tokio::test]
+async fn test_list_and_variant_sheets() {
+    let db = setup("mkm").await;
+    let config = BoosterConfig::bundled();
+    let template = config.template("MKM", BoosterKind::Play).unwrap();
+    assert_eq!(template.extra_sets(), vec!["plst", "spg"]);
+
+    let sheets = SetSheets::load(&db, "mkm", template).await.unwrap();
+    assert_eq!(sheets.sheet(&Sheet::Common).len(), 20);
+    assert_eq!(sheets.sheet(&Sheet::BasicLand).len(), 5);
+    assert_eq!(sheets.sheet(&Sheet::Variant(Rarity::Rare)).len(), 1);
+    assert_eq!(sheets.sheet(&Sheet::Set("plst".into())).len(), 4);
+    assert!(sheets.sheet(&Sheet::Set("spg".into())).is_empty());
+
+    let packs = template.generate(&sheets, 200, 9);
+    let listed: usize = packs.iter().map(|p| count(&p.cards, "Listed")).sum();
+    assert!(
+        listed > 0 && listed < 100,
+        "{listed} List cards in 200 packs"
+    );
+
+    let collector = config.template("mkm", BoosterKind::Collector).unwrap();
+    let packs = collector.generate(&sheets, 10, 9);
+    assert!(packs.iter().all(|p| count(&p.cards, "Showcase") >= 1));
+
+    assert!(matches!(
+        SetSheets::load(&db, "nope", template).await,
+        Err(DraftError::EmptySet(_))
+    ));
+}
+
+#[test]
+fn test_template_json() {
+    let template: BoosterTemplate = serde_json::from_str(
+        r#"{"slots": [{"count": 2, "options": [
+            {"weight": 3, "sheet": "common"},
+            {"weight": 1, "sheet": {"set": "spg"}, "foil": true}
+        ]}]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        template.slots,
+        vec![Slot {
+            count: 2,
+            options: vec![
+                SlotOption {
+                    weight: 3,
+                    sheet: Sheet::Common,
+                    foil: false,
+                },
+                SlotOption {
+                    weight: 1,
+                    sheet: Sheet::Set("spg".into()),
+                    foil: true,
+                },
+            ],
+        }]
+    );
+}
@@ -0,0 +1,82 @@
+// Shared fixtures; each test binary uses its own subset.
+#![allow(dead_code)]
+
+use elbrus_core::{
+    CardFace, CardLayout, CardType, OracleCard, Printing, Rarity, color::ColorSet,
+    legality::Legalities, oracle::OracleText, types::TypeLine,
+};
+use elbrus_db::repo::CardRepository;
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_draft::DraftCard;
+use uuid::Uuid;
+
+/// A typeless two-drop not backed by any oracle card.
+pub fn card(name: &str, rarity: Rarity, colors: ColorSet) -> DraftCard {
+    DraftCard {
+        oracle_id: Uuid::new_v4(),
+        printing_id: None,
+        name: name.into(),
+        rarity,
+        colors,
+        mana_value: 2.0,
+        type_line: TypeLine::default(),
+        foil: false,
+    }
+}
+
+/// A single-faced card of one type; an empty `cost` means no mana cost.
+pub fn oracle(name: &str, cost: &str, colors: ColorSet, card_type: CardType) -> OracleCard {
+    OracleCard {
+        oracle_id: Uuid::new_v4(),
+        layout: CardLayout::Normal,
+        faces: smallvec::smallvec![CardFace {
+            name: name.into(),
+            mana_cost: (!cost.is_empty()).then(|| elbrus_parser::mana_cost::parse(cost).unwrap()),
+            type_line: TypeLine {
+                card_types: smallvec::smallvec![card_type],
+                ..TypeLine::default()
+            },
+            oracle_text: OracleText::default(),
+            colors,
+            power: None,
+            toughness: None,
+            loyalty: None,
+            defense: None,
+            flavor_text: None,
+        }],
+        color_identity: colors,
+        keywords: vec![],
+        legalities: Legalities::default(),
+        edh_rank: None,
+        reserved: false,
+    }
+}
+
+/// An English booster printing, #1 in `set_code`, of `card`.
+pub fn printing(card: &OracleCard, set_code: &str, rarity: Rarity) -> Printing {
+    Printing {
+        id: Uuid::new_v4(),
+        oracle_id: card.oracle_id,
+        set_code: set_code.into(),
+        collector_number: "1".into(),
+        rarity,
+        lang: "en".into(),
+        released_at: chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap(),
+        image_uris: None,
+        promo: false,
+        digital: false,
+        full_art: false,
+        textless: false,
+        reprint: false,
+        booster: true,
+        prices: None,
+    }
+}
+
+/// Store `card` and its `printings`.
+pub async fn insert(db: &SqliteBackend, card: &OracleCard, printings: &[Printing]) {
+    db.upsert_oracle(card).await.unwrap();
+    for printing in printings {
+        db.upsert_printing(printing).await.unwrap();
+    }
+}
@@ -0,0 +1,163 @@
+use common::{insert, oracle};
+use elbrus_core::{CardType, Printing, Rarity, color::ColorSet, types::TypeLine};
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_draft::{
+    ColorCategory, Cube, CubeStats, DraftCard, DraftConfig, DraftError, DraftState, PickStrategy,
+    RarityPicker, cube_packs,
+};
+use uuid::Uuid;
+
+mod common;
+
+fn draft_card(name: &str, colors: ColorSet, mana_value: f32, card_type: CardType) -> DraftCard {
+    DraftCard {
+        mana_value,
+        type_line: TypeLine {
+            card_types: smallvec::smallvec![card_type],
+            ..TypeLine::default()
+        },
+        ..common::card(name, Rarity::Common, colors)
+    }
+}
+
+#[test]
+fn test_parse_text() {
+    let cube = Cube::parse(
+        "# White\nSwords to Plowshares\n2x Porcelain Legionnaire\n\n\
+         # Blue\nCounterspell (MH2) 267\n// a comment\n",
+    )
+    .unwrap();
+    let names: Vec<&str> = cube.cards.iter().map(|c| &*c.name).collect();
+    assert_eq!(
+        names,
+        [
+            "Swords to Plowshares",
+            "Porcelain Legionnaire",
+            "Porcelain Legionnaire",
+            "Counterspell"
+        ]
+    );
+    assert_eq!(cube.sections(), ["White".into(), "Blue".into()]);
+    assert_eq!(cube.cards[3].section.as_deref(), Some("Blue"));
+    assert_eq!(cube.cards[3].set_hint.as_deref(), Some("mh2"));
+    assert_eq!(cube.cards[3].collector_number.as_deref(), Some("267"));
+}
+
+#[test]
+fn test_parse_csv() {
+    let csv = "name,CMC,Type,Color,Set,Collector Number,Rarity,Color Category,status,Finish,maybeboard,image URL,image Back URL,tags,Notes,MTGO ID\n\
+        \"Lightning Bolt\",1,\"Instant\",R,\"2xm\",\"141\",uncommon,r,Owned,Foil,false,,,\"burn;cheap\",,\n\
+        \"Fire // Ice\",4,\"Instant\",UR,\"mh2\",\"290\",rare,m,Owned,Non-foil,false,,,,,\n\
+        \"Black Lotus\",0,\"Artifact\",,\"lea\",\"232\",rare,c,Not Owned,Non-foil,true,,,,,\n";
+    let cube = Cube::parse(csv).unwrap();
+    assert_eq!(cube.cards.len(), 2, "maybeboard left out");
+
+    let bolt = &cube.cards[0];
+    assert_eq!(&*bolt.name, "Lightning Bolt");
+    assert_eq!(bolt.set_hint.as_deref(), Some("2xm"));
+    assert_eq!(bolt.rarity, Some(Rarity::Uncommon));
+    assert!(bolt.foil);
+    assert_eq!(bolt.tags, ["burn".into(), "cheap".into()]);
+    assert_eq!(cube.tagged("BURN").count(), 1);
+    assert_eq!(&*cube.cards[1].name, "Fire // Ice");
+    assert!(!cube.cards[1].foil);
+}
+
+#[tokio::test]
+async fn test_resolve_and_load() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let bolt = oracle("Lightning Bolt", "{R}", ColorSet::RED, CardType::Instant);
+    let printing = Printing {
+        collector_number: "141".into(),
+        released_at: chrono::NaiveDate::from_ymd_opt(2020, 8, 7).unwrap(),
+        reprint: true,
+        ..common::printing(&bolt, "2xm", Rarity::Uncommon)
+    };
+    let printing_id = printing.id;
+    insert(&db, &bolt, &[printing]).await;
+    let counterspell = oracle("Counterspell", "{U}{U}", ColorSet::BLUE, CardType::Instant);
+    insert(&db, &counterspell, &[]).await;
+
+    let mut cube = Cube::parse_text("Lightning Bolt (2XM) 141\ncounterspell\nNot A Card\n");
+    let unresolved = cube.resolve(&db).await.unwrap();
+    assert_eq!(unresolved, ["Not A Card".into()]);
+    assert_eq!(cube.cards[0].printing_id, Some(printing_id));
+
+    let cards = cube.draft_cards(&db).await.unwrap();
+    assert_eq!(cards.len(), 2);
+    assert_eq!(cards[0].rarity, Rarity::Uncommon);
+    assert_eq!(cards[1].rarity, Rarity::Common);
+    assert_eq!(&*cards[1].name, "Counterspell");
+    assert_eq!(cards[1].mana_value, 2.0);
+}
+
+#[test]
+fn test_stats() {
+    let cards = vec![
+        draft_card("a", ColorSet::WHITE, 1.0, CardType::Creature),
+        draft_card("b", ColorSet::WHITE, 2.0, CardType::Creature),
+        draft_card("c", ColorSet::BLUE, 2.0, CardType::Instant),
+        draft_card("d", ColorSet::BLUE | ColorSet::RED, 9.0, CardType::Sorcery),
+        draft_card("e", ColorSet::empty(), 3.0, CardType::Artifact),
+        draft_card("f", ColorSet::empty(), 0.0, CardType::Land),
+    ];
+    let stats = CubeStats::new(&cards);
+    assert_eq!(stats.total, 6);
+    assert_eq!(stats.count(ColorCategory::White), 2);
+    assert_eq!(stats.count(ColorCategory::Multicolor), 1);
+    assert_eq!(stats.count(ColorCategory::Colorless), 1);
+    assert_eq!(stats.count(ColorCategory::Land), 1);
+    assert_eq!(
+        stats.curves[&ColorCategory::White],
+        [0, 1, 1, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(stats.curves[&ColorCategory::Multicolor][7], 1);
+    assert!(!stats.curves.contains_key(&ColorCategory::Land));
+    assert_eq!(stats.types[&CardType::Creature], 2);
+    assert!((stats.share(ColorCategory::Blue) - 1.0 / 6.0).abs() < 1e-9);
+    assert_eq!(stats.color_balance(), None, "no black or green");
+}
+
+#[test]
+fn test_cube_draft() {
+    let colors = [
+        ColorSet::WHITE,
+        ColorSet::BLUE,
+        ColorSet::BLACK,
+        ColorSet::RED,
+        ColorSet::GREEN,
+    ];
+    let cards: Vec<DraftCard> = (0..540)
+        .map(|i| draft_card(&format!("Card {i}"), colors[i % 5], 2.0, CardType::Creature))
+        .collect();
+    let config = DraftConfig::default();
+
+    let packs = cube_packs(&cards, config, 15, 3).unwrap();
+    assert_eq!(packs.len(), 24);
+    assert!(packs.iter().all(|p| p.cards.len() == 15));
+    let mut ids: Vec<Uuid> = packs
+        .iter()
+        .flat_map(|p| &p.cards)
+        .map(|c| c.oracle_id)
+        .collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), 360, "no card dealt twice");
+    assert_eq!(packs, cube_packs(&cards, config, 15, 3).unwrap());
+
+    let mut state = DraftState::new(config, packs, 3).unwrap();
+    state.run(&[&RarityPicker as &dyn PickStrategy; 8]).unwrap();
+    assert!(state.players.iter().all(|p| p.pool.len() == 45));
+
+    assert!(matches!(
+        cube_packs(&cards[..100], config, 15, 3),
+        Err(DraftError::CubeTooSmall {
+            needed: 360,
+            found: 100
+        })
+    ));
+    assert!(matches!(
+        cube_packs(&cards, config, 0, 3),
+        Err(DraftError::EmptyPacks)
+    ));
+}
@@ -0,0 +1,218 @@
+use common::card;
+use elbrus_core::{Rarity, color::ColorSet};
+use elbrus_draft::{
+    BoosterPack, ColorCommitPicker, Direction, DraftConfig, DraftError, DraftState, PickContext,
+    PickLog, PickStrategy, RandomPicker, RarityPicker,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+mod common;
+
+/// Packs named `R{round}S{seat}C{i}`, the first card of each a mythic.
+fn packs(config: DraftConfig, size: usize) -> Vec<BoosterPack> {
+    let mut packs = Vec::new();
+    for round in 1..=config.rounds {
+        for seat in 0..config.seats {
+            let cards = (0..size)
+                .map(|i| {
+                    let rarity = if i == 0 {
+                        Rarity::Mythic
+                    } else {
+                        Rarity::Common
+                    };
+                    let color = [ColorSet::WHITE, ColorSet::BLUE, ColorSet::RED][i % 3];
+                    card(&format!("R{round}S{seat}C{i}"), rarity, color)
+                })
+                .collect();
+            packs.push(BoosterPack { cards });
+        }
+    }
+    packs
+}
+
+fn run(seed: u64) -> DraftState {
+    let config = DraftConfig::default();
+    let mut state = DraftState::new(config, packs(config, 15), seed).unwrap();
+    let random = RandomPicker;
+    let rarity = RarityPicker;
+    let commit = ColorCommitPicker::default();
+    let bots: Vec<&dyn PickStrategy> = vec![
+        &random, &rarity, &commit, &random, &rarity, &commit, &random, &rarity,
+    ];
+    state.run(&bots).unwrap();
+    state
+}
+
+#[test]
+fn test_full_draft() {
+    let state = run(1);
+    assert!(state.is_finished());
+    assert!(state.players.iter().all(|p| p.pool.len() == 45));
+    assert!(state.players.iter().all(|p| p.current_pack.is_none()));
+
+    let log = state.log();
+    assert_eq!(log.picks.len(), 360);
+    let seat_picks: Vec<(u32, u32)> = log.picks_by(3).map(|p| (p.round, p.pick)).collect();
+    let expected: Vec<(u32, u32)> = (1..=3)
+        .flat_map(|r| (1..=15).map(move |p| (r, p)))
+        .collect();
+    assert_eq!(seat_picks, expected);
+
+    // Rarity bots open with the mythic in their own pack.
+    let first = log.picks_by(1).next().unwrap();
+    assert_eq!(&*first.card.name, "R1S1C0");
+}
+
+#[test]
+fn test_pass_direction() {
+    let config = DraftConfig::default();
+    let mut state = DraftState::new(config, packs(config, 15), 0).unwrap();
+    assert_eq!(state.direction, Direction::Left);
+    for seat in 0..8 {
+        state.make_pick(seat, 0).unwrap();
+    }
+    assert_eq!(state.pick, 2);
+    assert!(
+        state.current_pack(1).unwrap().cards[0]
+            .name
+            .starts_with("R1S0")
+    );
+    assert!(
+        state.current_pack(0).unwrap().cards[0]
+            .name
+            .starts_with("R1S7")
+    );
+    assert_eq!(state.players[1].passed.len(), 14);
+
+    for _ in 0..14 {
+        for seat in 0..8 {
+            state.make_pick(seat, 0).unwrap();
+        }
+    }
+    assert_eq!((state.round, state.pick), (2, 1));
+    assert_eq!(state.direction, Direction::Right);
+    for seat in 0..8 {
+        state.make_pick(seat, 0).unwrap();
+    }
+    assert!(
+        state.current_pack(1).unwrap().cards[0]
+            .name
+            .starts_with("R2S2")
+    );
+    assert!(
+        state.current_pack(7).unwrap().cards[0]
+            .name
+            .starts_with("R2S0")
+    );
+}
+
+#[test]
+fn test_deterministic_and_replayable() {
+    let names = |state: &DraftState| -> Vec<String> {
+        state
+            .log()
+            .picks
+            .iter()
+            .map(|p| p.card.name.to_string())
+            .collect()
+    };
+    let a = run(99);
+    assert_eq!(names(&a), names(&run(99)));
+    assert_ne!(names(&a), names(&run(100)));
+
+    let json = a.log().to_json().unwrap();
+    let log = PickLog::from_json(&json).unwrap();
+    assert_eq!(&log, a.log());
+
+    let replayed = DraftState::replay(&log).unwrap();
+    assert!(replayed.is_finished());
+    assert_eq!(replayed.players, a.players);
+
+    let mut tampered = log.clone();
+    tampered.picks.swap(0, 8);
+    assert!(matches!(
+        DraftState::replay(&tampered),
+        Err(DraftError::InvalidPick(_))
+    ));
+}
+
+#[test]
+fn test_invalid_moves() {
+    let config = DraftConfig {
+        seats: 2,
+        rounds: 1,
+    };
+    assert!(matches!(
+        DraftState::new(config, packs(config, 3)[..1].to_vec(), 0),
+        Err(DraftError::PackCount {
+            expected: 2,
+            found: 1
+        })
+    ));
+
+    let mut state = DraftState::new(config, packs(config, 3), 0).unwrap();
+    assert!(state.make_pick(0, 3).is_err());
+    state.make_pick(0, 0).unwrap();
+    assert!(state.make_pick(0, 0).is_err(), "one pick per pass");
+    assert!(state.make_pick(2, 0).is_err());
+    state.make_pick(1, 2).unwrap();
+    assert_eq!(state.pick, 2);
+}
+
+#[test]
+fn test_color_commit() {
+    let picker = ColorCommitPicker { commit_after: 3 };
+    let red = |n: &str| card(n, Rarity::Common, ColorSet::RED);
+    let green = |n: &str| card(n, Rarity::Common, ColorSet::GREEN);
+    let pool = vec![
+        red("a"),
+        red("b"),
+        card("c", Rarity::Common, ColorSet::WHITE),
+    ];
+    let passed = vec![
+        card("d", Rarity::Uncommon, ColorSet::GREEN),
+        card("e", Rarity::Rare, ColorSet::GREEN),
+        card("f", Rarity::Mythic, ColorSet::GREEN),
+        card("g", Rarity::Common, ColorSet::WHITE),
+    ];
+    assert_eq!(
+        ColorCommitPicker::committed_colors(&pool, &passed),
+        ColorSet::RED | ColorSet::GREEN
+    );
+
+    let pack = vec![
+        card("Blue Rare", Rarity::Rare, ColorSet::BLUE),
+        red("Red Common"),
+        green("Green Common"),
+        card(
+            "Boros Uncommon",
+            Rarity::Uncommon,
+            ColorSet::RED | ColorSet::WHITE,
+        ),
+    ];
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let ctx = PickContext {
+        pack: &pack,
+        pool: &pool,
+        passed: &passed,
+        round: 1,
+        pick: 4,
+    };
+    let pick = picker.pick(&ctx, &mut rng);
+    assert!(pick == 1 || pick == 2, "picked {}", pack[pick].name);
+
+    // Before committing it takes the best card, leaning towards its colors.
+    let ctx = PickContext {
+        pool: &pool[..1],
+        ..ctx
+    };
+    assert_eq!(picker.pick(&ctx, &mut rng), 0);
+    let pack = vec![green("Green Common"), red("Red Common")];
+    let ctx = PickContext {
+        pack: &pack,
+        passed: &[],
+        ..ctx
+    };
+    assert_eq!(picker.pick(&ctx, &mut rng), 1);
+}
@@ -0,0 +1,163 @@
+use elbrus_core::{Rarity, color::ColorSet};
+use elbrus_draft::{
+    BoosterPack, DraftConfig, DraftError, DraftFormat, DraftState, GridDraft, GridLine, PickLog,
+    PickStrategy, RandomPicker, RarityPicker, RochesterDraft, SEALED_BOOSTERS, WinstonDraft,
+    sealed,
+};
+
+mod common;
+
+fn packs(count: usize, size: usize) -> Vec<BoosterPack> {
+    (0..count)
+        .map(|p| BoosterPack {
+            cards: (0..size)
+                .map(|i| {
+                    let rarity = if i == 0 { Rarity::Rare } else { Rarity::Common };
+                    common::card(&format!("P{p}C{i}"), rarity, ColorSet::GREEN)
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn names(log: &PickLog) -> Vec<(usize, String)> {
+    log.picks
+        .iter()
+        .map(|p| (p.seat, p.card.name.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_rochester_snake_order() {
+    let config = DraftConfig {
+        seats: 3,
+        rounds: 2,
+    };
+    let mut draft = RochesterDraft::new(config, packs(6, 7), 1).unwrap();
+    let mut order = Vec::new();
+    while let Some(seat) = draft.current_seat() {
+        order.push(seat);
+        draft.make_pick(0).unwrap();
+    }
+    // Round one passes left from each opener, snaking back at the end.
+    assert_eq!(&order[..7], [0, 1, 2, 2, 1, 0, 0]);
+    assert_eq!(&order[7..14], [1, 2, 0, 0, 2, 1, 1]);
+    // Round two passes right.
+    assert_eq!(&order[21..28], [0, 2, 1, 1, 2, 0, 0]);
+    assert_eq!(draft.log().picks.len(), 42);
+    assert!(draft.players.iter().all(|p| p.passed.len() == 42));
+}
+
+#[test]
+fn test_rochester_replay() {
+    let config = DraftConfig {
+        seats: 4,
+        rounds: 3,
+    };
+    let mut draft = RochesterDraft::new(config, packs(12, 10), 5).unwrap();
+    draft.run(&[&RarityPicker as &dyn PickStrategy; 4]).unwrap();
+    assert!(draft.is_finished());
+    assert!(draft.players.iter().all(|p| p.pool.len() == 30));
+
+    let log = PickLog::from_json(&draft.log().to_json().unwrap()).unwrap();
+    assert_eq!(log.format, DraftFormat::Rochester);
+    let replayed = RochesterDraft::replay(&log).unwrap();
+    assert_eq!(replayed.log().picks, draft.log().picks);
+    assert!(matches!(
+        DraftState::replay(&log),
+        Err(DraftError::WrongFormat {
+            expected: DraftFormat::Booster,
+            found: DraftFormat::Rochester
+        })
+    ));
+}
+
+#[test]
+fn test_winston_passing() {
+    let mut draft = WinstonDraft::new(packs(1, 6), 2);
+    assert_eq!(draft.deck_len(), 3);
+    assert_eq!(draft.current_pile().len(), 1);
+
+    assert_eq!(draft.pass().unwrap(), None);
+    assert_eq!(draft.pile_index(), 1);
+    assert_eq!(draft.pass().unwrap(), None);
+    assert!(!draft.can_pass(), "one card left for the last pile");
+    let taken = draft.take().unwrap();
+    assert_eq!(taken.len(), 1);
+    assert_eq!(draft.active, 1);
+    assert_eq!(draft.pile_index(), 0);
+    assert_eq!(draft.current_pile().len(), 2);
+    assert_eq!(draft.deck_len(), 0);
+}
+
+#[test]
+fn test_winston_run_and_replay() {
+    let mut draft = WinstonDraft::new(packs(6, 15), 9);
+    draft.run([&RarityPicker, &RandomPicker]).unwrap();
+    assert!(draft.is_finished());
+    let total: usize = draft.players.iter().map(|p| p.pool.len()).sum();
+    assert_eq!(total, 90, "every card ends up in a pool");
+
+    let log = draft.log();
+    assert_eq!(log.format, DraftFormat::Winston);
+    assert_eq!(log.pools().iter().map(Vec::len).sum::<usize>(), 90);
+    for (turn, pair) in log.turns().zip(0..) {
+        assert_eq!(turn[0].seat, pair % 2, "players alternate");
+    }
+    let replayed = WinstonDraft::replay(log).unwrap();
+    assert_eq!(names(replayed.log()), names(log));
+}
+
+#[test]
+fn test_grid_draft() {
+    let mut draft = GridDraft::new(packs(2, 10), 2, 4).unwrap();
+    assert_eq!(draft.log().packs.len(), 2, "twenty cards make two grids");
+
+    let row = draft.line(GridLine::Row(0));
+    assert_eq!(draft.take_line(GridLine::Row(0)).unwrap(), row);
+    assert_eq!(draft.current_seat(), Some(1));
+    assert_eq!(draft.line(GridLine::Column(1)).len(), 2);
+    assert_eq!(draft.take_line(GridLine::Column(1)).unwrap().len(), 2);
+    assert_eq!(draft.round, 2, "leftovers discarded");
+    assert_eq!(draft.current_seat(), Some(1), "first chooser moves along");
+    draft.take_line(GridLine::Column(0)).unwrap();
+    assert!(matches!(
+        draft.take_line(GridLine::Column(0)),
+        Err(DraftError::InvalidPick(_))
+    ));
+}
+
+#[test]
+fn test_grid_run_and_replay() {
+    let mut draft = GridDraft::new(packs(6, 15), 2, 8).unwrap();
+    draft.run(&[&RarityPicker as &dyn PickStrategy; 2]).unwrap();
+    assert!(draft.is_finished());
+    assert_eq!(draft.log().rounds, 10);
+    for player in &draft.players {
+        assert!((20..=30).contains(&player.pool.len()));
+    }
+
+    let replayed = GridDraft::replay(draft.log()).unwrap();
+    assert_eq!(names(replayed.log()), names(draft.log()));
+}
+
+#[test]
+fn test_sealed_pools() {
+    let config = DraftConfig::sealed(2);
+    let log = sealed(config, packs(12, 14), 0).unwrap();
+    assert_eq!(log.format, DraftFormat::Sealed);
+    assert_eq!(log.rounds, SEALED_BOOSTERS);
+    let pools = log.pools();
+    assert_eq!(pools.len(), 2);
+    assert!(pools.iter().all(|p| p.len() == 84));
+    assert_eq!(&*pools[1][0].name, "P6C0");
+    assert_eq!(log.picks.last().unwrap().round, 6);
+
+    assert!(matches!(
+        sealed(config, packs(11, 14), 0),
+        Err(DraftError::PackCount {
+            expected: 12,
+            found: 11
+        })
+    ));
+}
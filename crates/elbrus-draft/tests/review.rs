@@ -0,0 +1,190 @@
+use common::{insert, oracle, printing};
+use elbrus_core::{CardType, Rarity, color::ColorSet};
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_draft::{
+    BoosterPack, CsvRatings, DraftCard, DraftConfig, DraftError, DraftFormat, DraftState,
+    ImportedDraft, PickLog, PickStrategy, RarityPicker, review_picks,
+};
+use uuid::Uuid;
+
+mod common;
+
+const MTGO_LOG: &str = "\
+Event #: 8675309
+Time:    10/1/2026 8:00:00 PM
+Players:
+    Alice
+--> Bob
+    Carol
+
+------ MKM ------
+
+Pack 1 pick 1:
+    Pack Rat
+--> Lightning Bolt
+    Mystery Card
+
+Pack 1 pick 2:
+--> Pack Rat
+
+------ OTJ ------
+
+Pack 2 pick 1:
+--> Shock
+    Lightning Bolt
+";
+
+const RATINGS: &str =
+    "Name,Rating\nLightning Bolt,3.0\nPack Rat,4.5\nMystery Card,1.0\nShock,2.0\n";
+
+async fn insert_card(db: &SqliteBackend, name: &str, set_code: &str, rarity: Rarity) {
+    let card = oracle(name, "{R}", ColorSet::RED, CardType::Instant);
+    insert(db, &card, &[printing(&card, set_code, rarity)]).await;
+}
+
+fn card(name: &str, rarity: Rarity) -> DraftCard {
+    common::card(name, rarity, ColorSet::RED)
+}
+
+#[test]
+fn test_parse_mtgo() {
+    let draft = ImportedDraft::parse(MTGO_LOG).unwrap();
+    assert_eq!(draft.event.as_deref(), Some("8675309"));
+    assert_eq!(draft.players, ["Alice", "Bob", "Carol"]);
+    assert_eq!(draft.seat, 1);
+    assert_eq!(draft.sets, ["MKM", "OTJ"]);
+    let picks: Vec<(u32, u32, &str, usize)> = draft
+        .picks
+        .iter()
+        .map(|p| (p.round, p.pick, &*p.taken, p.pack.len()))
+        .collect();
+    assert_eq!(
+        picks,
+        [
+            (1, 1, "Lightning Bolt", 3),
+            (1, 2, "Pack Rat", 1),
+            (2, 1, "Shock", 2),
+        ]
+    );
+    assert_eq!(&*draft.picks[0].pack[0], "Pack Rat", "a card, not a header");
+    assert_eq!(draft.set_for(2), Some("OTJ"));
+
+    assert!(matches!(
+        ImportedDraft::parse("Pack 1 pick 1:\n    Shock\n    Bolt\n"),
+        Err(DraftError::InvalidDraftLog(_))
+    ));
+}
+
+#[test]
+fn test_parse_arena() {
+    let draft = ImportedDraft::parse(
+        r#"{
+            "EventName": "PremierDraft_MKM",
+            "expansion": "MKM",
+            "picks": [
+                {"packNumber": 0, "pickNumber": 0, "cardsInPack": ["Shock", "Pack Rat"], "pickedCard": "Pack Rat"},
+                {"packNumber": 0, "pickNumber": 1, "cardsInPack": ["Lightning Bolt"], "pickedCard": "Lightning Bolt"}
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(draft.event.as_deref(), Some("PremierDraft_MKM"));
+    assert_eq!(draft.set_for(3), Some("MKM"), "one set for every round");
+    let picks: Vec<(u32, u32, &str)> = draft
+        .picks
+        .iter()
+        .map(|p| (p.round, p.pick, &*p.taken))
+        .collect();
+    assert_eq!(picks, [(1, 1, "Pack Rat"), (1, 2, "Lightning Bolt")]);
+
+    assert!(matches!(
+        ImportedDraft::parse(
+            r#"{"picks": [{"pack_number": 1, "pick_number": 1, "pack": ["Shock"], "pick": "Bolt"}]}"#
+        ),
+        Err(DraftError::InvalidDraftLog(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_resolve_and_review() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    insert_card(&db, "Lightning Bolt", "MKM", Rarity::Uncommon).await;
+    insert_card(&db, "Pack Rat", "MKM", Rarity::Rare).await;
+    insert_card(&db, "Shock", "M10", Rarity::Common).await;
+
+    let draft = ImportedDraft::parse(MTGO_LOG).unwrap();
+    let (log, unresolved) = draft.resolve(&db).await.unwrap();
+    assert_eq!(unresolved, ["Mystery Card".into()]);
+    assert_eq!(log.seats, 3);
+    assert_eq!(log.rounds, 2);
+    assert_eq!(log.picks_by(1).count(), 3);
+    assert_eq!(log.seen.len(), 3);
+    assert_eq!(log.picks[0].card.rarity, Rarity::Uncommon);
+    assert!(log.picks[0].card.printing_id.is_some());
+    assert_eq!(log.seen[0].cards[2].oracle_id, Uuid::nil());
+    assert!(
+        log.picks[2].card.printing_id.is_none(),
+        "not printed in OTJ"
+    );
+    assert_eq!(PickLog::from_json(&log.to_json().unwrap()).unwrap(), log);
+
+    let ratings = CsvRatings::parse(RATINGS).unwrap();
+    let reviews = review_picks(&log, 1, &ratings).unwrap();
+    let summary: Vec<(&str, Option<&str>, usize, f64)> = reviews
+        .iter()
+        .map(|r| {
+            (
+                &*r.taken.card.name,
+                r.best_alternative().map(|c| &*c.card.name),
+                r.rank(),
+                r.rating_lost(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("Lightning Bolt", Some("Pack Rat"), 2, 1.5),
+            ("Pack Rat", None, 1, 0.0),
+            ("Shock", Some("Lightning Bolt"), 2, 1.0),
+        ]
+    );
+    assert_eq!(reviews[0].passed[1].rating, 1.0);
+    assert!(review_picks(&log, 0, &ratings).unwrap().is_empty());
+}
+
+#[test]
+fn test_review_simulated_draft() {
+    let packs: Vec<BoosterPack> = (0..2)
+        .map(|seat| BoosterPack {
+            cards: vec![
+                card(&format!("Common {seat}"), Rarity::Common),
+                card(&format!("Rare {seat}"), Rarity::Rare),
+                card(&format!("Uncommon {seat}"), Rarity::Uncommon),
+            ],
+        })
+        .collect();
+    let config = DraftConfig {
+        seats: 2,
+        rounds: 1,
+    };
+    let mut draft = DraftState::new(config, packs, 7).unwrap();
+    let bots: [&dyn PickStrategy; 2] = [&RarityPicker, &RarityPicker];
+    draft.run(&bots).unwrap();
+    let mut log = draft.into_log();
+
+    let ratings = CsvRatings::parse("Name,Rating\nRare 0,4\nUncommon 1,3.5\n").unwrap();
+    let reviews = review_picks(&log, 0, &ratings).unwrap();
+    let sizes: Vec<usize> = reviews.iter().map(|r| r.passed.len()).collect();
+    assert_eq!(sizes, [2, 1, 0]);
+    assert_eq!(&*reviews[0].taken.card.name, "Rare 0");
+    assert_eq!(reviews[0].rank(), 1);
+    // Seat 1 passed its uncommon on, so seat 0 sees it at pick 2.
+    assert_eq!(&*reviews[1].taken.card.name, "Uncommon 1");
+
+    log.format = DraftFormat::Winston;
+    assert!(matches!(
+        review_picks(&log, 0, &ratings),
+        Err(DraftError::WrongFormat { .. })
+    ));
+}
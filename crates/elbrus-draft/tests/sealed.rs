@@ -0,0 +1,218 @@
+use common::oracle;
+use elbrus_core::{CardType, Color, OracleCard, Rarity, color::ColorSet, types::Supertype};
+use elbrus_draft::{
+    CardRating, CsvRatings, DraftCard, DraftError, HeuristicRating, SealedBuilder, rank_pool,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+mod common;
+
+fn basic(color: Color) -> OracleCard {
+    let mut card = oracle(&color.land(), "", ColorSet::empty(), CardType::Land);
+    card.faces[0].type_line.supertypes = smallvec::smallvec![Supertype::Basic];
+    card
+}
+
+/// A pool of `count` copies of each card, with the oracle cards by id.
+fn pool(cards: &[(OracleCard, usize)]) -> (Vec<DraftCard>, HashMap<Uuid, OracleCard>) {
+    let mut pool = Vec::new();
+    let mut oracles = HashMap::new();
+    for (card, count) in cards {
+        for _ in 0..*count {
+            pool.push(DraftCard::from_oracle(card, Rarity::Common));
+        }
+        oracles.insert(card.oracle_id, card.clone());
+    }
+    (pool, oracles)
+}
+
+#[test]
+fn test_csv_ratings() {
+    let ratings = CsvRatings::parse(
+        "Name,Color,Rating\n\
+         Lightning Bolt,R,4.0\n\
+         Fire,UR,B+\n\
+         Counterspell,U,C-\n\
+         Plains,,\n",
+    )
+    .unwrap();
+    assert_eq!(ratings.len(), 3);
+    let card = |name: &str| {
+        DraftCard::from_oracle(
+            &oracle(name, "{R}", ColorSet::RED, CardType::Instant),
+            Rarity::Common,
+        )
+    };
+    assert_eq!(ratings.rate(&card("lightning bolt")), Some(4.0));
+    assert_eq!(ratings.rate(&card("Fire // Ice")), Some(3.5));
+    assert_eq!(ratings.rate(&card("Counterspell")), Some(1.0));
+    assert_eq!(ratings.rate(&card("Shock")), None);
+    assert_eq!(
+        ratings.rate_or_guess(&card("Shock")),
+        HeuristicRating::grade(&card("Shock"))
+    );
+
+    assert!(matches!(
+        CsvRatings::parse("Name,Grade\nShock,B\n"),
+        Err(DraftError::InvalidRatings(_))
+    ));
+    assert!(matches!(
+        CsvRatings::parse("Name,Rating\nShock,Z\n"),
+        Err(DraftError::InvalidRatings(_))
+    ));
+}
+
+#[test]
+fn test_heuristic_rating() {
+    let bear = oracle("Bear", "{1}{G}", ColorSet::GREEN, CardType::Creature);
+    let giant = oracle("Giant", "{7}{G}", ColorSet::GREEN, CardType::Creature);
+    let (cards, _) = pool(&[(bear, 1), (giant, 1), (basic(Color::G), 1)]);
+    let mythic = DraftCard {
+        rarity: Rarity::Mythic,
+        ..cards[0].clone()
+    };
+
+    let ranked = rank_pool(
+        &[cards[2].clone(), cards[1].clone(), cards[0].clone(), mythic],
+        &HeuristicRating,
+    );
+    let order: Vec<(&str, Rarity)> = ranked
+        .iter()
+        .map(|r| (&*r.card.name, r.card.rarity))
+        .collect();
+    assert_eq!(
+        order,
+        [
+            ("Bear", Rarity::Mythic),
+            ("Bear", Rarity::Common),
+            ("Giant", Rarity::Common),
+            ("Forest", Rarity::Common),
+        ]
+    );
+    assert_eq!(ranked[3].rating, 0.0);
+}
+
+#[test]
+fn test_build_with_splash() {
+    let mut cards = Vec::new();
+    let mut csv = String::from("Name,Rating\n");
+    for i in 0..12 {
+        let name = format!("White {i}");
+        cards.push((
+            oracle(&name, "{1}{W}", ColorSet::WHITE, CardType::Creature),
+            1,
+        ));
+        csv += &format!("{name},3.5\n");
+        let name = format!("Blue {i}");
+        cards.push((
+            oracle(&name, "{2}{U}", ColorSet::BLUE, CardType::Instant),
+            1,
+        ));
+        csv += &format!("{name},3.0\n");
+    }
+    cards.push((
+        oracle("Black", "{B}", ColorSet::BLACK, CardType::Creature),
+        5,
+    ));
+    cards.push((
+        oracle("Red Bomb", "{3}{R}", ColorSet::RED, CardType::Sorcery),
+        1,
+    ));
+    cards.push((
+        oracle("Double Red", "{R}{R}", ColorSet::RED, CardType::Creature),
+        1,
+    ));
+    csv += "Black,1.0\nRed Bomb,5.0\nDouble Red,5.0\n";
+    let plains = basic(Color::W);
+    let plains_id = plains.oracle_id;
+    let (pool, mut oracles) = pool(&cards);
+    oracles.insert(plains_id, plains);
+    let ratings = CsvRatings::parse(&csv).unwrap();
+
+    let builder = SealedBuilder::default();
+    let builds = builder.builds(&pool, &ratings, &oracles);
+    assert_eq!(builds[0].colors, ColorSet::WHITE | ColorSet::BLUE);
+    assert_eq!(builds[0].splash, Some(Color::R));
+    assert_eq!(builds[1].splash, None, "the unsplashed build comes next");
+    assert!((builds[0].score - builds[1].score - 1.5).abs() < 1e-9);
+
+    let sealed = builder.build(&pool, &ratings, &oracles).unwrap();
+    let deck = &sealed.deck;
+    assert_eq!(deck.name.as_deref(), Some("Sealed WU+r"));
+    let count = |name: &str| {
+        deck.mainboard
+            .iter()
+            .find(|e| &*e.card_name == name)
+            .map_or(0, |e| e.quantity)
+    };
+    assert_eq!(count("Red Bomb"), 1);
+    assert_eq!(count("Double Red"), 0, "two pips is too many to splash");
+    assert_eq!(count("Mountain"), 3);
+    assert_eq!(count("Plains"), 8);
+    assert_eq!(count("Island"), 6);
+    let plains_entry = deck.mainboard.iter().find(|e| &*e.card_name == "Plains");
+    assert_eq!(plains_entry.unwrap().resolved, Some(plains_id));
+    assert_eq!(deck.mainboard.iter().map(|e| e.quantity).sum::<u32>(), 40);
+    assert_eq!(deck.sideboard.iter().map(|e| e.quantity).sum::<u32>(), 8);
+    assert_eq!(sealed.mana.deck_size, 40);
+    assert_eq!(sealed.mana.curve.total(), 23);
+}
+
+#[test]
+fn test_build_short_pool_with_nonbasic_lands() {
+    let mut cards = Vec::new();
+    for i in 0..8 {
+        cards.push((
+            oracle(
+                &format!("White {i}"),
+                "{1}{W}",
+                ColorSet::WHITE,
+                CardType::Creature,
+            ),
+            1,
+        ));
+        cards.push((
+            oracle(
+                &format!("Blue {i}"),
+                "{2}{U}",
+                ColorSet::BLUE,
+                CardType::Instant,
+            ),
+            1,
+        ));
+    }
+    let land = |name: &str, identity: ColorSet| {
+        let mut card = oracle(name, "", ColorSet::empty(), CardType::Land);
+        card.color_identity = identity;
+        card
+    };
+    cards.push((
+        land("Azorius Guildgate", ColorSet::WHITE | ColorSet::BLUE),
+        2,
+    ));
+    cards.push((land("Rakdos Guildgate", ColorSet::BLACK | ColorSet::RED), 1));
+    cards.push((land("Evolving Wilds", ColorSet::empty()), 1));
+    cards.push((basic(Color::W), 2));
+    let (pool, oracles) = pool(&cards);
+
+    let builder = SealedBuilder::default();
+    let sealed = builder.build(&pool, &HeuristicRating, &oracles).unwrap();
+    assert_eq!(sealed.build.colors, ColorSet::WHITE | ColorSet::BLUE);
+    assert_eq!(sealed.build.spells.len(), 16);
+    assert_eq!(sealed.build.lands.len(), 2);
+    let deck = &sealed.deck;
+    let count = |name: &str| {
+        deck.mainboard
+            .iter()
+            .find(|e| &*e.card_name == name)
+            .map_or(0, |e| e.quantity)
+    };
+    assert_eq!(count("Azorius Guildgate"), 2);
+    assert_eq!(count("Rakdos Guildgate"), 0);
+    assert_eq!(count("Evolving Wilds"), 0);
+    // Basics make up for the seven spells the pool lacks.
+    assert_eq!(count("Plains") + count("Island"), 22);
+    assert_eq!(deck.mainboard.iter().map(|e| e.quantity).sum::<u32>(), 40);
+    assert_eq!(deck.sideboard.iter().map(|e| e.quantity).sum::<u32>(), 4);
+}
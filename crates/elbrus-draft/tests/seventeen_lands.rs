@@ -0,0 +1,171 @@
+use elbrus_core::{ColorSet, Rarity};
+use elbrus_db::sqlite::SqliteBackend;
+use elbrus_draft::{
+    CardRating, CardStats, Dataset, DraftCard, DraftError, LimitedRatings, PickContext,
+    PickStrategy, RatingPicker, load_card_stats,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::sync::Arc;
+
+mod common;
+
+const GAME_DATA: &str = "\
+expansion,event_type,draft_id,main_colors,splash_colors,won,opening_hand_Bolt,drawn_Bolt,deck_Bolt,sideboard_Bolt,opening_hand_Bear,drawn_Bear,deck_Bear,sideboard_Bear
+MKM,PremierDraft,d1,WR,,True,1,0,1,0,0,0,2,0
+MKM,PremierDraft,d1,WR,,False,0,0,1,0,0,1,2,0
+MKM,PremierDraft,d2,G,,True,0,0,0,1,0,1,1,0
+";
+
+const DRAFT_DATA: &str = "\
+expansion,event_type,draft_id,draft_time,rank,pack_number,pick_number,pick,pack_card_Bolt,pack_card_Bear,pack_card_Shock,pool_Bolt,pool_Bear,pool_Shock
+MKM,PremierDraft,d1,,gold,0,0,Bolt,1,1,1,0,0,0
+MKM,PremierDraft,d1,,gold,0,1,Shock,0,1,1,1,0,0
+MKM,PremierDraft,d2,,gold,0,0,Bear,1,1,0,0,0,0
+";
+
+fn card(name: &str, colors: ColorSet) -> DraftCard {
+    common::card(name, Rarity::Common, colors)
+}
+
+fn stats(name: &str, games_in_hand: u64, gih_win_rate: f64) -> CardStats {
+    CardStats {
+        name: name.into(),
+        games_in_hand,
+        gih_win_rate: Some(gih_win_rate),
+        iwd: None,
+        alsa: None,
+        ata: None,
+    }
+}
+
+#[test]
+fn test_parse_game_data() {
+    let Dataset::Games(games) = Dataset::parse(GAME_DATA.as_bytes()).unwrap() else {
+        panic!("read as draft data");
+    };
+    let summary: Vec<(&str, ColorSet, u64, u64)> = games
+        .iter()
+        .map(|g| (&*g.name, g.colors, g.games_in_deck, g.games_in_hand))
+        .collect();
+    let wr = ColorSet::WHITE | ColorSet::RED;
+    assert_eq!(
+        summary,
+        [
+            ("Bear", wr, 2, 1),
+            ("Bolt", wr, 2, 1),
+            ("Bear", ColorSet::GREEN, 1, 1),
+        ]
+    );
+    let bolt = &games[1];
+    assert_eq!(bolt.gih_win_rate(), Some(1.0));
+    assert_eq!(bolt.gns_win_rate(), Some(0.0));
+    assert_eq!(bolt.iwd(), Some(1.0));
+    assert_eq!(games[0].wins_not_seen, 1);
+}
+
+#[test]
+fn test_parse_draft_data() {
+    let Dataset::Drafts(drafts) = Dataset::parse(DRAFT_DATA.as_bytes()).unwrap() else {
+        panic!("read as game data");
+    };
+    let summary: Vec<(&str, Option<f64>, Option<f64>)> = drafts
+        .iter()
+        .map(|d| (&*d.name, d.alsa(), d.ata()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("Bear", Some(1.5), Some(1.0)),
+            ("Bolt", Some(1.0), Some(1.0)),
+            ("Shock", Some(2.0), Some(2.0)),
+        ]
+    );
+
+    assert!(matches!(
+        Dataset::parse("name,rating\nBolt,4\n".as_bytes()),
+        Err(DraftError::InvalidDataset(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_store_and_load_stats() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    Dataset::parse(GAME_DATA.as_bytes())
+        .unwrap()
+        .store(&db, "game_data_mkm")
+        .await
+        .unwrap();
+    Dataset::parse(DRAFT_DATA.as_bytes())
+        .unwrap()
+        .store(&db, "draft_data_mkm")
+        .await
+        .unwrap();
+
+    let all = load_card_stats(&db, "mkm", None, None).await.unwrap();
+    let bear = all.iter().find(|s| &*s.name == "Bear").unwrap();
+    assert_eq!(bear.games_in_hand, 2);
+    assert_eq!(bear.gih_win_rate, Some(0.5));
+    assert_eq!(bear.alsa, Some(1.5));
+    let shock = all.iter().find(|s| &*s.name == "Shock").unwrap();
+    assert_eq!(shock.gih_win_rate, None, "drafted but never played");
+
+    let green = load_card_stats(&db, "MKM", Some("PremierDraft"), Some(ColorSet::GREEN))
+        .await
+        .unwrap();
+    let bear = green.iter().find(|s| &*s.name == "Bear").unwrap();
+    assert_eq!(bear.gih_win_rate, Some(1.0));
+    assert_eq!(bear.ata, Some(1.0), "draft stats aren't split by color");
+}
+
+#[test]
+fn test_limited_ratings() {
+    let ratings = LimitedRatings::new(
+        &[
+            stats("Good", 500, 0.60),
+            stats("Average", 500, 0.55),
+            stats("Bad", 500, 0.50),
+            stats("Rare Sighting", 10, 0.90),
+        ],
+        200,
+    );
+    assert_eq!(ratings.len(), 3);
+    let rate = |name: &str| ratings.rate(&card(name, ColorSet::WHITE));
+    assert!((rate("average").unwrap() - 2.5).abs() < 1e-9);
+    assert!(rate("Good").unwrap() > 3.5);
+    assert!(rate("Bad").unwrap() < 1.5);
+    assert_eq!(rate("Rare Sighting"), None, "too few games");
+}
+
+#[test]
+fn test_rating_picker() {
+    let ratings = LimitedRatings::new(
+        &[
+            stats("Red Bomb", 500, 0.58),
+            stats("White Card", 500, 0.57),
+            stats("Filler", 500, 0.50),
+        ],
+        200,
+    );
+    let picker = RatingPicker::new(Arc::new(ratings));
+    let pack = [
+        card("Filler", ColorSet::WHITE),
+        card("Red Bomb", ColorSet::RED),
+        card("White Card", ColorSet::WHITE),
+    ];
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let mut ctx = PickContext {
+        pack: &pack,
+        pool: &[],
+        passed: &[],
+        round: 1,
+        pick: 1,
+    };
+    assert_eq!(picker.pick(&ctx, &mut rng), 1, "best card early on");
+
+    let pool: Vec<DraftCard> = (0..5)
+        .map(|i| card(&format!("W{i}"), ColorSet::WHITE | ColorSet::BLUE))
+        .collect();
+    ctx.pool = &pool;
+    assert_eq!(picker.pick(&ctx, &mut rng), 2, "stays in its colors");
+}
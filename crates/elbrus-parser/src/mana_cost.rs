@@ -1,53 +1,77 @@
-use elbrus_core::{GenericCost, ManaCost, ManaSymbol};
+use elbrus_core::{Color, GenericCost, ManaCost, ManaSymbol};
 use nom::{
     IResult, Parser,
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_till1},
     character::complete::char,
-    combinator::{map, value},
+    combinator::{all_consuming, eof, map, map_parser, rest, value},
     multi::many0,
-    sequence::delimited,
+    sequence::{delimited, separated_pair, terminated},
 };
+use std::sync::Arc;
+
 fn symbol(input: &str) -> IResult<&str, ManaSymbol> {
-    delimited(char('{'), symbol_inner, char('}')).parse(input)
+    // Parse each `{...}` body on its own so a branch that matches only a
+    // prefix (`C` of `C/W`) fails and the next branch gets a turn.
+    map_parser(
+        delimited(char('{'), take_till1(|c| c == '}'), char('}')),
+        symbol_inner,
+    )
+    .parse(input)
 }
 
 fn symbol_inner(input: &str) -> IResult<&str, ManaSymbol> {
     alt((
-        hybrid_phyrexian, // {W/P} — most specific first
-        hybrid,           // {2/W}, {W/U}
-        phyrexian,        // {P} alone (less common)
-        colored,          // {W} {U} {B} {R} {G}
-        variable,         // {X}
-        colorless,        // {C}
-        snow,             // {S}
-        tap,              // {T}
-        generic,          // {1}, {12}, {100}
-        chaos,            // {CHAOS}
-        unknown_fallback,
+        terminated(hybrid_phyrexian, eof), // {G/U/P} — most specific first
+        terminated(hybrid, eof),           // {2/W}, {W/U}
+        terminated(phyrexian, eof),        // {W/P}
+        terminated(colored, eof),          // {W} {U} {B} {R} {G}
+        terminated(variable, eof),         // {X}
+        terminated(chaos, eof),            // {CHAOS}
+        terminated(colorless, eof),        // {C}
+        terminated(snow, eof),             // {S}
+        terminated(tap, eof),              // {T}
+        terminated(generic, eof),          // {1}, {12}, {100}
+        unknown_fallback,                  // {HW}, {C/W}, {D}, ...
     ))
     .parse(input)
 }
 
-fn hybrid_phyrexian(_input: &str) -> IResult<&str, ManaSymbol> {
-    todo!()
-}
-fn hybrid(_input: &str) -> IResult<&str, ManaSymbol> {
-    todo!()
+fn color(input: &str) -> IResult<&str, Color> {
+    alt((
+        value(Color::W, char('W')),
+        value(Color::U, char('U')),
+        value(Color::B, char('B')),
+        value(Color::R, char('R')),
+        value(Color::G, char('G')),
+    ))
+    .parse(input)
 }
-fn phyrexian(_input: &str) -> IResult<&str, ManaSymbol> {
-    todo!()
+
+fn hybrid_phyrexian(input: &str) -> IResult<&str, ManaSymbol> {
+    map(
+        terminated(separated_pair(color, char('/'), color), tag("/P")),
+        |(a, b)| ManaSymbol::HybridPhyrexian(a, b),
+    )
+    .parse(input)
 }
-fn colored(input: &str) -> IResult<&str, ManaSymbol> {
+fn hybrid(input: &str) -> IResult<&str, ManaSymbol> {
     alt((
-        value(ManaSymbol::Colored(elbrus_core::Color::W), tag("W")),
-        value(ManaSymbol::Colored(elbrus_core::Color::U), tag("U")),
-        value(ManaSymbol::Colored(elbrus_core::Color::B), tag("B")),
-        value(ManaSymbol::Colored(elbrus_core::Color::R), tag("R")),
-        value(ManaSymbol::Colored(elbrus_core::Color::G), tag("G")),
+        map(separated_pair(color, char('/'), color), |(a, b)| {
+            ManaSymbol::Hybrid(a, b)
+        }),
+        map(separated_pair(char('2'), char('/'), color), |(_, c)| {
+            ManaSymbol::TwoBrid(c)
+        }),
     ))
     .parse(input)
 }
+fn phyrexian(input: &str) -> IResult<&str, ManaSymbol> {
+    map(terminated(color, tag("/P")), ManaSymbol::Phyrexian).parse(input)
+}
+fn colored(input: &str) -> IResult<&str, ManaSymbol> {
+    map(color, ManaSymbol::Colored).parse(input)
+}
 
 fn variable(input: &str) -> IResult<&str, ManaSymbol> {
     alt((
@@ -73,15 +97,20 @@ fn generic(input: &str) -> IResult<&str, ManaSymbol> {
     })
     .parse(input)
 }
-fn chaos(_input: &str) -> IResult<&str, ManaSymbol> {
-    todo!()
+fn chaos(input: &str) -> IResult<&str, ManaSymbol> {
+    // Planar die symbol; there is no dedicated variant for it.
+    map(tag("CHAOS"), |s: &str| ManaSymbol::Unknown(Arc::from(s))).parse(input)
 }
-fn unknown_fallback(_input: &str) -> IResult<&str, ManaSymbol> {
-    todo!()
+fn unknown_fallback(input: &str) -> IResult<&str, ManaSymbol> {
+    map(rest, |s: &str| ManaSymbol::Unknown(Arc::from(s))).parse(input)
 }
 
 pub fn mana_cost(input: &str) -> IResult<&str, ManaCost> {
     map(many0(symbol), |symbols| ManaCost(symbols.into())).parse(input)
 }
 
-// pub fn parse(input: &str) -> Result<ManaCost, nom::Err<...>> { ... }
+/// Parse a complete mana cost such as `{2}{W/U}{W/U}`. Split-card costs
+/// joined with ` // ` are not a single cost and fail to parse.
+pub fn parse(input: &str) -> Result<ManaCost, nom::Err<nom::error::Error<&str>>> {
+    all_consuming(mana_cost).parse(input).map(|(_, cost)| cost)
+}
@@ -70,7 +70,40 @@ fn variable() {
     );
 }
 
-// #[test] TODO
-// fn complex() {
-//     assert_eq!(parse("{X}{2/W}{W/P}{G}"),);
-// }
+#[test]
+fn complex() {
+    assert_eq!(
+        parse("{X}{2/W}{W/P}{G}"),
+        ManaCost(smallvec![
+            ManaSymbol::Variable(VarSym::X),
+            ManaSymbol::TwoBrid(Color::W),
+            ManaSymbol::Phyrexian(Color::W),
+            ManaSymbol::Colored(Color::G),
+        ])
+    );
+}
+
+#[test]
+fn hybrid_phyrexian() {
+    assert_eq!(
+        parse("{G/U/P}"),
+        ManaCost(smallvec![ManaSymbol::HybridPhyrexian(Color::G, Color::U)])
+    );
+}
+
+#[test]
+fn unknown_fallback() {
+    assert_eq!(
+        parse("{C/W}{HW}"),
+        ManaCost(smallvec![
+            ManaSymbol::Unknown("C/W".into()),
+            ManaSymbol::Unknown("HW".into()),
+        ])
+    );
+}
+
+#[test]
+fn parse_whole_cost() {
+    assert!(elbrus_parser::mana_cost::parse("{1}{B}").is_ok());
+    assert!(elbrus_parser::mana_cost::parse("{1}{B} // {2}{U}").is_err());
+}
@@ -1,6 +1,7 @@
 use crate::models::{ScryfallCard, ScryfallError};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use elbrus_core::{OracleCard, Printing};
-use elbrus_db::repo::card::CardRepository;
+use elbrus_db::repo::{card::CardRepository, price::PriceRepository};
 use futures::Stream;
 use std::path::Path;
 
@@ -102,47 +103,170 @@ impl BulkIngestor {
     }
 
     /// Convenience: ingest directly into db.
-    pub async fn ingest_into_db(
-        &self,
-        path: &Path,
-        db: &dyn CardRepository,
-    ) -> Result<IngestStats, ScryfallError> {
+    ///
+    /// Every printing with prices also gets a `price_snapshots` row stamped
+    /// with the bulk file's timestamp (see [`snapshot_time`]), unless its
+    /// prices are unchanged since the previous snapshot. The run itself is
+    /// recorded under the same timestamp once every card is in.
+    pub async fn ingest_into_db<R>(&self, path: &Path, db: &R) -> Result<IngestStats, ScryfallError>
+    where
+        R: CardRepository + PriceRepository + ?Sized,
+    {
         use futures::StreamExt;
 
-        let mut stats = IngestStats {
-            cards_processed: 0,
-            cards_inserted: 0,
-            cards_updated: 0,
-            duration: std::time::Duration::from_secs(0),
-        };
+        let mut stats = IngestStats::default();
         let start = std::time::Instant::now();
+        let fetched_at = snapshot_time(path);
 
         // Wait, traits from other crates need to be available? StreamExt is imported.
         let mut stream = Box::pin(self.ingest_file(path));
 
         while let Some(res) = stream.next().await {
             let batch = res?;
-            for (oracle_card, printing) in batch {
+            for (oracle_card, mut printing) in batch {
+                if let Some(prices) = printing.prices.as_mut() {
+                    prices.fetched_at = fetched_at;
+                }
                 db.upsert_oracle(&oracle_card)
                     .await
                     .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
                 db.upsert_printing(&printing)
                     .await
                     .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+                record_snapshot(db, &printing, &mut stats).await?;
                 stats.cards_processed += 1;
                 stats.cards_inserted += 1;
             }
         }
 
+        db.record_ingest_run(fetched_at)
+            .await
+            .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+        stats.files_processed = 1;
+        stats.duration = start.elapsed();
+        Ok(stats)
+    }
+
+    /// Record price history from a directory of dated `default-cards-*.json` dumps,
+    /// oldest first.
+    ///
+    /// Existing printings are left untouched so an old dump never overwrites newer
+    /// card data; printings missing from the db are inserted so their snapshots
+    /// have something to reference.
+    pub async fn backfill_prices<R>(&self, dir: &Path, db: &R) -> Result<IngestStats, ScryfallError>
+    where
+        R: CardRepository + PriceRepository + ?Sized,
+    {
+        use futures::StreamExt;
+
+        let mut dumps = Vec::new();
+        for dir_entry in std::fs::read_dir(dir)? {
+            let path = dir_entry?.path();
+            let is_default_cards = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("default-cards-") && n.ends_with(".json"));
+            if let Some(at) = bulk_timestamp(&path).filter(|_| is_default_cards) {
+                dumps.push((at, path));
+            }
+        }
+        dumps.sort();
+
+        let mut stats = IngestStats::default();
+        let start = std::time::Instant::now();
+
+        for (fetched_at, path) in dumps {
+            let mut stream = Box::pin(self.ingest_file(&path));
+            while let Some(res) = stream.next().await {
+                let batch = res?;
+                for (oracle_card, mut printing) in batch {
+                    if let Some(prices) = printing.prices.as_mut() {
+                        prices.fetched_at = fetched_at;
+                    }
+                    let existing = db
+                        .get_by_id(printing.id)
+                        .await
+                        .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+                    if existing.is_none() {
+                        db.upsert_oracle(&oracle_card)
+                            .await
+                            .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+                        db.upsert_printing(&printing)
+                            .await
+                            .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+                        stats.cards_inserted += 1;
+                    }
+                    record_snapshot(db, &printing, &mut stats).await?;
+                    stats.cards_processed += 1;
+                }
+            }
+            stats.files_processed += 1;
+        }
+
         stats.duration = start.elapsed();
         Ok(stats)
     }
 }
 
+/// Insert `printing.prices` as a snapshot unless it matches the one before it.
+async fn record_snapshot<R>(
+    db: &R,
+    printing: &Printing,
+    stats: &mut IngestStats,
+) -> Result<(), ScryfallError>
+where
+    R: PriceRepository + ?Sized,
+{
+    let Some(prices) = &printing.prices else {
+        return Ok(());
+    };
+    let previous = db
+        .get_price_as_of(printing.id, prices.fetched_at)
+        .await
+        .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+    if previous.is_some_and(|p| p.same_prices(prices)) {
+        stats.snapshots_unchanged += 1;
+        return Ok(());
+    }
+    db.insert_snapshot(printing.id, prices)
+        .await
+        .map_err(|e| ScryfallError::Network(format!("DB error: {e:?}")))?;
+    stats.snapshots_recorded += 1;
+    Ok(())
+}
+
+/// Timestamp encoded in a Scryfall bulk file name, e.g.
+/// `default-cards-20240601091408.json` → 2024-06-01 09:14:08 UTC.
+pub fn bulk_timestamp(path: &Path) -> Option<DateTime<Utc>> {
+    let stem = path.file_stem()?.to_str()?;
+    let (_, stamp) = stem.rsplit_once('-')?;
+    NaiveDateTime::parse_from_str(stamp, "%Y%m%d%H%M%S")
+        .ok()
+        .map(|dt| dt.and_utc())
+}
+
+/// When the prices in a bulk file were current: the timestamp in its name,
+/// falling back to the file's modification time and then to now.
+pub fn snapshot_time(path: &Path) -> DateTime<Utc> {
+    bulk_timestamp(path)
+        .or_else(|| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from)
+        })
+        .unwrap_or_else(Utc::now)
+}
+
+#[derive(Debug, Default)]
 pub struct IngestStats {
     pub cards_processed: u64,
     pub cards_inserted: u64,
     pub cards_updated: u64,
+    pub snapshots_recorded: u64,
+    /// Printings whose prices matched their previous snapshot.
+    pub snapshots_unchanged: u64,
+    pub files_processed: u64,
     pub duration: std::time::Duration,
 }
 
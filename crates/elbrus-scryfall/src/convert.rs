@@ -45,11 +45,7 @@ impl ScryfallCard {
                     mana_cost: if face.mana_cost.is_empty() {
                         None
                     } else {
-                        Some(ManaCost::parse(&face.mana_cost).unwrap_or_else(|_| {
-                            let mut symbols = smallvec::SmallVec::new();
-                            symbols.push(ManaSymbol::Unknown(Arc::from(face.mana_cost)));
-                            ManaCost(symbols)
-                        }))
+                        Some(parse_mana_cost(&face.mana_cost))
                     },
                     type_line: face
                         .type_line
@@ -82,13 +78,7 @@ impl ScryfallCard {
                     .mana_cost
                     .as_deref()
                     .filter(|s| !s.is_empty())
-                    .map(|s| {
-                        ManaCost::parse(s).unwrap_or_else(|_| {
-                            let mut symbols = smallvec::SmallVec::new();
-                            symbols.push(ManaSymbol::Unknown(Arc::from(s)));
-                            ManaCost(symbols)
-                        })
-                    }),
+                    .map(parse_mana_cost),
                 type_line: self
                     .type_line
                     .as_deref()
@@ -156,10 +146,18 @@ impl ScryfallCard {
                 .get("usd_foil")
                 .and_then(|v| v.as_deref())
                 .and_then(|s| s.parse().ok()),
+            usd_etched: p
+                .get("usd_etched")
+                .and_then(|v| v.as_deref())
+                .and_then(|s| s.parse().ok()),
             eur: p
                 .get("eur")
                 .and_then(|v| v.as_deref())
                 .and_then(|s| s.parse().ok()),
+            eur_foil: p
+                .get("eur_foil")
+                .and_then(|v| v.as_deref())
+                .and_then(|s| s.parse().ok()),
             tix: p
                 .get("tix")
                 .and_then(|v| v.as_deref())
@@ -184,6 +182,7 @@ impl ScryfallCard {
             full_art: false, // Add to ScryfallCard
             textless: false, // Add to ScryfallCard
             reprint: false,  // Add to ScryfallCard
+            booster: self.booster,
             prices,
         };
 
@@ -191,6 +190,16 @@ impl ScryfallCard {
     }
 }
 
+/// Parse a Scryfall mana cost, keeping the raw string as a single unknown
+/// symbol if the grammar rejects it.
+fn parse_mana_cost(s: &str) -> ManaCost {
+    elbrus_parser::mana_cost::parse(s).unwrap_or_else(|_| {
+        let mut symbols = smallvec::SmallVec::new();
+        symbols.push(ManaSymbol::Unknown(Arc::from(s)));
+        ManaCost(symbols)
+    })
+}
+
 fn parse_colors(colors: &[String]) -> ColorSet {
     let mut set = ColorSet::empty();
     for c in colors {
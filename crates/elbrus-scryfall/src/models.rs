@@ -83,6 +83,9 @@ pub struct ScryfallCard {
     #[serde(default)]
     pub reprint: bool,
 
+    #[serde(default)]
+    pub booster: bool,
+
     #[serde(default)]
     pub image_uris: Option<HashMap<String, String>>,
 
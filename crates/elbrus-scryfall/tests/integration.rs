@@ -1,13 +1,20 @@
+use chrono::TimeZone;
 use elbrus_db::repo::card::CardRepository;
+use elbrus_db::repo::price::PriceRepository;
 use elbrus_db::sqlite::SqliteBackend;
-use elbrus_scryfall::bulk::BulkIngestor;
+use elbrus_scryfall::bulk::{BulkIngestor, bulk_timestamp};
 use std::path::PathBuf;
 
-#[tokio::test]
-async fn test_ingest_sample() {
+fn sample_path() -> PathBuf {
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     let mut sample_path = PathBuf::from(manifest_dir);
     sample_path.push("tests/data/sample.json");
+    sample_path
+}
+
+#[tokio::test]
+async fn test_ingest_sample() {
+    let sample_path = sample_path();
 
     let db = SqliteBackend::open_in_memory()
         .await
@@ -45,3 +52,79 @@ async fn test_ingest_sample() {
     assert_eq!(printing.set_code.as_ref(), "mma");
     assert_eq!(printing.collector_number.as_ref(), "33");
 }
+
+#[tokio::test]
+async fn test_ingest_records_price_snapshots() {
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let ingestor = BulkIngestor { chunk_size: 10 };
+
+    let first = ingestor.ingest_into_db(&sample_path(), &db).await.unwrap();
+    assert_eq!(first.snapshots_recorded, 50);
+
+    // Same file again: prices unchanged, nothing new recorded.
+    let second = ingestor.ingest_into_db(&sample_path(), &db).await.unwrap();
+    assert_eq!(second.snapshots_recorded, 0);
+    assert_eq!(second.snapshots_unchanged, 50);
+    assert_eq!(db.ingest_runs(5).await.unwrap().len(), 1, "one bulk file");
+
+    let printing_id = uuid::Uuid::parse_str("67ba07ca-7be4-400e-a104-f7bbd527b6b4").unwrap();
+    let history = db.get_price_history(printing_id).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].usd, Some("0.12".parse().unwrap()));
+    assert_eq!(history[0].eur_foil, Some("0.18".parse().unwrap()));
+}
+
+#[tokio::test]
+async fn test_backfill_prices() {
+    assert_eq!(
+        bulk_timestamp(std::path::Path::new(
+            "data/json/default-cards-20240601091408.json"
+        )),
+        Some(chrono::Utc.with_ymd_and_hms(2024, 6, 1, 9, 14, 8).unwrap())
+    );
+
+    let dir = std::env::temp_dir().join(format!("elbrus-backfill-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Three dumps; only the first card's price moves, and only in the newest one.
+    let raw = std::fs::read_to_string(sample_path()).unwrap();
+    let mut cards: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    std::fs::write(dir.join("default-cards-20240101000000.json"), &raw).unwrap();
+    std::fs::write(dir.join("default-cards-20240201000000.json"), &raw).unwrap();
+    cards[0]["prices"]["usd"] = "0.50".into();
+    std::fs::write(
+        dir.join("default-cards-20240301000000.json"),
+        serde_json::to_string(&cards).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(dir.join("notes.json"), "[]").unwrap();
+
+    let db = SqliteBackend::open_in_memory().await.unwrap();
+    let stats = BulkIngestor::default()
+        .backfill_prices(&dir, &db)
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(stats.files_processed, 3);
+    assert_eq!(stats.cards_inserted, 50);
+    assert_eq!(stats.snapshots_recorded, 51);
+    assert_eq!(stats.snapshots_unchanged, 99);
+    assert!(
+        db.ingest_runs(5).await.unwrap().is_empty(),
+        "backfills aren't ingest runs"
+    );
+
+    let printing_id = uuid::Uuid::parse_str("67ba07ca-7be4-400e-a104-f7bbd527b6b4").unwrap();
+    let history = db.get_price_history(printing_id).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].usd, Some("0.50".parse().unwrap()));
+    assert_eq!(
+        history[0].fetched_at,
+        chrono::Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+        history[1].fetched_at,
+        chrono::Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+    );
+}
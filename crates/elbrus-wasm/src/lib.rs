@@ -1,6 +1,31 @@
+use elbrus_analysis::{OracleIndex, SimilarFilter};
+use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub fn version() -> String {
     "0.1.0".to_string()
 }
+
+/// A prebuilt [`OracleIndex`], loaded from its JSON serialisation.
+#[wasm_bindgen]
+pub struct SimilarityIndex(OracleIndex);
+
+#[wasm_bindgen]
+impl SimilarityIndex {
+    #[wasm_bindgen(constructor)]
+    pub fn new(json: &str) -> Result<SimilarityIndex, JsError> {
+        Ok(Self(serde_json::from_str(json)?))
+    }
+
+    /// The `k` cards most like `oracle_id`, best first, as
+    /// `{ oracle_id, name, score }` objects.
+    #[wasm_bindgen(js_name = similarCards)]
+    pub fn similar_cards(&self, oracle_id: &str, k: usize) -> Result<JsValue, JsError> {
+        let oracle_id = Uuid::parse_str(oracle_id)?;
+        let similar = self
+            .0
+            .similar_cards(oracle_id, k, &SimilarFilter::default());
+        Ok(serde_wasm_bindgen::to_value(&similar)?)
+    }
+}